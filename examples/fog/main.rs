@@ -47,10 +47,7 @@ fn main() {
             .unwrap();
             monkey.cull = CullType::Back;
 
-            let ambient_light = AmbientLight {
-                intensity: 0.2,
-                color: vec3(1.0, 1.0, 1.0),
-            };
+            let ambient_light = AmbientLight::new(vec3(1.0, 1.0, 1.0), 0.2);
             let directional_light =
                 DirectionalLight::new(&context, 0.5, &vec3(1.0, 1.0, 1.0), &vec3(-1.0, -1.0, -1.0))
                     .unwrap();
@@ -75,10 +75,7 @@ fn main() {
             };
 
             // Lights
-            let ambient_light = AmbientLight {
-                intensity: 0.1,
-                color: vec3(1.0, 1.0, 1.0),
-            };
+            let ambient_light = AmbientLight::new(vec3(1.0, 1.0, 1.0), 0.1);
             let mut directional_light =
                 DirectionalLight::new(&context, 0.5, &vec3(1.0, 1.0, 1.0), &vec3(-1.0, -1.0, -1.0))
                     .unwrap();
@@ -87,6 +84,8 @@ fn main() {
             let mut aabb = tree_cpu_mesh.compute_aabb();
             aabb.expand_with_aabb(&leaves_cpu_mesh.compute_aabb());
             let mut imposters = Imposters::new(&context).unwrap();
+            // The leaves are single planes which must be captured from both sides
+            imposters.settings.cull = CullType::None;
             imposters
                 .update_texture(
                     |viewport: Viewport, camera: &Camera| {
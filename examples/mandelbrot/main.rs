@@ -39,7 +39,7 @@ fn main() {
     )
     .unwrap();
     mesh.cull = CullType::Back;
-    mesh.transformation = Mat4::from_scale(10.0);
+    mesh.set_transformation(Mat4::from_scale(10.0));
     let program =
         MeshProgram::new(&context, include_str!("../assets/shaders/mandelbrot.frag")).unwrap();
 
@@ -55,10 +55,7 @@ fn main() {
         )
         .unwrap();
 
-        let ambient_light = AmbientLight {
-            color: vec3(1.0, 1.0, 1.0),
-            intensity: 0.2,
-        };
+        let ambient_light = AmbientLight::new(vec3(1.0, 1.0, 1.0), 0.2);
         let mut directional_light0 =
             DirectionalLight::new(&context, 0.3, &vec3(1.0, 1.0, 1.0), &vec3(0.0, -1.0, 0.0))
                 .unwrap();
@@ -67,7 +67,7 @@ fn main() {
                     (1.2 * std::f32::consts::PI - angle).cos() * 21.0 - 33.0,
                     angle.sin() * dist,
                 ));
-                statue.transformation = translation * scale * rotation;
+                statue.set_transformation(translation * scale * rotation);
                 statues.push(statue.clone());
             }
 
@@ -78,12 +78,9 @@ fn main() {
                 Mesh::new_with_material(&context, &fountain_cpu_meshes[0], &fountain_material)
                     .unwrap();
             fountain.cull = CullType::Back;
-            fountain.transformation = Mat4::from_angle_x(degrees(-90.0));
+            fountain.set_transformation(Mat4::from_angle_x(degrees(-90.0)));
 
-            let ambient_light = AmbientLight {
-                intensity: 0.4,
-                color: vec3(1.0, 1.0, 1.0),
-            };
+            let ambient_light = AmbientLight::new(vec3(1.0, 1.0, 1.0), 0.4);
             let mut directional_light =
                 DirectionalLight::new(&context, 1.0, &vec3(0.8, 0.7, 0.5), &vec3(0.0, -1.0, -1.0))
                     .unwrap();
@@ -82,17 +82,14 @@ fn main() {
                 Mesh::new_with_material(&context, &penguin_cpu_meshes[0], &penguin_cpu_material)
                     .unwrap();
             penguin_deferred.cull = CullType::Back;
-            penguin_deferred.transformation = Mat4::from_translation(vec3(-0.5, 1.0, 0.0));
+            penguin_deferred.set_transformation(Mat4::from_translation(vec3(-0.5, 1.0, 0.0)));
             let mut penguin_forward =
                 Mesh::new_with_material(&context, &penguin_cpu_meshes[0], &penguin_cpu_material)
                     .unwrap();
             penguin_forward.cull = CullType::Back;
-            penguin_forward.transformation = Mat4::from_translation(vec3(0.5, 1.0, 0.0));
+            penguin_forward.set_transformation(Mat4::from_translation(vec3(0.5, 1.0, 0.0)));
 
-            let ambient_light = AmbientLight {
-                intensity: 0.4,
-                color: vec3(1.0, 1.0, 1.0),
-            };
+            let ambient_light = AmbientLight::new(vec3(1.0, 1.0, 1.0), 0.4);
             let directional_light =
                 DirectionalLight::new(&context, 1.0, &vec3(1.0, 1.0, 1.0), &vec3(0.0, -1.0, -1.0))
                     .unwrap();
@@ -0,0 +1,153 @@
+use three_d::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let window = Window::new(WindowSettings {
+        title: "Timer!".to_string(),
+        max_size: Some((1280, 720)),
+        ..Default::default()
+    })
+    .unwrap();
+    let context = window.gl().unwrap();
+
+    let camera = Camera::new_perspective(
+        &context,
+        vec3(4.0, 4.0, 8.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        window.viewport().unwrap().aspect(),
+        0.1,
+        100.0,
+    )
+    .unwrap();
+    let mut camera = CameraControl::new(camera);
+
+    let mut spheres = Vec::new();
+    for i in 0..5 {
+        for j in 0..5 {
+            let mut sphere = Mesh::new_with_material(
+                &context,
+                &CPUMesh::sphere(0.4),
+                &Material {
+                    color_source: ColorSource::Color(vec4(0.8, 0.2, 0.2, 1.0)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            sphere.set_transformation(Mat4::from_translation(vec3(
+                i as f32 - 2.0,
+                0.0,
+                j as f32 - 2.0,
+            )));
+            spheres.push(sphere);
+        }
+    }
+    let mut plane = Mesh::new_with_material(
+        &context,
+        &CPUMesh::square(10.0),
+        &Material {
+            color_source: ColorSource::Color(vec4(0.5, 0.7, 0.3, 1.0)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    plane.set_transformation(
+        Mat4::from_translation(vec3(0.0, -0.5, 0.0)) * Mat4::from_angle_x(degrees(-90.0)),
+    );
+
+    let ambient_light = AmbientLight::new(vec3(1.0, 1.0, 1.0), 0.2);
+    let mut directional_light =
+        DirectionalLight::new(&context, 1.0, &vec3(1.0, 1.0, 1.0), &vec3(-1.0, -1.0, -1.0))
+            .unwrap();
+
+    // The shadow pass is measured as a scope nested inside the frame scope.
+    // Timer queries can not overlap, so the frame scope is measured as the nested shadow pass
+    // plus the rest of the frame, which are measured by separate timer queries and added up.
+    let mut shadow_pass_timer = TimerQuery::new(&context).unwrap();
+    let mut rest_of_frame_timer = TimerQuery::new(&context).unwrap();
+    let mut time_since_print = 0.0;
+
+    window
+        .render_loop(move |frame_input| {
+            camera.set_aspect(frame_input.viewport.aspect()).unwrap();
+            camera
+                .rotate_around_with_fixed_up(
+                    &vec3(0.0, 0.0, 0.0),
+                    0.01 * frame_input.elapsed_time as f32,
+                    0.0,
+                )
+                .unwrap();
+
+            let mut geometries: Vec<&dyn Geometry> =
+                spheres.iter().map(|s| s as &dyn Geometry).collect();
+            geometries.push(&plane);
+
+            // Frame scope
+            {
+                // Shadow pass scope
+                shadow_pass_timer
+                    .measure(|| {
+                        directional_light.generate_shadow_map(
+                            &vec3(0.0, 0.0, 0.0),
+                            10.0,
+                            10.0,
+                            20.0,
+                            1024,
+                            1024,
+                            &geometries,
+                        )
+                    })
+                    .unwrap();
+
+                // The rest of the frame, ie. the main pass
+                rest_of_frame_timer
+                    .measure(|| {
+                        Screen::write(&context, ClearState::default(), || {
+                            for geometry in spheres.iter().chain(std::iter::once(&plane)) {
+                                geometry.render_with_lighting(
+                                    RenderStates::default(),
+                                    frame_input.viewport,
+                                    &camera,
+                                    Some(&ambient_light),
+                                    &[&directional_light],
+                                    &[],
+                                    &[],
+                                )?;
+                            }
+                            Ok(())
+                        })
+                    })
+                    .unwrap();
+            }
+
+            // The results are read back when they are available to avoid stalling the GPU
+            time_since_print += frame_input.elapsed_time;
+            if time_since_print > 1000.0 {
+                time_since_print = 0.0;
+                if let (Some(shadow_pass), Some(rest_of_frame)) = (
+                    shadow_pass_timer.elapsed_ms(),
+                    rest_of_frame_timer.elapsed_ms(),
+                ) {
+                    println!(
+                        "Frame: {:.3} ms, of which the shadow pass: {:.3} ms",
+                        shadow_pass + rest_of_frame,
+                        shadow_pass
+                    );
+                }
+            }
+
+            if args.len() > 1 {
+                // To automatically generate screenshots of the examples, can safely be ignored.
+                FrameOutput {
+                    screenshot: Some(args[1].clone().into()),
+                    exit: true,
+                    ..Default::default()
+                }
+            } else {
+                FrameOutput::default()
+            }
+        })
+        .unwrap();
+}
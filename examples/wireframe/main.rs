@@ -44,7 +44,7 @@ fn main() {
                 &Material::new(&gl, &cpu_material).unwrap(),
             )
             .unwrap();
-            model.transformation = Mat4::from_translation(vec3(0.0, 2.0, 0.0));
+            model.set_transformation(Mat4::from_translation(vec3(0.0, 2.0, 0.0)));
             model.cull = CullType::Back;
 
             let wireframe_material = Material {
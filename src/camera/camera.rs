@@ -17,6 +17,25 @@ pub(super) enum ProjectionType {
     },
 }
 
+///
+/// The corner of the viewport where the origin is placed by a [2D camera](Camera::new_orthographic_2d),
+/// which also defines the direction of the y-axis.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Corner {
+    ///
+    /// The origin is in the top left corner and the y-axis points down, which is common for user interfaces and images.
+    ///
+    /// **Note:** Since the y-axis is flipped, triangles that are counter-clockwise in world space are clockwise on the screen,
+    /// so use [FrontFace::Cw](crate::FrontFace::Cw) when culling back faces.
+    ///
+    TopLeft,
+    ///
+    /// The origin is in the bottom left corner and the y-axis points up, like the OpenGL window coordinates.
+    ///
+    BottomLeft,
+}
+
 ///
 /// Used in a render call to define how to view the 3D world.
 ///
@@ -28,6 +47,8 @@ pub struct Camera {
     up: Vec3,
     view: Mat4,
     projection: Mat4,
+    view_projection: Mat4,
+    jitter: Vec2,
     screen2ray: Mat4,
     uniform_buffer: UniformBuffer,
     frustrum: [Vec4; 6],
@@ -54,6 +75,41 @@ impl Camera {
         Ok(camera)
     }
 
+    ///
+    /// New camera for 2D rendering which projects the world with an orthographic projection where one world unit equals one pixel in the given viewport.
+    /// The origin is placed in the given corner of the viewport, the x-axis points right and the direction of the y-axis is given by the corner,
+    /// so the pixel in column `x` and row `y` counted from the corner covers the area from `(x, y)` to `(x + 1, y + 1)`.
+    /// Everything with a z coordinate between -1 and 1 is visible, see also [set_orthographic_2d](Self::set_orthographic_2d).
+    ///
+    pub fn new_orthographic_2d(
+        context: &Context,
+        viewport: Viewport,
+        origin: Corner,
+    ) -> Result<Camera, Error> {
+        let mut camera = Camera::new(context);
+        camera.set_orthographic_2d(viewport, origin)?;
+        Ok(camera)
+    }
+
+    ///
+    /// Specify the camera to be a 2D camera with one world unit equal to one pixel in the given viewport, see [new_orthographic_2d](Self::new_orthographic_2d).
+    /// Call this when the viewport is resized.
+    ///
+    pub fn set_orthographic_2d(&mut self, viewport: Viewport, origin: Corner) -> Result<(), Error> {
+        let center = vec3(
+            0.5 * viewport.width as f32,
+            0.5 * viewport.height as f32,
+            0.0,
+        );
+        // Looking in the positive z direction with the up direction flipped keeps the x-axis pointing right
+        let (direction, up) = match origin {
+            Corner::TopLeft => (vec3(0.0, 0.0, 1.0), vec3(0.0, -1.0, 0.0)),
+            Corner::BottomLeft => (vec3(0.0, 0.0, -1.0), vec3(0.0, 1.0, 0.0)),
+        };
+        self.set_view(center - direction, center, up)?;
+        self.set_orthographic_projection(viewport.width as f32, viewport.height as f32, 2.0)
+    }
+
     ///
     /// New camera which projects the world with a perspective projection.
     ///
@@ -93,11 +149,9 @@ impl Camera {
             z_near,
             z_far,
         };
-        self.projection = perspective(field_of_view_y, aspect, z_near, z_far);
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.projection =
+            jitter_matrix(self.jitter) * perspective(field_of_view_y, aspect, z_near, z_far);
+        self.update()
     }
 
     ///
@@ -115,18 +169,16 @@ impl Camera {
             height,
             depth,
         };
-        self.projection = ortho(
-            -0.5 * width,
-            0.5 * width,
-            -0.5 * height,
-            0.5 * height,
-            0.0,
-            depth,
-        );
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.projection = jitter_matrix(self.jitter)
+            * ortho(
+                -0.5 * width,
+                0.5 * width,
+                -0.5 * height,
+                0.5 * height,
+                0.0,
+                depth,
+            );
+        self.update()
     }
 
     ///
@@ -160,6 +212,24 @@ impl Camera {
         Ok(change)
     }
 
+    ///
+    /// Offsets the projection by the given amount in normalized device coordinates, ie. an offset of `2.0 / viewport.width` moves the image one pixel horizontally.
+    /// This is used to move the image a fraction of a pixel each frame for [temporal anti-aliasing](crate::TAAEffect).
+    /// Set the jitter to zero to disable it.
+    ///
+    pub fn set_jitter(&mut self, jitter: Vec2) -> Result<(), Error> {
+        self.projection = jitter_matrix(jitter - self.jitter) * self.projection;
+        self.jitter = jitter;
+        self.update()
+    }
+
+    ///
+    /// Returns the current jitter of the projection in normalized device coordinates, see [set_jitter](Self::set_jitter).
+    ///
+    pub fn jitter(&self) -> Vec2 {
+        self.jitter
+    }
+
     ///
     /// Change the view of the camera.
     /// The camera is placed at the given position, looking at the given target and with the given up direction.
@@ -173,10 +243,7 @@ impl Camera {
             Point::from_vec(self.target),
             self.up,
         );
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.update()
     }
 
     ///
@@ -186,10 +253,15 @@ impl Camera {
         self.view[1][0] = -self.view[1][0];
         self.view[1][1] = -self.view[1][1];
         self.view[1][2] = -self.view[1][2];
-        self.update_screen2ray();
-        self.update_uniform_buffer()?;
-        self.update_frustrum();
-        Ok(())
+        self.update()
+    }
+
+    ///
+    /// The six planes of the camera frustum, where a position `p` is inside a plane if `plane.dot(vec4(p.x, p.y, p.z, 1.0))` is not negative.
+    /// The planes are not normalized.
+    ///
+    pub(crate) fn frustum_planes(&self) -> &[Vec4; 6] {
+        &self.frustrum
     }
 
     ///
@@ -233,6 +305,29 @@ impl Camera {
         true
     }
 
+    ///
+    /// Returns whether or not the given point is within the camera frustum.
+    /// A point exactly on one of the frustum planes is inside, consistent with [in_frustum](Self::in_frustum).
+    ///
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        let p = point.extend(1.0);
+        self.frustum_planes()
+            .iter()
+            .all(|plane| plane.dot(p) >= 0.0)
+    }
+
+    ///
+    /// Returns whether or not the sphere with the given center and radius is within the camera frustum.
+    /// It returns false if it is fully outside and true if it is inside or intersects, where touching one of the frustum planes counts as intersecting.
+    /// Like [in_frustum](Self::in_frustum), this test is conservative and might return true for spheres near the corners of the frustum which are outside.
+    ///
+    pub fn sphere_in_frustum(&self, center: Vec3, radius: f32) -> bool {
+        let c = center.extend(1.0);
+        self.frustum_planes()
+            .iter()
+            .all(|plane| plane.dot(c) >= -radius * plane.truncate().magnitude())
+    }
+
     ///
     /// Finds the closest intersection between a ray from this camera in the direction of the given screen coordinates and the given geometries.
     /// Returns ```None``` if no geometry was hit before the given maximum depth.
@@ -248,6 +343,19 @@ impl Camera {
         ray_intersect(&self.context, pos, dir, max_depth, objects)
     }
 
+    ///
+    /// Returns the ray from this camera in the direction of the given screen coordinates, which can be used for picking on the CPU,
+    /// for example using [CPUMesh::raycast](crate::CPUMesh::raycast).
+    /// The coordinates must be between 0 and 1, where (0, 0) indicate the top left corner of the screen
+    /// and (1, 1) indicate the bottom right corner.
+    ///
+    pub fn ray_at(&self, screen_coordinates: (f32, f32)) -> Ray {
+        Ray::new(
+            self.position_at(screen_coordinates),
+            self.view_direction_at(screen_coordinates),
+        )
+    }
+
     ///
     /// Returns the 3D position at the given screen/image plane coordinates.
     /// The coordinates must be between 0 and 1, where (0, 0) indicate the top left corner of the screen
@@ -288,7 +396,7 @@ impl Camera {
     }
 
     pub fn uv_coordinate_at(&self, position: Vec3) -> (f32, f32) {
-        let proj = self.projection() * self.view() * position.extend(1.0);
+        let proj = self.view_projection() * position.extend(1.0);
         (0.5 * (proj.x / proj.w + 1.0), 0.5 * (proj.y / proj.w + 1.0))
     }
 
@@ -310,6 +418,14 @@ impl Camera {
         &self.projection
     }
 
+    ///
+    /// Returns the combined projection and view matrix, ie. the matrix that transforms objects from world space to clip space.
+    /// It is computed when the view or projection changes, so calling this is cheaper than multiplying the [projection](Self::projection) and [view](Self::view) matrices.
+    ///
+    pub fn view_projection(&self) -> &Mat4 {
+        &self.view_projection
+    }
+
     ///
     /// Returns the position of this camera.
     ///
@@ -380,10 +496,20 @@ impl Camera {
             up: vec3(0.0, 1.0, 0.0),
             view: Mat4::identity(),
             projection: Mat4::identity(),
+            view_projection: Mat4::identity(),
+            jitter: vec2(0.0, 0.0),
             screen2ray: Mat4::identity(),
         }
     }
 
+    fn update(&mut self) -> Result<(), Error> {
+        self.view_projection = self.projection * self.view;
+        self.update_screen2ray();
+        self.update_uniform_buffer()?;
+        self.update_frustrum();
+        Ok(())
+    }
+
     fn update_screen2ray(&mut self) {
         let mut v = self.view;
         v[3] = vec4(0.0, 0.0, 0.0, 1.0);
@@ -391,16 +517,16 @@ impl Camera {
     }
 
     fn update_uniform_buffer(&mut self) -> Result<(), Error> {
-        self.uniform_buffer
-            .update(0, &(self.projection * self.view).to_slice())?;
-        self.uniform_buffer.update(1, &self.view.to_slice())?;
-        self.uniform_buffer.update(2, &self.projection.to_slice())?;
-        self.uniform_buffer.update(3, &self.position.to_slice())?;
-        Ok(())
+        self.uniform_buffer.update_many(&[
+            (0, &self.view_projection.to_slice()),
+            (1, &self.view.to_slice()),
+            (2, &self.projection.to_slice()),
+            (3, &self.position.to_slice()),
+        ])
     }
 
     fn update_frustrum(&mut self) {
-        let m = self.projection * self.view;
+        let m = self.view_projection;
         self.frustrum = [
             vec4(m.x.w + m.x.x, m.y.w + m.y.x, m.z.w + m.z.x, m.w.w + m.w.x),
             vec4(m.x.w - m.x.x, m.y.w - m.y.x, m.z.w - m.z.x, m.w.w - m.w.x),
@@ -411,3 +537,7 @@ impl Camera {
         ];
     }
 }
+
+fn jitter_matrix(jitter: Vec2) -> Mat4 {
+    Mat4::from_translation(vec3(jitter.x, jitter.y, 0.0))
+}
@@ -21,3 +21,11 @@ mod wgl2;
 #[doc(inline)]
 #[cfg(target_arch = "wasm32")]
 pub use wgl2::*;
+
+mod render_stats;
+#[doc(inline)]
+pub use render_stats::*;
+
+mod memory_stats;
+#[doc(inline)]
+pub use memory_stats::*;
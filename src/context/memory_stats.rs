@@ -0,0 +1,47 @@
+use super::Context;
+
+///
+/// The number and approximate size of the GPU resources that are currently alive on a [Context],
+/// for example used for finding leaks in long-running applications or for showing in a HUD overlay.
+/// The resources are freed when they are dropped, so the statistics decrease when a resource goes out of scope
+/// or is disposed early using [drop].
+///
+/// **Note:** The sizes are estimated from the size of the data given to the resources,
+/// the actual memory used by the graphics driver can be larger, for example because of alignment and padding.
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct MemoryStats {
+    /// The number of textures of all kinds, for example [Texture2D](crate::Texture2D), [ColorTargetTexture2D](crate::ColorTargetTexture2D),
+    /// [DepthTargetTexture2D](crate::DepthTargetTexture2D), [TextureCubeMap](crate::TextureCubeMap) and the texture arrays.
+    /// The color and depth buffers of a [RenderTargetMultisample](crate::RenderTargetMultisample) count as two textures.
+    pub textures: u32,
+    /// The approximate number of bytes used by the textures, including mip maps.
+    pub texture_bytes: u64,
+    /// The number of [VertexBuffer](crate::VertexBuffer) buffers.
+    pub vertex_buffers: u32,
+    /// The number of bytes used by the [VertexBuffer](crate::VertexBuffer) buffers.
+    pub vertex_buffer_bytes: u64,
+    /// The number of [ElementBuffer](crate::ElementBuffer) buffers.
+    pub element_buffers: u32,
+    /// The number of bytes used by the [ElementBuffer](crate::ElementBuffer) buffers.
+    pub element_buffer_bytes: u64,
+    /// The number of [UniformBuffer](crate::UniformBuffer) buffers.
+    pub uniform_buffers: u32,
+    /// The number of bytes used by the [UniformBuffer](crate::UniformBuffer) buffers.
+    pub uniform_buffer_bytes: u64,
+}
+
+impl Context {
+    ///
+    /// Returns the number and approximate size of the textures and buffers that are currently alive on this context.
+    ///
+    pub fn memory_stats(&self) -> MemoryStats {
+        self.memory.get()
+    }
+
+    pub(crate) fn update_memory_stats(&self, update: impl FnOnce(&mut MemoryStats)) {
+        let mut stats = self.memory.get();
+        update(&mut stats);
+        self.memory.set(stats);
+    }
+}
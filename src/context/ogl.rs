@@ -4,6 +4,7 @@ pub mod consts {
 
 use std::rc::Rc;
 
+use super::{MemoryStats, RenderStats};
 use consts::Gl as InnerGl;
 
 pub type AttributeLocation = u32;
@@ -15,6 +16,8 @@ pub type Framebuffer = u32;
 pub type Texture = u32;
 pub type VertexArrayObject = u32;
 pub type Sync = consts::types::GLsync;
+pub type Query = u32;
+pub type Renderbuffer = u32;
 pub struct ActiveInfo {
     size: u32,
     type_: u32,
@@ -41,9 +44,19 @@ impl ActiveInfo {
 ///
 /// Calls to this API can be combined with higher level features.
 ///
+/// All GPU resources (programs, buffers, textures, render targets etc.) are owned by the context they are created with
+/// and resources are not shared between contexts, for example when having multiple windows.
+/// Using a buffer or texture with a program from another context returns an [Error::ContextMismatch](crate::Error::ContextMismatch).
+/// The shader programs that are cached internally, for example by [Mesh](crate::Mesh), are cached per context.
+///
 #[derive(Clone)]
 pub struct Context {
     inner: Rc<InnerGl>,
+    pub(super) stats: Rc<std::cell::Cell<RenderStats>>,
+    pub(super) memory: Rc<std::cell::Cell<MemoryStats>>,
+    pub(crate) state_cache: Rc<std::cell::RefCell<crate::core::StateCache>>,
+    // Keeps the native context alive as long as this context is used, for example for a headless context.
+    _owner: Option<Rc<dyn std::any::Any>>,
 }
 
 impl Context {
@@ -53,17 +66,155 @@ impl Context {
     {
         let gl = Context {
             inner: Rc::new(InnerGl::load_with(loadfn)),
+            stats: Rc::new(std::cell::Cell::new(RenderStats::default())),
+            memory: Rc::new(std::cell::Cell::new(MemoryStats::default())),
+            state_cache: Rc::new(std::cell::RefCell::new(Default::default())),
+            _owner: None,
         };
         gl.bind_vertex_array(&gl.create_vertex_array().unwrap());
+        // The size of points is always given by gl_PointSize in the vertex shader, like in WebGL
+        gl.enable(consts::PROGRAM_POINT_SIZE);
         gl
     }
 
+    ///
+    /// A context where no graphics functions are loaded, used for testing the parts of a context that do not call into the graphics API.
+    ///
+    #[cfg(test)]
+    pub(crate) fn new_without_gl() -> Context {
+        Context {
+            inner: Rc::new(InnerGl::load_with(|_| std::ptr::null())),
+            stats: Rc::new(std::cell::Cell::new(RenderStats::default())),
+            memory: Rc::new(std::cell::Cell::new(MemoryStats::default())),
+            state_cache: Rc::new(std::cell::RefCell::new(Default::default())),
+            _owner: None,
+        }
+    }
+
+    ///
+    /// Returns the underlying OpenGL function loader, which can be used for calling graphics functions that are not wrapped by this crate,
+    /// for example a custom extension or a third-party library rendering into the same context.
+    ///
+    /// # Safety
+    /// This crate caches the graphics state, for example the depth test, blending, culling and viewport used by the last draw call,
+    /// and assumes that no framebuffer, program, buffer or vertex array other than its own are bound between its calls.
+    /// Any state that is changed using the returned functions must therefore be restored before calling this crate again,
+    /// otherwise rendering can silently be wrong. Draw calls issued directly are not counted in the [render statistics](Context::render_stats)
+    /// and resources created directly are not counted in the [memory statistics](Context::memory_stats) or deleted by this crate.
+    ///
+    pub unsafe fn raw_gl(&self) -> &consts::Gl {
+        &self.inner
+    }
+
+    ///
+    /// Returns true if the two contexts are the same graphics context, ie. one is a clone of the other.
+    /// GPU resources (programs, buffers, textures etc.) are owned by the context they are created with and cannot be used with another context.
+    ///
+    pub fn is_same_context(&self, other: &Context) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    #[cfg(feature = "glutin-window")]
+    pub(crate) fn with_owner(mut self, owner: Rc<dyn std::any::Any>) -> Self {
+        self._owner = Some(owner);
+        self
+    }
+
+    ///
+    /// An identifier of this graphics context which is unique among the contexts that are alive.
+    ///
+    pub(crate) fn id(&self) -> usize {
+        Rc::as_ptr(&self.inner) as usize
+    }
+
     pub fn finish(&self) {
         unsafe {
             self.inner.Finish();
         }
     }
 
+    ///
+    /// Returns true if the context has been lost, for example because of a graphics device reset.
+    /// Always returns false if the driver does not support querying the reset status.
+    ///
+    pub fn check_context_lost(&self) -> bool {
+        if !self.inner.GetGraphicsResetStatus.is_loaded() {
+            return false;
+        }
+        unsafe { self.inner.GetGraphicsResetStatus() != consts::NO_ERROR }
+    }
+
+    ///
+    /// Sets a label on the texture which is shown in graphics debuggers.
+    /// Does nothing if the `debug-labels` feature is disabled or the `GL_KHR_debug` extension is not available.
+    ///
+    pub fn set_texture_label(&self, texture: &Texture, label: &str) {
+        self.object_label(consts::TEXTURE, *texture, label);
+    }
+
+    ///
+    /// Sets a label on the buffer which is shown in graphics debuggers.
+    /// Does nothing if the `debug-labels` feature is disabled or the `GL_KHR_debug` extension is not available.
+    ///
+    pub fn set_buffer_label(&self, buffer: &Buffer, label: &str) {
+        self.object_label(consts::BUFFER, *buffer, label);
+    }
+
+    ///
+    /// Sets a label on the program which is shown in graphics debuggers.
+    /// Does nothing if the `debug-labels` feature is disabled or the `GL_KHR_debug` extension is not available.
+    ///
+    pub fn set_program_label(&self, program: &Program, label: &str) {
+        self.object_label(consts::PROGRAM, *program, label);
+    }
+
+    ///
+    /// Sets a label on the framebuffer which is shown in graphics debuggers.
+    /// Does nothing if the `debug-labels` feature is disabled or the `GL_KHR_debug` extension is not available.
+    ///
+    pub fn set_framebuffer_label(&self, framebuffer: &Framebuffer, label: &str) {
+        self.object_label(consts::FRAMEBUFFER, *framebuffer, label);
+    }
+
+    #[cfg_attr(not(feature = "debug-labels"), allow(unused_variables))]
+    fn object_label(&self, identifier: u32, name: u32, label: &str) {
+        #[cfg(feature = "debug-labels")]
+        if self.inner.ObjectLabel.is_loaded() {
+            let c_str = std::ffi::CString::new(label).unwrap();
+            unsafe {
+                self.inner.ObjectLabel(identifier, name, -1, c_str.as_ptr());
+            }
+        }
+    }
+
+    ///
+    /// Starts a named group of commands which is shown in graphics debuggers, must be followed by a call to [pop_debug_group](Self::pop_debug_group).
+    /// Does nothing if the `debug-labels` feature is disabled or the `GL_KHR_debug` extension is not available.
+    ///
+    #[cfg_attr(not(feature = "debug-labels"), allow(unused_variables))]
+    pub fn push_debug_group(&self, message: &str) {
+        #[cfg(feature = "debug-labels")]
+        if self.inner.PushDebugGroup.is_loaded() {
+            let c_str = std::ffi::CString::new(message).unwrap();
+            unsafe {
+                self.inner
+                    .PushDebugGroup(consts::DEBUG_SOURCE_APPLICATION, 0, -1, c_str.as_ptr());
+            }
+        }
+    }
+
+    ///
+    /// Ends the group of commands started by the last call to [push_debug_group](Self::push_debug_group).
+    ///
+    pub fn pop_debug_group(&self) {
+        #[cfg(feature = "debug-labels")]
+        if self.inner.PopDebugGroup.is_loaded() {
+            unsafe {
+                self.inner.PopDebugGroup();
+            }
+        }
+    }
+
     pub fn create_shader(&self, type_: u32) -> Option<Shader> {
         let id = unsafe { self.inner.CreateShader(type_) };
         Some(id)
@@ -287,6 +438,17 @@ impl Context {
         }
     }
 
+    pub fn buffer_sub_data_f32(&self, target: u32, offset: u32, data: &[f32]) {
+        unsafe {
+            self.inner.BufferSubData(
+                target,
+                offset as consts::types::GLintptr, // offset in bytes
+                std::mem::size_of_val(data) as consts::types::GLsizeiptr, // size of data in bytes
+                data.as_ptr() as *const consts::types::GLvoid, // pointer to data
+            );
+        }
+    }
+
     pub fn create_vertex_array(&self) -> Option<VertexArrayObject> {
         let mut id: u32 = 0;
         unsafe {
@@ -507,6 +669,63 @@ impl Context {
         }
     }
 
+    pub fn create_renderbuffer(&self) -> Option<Renderbuffer> {
+        let mut id: u32 = 0;
+        unsafe {
+            self.inner.GenRenderbuffers(1, &mut id);
+        }
+        Some(id)
+    }
+
+    pub fn bind_renderbuffer(&self, target: u32, renderbuffer: Option<&Renderbuffer>) {
+        unsafe {
+            self.inner
+                .BindRenderbuffer(target, renderbuffer.copied().unwrap_or(0));
+        }
+    }
+
+    pub fn delete_renderbuffer(&self, renderbuffer: &Renderbuffer) {
+        unsafe {
+            self.inner.DeleteRenderbuffers(1, renderbuffer);
+        }
+    }
+
+    pub fn renderbuffer_storage_multisample(
+        &self,
+        target: u32,
+        samples: u32,
+        internalformat: u32,
+        width: u32,
+        height: u32,
+    ) {
+        unsafe {
+            self.inner.RenderbufferStorageMultisample(
+                target,
+                samples as i32,
+                internalformat,
+                width as i32,
+                height as i32,
+            );
+        }
+    }
+
+    pub fn framebuffer_renderbuffer(
+        &self,
+        target: u32,
+        attachment: u32,
+        renderbuffer_target: u32,
+        renderbuffer: &Renderbuffer,
+    ) {
+        unsafe {
+            self.inner.FramebufferRenderbuffer(
+                target,
+                attachment,
+                renderbuffer_target,
+                *renderbuffer,
+            );
+        }
+    }
+
     pub fn check_framebuffer_status(&self) -> Result<(), String> {
         let status = unsafe { self.inner.CheckFramebufferStatus(consts::FRAMEBUFFER) };
 
@@ -571,6 +790,60 @@ impl Context {
         }
     }
 
+    ///
+    /// Enables or disables primitive restart using the maximum value of the index type as the restart index.
+    ///
+    pub fn set_primitive_restart(&self, enabled: bool) {
+        unsafe {
+            if enabled {
+                self.inner.Enable(consts::PRIMITIVE_RESTART_FIXED_INDEX);
+            } else {
+                self.inner.Disable(consts::PRIMITIVE_RESTART_FIXED_INDEX);
+            }
+        }
+    }
+
+    pub fn polygon_mode(&self, mode: u32) {
+        unsafe {
+            self.inner.PolygonMode(consts::FRONT_AND_BACK, mode);
+        }
+    }
+
+    pub fn line_width(&self, width: f32) {
+        unsafe {
+            self.inner.LineWidth(width);
+        }
+    }
+
+    pub fn get_aliased_line_width_range(&self) -> (f32, f32) {
+        let mut range = [1.0f32; 2];
+        unsafe {
+            self.inner
+                .GetFloatv(consts::ALIASED_LINE_WIDTH_RANGE, range.as_mut_ptr());
+        }
+        (range[0], range[1])
+    }
+
+    pub fn get_max_samples(&self) -> u32 {
+        let mut samples = 0;
+        unsafe {
+            self.inner.GetIntegerv(consts::MAX_SAMPLES, &mut samples);
+        }
+        samples.max(1) as u32
+    }
+
+    pub fn polygon_offset(&self, factor: f32, units: f32) {
+        unsafe {
+            self.inner.PolygonOffset(factor, units);
+        }
+    }
+
+    pub fn scissor(&self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe {
+            self.inner.Scissor(x, y, width, height);
+        }
+    }
+
     pub fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
         unsafe {
             self.inner.ClearColor(red, green, blue, alpha);
@@ -632,6 +905,12 @@ impl Context {
         }
     }
 
+    pub fn front_face(&self, mode: u32) {
+        unsafe {
+            self.inner.FrontFace(mode);
+        }
+    }
+
     pub fn depth_func(&self, func: u32) {
         unsafe {
             self.inner.DepthFunc(func);
@@ -685,6 +964,31 @@ impl Context {
         }
     }
 
+    pub fn tex_storage_2d_multisample(
+        &self,
+        target: u32,
+        samples: u32,
+        internalformat: u32,
+        width: u32,
+        height: u32,
+        fixed_sample_locations: bool,
+    ) {
+        unsafe {
+            self.inner.TexStorage2DMultisample(
+                target,
+                samples as i32,
+                internalformat,
+                width as i32,
+                height as i32,
+                if fixed_sample_locations {
+                    consts::TRUE
+                } else {
+                    consts::FALSE
+                },
+            );
+        }
+    }
+
     pub fn tex_storage_2d(
         &self,
         target: u32,
@@ -886,6 +1190,99 @@ impl Context {
         }
     }
 
+    pub fn tex_sub_image_3d_with_u8_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[u8],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
+    pub fn tex_sub_image_3d_with_f32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[f32],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
+    pub fn tex_sub_image_3d_with_u32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[u32],
+    ) {
+        unsafe {
+            self.inner.TexSubImage3D(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                pixels.as_ptr() as *const consts::types::GLvoid,
+            );
+        }
+    }
+
     pub fn tex_image_3d(
         &self,
         target: u32,
@@ -948,6 +1345,12 @@ impl Context {
         }
     }
 
+    pub fn tex_parameterfv(&self, target: u32, pname: u32, params: &[f32]) {
+        unsafe {
+            self.inner.TexParameterfv(target, pname, params.as_ptr());
+        }
+    }
+
     pub fn delete_texture(&self, texture: &Texture) {
         unsafe {
             self.inner.DeleteTextures(1, texture);
@@ -988,6 +1391,7 @@ impl Context {
     }
 
     pub fn draw_arrays(&self, mode: u32, first: u32, count: u32) {
+        self.record_draw(mode, count, 1);
         unsafe {
             self.inner.DrawArrays(
                 mode as consts::types::GLenum,
@@ -998,6 +1402,7 @@ impl Context {
     }
 
     pub fn draw_arrays_instanced(&self, mode: u32, first: u32, count: u32, instance_count: u32) {
+        self.record_draw(mode, count, instance_count);
         unsafe {
             self.inner.DrawArraysInstanced(
                 mode as consts::types::GLenum,
@@ -1009,6 +1414,7 @@ impl Context {
     }
 
     pub fn draw_elements(&self, mode: u32, count: u32, data_type: u32, offset: u32) {
+        self.record_draw(mode, count, 1);
         unsafe {
             self.inner.DrawElements(
                 mode as consts::types::GLenum,
@@ -1027,6 +1433,7 @@ impl Context {
         offset: u32,
         instance_count: u32,
     ) {
+        self.record_draw(mode, count, instance_count);
         unsafe {
             self.inner.DrawElementsInstanced(
                 mode as consts::types::GLenum,
@@ -1129,6 +1536,101 @@ impl Context {
         }
     }
 
+    pub fn supports_timer_queries(&self) -> bool {
+        true
+    }
+
+    pub fn is_timer_query_disjoint(&self) -> bool {
+        false
+    }
+
+    pub fn create_query(&self) -> Option<Query> {
+        let mut id: u32 = 0;
+        unsafe {
+            self.inner.GenQueries(1, &mut id);
+        }
+        Some(id)
+    }
+
+    pub fn delete_query(&self, query: &Query) {
+        unsafe {
+            self.inner.DeleteQueries(1, query);
+        }
+    }
+
+    pub fn begin_query(&self, target: u32, query: &Query) {
+        unsafe {
+            self.inner.BeginQuery(target, *query);
+        }
+    }
+
+    pub fn end_query(&self, target: u32) {
+        unsafe {
+            self.inner.EndQuery(target);
+        }
+    }
+
+    pub fn get_query_parameter_u32(&self, query: &Query, pname: u32) -> u32 {
+        let mut result: u32 = 0;
+        unsafe {
+            self.inner.GetQueryObjectuiv(*query, pname, &mut result);
+        }
+        result
+    }
+
+    pub fn get_query_parameter_u64(&self, query: &Query, pname: u32) -> u64 {
+        let mut result: u64 = 0;
+        unsafe {
+            self.inner.GetQueryObjectui64v(*query, pname, &mut result);
+        }
+        result
+    }
+
+    pub fn transform_feedback_varyings(
+        &self,
+        program: &Program,
+        varyings: &[&str],
+        buffer_mode: u32,
+    ) {
+        use std::ffi::CString;
+        let names: Vec<CString> = varyings
+            .iter()
+            .map(|varying| CString::new(*varying).unwrap())
+            .collect();
+        let pointers: Vec<*const consts::types::GLchar> =
+            names.iter().map(|name| name.as_ptr()).collect();
+        unsafe {
+            self.inner.TransformFeedbackVaryings(
+                *program,
+                pointers.len() as i32,
+                pointers.as_ptr(),
+                buffer_mode,
+            );
+        }
+    }
+
+    pub fn bind_transform_feedback_buffer(&self, index: u32, buffer: Option<&Buffer>) {
+        unsafe {
+            self.inner.BindBufferBase(
+                consts::TRANSFORM_FEEDBACK_BUFFER,
+                index,
+                buffer.copied().unwrap_or(0),
+            );
+        }
+    }
+
+    pub fn begin_transform_feedback(&self, primitive_mode: u32) {
+        unsafe {
+            self.inner.BeginTransformFeedback(primitive_mode);
+        }
+    }
+
+    pub fn end_transform_feedback(&self) {
+        unsafe {
+            self.inner.EndTransformFeedback();
+        }
+    }
+
     pub fn flush(&self) {
         unsafe {
             self.inner.Flush();
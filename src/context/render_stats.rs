@@ -0,0 +1,56 @@
+use super::{consts, Context};
+
+///
+/// Statistics about the draw calls issued on a [Context] since the statistics were last [reset](Context::reset_render_stats),
+/// for example used for profiling or for showing in a HUD overlay.
+/// Every draw call is counted, for example when rendering a [Mesh](crate::Mesh) or an [InstancedMesh](crate::InstancedMesh),
+/// including draw calls used for shadow maps and post effects.
+///
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct RenderStats {
+    /// The number of draw calls.
+    pub draw_calls: u32,
+    /// The number of triangles drawn, counting the triangles of each instance.
+    /// The triangles of a triangle strip which is restarted are slightly overestimated.
+    pub triangles_drawn: u64,
+    /// The number of instances drawn, where a draw call without instancing counts as one instance.
+    pub instances_drawn: u64,
+    /// The number of graphics state changes, for example enabling blending or changing the cull mode.
+    /// Setting a state to the value it already has is skipped and not counted.
+    pub state_changes: u32,
+}
+
+impl Context {
+    ///
+    /// Returns the statistics about the draw calls issued since the last call to [reset_render_stats](Context::reset_render_stats).
+    ///
+    pub fn render_stats(&self) -> RenderStats {
+        self.stats.get()
+    }
+
+    ///
+    /// Resets the [render statistics](Context::render_stats), for example at the start of each frame.
+    ///
+    pub fn reset_render_stats(&self) {
+        self.stats.set(RenderStats::default());
+    }
+
+    pub(crate) fn record_state_change(&self) {
+        let mut stats = self.stats.get();
+        stats.state_changes += 1;
+        self.stats.set(stats);
+    }
+
+    pub(super) fn record_draw(&self, mode: u32, count: u32, instance_count: u32) {
+        let triangles = match mode {
+            consts::TRIANGLES => count / 3,
+            consts::TRIANGLE_STRIP | consts::TRIANGLE_FAN => count.saturating_sub(2),
+            _ => 0,
+        };
+        let mut stats = self.stats.get();
+        stats.draw_calls += 1;
+        stats.triangles_drawn += triangles as u64 * instance_count as u64;
+        stats.instances_drawn += instance_count as u64;
+        self.stats.set(stats);
+    }
+}
@@ -8,6 +8,8 @@ pub use web_sys::WebGlActiveInfo as ActiveInfo;
 pub use web_sys::WebGlBuffer as Buffer;
 pub use web_sys::WebGlFramebuffer as Framebuffer;
 pub use web_sys::WebGlProgram as Program;
+pub use web_sys::WebGlQuery as Query;
+pub use web_sys::WebGlRenderbuffer as Renderbuffer;
 pub use web_sys::WebGlShader as Shader;
 pub use web_sys::WebGlSync as Sync;
 pub use web_sys::WebGlTexture as Texture;
@@ -17,19 +19,116 @@ pub use web_sys::WebGlVertexArrayObject as VertexArrayObject;
 #[derive(Clone)]
 pub struct Context {
     inner: std::rc::Rc<InnerGl>,
+    pub(super) stats: std::rc::Rc<std::cell::Cell<super::RenderStats>>,
+    pub(super) memory: std::rc::Rc<std::cell::Cell<super::MemoryStats>>,
+    pub(crate) state_cache: std::rc::Rc<std::cell::RefCell<crate::core::StateCache>>,
 }
 
 impl Context {
     pub fn new(webgl_context: InnerGl) -> Self {
         Self {
             inner: std::rc::Rc::new(webgl_context),
+            stats: std::rc::Rc::new(std::cell::Cell::new(super::RenderStats::default())),
+            memory: std::rc::Rc::new(std::cell::Cell::new(super::MemoryStats::default())),
+            state_cache: std::rc::Rc::new(std::cell::RefCell::new(Default::default())),
         }
     }
 
+    ///
+    /// Returns the underlying WebGL 2 rendering context, which can be used for calling graphics functions that are not wrapped by this crate,
+    /// for example a custom extension or a third-party library rendering into the same context.
+    ///
+    /// # Safety
+    /// This crate caches the graphics state, for example the depth test, blending, culling and viewport used by the last draw call,
+    /// and assumes that no framebuffer, program, buffer or vertex array other than its own are bound between its calls.
+    /// Any state that is changed using the returned context must therefore be restored before calling this crate again,
+    /// otherwise rendering can silently be wrong. Draw calls issued directly are not counted in the [render statistics](Context::render_stats)
+    /// and resources created directly are not counted in the [memory statistics](Context::memory_stats) or deleted by this crate.
+    ///
+    pub unsafe fn raw_gl(&self) -> &InnerGl {
+        &self.inner
+    }
+
     pub fn finish(&self) {
         self.inner.finish();
     }
 
+    ///
+    /// Returns true if the two contexts are the same graphics context, ie. one is a clone of the other.
+    /// GPU resources (programs, buffers, textures etc.) are owned by the context they are created with and cannot be used with another context.
+    ///
+    pub fn is_same_context(&self, other: &Context) -> bool {
+        std::rc::Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    ///
+    /// An identifier of this graphics context which is unique among the contexts that are alive.
+    ///
+    pub(crate) fn id(&self) -> usize {
+        std::rc::Rc::as_ptr(&self.inner) as usize
+    }
+
+    ///
+    /// Returns true if the context has been lost, for example because the browser reclaimed the graphics device.
+    ///
+    pub fn check_context_lost(&self) -> bool {
+        self.inner.is_context_lost()
+    }
+
+    ///
+    /// Debug labels are not supported in WebGL, so this does nothing.
+    ///
+    pub fn set_texture_label(&self, _texture: &Texture, _label: &str) {}
+
+    ///
+    /// Debug labels are not supported in WebGL, so this does nothing.
+    ///
+    pub fn set_buffer_label(&self, _buffer: &Buffer, _label: &str) {}
+
+    ///
+    /// Debug labels are not supported in WebGL, so this does nothing.
+    ///
+    pub fn set_program_label(&self, _program: &Program, _label: &str) {}
+
+    ///
+    /// Debug labels are not supported in WebGL, so this does nothing.
+    ///
+    pub fn set_framebuffer_label(&self, _framebuffer: &Framebuffer, _label: &str) {}
+
+    ///
+    /// Debug groups are not supported in WebGL, so this does nothing.
+    ///
+    pub fn push_debug_group(&self, _message: &str) {}
+
+    ///
+    /// Debug groups are not supported in WebGL, so this does nothing.
+    ///
+    pub fn pop_debug_group(&self) {}
+
+    ///
+    /// Primitive restart using the maximum value of the index type is always enabled in WebGL 2, so this does nothing.
+    ///
+    pub fn set_primitive_restart(&self, _enabled: bool) {}
+
+    pub fn get_aliased_line_width_range(&self) -> (f32, f32) {
+        use wasm_bindgen::JsCast;
+        self.inner
+            .get_parameter(consts::ALIASED_LINE_WIDTH_RANGE)
+            .ok()
+            .and_then(|value| value.dyn_into::<js_sys::Float32Array>().ok())
+            .map(|range| (range.get_index(0), range.get_index(1)))
+            .unwrap_or((1.0, 1.0))
+    }
+
+    pub fn get_max_samples(&self) -> u32 {
+        self.inner
+            .get_parameter(consts::MAX_SAMPLES)
+            .ok()
+            .and_then(|value| value.as_f64())
+            .map(|samples| (samples as u32).max(1))
+            .unwrap_or(1)
+    }
+
     pub fn bind_buffer_base(&self, target: u32, index: u32, buffer: &Buffer) {
         self.inner.bind_buffer_base(target, index, Some(buffer));
     }
@@ -97,8 +196,22 @@ impl Context {
             .buffer_data_with_array_buffer_view(target, &array, usage);
     }
 
+    pub fn buffer_sub_data_f32(&self, target: u32, offset: u32, data: &[f32]) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = data.as_ptr() as u32 / 4;
+        let array = js_sys::Float32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + data.len() as u32);
+
+        self.inner
+            .buffer_sub_data_with_i32_and_array_buffer_view(target, offset as i32, &array);
+    }
+
     pub fn compile_shader(&self, source: &str, shader: &Shader) {
-        let header = "#version 300 es\nprecision highp float;\nprecision highp int;\nprecision highp sampler2DArray;\n";
+        let header = "#version 300 es\nprecision highp float;\nprecision highp int;\nprecision highp sampler2DArray;\nprecision highp sampler2DShadow;\n";
         let s: &str = &[header, source].concat();
 
         self.inner.shader_source(shader, s);
@@ -218,6 +331,117 @@ impl Context {
             .unwrap();
     }
 
+    pub fn tex_sub_image_3d_with_u8_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[u8],
+    ) {
+        self.inner
+            .tex_sub_image_3d_with_opt_u8_array(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                Some(pixels),
+            )
+            .unwrap();
+    }
+
+    pub fn tex_sub_image_3d_with_f32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[f32],
+    ) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = pixels.as_ptr() as u32 / 4;
+        let array = js_sys::Float32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + pixels.len() as u32);
+
+        self.inner
+            .tex_sub_image_3d_with_opt_array_buffer_view(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                Some(&array),
+            )
+            .unwrap();
+    }
+
+    pub fn tex_sub_image_3d_with_u32_data(
+        &self,
+        target: u32,
+        level: u32,
+        x_offset: u32,
+        y_offset: u32,
+        z_offset: u32,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: u32,
+        data_type: u32,
+        pixels: &[u32],
+    ) {
+        use wasm_bindgen::JsCast;
+        let memory_buffer = wasm_bindgen::memory()
+            .dyn_into::<js_sys::WebAssembly::Memory>()
+            .unwrap()
+            .buffer();
+        let data_location = pixels.as_ptr() as u32 / 4;
+        let array = js_sys::Uint32Array::new(&memory_buffer)
+            .subarray(data_location, data_location + pixels.len() as u32);
+
+        self.inner
+            .tex_sub_image_3d_with_opt_array_buffer_view(
+                target,
+                level as i32,
+                x_offset as i32,
+                y_offset as i32,
+                z_offset as i32,
+                width as i32,
+                height as i32,
+                depth as i32,
+                format,
+                data_type,
+                Some(&array),
+            )
+            .unwrap();
+    }
+
     pub fn tex_image_2d_with_u8_data(
         &self,
         target: u32,
@@ -391,6 +615,42 @@ impl Context {
             .unwrap();
     }
 
+    pub fn delete_renderbuffer(&self, renderbuffer: &Renderbuffer) {
+        self.inner.delete_renderbuffer(Some(renderbuffer));
+    }
+
+    pub fn renderbuffer_storage_multisample(
+        &self,
+        target: u32,
+        samples: u32,
+        internalformat: u32,
+        width: u32,
+        height: u32,
+    ) {
+        self.inner.renderbuffer_storage_multisample(
+            target,
+            samples as i32,
+            internalformat,
+            width as i32,
+            height as i32,
+        );
+    }
+
+    pub fn framebuffer_renderbuffer(
+        &self,
+        target: u32,
+        attachment: u32,
+        renderbuffer_target: u32,
+        renderbuffer: &Renderbuffer,
+    ) {
+        self.inner.framebuffer_renderbuffer(
+            target,
+            attachment,
+            renderbuffer_target,
+            Some(renderbuffer),
+        );
+    }
+
     pub fn framebuffer_texture_2d(
         &self,
         target: u32,
@@ -527,6 +787,7 @@ impl Context {
     }
 
     pub fn draw_arrays(&self, mode: u32, first: u32, count: u32) {
+        self.record_draw(mode, count, 1);
         self.inner.draw_arrays(
             mode,
             first as i32, // starting index in the enabled arrays
@@ -535,6 +796,7 @@ impl Context {
     }
 
     pub fn draw_arrays_instanced(&self, mode: u32, first: u32, count: u32, instance_count: u32) {
+        self.record_draw(mode, count, instance_count);
         self.inner.draw_arrays_instanced(
             mode,
             first as i32, // starting index in the enabled arrays
@@ -544,8 +806,13 @@ impl Context {
     }
 
     pub fn draw_elements(&self, mode: u32, count: u32, data_type: u32, offset: u32) {
-        self.inner
-            .draw_elements_with_i32(mode, count as i32, data_type, offset as i32);
+        self.record_draw(mode, count, 1);
+        self.inner.draw_elements_with_i32(
+            mode,
+            count as i32,
+            data_type,
+            byte_size_for_type(data_type, offset) as i32,
+        );
     }
 
     pub fn draw_elements_instanced(
@@ -556,11 +823,12 @@ impl Context {
         offset: u32,
         instance_count: u32,
     ) {
+        self.record_draw(mode, count, instance_count);
         self.inner.draw_elements_instanced_with_i32(
             mode,
             count as i32,
             data_type,
-            offset as i32,
+            byte_size_for_type(data_type, offset) as i32,
             instance_count as i32,
         );
     }
@@ -691,6 +959,46 @@ impl Context {
         self.inner.get_active_uniform(program, index).unwrap()
     }
 
+    pub fn supports_timer_queries(&self) -> bool {
+        self.inner
+            .get_extension("EXT_disjoint_timer_query_webgl2")
+            .map(|e| e.is_some())
+            .unwrap_or(false)
+    }
+
+    pub fn is_timer_query_disjoint(&self) -> bool {
+        const GPU_DISJOINT_EXT: u32 = 0x8FBB;
+        self.inner
+            .get_parameter(GPU_DISJOINT_EXT)
+            .map(|v| v.as_bool().unwrap_or(false))
+            .unwrap_or(false)
+    }
+
+    pub fn delete_query(&self, query: &Query) {
+        self.inner.delete_query(Some(query));
+    }
+
+    pub fn begin_query(&self, target: u32, query: &Query) {
+        self.inner.begin_query(target, query);
+    }
+
+    pub fn get_query_parameter_u32(&self, query: &Query, pname: u32) -> u32 {
+        let value = self.inner.get_query_parameter(query, pname);
+        value
+            .as_bool()
+            .map(|b| b as u32)
+            .or_else(|| value.as_f64().map(|v| v as u32))
+            .unwrap_or(0)
+    }
+
+    pub fn get_query_parameter_u64(&self, query: &Query, pname: u32) -> u64 {
+        self.inner
+            .get_query_parameter(query, pname)
+            .as_f64()
+            .map(|v| v as u64)
+            .unwrap_or(0)
+    }
+
     pub fn fence_sync(&self) -> Sync {
         self.inner
             .fence_sync(consts::SYNC_GPU_COMMANDS_COMPLETE, 0)
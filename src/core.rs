@@ -10,10 +10,21 @@ mod render_states;
 #[doc(inline)]
 pub use render_states::*;
 
+mod state_cache;
+pub(crate) use state_cache::*;
+
 mod texture2d;
 #[doc(inline)]
 pub use texture2d::*;
 
+mod texture2d_array;
+#[doc(inline)]
+pub use texture2d_array::*;
+
+mod texture3d;
+#[doc(inline)]
+pub use texture3d::*;
+
 mod texture_cube_map;
 #[doc(inline)]
 pub use texture_cube_map::*;
@@ -34,6 +45,10 @@ mod depth_target_texture2d_array;
 #[doc(inline)]
 pub use depth_target_texture2d_array::*;
 
+mod texture_builder;
+#[doc(inline)]
+pub use texture_builder::*;
+
 mod element_buffer;
 #[doc(inline)]
 pub use element_buffer::*;
@@ -46,10 +61,34 @@ mod uniform_buffer;
 #[doc(inline)]
 pub use uniform_buffer::*;
 
+mod stream_buffer;
+#[doc(inline)]
+pub use stream_buffer::*;
+
+mod timer_query;
+#[doc(inline)]
+pub use timer_query::*;
+
+mod occlusion_query;
+#[doc(inline)]
+pub use occlusion_query::*;
+
 mod render_target;
 #[doc(inline)]
 pub use render_target::*;
 
+mod render_target_cube_map;
+#[doc(inline)]
+pub use render_target_cube_map::*;
+
+mod render_target_multisample;
+#[doc(inline)]
+pub use render_target_multisample::*;
+
+mod render_graph;
+#[doc(inline)]
+pub use render_graph::*;
+
 mod program;
 #[doc(inline)]
 pub use program::*;
@@ -89,8 +128,79 @@ pub enum Error {
         /// Error message
         message: String,
     },
+    /// An error when using a query.
+    QueryError {
+        /// Error message
+        message: String,
+    },
+    /// A shader failed to compile or a shader program failed to link.
+    ShaderCompile {
+        /// Error message, including the shader info logs if available
+        message: String,
+    },
+    /// An attribute was sent to a shader program which does not use it.
+    MissingAttribute {
+        /// The name of the attribute
+        name: String,
+    },
+    /// Data was written outside the bounds of a buffer or the data has the wrong length.
+    BufferOverflow {
+        /// Error message
+        message: String,
+    },
+    /// The combination of texture format and data type is not supported.
+    UnsupportedFormat {
+        /// Error message
+        message: String,
+    },
+    /// A GPU resource was used with another graphics context than the one it was created with.
+    ContextMismatch {
+        /// Error message
+        message: String,
+    },
+    /// Failed to create a graphics context.
+    ContextCreationError {
+        /// Error message
+        message: String,
+    },
+    ///
+    /// The graphics context has been lost, for example because of a graphics device reset or because the browser reclaimed the graphics device.
+    /// All GPU resources (textures, buffers, programs, render targets etc.) are invalid after this.
+    /// To recover, create a new [Context](crate::context::Context) (or wait for the browser to restore it on web) and recreate all resources from the CPU side data,
+    /// for example from [CPUMesh](crate::CPUMesh) and [CPUTexture](crate::CPUTexture).
+    ///
+    ContextLost,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::ProgramError { message } => write!(f, "Program error: {}", message),
+            Error::RenderTargetError { message } => write!(f, "Render target error: {}", message),
+            Error::TextureError { message } => write!(f, "Texture error: {}", message),
+            Error::BufferError { message } => write!(f, "Buffer error: {}", message),
+            Error::MeshError { message } => write!(f, "Mesh error: {}", message),
+            Error::CameraError { message } => write!(f, "Camera error: {}", message),
+            Error::QueryError { message } => write!(f, "Query error: {}", message),
+            Error::ShaderCompile { message } => write!(f, "Shader compile error: {}", message),
+            Error::MissingAttribute { name } => write!(
+                f,
+                "The attribute {} is sent to the shader but it is never used.",
+                name
+            ),
+            Error::BufferOverflow { message } => write!(f, "Buffer overflow: {}", message),
+            Error::UnsupportedFormat { message } => write!(f, "Unsupported format: {}", message),
+            Error::ContextMismatch { message } => write!(f, "Context mismatch: {}", message),
+            Error::ContextCreationError { message } => {
+                write!(f, "Context creation error: {}", message)
+            }
+            Error::ContextLost => write!(f, "The graphics context has been lost."),
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
 pub trait VertexBufferDataType:
     Default + std::fmt::Debug + Clone + internal::BufferDataTypeExtension
 {
@@ -176,6 +286,15 @@ pub(crate) mod internal {
             format: Format,
             data: &[Self],
         );
+        fn fill_3d(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        );
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]);
     }
 
@@ -188,6 +307,12 @@ pub(crate) mod internal {
                 Format::RGBA => crate::context::consts::RGBA8,
                 Format::SRGB => crate::context::consts::SRGB8,
                 Format::SRGBA => crate::context::consts::SRGB8_ALPHA8,
+                Format::R16F | Format::RG16F => {
+                    return Err(crate::Error::UnsupportedFormat {
+                        message: "Can only use the R16F and RG16F formats together with a f32 texture value type."
+                            .to_string(),
+                    });
+                }
             })
         }
 
@@ -212,6 +337,30 @@ pub(crate) mod internal {
             );
         }
 
+        fn fill_3d(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_u8_data(
+                target,
+                0,
+                0,
+                0,
+                0,
+                width,
+                height,
+                depth,
+                format_from(format),
+                consts::UNSIGNED_BYTE,
+                data,
+            );
+        }
+
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
             context.read_pixels_with_u8_data(
                 viewport.x as u32,
@@ -229,10 +378,12 @@ pub(crate) mod internal {
             Ok(match format {
                 Format::R => crate::context::consts::R32F,
                 Format::RG => crate::context::consts::RG32F,
+                Format::R16F => crate::context::consts::R16F,
+                Format::RG16F => crate::context::consts::RG16F,
                 Format::RGB => crate::context::consts::RGB32F,
                 Format::RGBA => crate::context::consts::RGBA32F,
                 _ => {
-                    return Err(crate::Error::TextureError {
+                    return Err(crate::Error::UnsupportedFormat {
                         message:
                             "Cannot only use the sRGB(A) format together with a u8 texture value type."
                                 .to_string(),
@@ -262,6 +413,30 @@ pub(crate) mod internal {
             );
         }
 
+        fn fill_3d(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_f32_data(
+                target,
+                0,
+                0,
+                0,
+                0,
+                width,
+                height,
+                depth,
+                format_from(format),
+                consts::FLOAT,
+                data,
+            );
+        }
+
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
             context.read_pixels_with_f32_data(
                 viewport.x as u32,
@@ -282,8 +457,14 @@ pub(crate) mod internal {
                 Format::RG => crate::context::consts::RG32UI,
                 Format::RGB => crate::context::consts::RGB32UI,
                 Format::RGBA => crate::context::consts::RGBA32UI,
+                Format::R16F | Format::RG16F => {
+                    return Err(crate::Error::UnsupportedFormat {
+                        message: "Can only use the R16F and RG16F formats together with a f32 texture value type."
+                            .to_string(),
+                    });
+                }
                 _ => {
-                    return Err(crate::Error::TextureError {
+                    return Err(crate::Error::UnsupportedFormat {
                         message:
                             "Cannot only use the sRGB(A) format together with a u8 texture value type."
                                 .to_string(),
@@ -312,6 +493,30 @@ pub(crate) mod internal {
                 data,
             );
         }
+
+        fn fill_3d(
+            context: &Context,
+            target: u32,
+            width: u32,
+            height: u32,
+            depth: u32,
+            format: Format,
+            data: &[Self],
+        ) {
+            context.tex_sub_image_3d_with_u32_data(
+                target,
+                0,
+                0,
+                0,
+                0,
+                width,
+                height,
+                depth,
+                format_from(format),
+                consts::UNSIGNED_INT,
+                data,
+            );
+        }
         fn read(context: &Context, viewport: Viewport, format: Format, pixels: &mut [Self]) {
             context.read_pixels_with_u32_data(
                 viewport.x as u32,
@@ -325,10 +530,12 @@ pub(crate) mod internal {
         }
     }
 
-    fn format_from(format: Format) -> u32 {
+    pub(crate) fn format_from(format: Format) -> u32 {
         match format {
             Format::R => consts::RED,
             Format::RG => consts::RG,
+            Format::R16F => consts::RED,
+            Format::RG16F => consts::RG,
             Format::RGB => consts::RGB,
             Format::SRGB => consts::RGB,
             Format::RGBA => consts::RGBA,
@@ -351,6 +558,21 @@ pub trait Texture {
     fn width(&self) -> u32;
     /// The height of this texture.
     fn height(&self) -> u32;
+    ///
+    /// Whether this texture contains colors in gamma color space (sRGB) which are decoded to linear color space when sampled,
+    /// for example an albedo texture, or linear data, for example a normal map or roughness texture.
+    /// Textures with the [SRGB](crate::Format::SRGB) and [SRGBA](crate::Format::SRGBA) formats and `u8` data are sRGB.
+    ///
+    fn is_srgb(&self) -> bool {
+        false
+    }
+    ///
+    /// The context this texture was created with, which is checked against the context of the program when the texture is used
+    /// (see [use_texture](crate::Program::use_texture)). The check is skipped if this returns `None`, which is the default.
+    ///
+    fn context(&self) -> Option<&crate::context::Context> {
+        None
+    }
 }
 
 ///
@@ -365,6 +587,13 @@ pub trait TextureArray {
     fn height(&self) -> u32;
     /// The depth of this texture, ie. the number of layers.
     fn depth(&self) -> u32;
+    ///
+    /// The context this texture array was created with, which is checked against the context of the program when the texture array is used
+    /// (see [use_texture_array](crate::Program::use_texture_array)). The check is skipped if this returns `None`, which is the default.
+    ///
+    fn context(&self) -> Option<&crate::context::Context> {
+        None
+    }
 }
 
 ///
@@ -377,9 +606,20 @@ pub trait TextureCube {
     fn width(&self) -> u32;
     /// The height of one of the sides of this texture.
     fn height(&self) -> u32;
+    ///
+    /// The context this texture cube was created with, which is checked against the context of the program when the texture cube is used
+    /// (see [use_texture_cube](crate::Program::use_texture_cube)). The check is skipped if this returns `None`, which is the default.
+    ///
+    fn context(&self) -> Option<&crate::context::Context> {
+        None
+    }
 }
 
 // COMMON TEXTURE FUNCTIONS
+fn is_srgb_internal_format(internal_format: u32) -> bool {
+    internal_format == consts::SRGB8 || internal_format == consts::SRGB8_ALPHA8
+}
+
 fn generate(context: &Context) -> Result<crate::context::Texture, Error> {
     context.create_texture().ok_or_else(|| Error::TextureError {
         message: "Failed to create texture".to_string(),
@@ -401,6 +641,18 @@ fn set_parameters(
     wrap_s: Wrapping,
     wrap_t: Wrapping,
     wrap_r: Option<Wrapping>,
+) {
+    set_interpolation_parameters(context, id, target, min_filter, mag_filter, mip_map_filter);
+    set_wrapping_parameters(context, id, target, wrap_s, wrap_t, wrap_r);
+}
+
+fn set_interpolation_parameters(
+    context: &Context,
+    id: &crate::context::Texture,
+    target: u32,
+    min_filter: Interpolation,
+    mag_filter: Interpolation,
+    mip_map_filter: Option<Interpolation>,
 ) {
     context.bind_texture(target, id);
     match mip_map_filter {
@@ -445,6 +697,17 @@ fn set_parameters(
         consts::TEXTURE_MAG_FILTER,
         interpolation_from(mag_filter),
     );
+}
+
+fn set_wrapping_parameters(
+    context: &Context,
+    id: &crate::context::Texture,
+    target: u32,
+    wrap_s: Wrapping,
+    wrap_t: Wrapping,
+    wrap_r: Option<Wrapping>,
+) {
+    context.bind_texture(target, id);
     context.tex_parameteri(target, consts::TEXTURE_WRAP_S, wrapping_from(wrap_s));
     context.tex_parameteri(target, consts::TEXTURE_WRAP_T, wrapping_from(wrap_t));
     if let Some(r) = wrap_r {
@@ -452,6 +715,24 @@ fn set_parameters(
     }
 }
 
+#[allow(unused_variables)]
+fn set_border_color_parameter(
+    context: &Context,
+    id: &crate::context::Texture,
+    target: u32,
+    color: &crate::math::Vec4,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        context.bind_texture(target, id);
+        context.tex_parameterfv(
+            target,
+            consts::TEXTURE_BORDER_COLOR,
+            &[color.x, color.y, color.z, color.w],
+        );
+    }
+}
+
 fn calculate_number_of_mip_maps(
     mip_map_filter: Option<Interpolation>,
     width: u32,
@@ -497,14 +778,91 @@ fn internal_format_from_depth(format: DepthFormat) -> u32 {
     }
 }
 
+// The number of bytes the GPU uses for one texel with the given internal format,
+// which is not always the size of the data type, for example f32 data is stored as 16 bit floats in an R16F texture
+fn internal_format_texel_bytes(internal_format: u32) -> u64 {
+    match internal_format {
+        consts::R8 => 1,
+        consts::RG8 | consts::R16F | consts::DEPTH_COMPONENT16 => 2,
+        consts::RGB8 | consts::SRGB8 | consts::DEPTH_COMPONENT24 => 3,
+        consts::RGBA8
+        | consts::SRGB8_ALPHA8
+        | consts::RG16F
+        | consts::R32F
+        | consts::R32UI
+        | consts::DEPTH_COMPONENT32F => 4,
+        consts::RG32F | consts::RG32UI => 8,
+        consts::RGB32F | consts::RGB32UI => 12,
+        consts::RGBA32F | consts::RGBA32UI => 16,
+        _ => unreachable!(),
+    }
+}
+
+fn color_texel_bytes<T: crate::TextureDataType>(format: Format) -> u64 {
+    T::internal_format(format)
+        .map(internal_format_texel_bytes)
+        .unwrap_or(0)
+}
+
+fn depth_texel_bytes(format: DepthFormat) -> u64 {
+    internal_format_texel_bytes(internal_format_from_depth(format))
+}
+
+// The approximate number of bytes used by a texture with the given size, number of layers and mip maps
+fn texture_bytes(
+    width: u32,
+    height: u32,
+    layers: u32,
+    texel_bytes: u64,
+    number_of_mip_maps: u32,
+) -> u64 {
+    let mut bytes = width as u64 * height as u64 * layers as u64 * texel_bytes;
+    if number_of_mip_maps > 1 {
+        // The mip maps use approximately a third of the size of the base level
+        bytes += bytes / 3;
+    }
+    bytes
+}
+
+fn add_texture_memory(context: &Context, bytes: u64) {
+    context.update_memory_stats(|stats| {
+        stats.textures += 1;
+        stats.texture_bytes += bytes;
+    });
+}
+
+fn remove_texture_memory(context: &Context, bytes: u64) {
+    context.update_memory_stats(|stats| {
+        stats.textures -= 1;
+        stats.texture_bytes -= bytes;
+    });
+}
+
 fn wrapping_from(wrapping: Wrapping) -> i32 {
     (match wrapping {
         Wrapping::Repeat => consts::REPEAT,
         Wrapping::MirroredRepeat => consts::MIRRORED_REPEAT,
         Wrapping::ClampToEdge => consts::CLAMP_TO_EDGE,
+        #[cfg(not(target_arch = "wasm32"))]
+        Wrapping::ClampToBorder => consts::CLAMP_TO_BORDER,
+        #[cfg(target_arch = "wasm32")]
+        Wrapping::ClampToBorder => consts::CLAMP_TO_EDGE,
     }) as i32
 }
 
+fn depth_func_from(depth_test: DepthTestType) -> u32 {
+    match depth_test {
+        DepthTestType::Never => consts::NEVER,
+        DepthTestType::Less => consts::LESS,
+        DepthTestType::Equal => consts::EQUAL,
+        DepthTestType::LessOrEqual => consts::LEQUAL,
+        DepthTestType::Greater => consts::GREATER,
+        DepthTestType::NotEqual => consts::NOTEQUAL,
+        DepthTestType::GreaterOrEqual => consts::GEQUAL,
+        DepthTestType::Always => consts::ALWAYS,
+    }
+}
+
 fn interpolation_from(interpolation: Interpolation) -> i32 {
     (match interpolation {
         Interpolation::Nearest => consts::NEAREST,
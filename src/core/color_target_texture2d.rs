@@ -17,6 +17,7 @@ pub struct ColorTargetTexture2D<T: TextureDataType> {
     number_of_mip_maps: u32,
     format: Format,
     _dummy: T,
+    bytes: u64,
 }
 
 impl<T: TextureDataType> ColorTargetTexture2D<T> {
@@ -58,6 +59,14 @@ impl<T: TextureDataType> ColorTargetTexture2D<T> {
             width,
             height,
         );
+        let bytes = texture_bytes(
+            width,
+            height,
+            1,
+            color_texel_bytes::<T>(format),
+            number_of_mip_maps,
+        );
+        add_texture_memory(context, bytes);
         Ok(Self {
             context: context.clone(),
             id,
@@ -66,6 +75,7 @@ impl<T: TextureDataType> ColorTargetTexture2D<T> {
             number_of_mip_maps,
             format,
             _dummy: T::default(),
+            bytes,
         })
     }
 
@@ -84,6 +94,53 @@ impl<T: TextureDataType> ColorTargetTexture2D<T> {
         RenderTarget::<T>::new_color(&self.context, &self)?.write(clear_state, render)
     }
 
+    ///
+    /// Renders whatever rendered in the `render` closure into the given mip level of the texture,
+    /// for example to create a custom mip chain or a prefiltered environment map.
+    /// The `render` closure is given a viewport covering the entire mip level.
+    /// Before writing, the mip level is cleared based on the given clear state.
+    /// In contrast to [write](Self::write), the mip maps are not generated afterwards.
+    ///
+    /// # Errors
+    /// Will return an error if the mip level does not exist, see [number_of_mip_maps](Self::number_of_mip_maps).
+    ///
+    pub fn write_to_mip<F: FnOnce(Viewport) -> Result<(), Error>>(
+        &self,
+        mip_level: u32,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        RenderTarget::<T>::new_color(&self.context, self)?.write_to_mip(
+            mip_level,
+            clear_state,
+            render,
+        )
+    }
+
+    ///
+    /// The number of mip levels of this texture, including the base level.
+    ///
+    pub fn number_of_mip_maps(&self) -> u32 {
+        self.number_of_mip_maps
+    }
+
+    ///
+    /// Sets the color returned when the texture is sampled outside the range `[0, 1]` of the texture coordinates
+    /// in a direction with [Wrapping::ClampToBorder] wrapping. The default border color is transparent black.
+    ///
+    /// **Note:** Has no effect on web, since clamping to the border is not supported in WebGL 2.
+    ///
+    pub fn set_border_color(&mut self, color: Vec4) {
+        set_border_color_parameter(&self.context, &self.id, consts::TEXTURE_2D, &color);
+    }
+
+    ///
+    /// The format of this texture.
+    ///
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
     ///
     /// Copies the content of the color texture to the specified [destination](crate::CopyDestination) at the given viewport.
     /// Will only copy the channels specified by the write mask.
@@ -135,13 +192,13 @@ impl<T: TextureDataType> ColorTargetTexture2D<T> {
         }
     }
 
-    pub(super) fn bind_as_color_target(&self, channel: u32) {
+    pub(super) fn bind_as_color_target(&self, channel: u32, mip_level: u32) {
         self.context.framebuffer_texture_2d(
             consts::FRAMEBUFFER,
             consts::COLOR_ATTACHMENT0 + channel,
             consts::TEXTURE_2D,
             &self.id,
-            0,
+            mip_level,
         );
     }
 }
@@ -156,10 +213,19 @@ impl<T: TextureDataType> Texture for ColorTargetTexture2D<T> {
     fn height(&self) -> u32 {
         self.height
     }
+    fn is_srgb(&self) -> bool {
+        T::internal_format(self.format)
+            .map(is_srgb_internal_format)
+            .unwrap_or(false)
+    }
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
 }
 
 impl<T: TextureDataType> Drop for ColorTargetTexture2D<T> {
     fn drop(&mut self) {
+        remove_texture_memory(&self.context, self.bytes);
         self.context.delete_texture(&self.id);
     }
 }
@@ -16,7 +16,9 @@ pub struct ColorTargetTexture2DArray<T: TextureDataType> {
     height: u32,
     depth: u32,
     number_of_mip_maps: u32,
+    mip_map_filter: Option<Interpolation>,
     _dummy: T,
+    bytes: u64,
 }
 
 impl<T: TextureDataType> ColorTargetTexture2DArray<T> {
@@ -58,6 +60,14 @@ impl<T: TextureDataType> ColorTargetTexture2DArray<T> {
             height,
             depth,
         );
+        let bytes = texture_bytes(
+            width,
+            height,
+            depth,
+            color_texel_bytes::<T>(format),
+            number_of_mip_maps,
+        );
+        add_texture_memory(context, bytes);
         Ok(Self {
             context: context.clone(),
             id,
@@ -65,7 +75,13 @@ impl<T: TextureDataType> ColorTargetTexture2DArray<T> {
             height,
             depth,
             number_of_mip_maps,
+            mip_map_filter: if number_of_mip_maps == 1 {
+                None
+            } else {
+                mip_map_filter
+            },
             _dummy: T::default(),
+            bytes,
         })
     }
 
@@ -114,6 +130,38 @@ impl<T: TextureDataType> ColorTargetTexture2DArray<T> {
         )
     }
 
+    ///
+    /// Sets how each 2D layer of the texture array is sampled outside the range `[0, 1]` of the texture coordinates in the s (u) and t (v) directions.
+    /// The wrapping applies to all layers, the layer index itself is never wrapped but clamped to the number of layers.
+    /// Takes effect the next time the texture array is used in a draw call.
+    ///
+    pub fn set_wrapping(&mut self, wrap_s: Wrapping, wrap_t: Wrapping) {
+        set_wrapping_parameters(
+            &self.context,
+            &self.id,
+            consts::TEXTURE_2D_ARRAY,
+            wrap_s,
+            wrap_t,
+            None,
+        );
+    }
+
+    ///
+    /// Sets the interpolation used when the layers of the texture array are minified and magnified.
+    /// The mip map filter specified when the texture array was created is kept.
+    /// Takes effect the next time the texture array is used in a draw call.
+    ///
+    pub fn set_interpolation(&mut self, min_filter: Interpolation, mag_filter: Interpolation) {
+        set_interpolation_parameters(
+            &self.context,
+            &self.id,
+            consts::TEXTURE_2D_ARRAY,
+            min_filter,
+            mag_filter,
+            self.mip_map_filter,
+        );
+    }
+
     pub(crate) fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.context
@@ -146,10 +194,14 @@ impl<T: TextureDataType> TextureArray for ColorTargetTexture2DArray<T> {
     fn depth(&self) -> u32 {
         self.depth
     }
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
 }
 
 impl<T: TextureDataType> Drop for ColorTargetTexture2DArray<T> {
     fn drop(&mut self) {
+        remove_texture_memory(&self.context, self.bytes);
         self.context.delete_texture(&self.id);
     }
 }
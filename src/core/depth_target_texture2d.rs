@@ -22,6 +22,7 @@ pub struct DepthTargetTexture2D {
     id: crate::context::Texture,
     width: u32,
     height: u32,
+    bytes: u64,
 }
 
 impl DepthTargetTexture2D {
@@ -55,14 +56,69 @@ impl DepthTargetTexture2D {
             width as u32,
             height as u32,
         );
+        let bytes = texture_bytes(width, height, 1, depth_texel_bytes(format), 1);
+        add_texture_memory(context, bytes);
         Ok(Self {
             context: context.clone(),
             id,
             width,
             height,
+            bytes,
         })
     }
 
+    ///
+    /// Sets the color returned when the texture is sampled outside the range `[0, 1]` of the texture coordinates
+    /// in a direction with [Wrapping::ClampToBorder] wrapping. The default border color is transparent black.
+    ///
+    /// **Note:** Has no effect on web, since clamping to the border is not supported in WebGL 2.
+    ///
+    pub fn set_border_color(&mut self, color: Vec4) {
+        set_border_color_parameter(&self.context, &self.id, consts::TEXTURE_2D, &color);
+    }
+
+    ///
+    /// Sets whether the texture is sampled as a comparison sampler, ie. declared as `uniform sampler2DShadow` in the shader.
+    /// When a comparison function is given, a texture lookup, for example using `textureProj`, compares the given reference depth
+    /// with the stored depth using the comparison function and the results are linearly interpolated between the four nearest texels,
+    /// which gives a 2x2 percentage closer filtering of shadows for free.
+    /// When `None` (the default), the texture is sampled as a `uniform sampler2D` which returns the stored depth without interpolation.
+    ///
+    pub fn set_depth_comparison(&mut self, comparison: Option<DepthTestType>) {
+        self.context.bind_texture(consts::TEXTURE_2D, &self.id);
+        if let Some(comparison) = comparison {
+            self.context.tex_parameteri(
+                consts::TEXTURE_2D,
+                consts::TEXTURE_COMPARE_MODE,
+                consts::COMPARE_REF_TO_TEXTURE as i32,
+            );
+            self.context.tex_parameteri(
+                consts::TEXTURE_2D,
+                consts::TEXTURE_COMPARE_FUNC,
+                depth_func_from(comparison) as i32,
+            );
+        } else {
+            self.context.tex_parameteri(
+                consts::TEXTURE_2D,
+                consts::TEXTURE_COMPARE_MODE,
+                consts::NONE as i32,
+            );
+        }
+        let filter = if comparison.is_some() {
+            Interpolation::Linear
+        } else {
+            Interpolation::Nearest
+        };
+        set_interpolation_parameters(
+            &self.context,
+            &self.id,
+            consts::TEXTURE_2D,
+            filter,
+            filter,
+            None,
+        );
+    }
+
     ///
     /// Write the depth of whatever rendered in the `render` closure into the texture.
     /// Before writing, the texture is cleared based on the given clear state.
@@ -120,10 +176,14 @@ impl Texture for DepthTargetTexture2D {
     fn height(&self) -> u32 {
         self.height
     }
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
 }
 
 impl Drop for DepthTargetTexture2D {
     fn drop(&mut self) {
+        remove_texture_memory(&self.context, self.bytes);
         self.context.delete_texture(&self.id);
     }
 }
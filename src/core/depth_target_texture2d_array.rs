@@ -12,6 +12,7 @@ pub struct DepthTargetTexture2DArray {
     width: u32,
     height: u32,
     depth: u32,
+    bytes: u64,
 }
 
 impl DepthTargetTexture2DArray {
@@ -45,12 +46,15 @@ impl DepthTargetTexture2DArray {
             height,
             depth,
         );
+        let bytes = texture_bytes(width, height, depth, depth_texel_bytes(format), 1);
+        add_texture_memory(context, bytes);
         Ok(Self {
             context: context.clone(),
             id,
             width,
             height,
             depth,
+            bytes,
         })
     }
 
@@ -120,10 +124,14 @@ impl TextureArray for DepthTargetTexture2DArray {
     fn depth(&self) -> u32 {
         self.depth
     }
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
 }
 
 impl Drop for DepthTargetTexture2DArray {
     fn drop(&mut self) {
+        remove_texture_memory(&self.context, self.bytes);
         self.context.delete_texture(&self.id);
     }
 }
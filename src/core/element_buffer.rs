@@ -10,6 +10,7 @@ pub struct ElementBuffer {
     id: crate::context::Buffer,
     count: usize,
     data_type: u32,
+    bytes: u64,
 }
 
 impl ElementBuffer {
@@ -21,11 +22,13 @@ impl ElementBuffer {
         data: &[T],
     ) -> Result<ElementBuffer, Error> {
         let id = context.create_buffer().unwrap();
+        context.update_memory_stats(|stats| stats.element_buffers += 1);
         let mut buffer = ElementBuffer {
             context: context.clone(),
             id,
             count: 0,
             data_type: T::data_type(),
+            bytes: 0,
         };
         if data.len() > 0 {
             buffer.fill_with(data);
@@ -47,6 +50,12 @@ impl ElementBuffer {
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
         self.count = data.len();
+        let old_bytes = self.bytes;
+        let bytes = std::mem::size_of_val(data) as u64;
+        self.context.update_memory_stats(|stats| {
+            stats.element_buffer_bytes = stats.element_buffer_bytes - old_bytes + bytes
+        });
+        self.bytes = bytes;
     }
 
     ///
@@ -60,6 +69,10 @@ impl ElementBuffer {
         self.data_type
     }
 
+    pub(crate) fn context(&self) -> &Context {
+        &self.context
+    }
+
     pub(crate) fn bind(&self) {
         self.context
             .bind_buffer(consts::ELEMENT_ARRAY_BUFFER, &self.id);
@@ -68,6 +81,11 @@ impl ElementBuffer {
 
 impl Drop for ElementBuffer {
     fn drop(&mut self) {
+        let bytes = self.bytes;
+        self.context.update_memory_stats(|stats| {
+            stats.element_buffers -= 1;
+            stats.element_buffer_bytes -= bytes;
+        });
         self.context.delete_buffer(&self.id);
     }
 }
@@ -0,0 +1,117 @@
+use crate::context::{consts, Context};
+use crate::core::Error;
+
+///
+/// Tests whether any of the fragments rendered between a call to [begin](OcclusionQuery::begin) and [end](OcclusionQuery::end)
+/// passes the depth test, ie. whether anything rendered is visible.
+/// The result is read back when it is available, usually one or more frames later, to avoid stalling the GPU.
+///
+pub struct OcclusionQuery {
+    context: Context,
+    id: crate::context::Query,
+    active: bool,
+    querying: bool,
+    pending: bool,
+    visible: Option<bool>,
+}
+
+impl OcclusionQuery {
+    ///
+    /// Creates a new occlusion query.
+    ///
+    pub fn new(context: &Context) -> Result<OcclusionQuery, Error> {
+        let id = context.create_query().ok_or_else(|| Error::QueryError {
+            message: "Failed to create query".to_string(),
+        })?;
+        Ok(OcclusionQuery {
+            context: context.clone(),
+            id,
+            active: false,
+            querying: false,
+            pending: false,
+            visible: None,
+        })
+    }
+
+    ///
+    /// Starts testing. Must be followed by a call to [end](OcclusionQuery::end).
+    /// If the result of the previous test is not yet available, the graphics commands until the call to [end](OcclusionQuery::end) are not tested,
+    /// since the query is still in use by the GPU, and [is_visible](OcclusionQuery::is_visible) returns the result of the previous test when it is available.
+    ///
+    pub fn begin(&mut self) -> Result<(), Error> {
+        if self.active {
+            Err(Error::QueryError {
+                message: "The occlusion query is already active.".to_string(),
+            })?;
+        }
+        self.poll();
+        if !self.pending {
+            self.context
+                .begin_query(consts::ANY_SAMPLES_PASSED, &self.id);
+            self.querying = true;
+        }
+        self.active = true;
+        Ok(())
+    }
+
+    ///
+    /// Stops testing.
+    ///
+    pub fn end(&mut self) -> Result<(), Error> {
+        if !self.active {
+            Err(Error::QueryError {
+                message: "The occlusion query is not active.".to_string(),
+            })?;
+        }
+        if self.querying {
+            self.context.end_query(consts::ANY_SAMPLES_PASSED);
+            self.querying = false;
+            self.pending = true;
+        }
+        self.active = false;
+        Ok(())
+    }
+
+    ///
+    /// Tests the graphics commands issued in the `test` closure.
+    /// Equivalent to calling [begin](OcclusionQuery::begin), the closure and then [end](OcclusionQuery::end).
+    ///
+    pub fn test<F: FnOnce() -> Result<(), Error>>(&mut self, test: F) -> Result<(), Error> {
+        self.begin()?;
+        let result = test();
+        self.end()?;
+        result
+    }
+
+    ///
+    /// Returns whether anything was visible in the latest test which result is available
+    /// or `None` if no result is available yet.
+    /// Never waits for the GPU to finish.
+    ///
+    pub fn is_visible(&mut self) -> Option<bool> {
+        self.poll();
+        self.visible
+    }
+
+    fn poll(&mut self) {
+        if self.pending
+            && self
+                .context
+                .get_query_parameter_u32(&self.id, consts::QUERY_RESULT_AVAILABLE)
+                != 0
+        {
+            self.visible = Some(
+                self.context
+                    .get_query_parameter_u32(&self.id, consts::QUERY_RESULT)
+                    != 0,
+            );
+            self.pending = false;
+        }
+    }
+}
+
+impl Drop for OcclusionQuery {
+    fn drop(&mut self) {
+        self.context.delete_query(&self.id);
+    }
+}
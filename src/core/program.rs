@@ -14,6 +14,7 @@ pub struct Program {
     context: Context,
     id: crate::context::Program,
     vertex_attributes: HashMap<String, u32>,
+    vertex_attribute_locations: Vec<u32>,
     textures: RefCell<HashMap<String, u32>>,
     uniforms: HashMap<String, crate::context::UniformLocation>,
     uniform_blocks: RefCell<HashMap<String, (u32, u32)>>,
@@ -28,22 +29,69 @@ impl Program {
         vertex_shader_source: &str,
         fragment_shader_source: &str,
     ) -> Result<Program, Error> {
-        let vert_shader = context
-            .create_shader(consts::VERTEX_SHADER)
-            .ok_or(ProgramError {
-                message: "Unable to create Vertex shader object".to_string(),
-            })?;
-        let frag_shader = context
-            .create_shader(consts::FRAGMENT_SHADER)
-            .ok_or(ProgramError {
-                message: "Unable to create Fragment shader object".to_string(),
-            })?;
-        context.compile_shader(vertex_shader_source, &vert_shader);
-        context.compile_shader(fragment_shader_source, &frag_shader);
+        Self::from_shaders(
+            context,
+            &[
+                (consts::VERTEX_SHADER, "Vertex", vertex_shader_source),
+                (consts::FRAGMENT_SHADER, "Fragment", fragment_shader_source),
+            ],
+            &[],
+        )
+    }
+
+    ///
+    /// Creates a new shader program from the given vertex and geometry glsl shader source, where the given outputs of the geometry shader
+    /// are captured with transform feedback, see [transform_feedback_points](Self::transform_feedback_points).
+    /// The program has no fragment shader, since nothing is rasterized when capturing.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn from_transform_feedback_source(
+        context: &Context,
+        vertex_shader_source: &str,
+        geometry_shader_source: &str,
+        varyings: &[&str],
+    ) -> Result<Program, Error> {
+        Self::from_shaders(
+            context,
+            &[
+                (consts::VERTEX_SHADER, "Vertex", vertex_shader_source),
+                (consts::GEOMETRY_SHADER, "Geometry", geometry_shader_source),
+            ],
+            varyings,
+        )
+    }
+
+    fn from_shaders(
+        context: &Context,
+        sources: &[(u32, &str, &str)],
+        #[allow(unused_variables)] transform_feedback_varyings: &[&str],
+    ) -> Result<Program, Error> {
+        let mut shaders = Vec::new();
+        for (shader_type, name, _) in sources {
+            shaders.push(
+                context
+                    .create_shader(*shader_type)
+                    .ok_or_else(|| ProgramError {
+                        message: format!("Unable to create {} shader object", name),
+                    })?,
+            );
+        }
+        for ((_, _, source), shader) in sources.iter().zip(shaders.iter()) {
+            context.compile_shader(source, shader);
+        }
 
         let id = context.create_program();
-        context.attach_shader(&id, &vert_shader);
-        context.attach_shader(&id, &frag_shader);
+        for shader in shaders.iter() {
+            context.attach_shader(&id, shader);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if !transform_feedback_varyings.is_empty() {
+            context.transform_feedback_varyings(
+                &id,
+                transform_feedback_varyings,
+                consts::INTERLEAVED_ATTRIBS,
+            );
+        }
         let success = context.link_program(&id);
 
         if !success {
@@ -51,28 +99,37 @@ impl Program {
             if let Some(log) = context.get_program_info_log(&id) {
                 message = format!("{}\nLink error: {}", message, log);
             }
-            if let Some(log) = context.get_shader_info_log(&vert_shader) {
-                message = format!("{}\nVertex shader error: {}", message, log);
-            }
-            if let Some(log) = context.get_shader_info_log(&frag_shader) {
-                message = format!("{}\nFragment shader error: {}", message, log);
+            for ((_, name, _), shader) in sources.iter().zip(shaders.iter()) {
+                if let Some(log) = context.get_shader_info_log(shader) {
+                    message = format!("{}\n{} shader error: {}", message, name, log);
+                }
             }
-            return Err(Error::ProgramError { message });
+            return Err(Error::ShaderCompile { message });
         }
 
-        context.detach_shader(&id, &vert_shader);
-        context.detach_shader(&id, &frag_shader);
-        context.delete_shader(Some(&vert_shader));
-        context.delete_shader(Some(&frag_shader));
+        for shader in shaders.iter() {
+            context.detach_shader(&id, shader);
+            context.delete_shader(Some(shader));
+        }
 
         // Init vertex attributes
         let num_attribs = context.get_program_parameter(&id, consts::ACTIVE_ATTRIBUTES);
         let mut vertex_attributes = HashMap::new();
+        let mut vertex_attribute_locations = Vec::new();
         for i in 0..num_attribs {
             let info = context.get_active_attrib(&id, i);
             let location = context.get_attrib_location(&id, &info.name()).unwrap();
             //println!("Attribute location: {}, name: {}, type: {}, size: {}", location, info.name(), info.type_(), info.size());
             vertex_attributes.insert(info.name(), location);
+            // A matrix attribute occupies one location per column
+            let column_count = if info.type_() == consts::FLOAT_MAT4 {
+                4
+            } else {
+                1
+            };
+            for column in 0..column_count {
+                vertex_attribute_locations.push(location + column);
+            }
         }
 
         // Init uniforms
@@ -91,12 +148,21 @@ impl Program {
             context: context.clone(),
             id,
             vertex_attributes,
+            vertex_attribute_locations,
             uniforms,
             uniform_blocks: RefCell::new(HashMap::new()),
             textures: RefCell::new(HashMap::new()),
         })
     }
 
+    ///
+    /// Sets a label on the shader program which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.context.set_program_label(&self.id, label);
+    }
+
     ///
     /// Send the given integer value to this shader program and associate it with the given named variable.
     /// The glsl shader variable must be of type `uniform int`, meaning it is uniformly available across all processing of vertices and fragments.
@@ -206,6 +272,9 @@ impl Program {
     /// The glsl shader variable must be of type `uniform sampler2D` and can only be accessed in the fragment shader.
     ///
     pub fn use_texture(&self, texture: &impl Texture, texture_name: &str) -> Result<(), Error> {
+        if let Some(context) = texture.context() {
+            self.check_context(context, "texture")?;
+        }
         let index = self.get_texture_index(texture_name);
         texture.bind(index);
         self.use_uniform_int(texture_name, &(index as i32))?;
@@ -221,6 +290,9 @@ impl Program {
         texture: &impl TextureArray,
         texture_name: &str,
     ) -> Result<(), Error> {
+        if let Some(context) = texture.context() {
+            self.check_context(context, "texture array")?;
+        }
         let index = self.get_texture_index(texture_name);
         texture.bind(index);
         self.use_uniform_int(texture_name, &(index as i32))?;
@@ -236,6 +308,9 @@ impl Program {
         texture: &impl TextureCube,
         texture_name: &str,
     ) -> Result<(), Error> {
+        if let Some(context) = texture.context() {
+            self.check_context(context, "texture cube")?;
+        }
         let index = self.get_texture_index(texture_name);
         texture.bind(index);
         self.use_uniform_int(texture_name, &(index as i32))?;
@@ -251,7 +326,30 @@ impl Program {
         self.textures.borrow().get(texture_name).unwrap().clone()
     }
 
-    pub fn use_uniform_block(&self, buffer: &UniformBuffer, block_name: &str) {
+    ///
+    /// Returns true if this program uses the vertex attribute with the given name.
+    ///
+    pub fn requires_attribute(&self, name: &str) -> bool {
+        self.vertex_attributes.contains_key(name)
+    }
+
+    ///
+    /// Returns true if this program uses the uniform variable with the given name.
+    ///
+    pub fn requires_uniform(&self, name: &str) -> bool {
+        self.uniforms.contains_key(name)
+    }
+
+    ///
+    /// Returns true if this program uses the uniform block with the given name.
+    ///
+    pub fn requires_uniform_block(&self, block_name: &str) -> bool {
+        self.uniform_blocks.borrow().contains_key(block_name)
+            || self.context.get_uniform_block_index(&self.id, block_name) != consts::INVALID_INDEX
+    }
+
+    pub fn use_uniform_block(&self, buffer: &UniformBuffer, block_name: &str) -> Result<(), Error> {
+        self.check_context(buffer.context(), "uniform buffer")?;
         if !self.uniform_blocks.borrow().contains_key(block_name) {
             let mut map = self.uniform_blocks.borrow_mut();
             let location = self.context.get_uniform_block_index(&self.id, block_name);
@@ -268,6 +366,7 @@ impl Program {
             .uniform_block_binding(&self.id, location, index);
         buffer.bind(index);
         self.context.unbind_buffer(consts::UNIFORM_BUFFER);
+        Ok(())
     }
 
     pub fn use_attribute(&self, buffer: &VertexBuffer, attribute_name: &str) -> Result<(), Error> {
@@ -282,6 +381,7 @@ impl Program {
         divisor: u32,
     ) -> Result<(), Error> {
         if buffer.count() > 0 {
+            self.check_context(buffer.context(), "vertex buffer")?;
             buffer.bind();
             let loc = self.location(&attribute_name)?;
             self.context.enable_vertex_attrib_array(loc);
@@ -310,6 +410,7 @@ impl Program {
         divisor: u32,
     ) -> Result<(), Error> {
         if buffer.count() > 0 {
+            self.check_context(buffer.context(), "vertex buffer")?;
             buffer.bind();
             let loc = self.location(&attribute_name)?;
             self.context.enable_vertex_attrib_array(loc);
@@ -338,6 +439,7 @@ impl Program {
         divisor: u32,
     ) -> Result<(), Error> {
         if buffer.count() > 0 {
+            self.check_context(buffer.context(), "vertex buffer")?;
             buffer.bind();
             let loc = self.location(&attribute_name)?;
             self.context.enable_vertex_attrib_array(loc);
@@ -366,6 +468,7 @@ impl Program {
         divisor: u32,
     ) -> Result<(), Error> {
         if buffer.count() > 0 {
+            self.check_context(buffer.context(), "vertex buffer")?;
             buffer.bind();
             let loc = self.location(&attribute_name)?;
             self.context.enable_vertex_attrib_array(loc);
@@ -378,22 +481,135 @@ impl Program {
         Ok(())
     }
 
+    ///
+    /// Uses the given [VertexBuffer](crate::VertexBuffer) data in this shader program (vertex shader) as the `mat4` attribute with the given name,
+    /// which means the buffer must contain 16 values per vertex, one matrix in column-major order.
+    /// Since a `mat4` attribute occupies four consecutive attribute locations, one per column, each column is bound to the next location.
+    ///
+    /// # Errors
+    /// Will return an error if the attribute is not defined in the shader code or not used.
+    /// In the latter case the variable is removed by the shader compiler.
+    ///
+    pub fn use_attribute_mat4(
+        &self,
+        buffer: &VertexBuffer,
+        attribute_name: &str,
+    ) -> Result<(), Error> {
+        self.use_attribute_mat4_divisor(buffer, attribute_name, 0)?;
+        Ok(())
+    }
+
+    ///
+    /// Uses the given [VertexBuffer](crate::VertexBuffer) data in this shader program (vertex shader) as the `mat4` attribute with the given name,
+    /// see [use_attribute_mat4](Self::use_attribute_mat4).
+    /// The divisor specifies how many instances are rendered with the same matrix,
+    /// ie. a divisor of 1 means one matrix per instance, which is useful for per instance transformations.
+    ///
+    pub fn use_attribute_mat4_divisor(
+        &self,
+        buffer: &VertexBuffer,
+        attribute_name: &str,
+        divisor: u32,
+    ) -> Result<(), Error> {
+        if buffer.count() > 0 {
+            self.check_context(buffer.context(), "vertex buffer")?;
+            buffer.bind();
+            let loc = self.location(attribute_name)?;
+            for column in 0..4 {
+                self.context.enable_vertex_attrib_array(loc + column);
+                self.context.vertex_attrib_pointer(
+                    loc + column,
+                    4,
+                    buffer.data_type(),
+                    false,
+                    16 * std::mem::size_of::<f32>() as u32,
+                    4 * column * std::mem::size_of::<f32>() as u32,
+                );
+                self.context.vertex_attrib_divisor(loc + column, divisor);
+            }
+            self.context.unbind_buffer(consts::ARRAY_BUFFER);
+            self.context.unuse_program();
+        }
+        Ok(())
+    }
+
     pub fn draw_arrays(
         &self,
         render_states: RenderStates,
         cull: CullType,
         viewport: Viewport,
         count: u32,
+    ) {
+        self.draw_arrays_with_primitive_type(
+            render_states,
+            cull,
+            viewport,
+            count,
+            PrimitiveType::Triangles,
+        );
+    }
+
+    ///
+    /// Same as [draw_arrays](Self::draw_arrays), except that the vertices are assembled into triangles as specified by the primitive type.
+    ///
+    pub fn draw_arrays_with_primitive_type(
+        &self,
+        render_states: RenderStates,
+        cull: CullType,
+        viewport: Viewport,
+        count: u32,
+        primitive_type: PrimitiveType,
     ) {
         Self::set_viewport(&self.context, viewport);
         Self::set_cull(&self.context, cull);
         Self::set_states(&self.context, render_states);
         self.set_used();
-        self.context.draw_arrays(consts::TRIANGLES, 0, count);
-        for location in self.vertex_attributes.values() {
+        self.context
+            .draw_arrays(primitive_mode(primitive_type), 0, count);
+        for location in self.vertex_attribute_locations.iter() {
+            self.context.disable_vertex_attrib_array(*location);
+        }
+        self.context.unuse_program();
+    }
+
+    ///
+    /// Runs the vertex and geometry shader of a [transform feedback program](Self::from_transform_feedback_source) on the given number of points
+    /// and captures the points emitted by the geometry shader in the output buffer, which must be large enough to hold all of them.
+    /// Returns the number of captured points, which waits for the GPU to finish the capture.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn transform_feedback_points(
+        &self,
+        output: &VertexBuffer,
+        count: u32,
+    ) -> Result<u32, Error> {
+        let query = self
+            .context
+            .create_query()
+            .ok_or_else(|| Error::QueryError {
+                message: "Failed to create query".to_string(),
+            })?;
+        self.set_used();
+        output.bind_as_transform_feedback(0);
+        self.context.enable(consts::RASTERIZER_DISCARD);
+        self.context
+            .begin_query(consts::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN, &query);
+        self.context.begin_transform_feedback(consts::POINTS);
+        self.context.draw_arrays(consts::POINTS, 0, count);
+        self.context.end_transform_feedback();
+        self.context
+            .end_query(consts::TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN);
+        self.context.disable(consts::RASTERIZER_DISCARD);
+        self.context.bind_transform_feedback_buffer(0, None);
+        for location in self.vertex_attribute_locations.iter() {
             self.context.disable_vertex_attrib_array(*location);
         }
         self.context.unuse_program();
+        let written = self
+            .context
+            .get_query_parameter_u32(&query, consts::QUERY_RESULT);
+        self.context.delete_query(&query);
+        Ok(written)
     }
 
     pub fn draw_arrays_instanced(
@@ -411,7 +627,7 @@ impl Program {
         self.context
             .draw_arrays_instanced(consts::TRIANGLES, 0, count, instance_count);
         self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
-        for location in self.vertex_attributes.values() {
+        for location in self.vertex_attribute_locations.iter() {
             self.context.disable_vertex_attrib_array(*location);
         }
         self.context.unuse_program();
@@ -423,7 +639,7 @@ impl Program {
         cull: CullType,
         viewport: Viewport,
         element_buffer: &ElementBuffer,
-    ) {
+    ) -> Result<(), Error> {
         self.draw_subset_of_elements(
             render_states,
             cull,
@@ -431,7 +647,7 @@ impl Program {
             element_buffer,
             0,
             element_buffer.count() as u32,
-        );
+        )
     }
 
     pub fn draw_subset_of_elements(
@@ -442,20 +658,98 @@ impl Program {
         element_buffer: &ElementBuffer,
         first: u32,
         count: u32,
-    ) {
+    ) -> Result<(), Error> {
+        self.draw_elements_internal(
+            render_states,
+            cull,
+            viewport,
+            element_buffer,
+            first..first + count,
+            PrimitiveType::Triangles,
+        )
+    }
+
+    ///
+    /// Same as [draw_subset_of_elements](Self::draw_subset_of_elements), except that the indices are assembled into primitives as specified by the primitive type,
+    /// see [draw_elements_with_primitive_type](Self::draw_elements_with_primitive_type).
+    ///
+    pub fn draw_subset_of_elements_with_primitive_type(
+        &self,
+        render_states: RenderStates,
+        cull: CullType,
+        viewport: Viewport,
+        element_buffer: &ElementBuffer,
+        elements: std::ops::Range<u32>,
+        primitive_type: PrimitiveType,
+    ) -> Result<(), Error> {
+        self.draw_elements_internal(
+            render_states,
+            cull,
+            viewport,
+            element_buffer,
+            elements,
+            primitive_type,
+        )
+    }
+
+    ///
+    /// Same as [draw_elements](Self::draw_elements), except that the indices are assembled into triangles as specified by the primitive type.
+    /// When drawing a [TriangleStrip](PrimitiveType::TriangleStrip), primitive restart is enabled,
+    /// ie. the strip is restarted at each index with the maximum value of the index type.
+    ///
+    pub fn draw_elements_with_primitive_type(
+        &self,
+        render_states: RenderStates,
+        cull: CullType,
+        viewport: Viewport,
+        element_buffer: &ElementBuffer,
+        primitive_type: PrimitiveType,
+    ) -> Result<(), Error> {
+        self.draw_elements_internal(
+            render_states,
+            cull,
+            viewport,
+            element_buffer,
+            0..element_buffer.count() as u32,
+            primitive_type,
+        )
+    }
+
+    fn draw_elements_internal(
+        &self,
+        render_states: RenderStates,
+        cull: CullType,
+        viewport: Viewport,
+        element_buffer: &ElementBuffer,
+        elements: std::ops::Range<u32>,
+        primitive_type: PrimitiveType,
+    ) -> Result<(), Error> {
+        self.check_context(element_buffer.context(), "element buffer")?;
         Self::set_viewport(&self.context, viewport);
         Self::set_cull(&self.context, cull);
         Self::set_states(&self.context, render_states);
         self.set_used();
         element_buffer.bind();
-        self.context
-            .draw_elements(consts::TRIANGLES, count, element_buffer.data_type(), first);
+        let restart = primitive_type == PrimitiveType::TriangleStrip;
+        if restart {
+            self.context.set_primitive_restart(true);
+        }
+        self.context.draw_elements(
+            primitive_mode(primitive_type),
+            elements.end - elements.start,
+            element_buffer.data_type(),
+            elements.start,
+        );
+        if restart {
+            self.context.set_primitive_restart(false);
+        }
         self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
 
-        for location in self.vertex_attributes.values() {
+        for location in self.vertex_attribute_locations.iter() {
             self.context.disable_vertex_attrib_array(*location);
         }
         self.context.unuse_program();
+        Ok(())
     }
 
     pub fn draw_elements_instanced(
@@ -465,7 +759,8 @@ impl Program {
         viewport: Viewport,
         element_buffer: &ElementBuffer,
         count: u32,
-    ) {
+    ) -> Result<(), Error> {
+        self.check_context(element_buffer.context(), "element buffer")?;
         Self::set_viewport(&self.context, viewport);
         Self::set_cull(&self.context, cull);
         Self::set_states(&self.context, render_states);
@@ -479,10 +774,11 @@ impl Program {
             count,
         );
         self.context.unbind_buffer(consts::ELEMENT_ARRAY_BUFFER);
-        for location in self.vertex_attributes.values() {
+        for location in self.vertex_attribute_locations.iter() {
             self.context.disable_vertex_attrib_array(*location);
         }
         self.context.unuse_program();
+        Ok(())
     }
 
     fn location(&self, name: &str) -> Result<u32, Error> {
@@ -490,13 +786,22 @@ impl Program {
         let location = self
             .vertex_attributes
             .get(name)
-            .ok_or_else(|| ProgramError {
+            .ok_or_else(|| Error::MissingAttribute {
+                name: name.to_string(),
+            })?;
+        Ok(*location)
+    }
+
+    fn check_context(&self, context: &Context, resource: &str) -> Result<(), Error> {
+        if !self.context.is_same_context(context) {
+            Err(Error::ContextMismatch {
                 message: format!(
-                    "The attribute {} is sent to the shader but it is never used.",
-                    name
+                    "The {} was created with another context than the program it is used with",
+                    resource
                 ),
             })?;
-        Ok(*location)
+        }
+        Ok(())
     }
 
     fn set_used(&self) {
@@ -511,58 +816,121 @@ impl Program {
             render_states.write_mask.depth,
         );
         Self::set_blend(context, render_states.blend);
+        Self::set_polygon_mode(context, render_states.polygon_mode);
+        Self::set_line_width(context, render_states.line_width.unwrap_or(1.0));
+        Self::set_front_face(context, render_states.front_face);
+        Self::set_depth_clamp(context, render_states.depth_clamp);
+    }
+
+    #[allow(unused_variables)]
+    fn set_depth_clamp(context: &Context, depth_clamp: bool) {
+        context.set_cached_state(
+            |state| &mut state.depth_clamp,
+            depth_clamp,
+            |depth_clamp| {
+                #[cfg(not(target_arch = "wasm32"))]
+                if depth_clamp {
+                    context.enable(consts::DEPTH_CLAMP);
+                } else {
+                    context.disable(consts::DEPTH_CLAMP);
+                }
+            },
+        );
+    }
+
+    fn set_front_face(context: &Context, front_face: FrontFace) {
+        context.set_cached_state(
+            |state| &mut state.front_face,
+            front_face,
+            |front_face| {
+                context.front_face(match front_face {
+                    FrontFace::Ccw => consts::CCW,
+                    FrontFace::Cw => consts::CW,
+                })
+            },
+        );
+    }
+
+    fn set_line_width(context: &Context, line_width: f32) {
+        context.set_cached_state(
+            |state| &mut state.line_width,
+            line_width,
+            |line_width| {
+                let (min, max) = context.get_aliased_line_width_range();
+                context.line_width(line_width.max(min).min(max));
+            },
+        );
+    }
+
+    #[allow(unused_variables)]
+    fn set_polygon_mode(context: &Context, polygon_mode: PolygonMode) {
+        context.set_cached_state(
+            |state| &mut state.polygon_mode,
+            polygon_mode,
+            |polygon_mode| {
+                #[cfg(not(target_arch = "wasm32"))]
+                context.polygon_mode(match polygon_mode {
+                    PolygonMode::Fill => consts::FILL,
+                    PolygonMode::Line => consts::LINE,
+                    PolygonMode::Point => consts::POINT,
+                });
+            },
+        );
     }
 
     fn set_viewport(context: &Context, viewport: Viewport) {
-        unsafe {
-            static mut CURRENT_VIEWPORT: Viewport = Viewport {
-                x: 0,
-                y: 0,
-                width: 0,
-                height: 0,
-            };
-            if viewport != CURRENT_VIEWPORT {
+        context.set_cached_state(
+            |state| &mut state.viewport,
+            viewport,
+            |viewport| {
                 context.viewport(
                     viewport.x,
                     viewport.y,
                     viewport.width as i32,
                     viewport.height as i32,
-                );
-                CURRENT_VIEWPORT = viewport;
-            }
-        }
+                )
+            },
+        );
+    }
+
+    ///
+    /// Overrides the cull type given to all draw calls on the given context until the override is cleared with `None`.
+    /// Used when rendering user provided geometry with a specific cull type, for example when capturing imposters.
+    ///
+    pub(crate) fn set_cull_override(context: &Context, cull: Option<CullType>) {
+        context.state_cache.borrow_mut().cull_override = cull;
     }
 
     fn set_cull(context: &Context, cull: CullType) {
-        unsafe {
-            static mut CURRENT_CULL: CullType = CullType::None;
-            if cull != CURRENT_CULL {
-                match cull {
-                    CullType::None => {
-                        context.disable(consts::CULL_FACE);
-                    }
-                    CullType::Back => {
-                        context.enable(consts::CULL_FACE);
-                        context.cull_face(consts::BACK);
-                    }
-                    CullType::Front => {
-                        context.enable(consts::CULL_FACE);
-                        context.cull_face(consts::FRONT);
-                    }
-                    CullType::FrontAndBack => {
-                        context.enable(consts::CULL_FACE);
-                        context.cull_face(consts::FRONT_AND_BACK);
-                    }
+        let cull = context.state_cache.borrow().cull_override.unwrap_or(cull);
+        context.set_cached_state(
+            |state| &mut state.cull,
+            cull,
+            |cull| match cull {
+                CullType::None => {
+                    context.disable(consts::CULL_FACE);
                 }
-                CURRENT_CULL = cull;
-            }
-        }
+                CullType::Back => {
+                    context.enable(consts::CULL_FACE);
+                    context.cull_face(consts::BACK);
+                }
+                CullType::Front => {
+                    context.enable(consts::CULL_FACE);
+                    context.cull_face(consts::FRONT);
+                }
+                CullType::FrontAndBack => {
+                    context.enable(consts::CULL_FACE);
+                    context.cull_face(consts::FRONT_AND_BACK);
+                }
+            },
+        );
     }
 
     fn set_blend(context: &Context, blend: Option<BlendParameters>) {
-        unsafe {
-            static mut CURRENT: Option<BlendParameters> = None;
-            if blend != CURRENT {
+        context.set_cached_state(
+            |state| &mut state.blend,
+            blend,
+            |blend| {
                 if let Some(blend_parameters) = blend {
                     context.enable(consts::BLEND);
                     context.blend_func_separate(
@@ -582,9 +950,8 @@ impl Program {
                 } else {
                     context.disable(consts::BLEND);
                 }
-                CURRENT = blend;
-            }
-        }
+            },
+        );
     }
 
     fn blend_const_from_multiplier(multiplier: BlendMultiplierType) -> u32 {
@@ -614,9 +981,10 @@ impl Program {
     }
 
     pub(crate) fn set_write_mask(context: &Context, write_mask: WriteMask) {
-        unsafe {
-            static mut CURRENT_COLOR_MASK: WriteMask = WriteMask::COLOR_AND_DEPTH;
-            if write_mask != CURRENT_COLOR_MASK {
+        context.set_cached_state(
+            |state| &mut state.write_mask,
+            write_mask,
+            |write_mask| {
                 context.color_mask(
                     write_mask.red,
                     write_mask.green,
@@ -624,64 +992,35 @@ impl Program {
                     write_mask.alpha,
                 );
                 Self::set_depth(context, None, write_mask.depth);
-                CURRENT_COLOR_MASK = write_mask;
-            }
-        }
+            },
+        );
     }
 
     fn set_depth(context: &Context, depth_test: Option<DepthTestType>, depth_mask: bool) {
-        unsafe {
-            static mut CURRENT_DEPTH_ENABLE: bool = false;
-            static mut CURRENT_DEPTH_MASK: bool = true;
-            static mut CURRENT_DEPTH_TEST: DepthTestType = DepthTestType::Less;
-
-            if depth_mask == false && depth_test == Some(DepthTestType::Always) {
-                if CURRENT_DEPTH_ENABLE {
-                    context.disable(consts::DEPTH_TEST);
-                    CURRENT_DEPTH_ENABLE = false;
-                    return;
-                }
-            } else {
-                if !CURRENT_DEPTH_ENABLE {
-                    context.enable(consts::DEPTH_TEST);
-                    CURRENT_DEPTH_ENABLE = true;
-                }
-            }
-
-            if depth_mask != CURRENT_DEPTH_MASK {
-                context.depth_mask(depth_mask);
-                CURRENT_DEPTH_MASK = depth_mask;
-            }
-
-            if depth_test.is_some() && depth_test.unwrap() != CURRENT_DEPTH_TEST {
-                match depth_test.unwrap() {
-                    DepthTestType::Never => {
-                        context.depth_func(consts::NEVER);
-                    }
-                    DepthTestType::Less => {
-                        context.depth_func(consts::LESS);
-                    }
-                    DepthTestType::Equal => {
-                        context.depth_func(consts::EQUAL);
-                    }
-                    DepthTestType::LessOrEqual => {
-                        context.depth_func(consts::LEQUAL);
-                    }
-                    DepthTestType::Greater => {
-                        context.depth_func(consts::GREATER);
-                    }
-                    DepthTestType::NotEqual => {
-                        context.depth_func(consts::NOTEQUAL);
-                    }
-                    DepthTestType::GreaterOrEqual => {
-                        context.depth_func(consts::GEQUAL);
-                    }
-                    DepthTestType::Always => {
-                        context.depth_func(consts::ALWAYS);
-                    }
-                }
-                CURRENT_DEPTH_TEST = depth_test.unwrap();
-            }
+        if !depth_mask && depth_test == Some(DepthTestType::Always) {
+            context.set_cached_state(
+                |state| &mut state.depth_enable,
+                false,
+                |_| context.disable(consts::DEPTH_TEST),
+            );
+            return;
+        }
+        context.set_cached_state(
+            |state| &mut state.depth_enable,
+            true,
+            |_| context.enable(consts::DEPTH_TEST),
+        );
+        context.set_cached_state(
+            |state| &mut state.depth_mask,
+            depth_mask,
+            |depth_mask| context.depth_mask(depth_mask),
+        );
+        if let Some(depth_test) = depth_test {
+            context.set_cached_state(
+                |state| &mut state.depth_test,
+                depth_test,
+                |depth_test| context.depth_func(depth_func_from(depth_test)),
+            );
         }
     }
 }
@@ -691,3 +1030,42 @@ impl Drop for Program {
         self.context.delete_program(&self.id);
     }
 }
+
+///
+/// Shader programs which are created internally, for example by [Mesh](crate::Mesh), and cached for each context and key, see [get_or_insert_cached_program].
+///
+pub(crate) type ProgramCache<P> = Option<HashMap<usize, HashMap<String, P>>>;
+
+///
+/// Returns the program with the given key from the given cache and creates it using `create` the first time it is requested for the given context.
+/// The programs are cached per context, since a program can only be used with the context it is created with.
+///
+/// # Safety
+/// The cache must only be used from one thread, like the `static mut` caches of the mesh types,
+/// and it must not be cleared while the returned program is in use.
+///
+// The entry API requires an owned key, which would allocate a string each time a cached program is used
+#[allow(clippy::map_entry)]
+pub(crate) unsafe fn get_or_insert_cached_program<'a, P>(
+    cache: *mut ProgramCache<P>,
+    context: &Context,
+    key: &str,
+    create: impl FnOnce() -> Result<P, Error>,
+) -> Result<&'a P, Error> {
+    let programs = (*cache)
+        .get_or_insert_with(HashMap::new)
+        .entry(context.id())
+        .or_default();
+    if !programs.contains_key(key) {
+        programs.insert(key.to_owned(), create()?);
+    }
+    Ok(&programs[key])
+}
+
+fn primitive_mode(primitive_type: PrimitiveType) -> u32 {
+    match primitive_type {
+        PrimitiveType::Triangles => consts::TRIANGLES,
+        PrimitiveType::TriangleStrip => consts::TRIANGLE_STRIP,
+        PrimitiveType::Points => consts::POINTS,
+    }
+}
@@ -0,0 +1,350 @@
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// The id of a transient texture in a [RenderGraph].
+///
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RenderGraphTextureId(usize);
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum TextureDescription {
+    Color {
+        width: u32,
+        height: u32,
+        format: Format,
+    },
+    Depth {
+        width: u32,
+        height: u32,
+        format: DepthFormat,
+    },
+}
+
+enum PooledTexture {
+    Color(ColorTargetTexture2D<f32>),
+    Depth(DepthTargetTexture2D),
+}
+
+type PassExecution<'a> = Box<dyn FnOnce(&RenderGraphTextures) -> Result<(), Error> + 'a>;
+
+struct RenderPass<'a> {
+    name: String,
+    reads: Vec<RenderGraphTextureId>,
+    writes: Vec<RenderGraphTextureId>,
+    execute: PassExecution<'a>,
+}
+
+///
+/// Gives access to the transient textures of a [RenderGraph] inside a render pass.
+///
+pub struct RenderGraphTextures<'a> {
+    pool: &'a [(TextureDescription, PooledTexture)],
+    assignments: &'a [Option<usize>],
+}
+
+impl<'a> RenderGraphTextures<'a> {
+    ///
+    /// Returns the color texture with the given id.
+    ///
+    /// # Errors
+    /// Returns an error if the texture is a depth texture or if the pass did not declare that it reads or writes the texture.
+    ///
+    pub fn color_texture(
+        &self,
+        id: RenderGraphTextureId,
+    ) -> Result<&'a ColorTargetTexture2D<f32>, Error> {
+        match self.texture(id)? {
+            PooledTexture::Color(texture) => Ok(texture),
+            PooledTexture::Depth(_) => Err(Error::RenderTargetError {
+                message: format!("The render graph texture {} is not a color texture", id.0),
+            }),
+        }
+    }
+
+    ///
+    /// Returns the depth texture with the given id.
+    ///
+    /// # Errors
+    /// Returns an error if the texture is a color texture or if the pass did not declare that it reads or writes the texture.
+    ///
+    pub fn depth_texture(
+        &self,
+        id: RenderGraphTextureId,
+    ) -> Result<&'a DepthTargetTexture2D, Error> {
+        match self.texture(id)? {
+            PooledTexture::Depth(texture) => Ok(texture),
+            PooledTexture::Color(_) => Err(Error::RenderTargetError {
+                message: format!("The render graph texture {} is not a depth texture", id.0),
+            }),
+        }
+    }
+
+    fn texture(&self, id: RenderGraphTextureId) -> Result<&'a PooledTexture, Error> {
+        let index = self
+            .assignments
+            .get(id.0)
+            .copied()
+            .flatten()
+            .ok_or_else(|| Error::RenderTargetError {
+                message: format!(
+                    "The render graph texture {} is not read or written by the current pass",
+                    id.0
+                ),
+            })?;
+        Ok(&self.pool[index].1)
+    }
+}
+
+///
+/// A lightweight helper for organizing the render passes of a frame, for example a shadow pass followed by the main pass and a tone mapping pass.
+/// Each pass declares which transient textures it reads and writes and the graph executes the passes in an order
+/// where a texture is always written before it is read.
+/// The transient textures are created when needed and reused by later passes, also in the following frames, when they are no longer used.
+/// Passes which declare no dependencies are executed in the order they are added.
+///
+/// ```no_run
+/// # use three_d::*;
+/// # let context: Context = unimplemented!();
+/// # let viewport = Viewport::new_at_origo(1, 1);
+/// let mut graph = RenderGraph::new(&context);
+/// let shadow_map = graph.create_depth_texture(1024, 1024, DepthFormat::Depth32F);
+/// let hdr_color = graph.create_color_texture(viewport.width, viewport.height, Format::RGBA);
+/// let depth = graph.create_depth_texture(viewport.width, viewport.height, DepthFormat::Depth32F);
+///
+/// graph.add_pass("tone mapping", &[hdr_color], &[], |textures| {
+///     let hdr_color = textures.color_texture(hdr_color)?;
+///     Screen::write(&context, ClearState::default(), || {
+///         // Tone map the hdr color texture to the screen
+///         Ok(())
+///     })
+/// });
+/// graph.add_pass("main", &[shadow_map], &[hdr_color, depth], |textures| {
+///     let render_target = RenderTarget::new(&context, textures.color_texture(hdr_color)?, textures.depth_texture(depth)?)?;
+///     render_target.write(ClearState::default(), || {
+///         // Render the scene using the shadow map
+///         Ok(())
+///     })
+/// });
+/// graph.add_pass("shadow", &[], &[shadow_map], |textures| {
+///     textures.depth_texture(shadow_map)?.write(Some(1.0), || {
+///         // Render the depth of the scene seen from the light
+///         Ok(())
+///     })
+/// });
+///
+/// // Executes the shadow pass, then the main pass and finally the tone mapping pass
+/// graph.execute()?;
+/// # Ok::<(), Error>(())
+/// ```
+///
+pub struct RenderGraph<'a> {
+    context: Context,
+    descriptions: Vec<TextureDescription>,
+    passes: Vec<RenderPass<'a>>,
+    pool: Vec<(TextureDescription, PooledTexture)>,
+}
+
+impl<'a> RenderGraph<'a> {
+    ///
+    /// Creates a new empty render graph.
+    ///
+    pub fn new(context: &Context) -> Self {
+        Self {
+            context: context.clone(),
+            descriptions: Vec::new(),
+            passes: Vec::new(),
+            pool: Vec::new(),
+        }
+    }
+
+    ///
+    /// Declares a transient color texture with `f32` data, the given size and format.
+    /// The texture is created when the first pass using it is executed.
+    ///
+    pub fn create_color_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Format,
+    ) -> RenderGraphTextureId {
+        self.descriptions.push(TextureDescription::Color {
+            width,
+            height,
+            format,
+        });
+        RenderGraphTextureId(self.descriptions.len() - 1)
+    }
+
+    ///
+    /// Declares a transient depth texture with the given size and format.
+    /// The texture is created when the first pass using it is executed.
+    ///
+    pub fn create_depth_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: DepthFormat,
+    ) -> RenderGraphTextureId {
+        self.descriptions.push(TextureDescription::Depth {
+            width,
+            height,
+            format,
+        });
+        RenderGraphTextureId(self.descriptions.len() - 1)
+    }
+
+    ///
+    /// Adds a render pass which reads and writes the given transient textures.
+    /// The textures are accessed in the `execute` closure through the given [RenderGraphTextures].
+    ///
+    pub fn add_pass(
+        &mut self,
+        name: &str,
+        reads: &[RenderGraphTextureId],
+        writes: &[RenderGraphTextureId],
+        execute: impl FnOnce(&RenderGraphTextures) -> Result<(), Error> + 'a,
+    ) {
+        self.passes.push(RenderPass {
+            name: name.to_string(),
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+            execute: Box::new(execute),
+        });
+    }
+
+    ///
+    /// Executes all added passes in an order where each texture is written before it is read.
+    /// Afterwards the passes and texture declarations are removed, but the created textures are kept for reuse,
+    /// so the same graph can be set up again the next frame without creating new textures.
+    ///
+    /// # Errors
+    /// Returns an error if the passes depend on each other in a cycle, if a pass uses an undeclared texture or if any of the passes fails.
+    ///
+    pub fn execute(&mut self) -> Result<(), Error> {
+        let passes = std::mem::take(&mut self.passes);
+        let descriptions = std::mem::take(&mut self.descriptions);
+        for pass in passes.iter() {
+            for id in pass.reads.iter().chain(pass.writes.iter()) {
+                if id.0 >= descriptions.len() {
+                    Err(Error::RenderTargetError {
+                        message: format!(
+                            "The render pass {} uses the undeclared texture {}",
+                            pass.name, id.0
+                        ),
+                    })?;
+                }
+            }
+        }
+        let order = Self::sort(&passes)?;
+
+        // The position in the execution order of the last pass using each texture
+        let mut last_use = vec![None; descriptions.len()];
+        for (position, pass_index) in order.iter().enumerate() {
+            let pass = &passes[*pass_index];
+            for id in pass.reads.iter().chain(pass.writes.iter()) {
+                last_use[id.0] = Some(position);
+            }
+        }
+
+        let mut passes: Vec<Option<RenderPass>> = passes.into_iter().map(Some).collect();
+        let mut assignments: Vec<Option<usize>> = vec![None; descriptions.len()];
+        let mut in_use = vec![false; self.pool.len()];
+        for (position, pass_index) in order.into_iter().enumerate() {
+            let pass = passes[pass_index].take().unwrap();
+            for id in pass.reads.iter().chain(pass.writes.iter()) {
+                if assignments[id.0].is_none() {
+                    let index = self.acquire(descriptions[id.0], &mut in_use)?;
+                    assignments[id.0] = Some(index);
+                }
+            }
+            (pass.execute)(&RenderGraphTextures {
+                pool: &self.pool,
+                assignments: &assignments,
+            })?;
+            for (id, last) in last_use.iter().enumerate() {
+                if *last == Some(position) {
+                    in_use[assignments[id].unwrap()] = false;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn acquire(
+        &mut self,
+        description: TextureDescription,
+        in_use: &mut Vec<bool>,
+    ) -> Result<usize, Error> {
+        if let Some(index) = self
+            .pool
+            .iter()
+            .enumerate()
+            .position(|(i, (d, _))| !in_use[i] && *d == description)
+        {
+            in_use[index] = true;
+            return Ok(index);
+        }
+        let texture = match description {
+            TextureDescription::Color {
+                width,
+                height,
+                format,
+            } => PooledTexture::Color(ColorTargetTexture2D::new(
+                &self.context,
+                width,
+                height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                format,
+            )?),
+            TextureDescription::Depth {
+                width,
+                height,
+                format,
+            } => PooledTexture::Depth(DepthTargetTexture2D::new(
+                &self.context,
+                width,
+                height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                format,
+            )?),
+        };
+        self.pool.push((description, texture));
+        in_use.push(true);
+        Ok(self.pool.len() - 1)
+    }
+
+    fn sort(passes: &[RenderPass]) -> Result<Vec<usize>, Error> {
+        // A pass depends on all other passes writing a texture which it reads
+        let dependencies: Vec<Vec<usize>> = passes
+            .iter()
+            .map(|pass| {
+                (0..passes.len())
+                    .filter(|other| {
+                        passes[*other]
+                            .writes
+                            .iter()
+                            .any(|id| pass.reads.contains(id) && !pass.writes.contains(id))
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut order = Vec::with_capacity(passes.len());
+        let mut done = vec![false; passes.len()];
+        while order.len() < passes.len() {
+            let next = (0..passes.len())
+                .find(|i| !done[*i] && dependencies[*i].iter().all(|d| done[*d]))
+                .ok_or_else(|| Error::RenderTargetError {
+                    message: "The render passes depend on each other in a cycle".to_string(),
+                })?;
+            done[next] = true;
+            order.push(next);
+        }
+        Ok(order)
+    }
+}
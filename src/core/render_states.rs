@@ -22,6 +22,37 @@ pub struct RenderStates {
     /// This is usually used to simulate transparency.
     ///
     pub blend: Option<BlendParameters>,
+
+    ///
+    /// Defines how the triangles are rasterized, ie. whether they are filled or only their edges or corners are drawn.
+    ///
+    pub polygon_mode: PolygonMode,
+
+    ///
+    /// The width in pixels of the lines drawn in a render call, ie. when the [polygon_mode](Self::polygon_mode) is [PolygonMode::Line]
+    /// or when drawing line primitives. `None` means the default width of 1 pixel.
+    /// The width is clamped to the range of line widths supported by the graphics driver (`GL_ALIASED_LINE_WIDTH_RANGE`).
+    ///
+    /// **Note:** Most drivers, and in particular all drivers on web and on OpenGL core profiles on macOS, only support a line width of 1,
+    /// so this is only reliable for simple debug lines. Draw the lines as instanced quads or thin cylinders, for example using
+    /// [InstancedMesh](crate::InstancedMesh), to get a reliable line thickness.
+    ///
+    pub line_width: Option<f32>,
+
+    ///
+    /// Defines the winding order of the vertices of front facing triangles, which determines which triangles are skipped when culling.
+    /// [Mesh](crate::Mesh) automatically flips the winding order when its transformation is mirrored (has a negative determinant).
+    ///
+    pub front_face: FrontFace,
+
+    ///
+    /// Whether fragments in front of the near plane or behind the far plane are clamped to the depth range instead of being clipped.
+    /// This is for example useful when rendering shadow maps, where shadow casters outside the depth range of the light should still cast shadows.
+    /// Default is false.
+    ///
+    /// **Note:** Depth clamping is not available on web (WebGL 2 is based on OpenGL ES which does not support depth clamping), so it is ignored there.
+    ///
+    pub depth_clamp: bool,
 }
 
 impl Default for RenderStates {
@@ -30,13 +61,67 @@ impl Default for RenderStates {
             write_mask: WriteMask::default(),
             depth_test: DepthTestType::Less,
             blend: None,
+            polygon_mode: PolygonMode::Fill,
+            line_width: None,
+            front_face: FrontFace::Ccw,
+            depth_clamp: false,
         }
     }
 }
 
+///
+/// Defines how triangles are rasterized in a render call, see [RenderStates::polygon_mode].
+/// This is a simple way to draw a wireframe for debugging, see [Mesh::render_with_wireframe](crate::Mesh::render_with_wireframe) for a nicer wireframe.
+///
+/// **Note:** Only [PolygonMode::Fill] is supported on web (WebGL 2 is based on OpenGL ES which does not support other polygon modes),
+/// so the other modes are ignored there.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PolygonMode {
+    ///
+    /// The triangles are filled.
+    ///
+    #[default]
+    Fill,
+    ///
+    /// Only the edges of the triangles are drawn as lines.
+    ///
+    Line,
+    ///
+    /// Only the corners of the triangles are drawn as points.
+    ///
+    Point,
+}
+
+///
+/// Defines how the vertices (or indices) of a draw call are assembled into triangles or points.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum PrimitiveType {
+    ///
+    /// Every three vertices form a separate triangle.
+    ///
+    #[default]
+    Triangles,
+    ///
+    /// Every vertex after the first two forms a triangle with the two previous vertices.
+    /// When drawing with indices, the strip can be restarted by inserting the maximum value of the index type
+    /// (255 for `u8`, 65535 for `u16` and 4294967295 for `u32` indices), see [Indices::primitive_restart_index](crate::Indices::primitive_restart_index).
+    ///
+    TriangleStrip,
+    ///
+    /// Every vertex is rendered as a point, where the size in pixels is given by `gl_PointSize` in the vertex shader,
+    /// see also [PointCloud](crate::PointCloud) for rendering round points.
+    ///
+    Points,
+}
+
 ///
 /// Defines whether the triangles that are backfacing, frontfacing or both should be skipped in a render call.
 ///
+/// The meshes, for example [Mesh](crate::Mesh) and [InstancedMesh](crate::InstancedMesh), apply their `cull` field in all render calls
+/// and default to [CullType::None], ie. double-sided rendering, to support for example leaves and cloth.
+///
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum CullType {
     None,
@@ -45,6 +130,34 @@ pub enum CullType {
     FrontAndBack,
 }
 
+///
+/// Defines the winding order of the vertices of a front facing triangle when seen from the camera, see [RenderStates::front_face].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FrontFace {
+    ///
+    /// Triangles with counter-clockwise winding order are front facing.
+    ///
+    #[default]
+    Ccw,
+    ///
+    /// Triangles with clockwise winding order are front facing.
+    ///
+    Cw,
+}
+
+impl FrontFace {
+    ///
+    /// Returns the opposite winding order.
+    ///
+    pub fn flipped(self) -> Self {
+        match self {
+            FrontFace::Ccw => FrontFace::Cw,
+            FrontFace::Cw => FrontFace::Ccw,
+        }
+    }
+}
+
 ///
 /// Determines whether or not a fragment/pixel from the current render call should be discarded
 /// when comparing its depth with the depth of the current fragment/pixel.
@@ -159,6 +272,19 @@ impl BlendParameters {
         alpha_equation: BlendEquationType::Add,
     };
 
+    ///
+    /// Transparency blending parameters for output colors which are already multiplied by the alpha value,
+    /// for example when sampling a texture with [premultiplied alpha](crate::CPUTexture::premultiply_alpha).
+    ///
+    pub const PREMULTIPLIED_ALPHA: Self = Self {
+        source_rgb_multiplier: BlendMultiplierType::One,
+        source_alpha_multiplier: BlendMultiplierType::One,
+        destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+        destination_alpha_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+        rgb_equation: BlendEquationType::Add,
+        alpha_equation: BlendEquationType::Add,
+    };
+
     ///
     /// Adds the color of the render target with the output color of the render call.
     ///
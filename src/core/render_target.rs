@@ -4,11 +4,35 @@ use crate::definition::*;
 use crate::math::*;
 use crate::ImageEffect;
 
+///
+/// The color space of the color channels of a [ClearState].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    ///
+    /// The color is in gamma color space (sRGB), ie. the color is specified as it is seen on the screen or picked in an image editor.
+    ///
+    #[default]
+    Srgb,
+    ///
+    /// The color is in linear color space, ie. the color is specified as the light intensity used in the lighting calculations.
+    ///
+    Linear,
+}
+
 ///
 /// Defines which channels (red, green, blue, alpha and depth) to clear when starting to write to a
 /// [render target](crate::RenderTarget) or the [screen](crate::Screen).
 /// If `None` then the channel is not cleared and if `Some(value)` the channel is cleared to that value (the value must be between 0 and 1).
 ///
+/// The red, green and blue values are in gamma color space (sRGB), use [with_color_space](Self::with_color_space) to specify them in linear color space instead.
+/// The screen and textures with a linear format like [Format::RGBA] store the colors in gamma color space, since that is what the shaders write,
+/// so the values are used as they are. Textures with an sRGB format ([Format::SRGB] or [Format::SRGBA] with `u8` data)
+/// are encoded by the graphics hardware when written to, so the values are converted to linear color space to avoid encoding them twice.
+/// Texture arrays and cube maps are always treated as having a linear format.
+///
+/// The alpha channel and the depth are never converted.
+///
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct ClearState {
     /// Defines the clear value for the red channel.
@@ -77,6 +101,24 @@ impl ClearState {
     }
 }
 
+impl ClearState {
+    ///
+    /// Returns the same clear state, except that the red, green and blue values are interpreted in the given color space.
+    /// Linear values are converted to gamma color space (sRGB), so the red, green and blue fields of the returned clear state are always in gamma color space.
+    ///
+    pub fn with_color_space(self, color_space: ColorSpace) -> Self {
+        match color_space {
+            ColorSpace::Srgb => self,
+            ColorSpace::Linear => Self {
+                red: self.red.map(linear_to_srgb),
+                green: self.green.map(linear_to_srgb),
+                blue: self.blue.map(linear_to_srgb),
+                ..self
+            },
+        }
+    }
+}
+
 impl Default for ClearState {
     fn default() -> Self {
         Self::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0)
@@ -93,14 +135,16 @@ impl Screen {
     /// Call this function and make a render call (for example on some [object](crate::object))
     /// in the `render` closure to render something to the screen.
     /// Before writing, the screen is cleared based on the given clear state.
+    /// Returns [Error::ContextLost] if the graphics context has been lost, see [Error::ContextLost] for how to recover.
     ///
     pub fn write<F: FnOnce() -> Result<(), Error>>(
         context: &Context,
         clear_state: ClearState,
         render: F,
     ) -> Result<(), Error> {
+        check_context_lost(context)?;
         context.bind_framebuffer(consts::DRAW_FRAMEBUFFER, None);
-        clear(context, &clear_state);
+        clear(context, &clear_state, false);
         render()?;
         Ok(())
     }
@@ -197,6 +241,7 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
         clear_state: ClearState,
         render: F,
     ) -> Result<(), Error> {
+        check_context_lost(&self.context)?;
         self.bind(consts::DRAW_FRAMEBUFFER)?;
         clear(
             &self.context,
@@ -207,6 +252,9 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
                 alpha: self.color_texture.and(clear_state.alpha),
                 depth: self.depth_texture.and(clear_state.depth),
             },
+            self.color_texture
+                .map(|color_texture| color_texture.is_srgb())
+                .unwrap_or(false),
         );
         render()?;
         if let Some(color_texture) = self.color_texture {
@@ -215,6 +263,51 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
         Ok(())
     }
 
+    ///
+    /// Renders whatever rendered in the `render` closure into the given mip level of the color texture and into the depth texture.
+    /// The `render` closure is given a viewport with the size of the mip level.
+    /// Before writing, the textures are cleared based on the given clear state.
+    /// In contrast to [write](Self::write), the mip maps of the color texture are not generated afterwards, so this can be used to create a custom mip chain.
+    ///
+    /// # Errors
+    /// Will return an error if this render target does not have a color texture or if the mip level does not exist in the color texture.
+    ///
+    pub fn write_to_mip<F: FnOnce(Viewport) -> Result<(), Error>>(
+        &self,
+        mip_level: u32,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        let color_texture = self.color_texture.ok_or_else(|| Error::RenderTargetError {
+            message: "Cannot write to a mip level of a render target without a color texture."
+                .to_owned(),
+        })?;
+        if mip_level >= color_texture.number_of_mip_maps() {
+            Err(Error::RenderTargetError {
+                message: format!(
+                    "Cannot write to mip level {} of a texture with {} mip levels.",
+                    mip_level,
+                    color_texture.number_of_mip_maps()
+                ),
+            })?;
+        }
+        check_context_lost(&self.context)?;
+        self.bind_mip_level(consts::DRAW_FRAMEBUFFER, mip_level)?;
+        clear(
+            &self.context,
+            &ClearState {
+                depth: self.depth_texture.and(clear_state.depth),
+                ..clear_state
+            },
+            color_texture.is_srgb(),
+        );
+        render(Viewport::new_at_origo(
+            (color_texture.width() >> mip_level).max(1),
+            (color_texture.height() >> mip_level).max(1),
+        ))?;
+        Ok(())
+    }
+
     ///
     /// Copies the content of the color and depth textures in this render target to the specified viewport of the specified [destination](crate::CopyDestination).
     /// Only copies the channels given by the write mask.
@@ -294,11 +387,25 @@ impl<'a, 'b, T: TextureDataType> RenderTarget<'a, 'b, T> {
         })
     }
 
+    ///
+    /// Sets a label on the render target which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.context
+            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, Some(&self.id));
+        self.context.set_framebuffer_label(&self.id, label);
+    }
+
     pub(super) fn bind(&self, target: u32) -> Result<(), Error> {
+        self.bind_mip_level(target, 0)
+    }
+
+    fn bind_mip_level(&self, target: u32, mip_level: u32) -> Result<(), Error> {
         self.context.bind_framebuffer(target, Some(&self.id));
         if let Some(tex) = self.color_texture {
             self.context.draw_buffers(&[consts::COLOR_ATTACHMENT0]);
-            tex.bind_as_color_target(0);
+            tex.bind_as_color_target(0, mip_level);
         }
         if let Some(tex) = self.depth_texture {
             tex.bind_as_depth_target();
@@ -383,6 +490,7 @@ impl<'a, 'b, T: TextureDataType> RenderTargetArray<'a, 'b, T> {
         clear_state: ClearState,
         render: F,
     ) -> Result<(), Error> {
+        check_context_lost(&self.context)?;
         self.bind(Some(color_layers), Some(depth_layer))?;
         clear(
             &self.context,
@@ -393,6 +501,7 @@ impl<'a, 'b, T: TextureDataType> RenderTargetArray<'a, 'b, T> {
                 alpha: self.color_texture.and(clear_state.alpha),
                 depth: self.depth_texture.and(clear_state.depth),
             },
+            false,
         );
         render()?;
         if let Some(color_texture) = self.color_texture {
@@ -492,7 +601,7 @@ impl<T: TextureDataType> Drop for RenderTargetArray<'_, '_, T> {
     }
 }
 
-fn new_framebuffer(context: &Context) -> Result<crate::context::Framebuffer, Error> {
+pub(super) fn new_framebuffer(context: &Context) -> Result<crate::context::Framebuffer, Error> {
     Ok(context
         .create_framebuffer()
         .ok_or_else(|| Error::RenderTargetError {
@@ -500,6 +609,13 @@ fn new_framebuffer(context: &Context) -> Result<crate::context::Framebuffer, Err
         })?)
 }
 
+pub(super) fn check_context_lost(context: &Context) -> Result<(), Error> {
+    if context.check_context_lost() {
+        Err(Error::ContextLost)?;
+    }
+    Ok(())
+}
+
 #[cfg(feature = "debug")]
 fn check(context: &Context) -> Result<(), Error> {
     context.check_framebuffer_status().or_else(|status| {
@@ -509,7 +625,7 @@ fn check(context: &Context) -> Result<(), Error> {
     })
 }
 
-fn clear(context: &Context, clear_state: &ClearState) {
+pub(super) fn clear(context: &Context, clear_state: &ClearState, srgb_target: bool) {
     Program::set_write_mask(
         context,
         WriteMask {
@@ -525,16 +641,28 @@ fn clear(context: &Context, clear_state: &ClearState) {
         || clear_state.blue.is_some()
         || clear_state.alpha.is_some();
     if clear_color {
+        let convert = |value: f32| {
+            if srgb_target {
+                srgb_to_linear(value)
+            } else {
+                value
+            }
+        };
         context.clear_color(
-            clear_state.red.unwrap_or(0.0),
-            clear_state.green.unwrap_or(0.0),
-            clear_state.blue.unwrap_or(0.0),
+            convert(clear_state.red.unwrap_or(0.0)),
+            convert(clear_state.green.unwrap_or(0.0)),
+            convert(clear_state.blue.unwrap_or(0.0)),
             clear_state.alpha.unwrap_or(1.0),
         );
     }
     if let Some(depth) = clear_state.depth {
         context.clear_depth(depth);
     }
+    // Desktop only encodes the written colors in sRGB when enabled, while it is always enabled on web.
+    #[cfg(not(target_arch = "wasm32"))]
+    if srgb_target {
+        context.enable(consts::FRAMEBUFFER_SRGB);
+    }
     context.clear(if clear_color && clear_state.depth.is_some() {
         consts::COLOR_BUFFER_BIT | consts::DEPTH_BUFFER_BIT
     } else {
@@ -544,6 +672,26 @@ fn clear(context: &Context, clear_state: &ClearState) {
             consts::DEPTH_BUFFER_BIT
         }
     });
+    #[cfg(not(target_arch = "wasm32"))]
+    if srgb_target {
+        context.disable(consts::FRAMEBUFFER_SRGB);
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
 }
 
 fn get_copy_effect(context: &Context) -> Result<&ImageEffect, Error> {
@@ -591,3 +739,30 @@ fn get_copy_array_effect(context: &Context) -> Result<&ImageEffect, Error> {
         Ok(COPY_EFFECT.as_ref().unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_clear_color_is_converted_to_srgb() {
+        let clear_state = ClearState::color_and_depth(0.5, 0.0, 1.0, 0.5, 0.25)
+            .with_color_space(ColorSpace::Linear);
+        assert!((clear_state.red.unwrap() - 0.7354).abs() < 1.0e-3);
+        assert_eq!(clear_state.green, Some(0.0));
+        assert!((clear_state.blue.unwrap() - 1.0).abs() < 1.0e-5);
+        assert_eq!(clear_state.alpha, Some(0.5));
+        assert_eq!(clear_state.depth, Some(0.25));
+        assert!((srgb_to_linear(clear_state.red.unwrap()) - 0.5).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn srgb_clear_color_is_unchanged() {
+        let clear_state = ClearState::color(0.5, 0.25, 1.0, 1.0);
+        assert_eq!(clear_state.with_color_space(ColorSpace::Srgb), clear_state);
+        assert_eq!(
+            ClearState::depth(1.0).with_color_space(ColorSpace::Linear),
+            ClearState::depth(1.0)
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use crate::camera::*;
+use crate::context::{consts, Context};
+use crate::core::render_target::{check_context_lost, clear, new_framebuffer};
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+
+///
+/// The six sides of a cube map in the order they are stored on the GPU.
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CubeMapSide {
+    /// The side in the positive x direction.
+    Right = 0,
+    /// The side in the negative x direction.
+    Left = 1,
+    /// The side in the positive y direction.
+    Top = 2,
+    /// The side in the negative y direction.
+    Bottom = 3,
+    /// The side in the positive z direction.
+    Front = 4,
+    /// The side in the negative z direction.
+    Back = 5,
+}
+
+impl CubeMapSide {
+    ///
+    /// All six sides in the order they are stored on the GPU.
+    ///
+    pub const ALL: [CubeMapSide; 6] = [
+        CubeMapSide::Right,
+        CubeMapSide::Left,
+        CubeMapSide::Top,
+        CubeMapSide::Bottom,
+        CubeMapSide::Front,
+        CubeMapSide::Back,
+    ];
+
+    ///
+    /// The direction a camera placed in the center of the cube must look in to see this side.
+    ///
+    pub fn direction(&self) -> Vec3 {
+        match self {
+            CubeMapSide::Right => vec3(1.0, 0.0, 0.0),
+            CubeMapSide::Left => vec3(-1.0, 0.0, 0.0),
+            CubeMapSide::Top => vec3(0.0, 1.0, 0.0),
+            CubeMapSide::Bottom => vec3(0.0, -1.0, 0.0),
+            CubeMapSide::Front => vec3(0.0, 0.0, 1.0),
+            CubeMapSide::Back => vec3(0.0, 0.0, -1.0),
+        }
+    }
+
+    ///
+    /// The up direction of a camera looking at this side, such that the rendered image matches the orientation used when sampling the cube map.
+    ///
+    pub fn up(&self) -> Vec3 {
+        match self {
+            CubeMapSide::Top => vec3(0.0, 0.0, 1.0),
+            CubeMapSide::Bottom => vec3(0.0, 0.0, -1.0),
+            _ => vec3(0.0, -1.0, 0.0),
+        }
+    }
+}
+
+///
+/// A render target for rendering a scene into the six sides of a [cube map](crate::TextureCubeMap), for example for dynamic reflections.
+/// Each side is rendered using a camera placed at the given position with a 90 degree field of view looking towards that side.
+///
+pub struct RenderTargetCubeMap {
+    context: Context,
+    id: crate::context::Framebuffer,
+    texture: TextureCubeMap,
+    depth_texture: DepthTargetTexture2D,
+    camera: Camera,
+    position: Vec3,
+}
+
+impl RenderTargetCubeMap {
+    ///
+    /// Constructs a new cube map render target where each side has the given size.
+    /// The scene is rendered from the given position, where `z_near` and `z_far` are the distances from the position to the near and far planes of the camera,
+    /// so only objects between these distances are visible in the cube map.
+    ///
+    pub fn new<T: TextureDataType>(
+        context: &Context,
+        size: u32,
+        format: Format,
+        position: Vec3,
+        z_near: f32,
+        z_far: f32,
+    ) -> Result<Self, Error> {
+        let side = CubeMapSide::Right;
+        Ok(Self {
+            context: context.clone(),
+            id: new_framebuffer(context)?,
+            texture: TextureCubeMap::new_empty::<T>(
+                context,
+                size,
+                format,
+                Some(Interpolation::Linear),
+            )?,
+            depth_texture: DepthTargetTexture2D::new(
+                context,
+                size,
+                size,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )?,
+            camera: Camera::new_perspective(
+                context,
+                position,
+                position + side.direction(),
+                side.up(),
+                degrees(90.0),
+                1.0,
+                z_near,
+                z_far,
+            )?,
+            position,
+        })
+    }
+
+    ///
+    /// Sets the position the scene is rendered from the next time a side is written.
+    ///
+    pub fn set_position(&mut self, position: Vec3) {
+        self.position = position;
+    }
+
+    ///
+    /// The position the scene is rendered from.
+    ///
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    ///
+    /// Sets the distances from the position to the near and far planes of the camera used for rendering the sides.
+    ///
+    pub fn set_near_far(&mut self, z_near: f32, z_far: f32) -> Result<(), Error> {
+        self.camera
+            .set_perspective_projection(degrees(90.0), 1.0, z_near, z_far)
+    }
+
+    ///
+    /// The cube map texture containing the rendered sides.
+    ///
+    pub fn texture(&self) -> &TextureCubeMap {
+        &self.texture
+    }
+
+    ///
+    /// Renders whatever rendered in the `render` closure into the given side of the cube map.
+    /// The `render` closure is given the viewport and the camera looking at the side, use these in the render calls.
+    /// Before writing, the side is cleared based on the given clear state.
+    /// The mip maps are not generated afterwards, call [generate_mip_maps](Self::generate_mip_maps) when all sides are written
+    /// or use [write](Self::write) to write all sides at once.
+    ///
+    pub fn write_side<F: FnOnce(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        side: CubeMapSide,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        check_context_lost(&self.context)?;
+        self.camera
+            .set_view(self.position, self.position + side.direction(), side.up())?;
+        self.context
+            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, Some(&self.id));
+        self.context.draw_buffers(&[consts::COLOR_ATTACHMENT0]);
+        self.texture.bind_as_color_target(side, 0);
+        self.depth_texture.bind_as_depth_target();
+        clear(&self.context, &clear_state, false);
+        render(
+            Viewport::new_at_origo(self.texture.width(), self.texture.height()),
+            &self.camera,
+        )
+    }
+
+    ///
+    /// Renders whatever rendered in the `render` closure into all six sides of the cube map and generates the mip maps afterwards.
+    /// The `render` closure is called once for each side, see [write_side](Self::write_side).
+    ///
+    pub fn write<F: FnMut(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        clear_state: ClearState,
+        mut render: F,
+    ) -> Result<(), Error> {
+        for side in CubeMapSide::ALL.iter() {
+            self.write_side(*side, clear_state, &mut render)?;
+        }
+        self.generate_mip_maps();
+        Ok(())
+    }
+
+    ///
+    /// Generates the mip maps of the cube map texture from the rendered sides.
+    ///
+    pub fn generate_mip_maps(&self) {
+        self.texture.generate_mip_maps();
+    }
+
+    ///
+    /// Sets a label on the render target which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.context
+            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, Some(&self.id));
+        self.context.set_framebuffer_label(&self.id, label);
+    }
+}
+
+impl Drop for RenderTargetCubeMap {
+    fn drop(&mut self) {
+        self.context.delete_framebuffer(Some(&self.id));
+    }
+}
@@ -0,0 +1,341 @@
+use crate::context::{consts, Context};
+use crate::core::render_target::{check_context_lost, clear, new_framebuffer};
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// A render target with multiple samples per pixel (MSAA) which reduces jagged edges when rendering into a texture.
+/// The multisampled image cannot be sampled directly, instead it is resolved into a [ColorTargetTexture2D] after rendering,
+/// either using the fast fixed function [resolve](Self::resolve) or, on desktop, using a custom shader with [resolve_with_shader](Self::resolve_with_shader).
+///
+pub struct RenderTargetMultisample {
+    context: Context,
+    id: crate::context::Framebuffer,
+    width: u32,
+    height: u32,
+    samples: u32,
+    srgb: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    color: crate::context::Texture,
+    #[cfg(target_arch = "wasm32")]
+    color: crate::context::Renderbuffer,
+    depth: crate::context::Renderbuffer,
+    color_bytes: u64,
+    depth_bytes: u64,
+}
+
+impl RenderTargetMultisample {
+    ///
+    /// A fragment shader for [resolve_with_shader](Self::resolve_with_shader) which averages the samples in tone mapped space
+    /// (each sample is weighted by `1 / (1 + luminance)`), so very bright samples, for example in a high dynamic range image, do not dominate the edges and cause fireflies.
+    ///
+    pub const TONE_MAPPED_RESOLVE_SHADER: &'static str = "
+uniform sampler2DMS colorMap;
+uniform int sampleCount;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    ivec2 coords = ivec2(gl_FragCoord.xy);
+    vec4 sum = vec4(0.0);
+    float weightSum = 0.0;
+    for (int i = 0; i < sampleCount; i++) {
+        vec4 s = texelFetch(colorMap, coords, i);
+        float weight = 1.0 / (1.0 + dot(s.rgb, vec3(0.2126, 0.7152, 0.0722)));
+        sum += s * weight;
+        weightSum += weight;
+    }
+    color = sum / weightSum;
+}";
+
+    ///
+    /// Constructs a new multisample render target with the given size and number of samples per pixel
+    /// and with a color buffer with the given format and data type and a depth buffer with the given depth format.
+    ///
+    /// # Errors
+    /// Returns an error if the number of samples is zero.
+    ///
+    pub fn new<T: TextureDataType>(
+        context: &Context,
+        width: u32,
+        height: u32,
+        samples: u32,
+        format: Format,
+        depth_format: DepthFormat,
+    ) -> Result<Self, Error> {
+        if samples == 0 {
+            Err(Error::RenderTargetError {
+                message: "A multisample render target must have at least one sample".to_owned(),
+            })?;
+        }
+        let internal_format = T::internal_format(format)?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let color = {
+            let color = generate(context)?;
+            context.bind_texture(consts::TEXTURE_2D_MULTISAMPLE, &color);
+            context.tex_storage_2d_multisample(
+                consts::TEXTURE_2D_MULTISAMPLE,
+                samples,
+                internal_format,
+                width,
+                height,
+                true,
+            );
+            color
+        };
+        #[cfg(target_arch = "wasm32")]
+        let color = new_renderbuffer(context, samples, internal_format, width, height)?;
+        let depth_internal_format = internal_format_from_depth(depth_format);
+        let depth = new_renderbuffer(context, samples, depth_internal_format, width, height)?;
+        let color_bytes = texture_bytes(
+            width,
+            height,
+            samples,
+            internal_format_texel_bytes(internal_format),
+            1,
+        );
+        let depth_bytes = texture_bytes(
+            width,
+            height,
+            samples,
+            internal_format_texel_bytes(depth_internal_format),
+            1,
+        );
+        let id = new_framebuffer(context)?;
+        add_texture_memory(context, color_bytes);
+        add_texture_memory(context, depth_bytes);
+        Ok(Self {
+            context: context.clone(),
+            id,
+            width,
+            height,
+            samples,
+            srgb: is_srgb_internal_format(internal_format),
+            color,
+            depth,
+            color_bytes,
+            depth_bytes,
+        })
+    }
+
+    ///
+    /// The width of this render target.
+    ///
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    ///
+    /// The height of this render target.
+    ///
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    ///
+    /// The number of samples per pixel.
+    ///
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    ///
+    /// Renders whatever rendered in the `render` closure into this render target.
+    /// Before writing, the render target is cleared based on the given clear state.
+    /// Call [resolve](Self::resolve) or [resolve_with_shader](Self::resolve_with_shader) afterwards to use the result.
+    ///
+    pub fn write<F: FnOnce() -> Result<(), Error>>(
+        &self,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        check_context_lost(&self.context)?;
+        self.bind(consts::DRAW_FRAMEBUFFER);
+        clear(&self.context, &clear_state, self.srgb);
+        render()?;
+        Ok(())
+    }
+
+    ///
+    /// Resolves the samples of the color buffer into the given texture using a framebuffer blit, which is the fast and default way to resolve.
+    /// The texture must have the same size as this render target and should have the same format and data type, since this is required on web.
+    /// The mip maps of the texture are regenerated afterwards.
+    ///
+    pub fn resolve<T: TextureDataType>(
+        &self,
+        target: &ColorTargetTexture2D<T>,
+    ) -> Result<(), Error> {
+        check_context_lost(&self.context)?;
+        let render_target = RenderTarget::new_color(&self.context, target)?;
+        render_target.bind(consts::DRAW_FRAMEBUFFER)?;
+        self.blit_color();
+        target.generate_mip_maps();
+        Ok(())
+    }
+
+    ///
+    /// Resolves the samples of the color buffer directly to the screen using a framebuffer blit.
+    /// The screen must not be multisampled, ie. [WindowSettings::multisamples](crate::WindowSettings::multisamples) must be zero.
+    ///
+    pub fn resolve_to_screen(&self) -> Result<(), Error> {
+        check_context_lost(&self.context)?;
+        self.context
+            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, None);
+        self.blit_color();
+        Ok(())
+    }
+
+    ///
+    /// Resolves the samples of the color buffer into the given texture by rendering a full screen quad with the given image effect, instead of a framebuffer blit.
+    /// This makes it possible to combine the samples in a custom way, for example averaging in tone mapped space using [TONE_MAPPED_RESOLVE_SHADER](Self::TONE_MAPPED_RESOLVE_SHADER).
+    /// The fragment shader of the image effect is given the samples in the `uniform sampler2DMS colorMap` and the number of samples in `uniform int sampleCount`.
+    ///
+    /// **Note:** Only available on desktop, since sampling a multisampled texture is not supported in WebGL 2.
+    ///
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn resolve_with_shader<T: TextureDataType>(
+        &self,
+        image_effect: &crate::ImageEffect,
+        target: &ColorTargetTexture2D<T>,
+    ) -> Result<(), Error> {
+        check_context_lost(&self.context)?;
+        let color = MultisampleTexture {
+            context: &self.context,
+            id: &self.color,
+            width: self.width,
+            height: self.height,
+        };
+        target.write(ClearState::none(), || {
+            image_effect.use_texture(&color, "colorMap")?;
+            image_effect.use_uniform_int("sampleCount", &(self.samples as i32))?;
+            image_effect.apply(
+                RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    depth_test: DepthTestType::Always,
+                    ..Default::default()
+                },
+                crate::math::Viewport::new_at_origo(target.width(), target.height()),
+            )
+        })
+    }
+
+    ///
+    /// Sets a label on the render target which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.bind(consts::DRAW_FRAMEBUFFER);
+        self.context.set_framebuffer_label(&self.id, label);
+    }
+
+    fn bind(&self, target: u32) {
+        self.context.bind_framebuffer(target, Some(&self.id));
+        self.context.draw_buffers(&[consts::COLOR_ATTACHMENT0]);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.context.framebuffer_texture_2d(
+            target,
+            consts::COLOR_ATTACHMENT0,
+            consts::TEXTURE_2D_MULTISAMPLE,
+            &self.color,
+            0,
+        );
+        #[cfg(target_arch = "wasm32")]
+        self.context.framebuffer_renderbuffer(
+            target,
+            consts::COLOR_ATTACHMENT0,
+            consts::RENDERBUFFER,
+            &self.color,
+        );
+        self.context.framebuffer_renderbuffer(
+            target,
+            consts::DEPTH_ATTACHMENT,
+            consts::RENDERBUFFER,
+            &self.depth,
+        );
+    }
+
+    fn blit_color(&self) {
+        self.bind(consts::READ_FRAMEBUFFER);
+        self.context.blit_framebuffer(
+            0,
+            0,
+            self.width,
+            self.height,
+            0,
+            0,
+            self.width,
+            self.height,
+            consts::COLOR_BUFFER_BIT,
+            consts::NEAREST,
+        );
+        self.context
+            .bind_framebuffer(consts::READ_FRAMEBUFFER, None);
+    }
+}
+
+impl Drop for RenderTargetMultisample {
+    fn drop(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.context.delete_texture(&self.color);
+        #[cfg(target_arch = "wasm32")]
+        self.context.delete_renderbuffer(&self.color);
+        self.context.delete_renderbuffer(&self.depth);
+        self.context.delete_framebuffer(Some(&self.id));
+        remove_texture_memory(&self.context, self.color_bytes);
+        remove_texture_memory(&self.context, self.depth_bytes);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct MultisampleTexture<'a> {
+    context: &'a Context,
+    id: &'a crate::context::Texture,
+    width: u32,
+    height: u32,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Texture for MultisampleTexture<'_> {
+    fn bind(&self, location: u32) {
+        bind_at(
+            self.context,
+            self.id,
+            consts::TEXTURE_2D_MULTISAMPLE,
+            location,
+        );
+    }
+    fn width(&self) -> u32 {
+        self.width
+    }
+    fn height(&self) -> u32 {
+        self.height
+    }
+    fn context(&self) -> Option<&Context> {
+        Some(self.context)
+    }
+}
+
+fn new_renderbuffer(
+    context: &Context,
+    samples: u32,
+    internal_format: u32,
+    width: u32,
+    height: u32,
+) -> Result<crate::context::Renderbuffer, Error> {
+    let renderbuffer = context
+        .create_renderbuffer()
+        .ok_or_else(|| Error::RenderTargetError {
+            message: "Failed to create renderbuffer".to_string(),
+        })?;
+    context.bind_renderbuffer(consts::RENDERBUFFER, Some(&renderbuffer));
+    context.renderbuffer_storage_multisample(
+        consts::RENDERBUFFER,
+        samples,
+        internal_format,
+        width,
+        height,
+    );
+    context.bind_renderbuffer(consts::RENDERBUFFER, None);
+    Ok(renderbuffer)
+}
@@ -0,0 +1,88 @@
+use crate::core::*;
+use crate::math::*;
+
+///
+/// The graphics state last set on a [Context], which is used for skipping graphics calls that do not change the state.
+/// A value of `None` means that the state is unknown, so the next call setting that state is always issued.
+///
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StateCache {
+    pub write_mask: Option<WriteMask>,
+    pub depth_enable: Option<bool>,
+    pub depth_mask: Option<bool>,
+    pub depth_test: Option<DepthTestType>,
+    pub blend: Option<Option<BlendParameters>>,
+    pub cull: Option<CullType>,
+    pub cull_override: Option<CullType>,
+    pub viewport: Option<Viewport>,
+    pub polygon_mode: Option<PolygonMode>,
+    pub line_width: Option<f32>,
+    pub front_face: Option<FrontFace>,
+    pub depth_clamp: Option<bool>,
+}
+
+impl Context {
+    ///
+    /// Forgets the graphics state cached by this crate, so the state is set again in the next draw call.
+    /// Call this after changing the graphics state outside of this crate, for example using [raw_gl](Context::raw_gl), without restoring it.
+    ///
+    pub fn invalidate_state_cache(&self) {
+        let mut state_cache = self.state_cache.borrow_mut();
+        *state_cache = StateCache {
+            cull_override: state_cache.cull_override,
+            ..Default::default()
+        };
+    }
+
+    ///
+    /// Sets the cached state selected by `state` to the given value and calls `apply` if the value was different from the cached value.
+    ///
+    pub(crate) fn set_cached_state<T: PartialEq + Copy>(
+        &self,
+        state: impl FnOnce(&mut StateCache) -> &mut Option<T>,
+        value: T,
+        apply: impl FnOnce(T),
+    ) {
+        let changed = {
+            let mut state_cache = self.state_cache.borrow_mut();
+            let current = state(&mut state_cache);
+            if *current == Some(value) {
+                false
+            } else {
+                *current = Some(value);
+                true
+            }
+        };
+        if changed {
+            self.record_state_change();
+            apply(value);
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setting_the_same_state_twice_is_only_applied_once() {
+        let context = Context::new_without_gl();
+        let applied = std::cell::Cell::new(0);
+        let set_cull = |cull: CullType| {
+            context.set_cached_state(|s| &mut s.cull, cull, |_| applied.set(applied.get() + 1))
+        };
+        set_cull(CullType::Back);
+        set_cull(CullType::Back);
+        assert_eq!(applied.get(), 1);
+        assert_eq!(context.render_stats().state_changes, 1);
+
+        set_cull(CullType::Front);
+        assert_eq!(applied.get(), 2);
+        assert_eq!(context.render_stats().state_changes, 2);
+
+        context.invalidate_state_cache();
+        set_cull(CullType::Front);
+        assert_eq!(applied.get(), 3);
+        assert_eq!(context.render_stats().state_changes, 3);
+    }
+}
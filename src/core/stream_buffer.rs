@@ -0,0 +1,74 @@
+use crate::context::Context;
+use crate::core::{Error, VertexBuffer, VertexBufferDataType};
+
+///
+/// A vertex buffer for data that is written every frame, for example the instance data of a particle system or an animated crowd.
+/// It rotates among a number of underlying [vertex buffers](crate::VertexBuffer), so that writing new data never has to wait for the GPU
+/// to finish using a buffer that is still in flight from one of the previous frames.
+///
+/// This costs the memory of the data times the number of buffers on the GPU.
+/// The rotation only avoids the implicit synchronization if the GPU is less than `buffer_count - 1` frames behind the CPU,
+/// so use two (double buffering) or three (triple buffering) buffers and write once per frame.
+///
+pub struct StreamBuffer {
+    buffers: Vec<VertexBuffer>,
+    current: usize,
+}
+
+impl StreamBuffer {
+    ///
+    /// Creates a new stream buffer with the given number of underlying vertex buffers, which are all empty.
+    ///
+    /// # Errors
+    /// Returns an error if the buffer count is zero.
+    ///
+    pub fn new(context: &Context, buffer_count: usize) -> Result<Self, Error> {
+        if buffer_count == 0 {
+            Err(Error::BufferError {
+                message: "A stream buffer needs at least one underlying buffer".to_string(),
+            })?;
+        }
+        let mut buffers = Vec::with_capacity(buffer_count);
+        for _ in 0..buffer_count {
+            buffers.push(VertexBuffer::new(context)?);
+        }
+        Ok(Self {
+            buffers,
+            current: buffer_count - 1,
+        })
+    }
+
+    ///
+    /// Writes the given data into the next underlying buffer and returns that buffer, which is also returned by [current](Self::current)
+    /// until the next write.
+    ///
+    pub fn write<T: VertexBufferDataType>(&mut self, data: &[T]) -> &VertexBuffer {
+        self.current = (self.current + 1) % self.buffers.len();
+        self.buffers[self.current].fill_with_dynamic(data);
+        &self.buffers[self.current]
+    }
+
+    ///
+    /// The underlying buffer which was written last, use this buffer when rendering.
+    ///
+    pub fn current(&self) -> &VertexBuffer {
+        &self.buffers[self.current]
+    }
+
+    ///
+    /// The number of underlying buffers.
+    ///
+    pub fn buffer_count(&self) -> usize {
+        self.buffers.len()
+    }
+
+    ///
+    /// Sets a label on all the underlying buffers which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        for (i, buffer) in self.buffers.iter().enumerate() {
+            buffer.set_label(&format!("{} {}", label, i));
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::context::{consts, Context};
 use crate::core::*;
 use crate::definition::*;
+use crate::math::*;
 
 ///
 /// A 2D texture, basically an image that is transferred to the GPU.
@@ -12,7 +13,10 @@ pub struct Texture2D {
     width: u32,
     height: u32,
     format: Format,
+    internal_format: u32,
     number_of_mip_maps: u32,
+    mip_map_filter: Option<Interpolation>,
+    bytes: u64,
 }
 
 impl Texture2D {
@@ -45,10 +49,19 @@ impl Texture2D {
             cpu_texture.wrap_t,
             None,
         );
+        let internal_format = T::internal_format(cpu_texture.format)?;
+        let bytes = texture_bytes(
+            cpu_texture.width,
+            cpu_texture.height,
+            1,
+            color_texel_bytes::<T>(cpu_texture.format),
+            number_of_mip_maps,
+        );
+        add_texture_memory(context, bytes);
         context.tex_storage_2d(
             consts::TEXTURE_2D,
             number_of_mip_maps,
-            T::internal_format(cpu_texture.format)?,
+            internal_format,
             cpu_texture.width as u32,
             cpu_texture.height as u32,
         );
@@ -58,7 +71,14 @@ impl Texture2D {
             width: cpu_texture.width,
             height: cpu_texture.height,
             format: cpu_texture.format,
+            internal_format,
             number_of_mip_maps,
+            mip_map_filter: if number_of_mip_maps == 1 {
+                None
+            } else {
+                cpu_texture.mip_map_filter
+            },
+            bytes,
         };
         tex.fill(&cpu_texture.data)?;
         Ok(tex)
@@ -85,6 +105,113 @@ impl Texture2D {
         Ok(())
     }
 
+    ///
+    /// Sets how the texture is sampled outside the range `[0, 1]` of the texture coordinates in the s (u) and t (v) directions.
+    /// Takes effect the next time the texture is used in a draw call.
+    ///
+    pub fn set_wrapping(&mut self, wrap_s: Wrapping, wrap_t: Wrapping) {
+        set_wrapping_parameters(
+            &self.context,
+            &self.id,
+            consts::TEXTURE_2D,
+            wrap_s,
+            wrap_t,
+            None,
+        );
+    }
+
+    ///
+    /// Sets the color returned when the texture is sampled outside the range `[0, 1]` of the texture coordinates
+    /// in a direction with [Wrapping::ClampToBorder] wrapping. The default border color is transparent black.
+    ///
+    /// **Note:** Has no effect on web, since clamping to the border is not supported in WebGL 2.
+    ///
+    pub fn set_border_color(&mut self, color: Vec4) {
+        set_border_color_parameter(&self.context, &self.id, consts::TEXTURE_2D, &color);
+    }
+
+    ///
+    /// Sets the interpolation used when the texture is minified and magnified.
+    /// The mip map filter specified when the texture was created is kept.
+    /// Takes effect the next time the texture is used in a draw call.
+    ///
+    pub fn set_interpolation(&mut self, min_filter: Interpolation, mag_filter: Interpolation) {
+        set_interpolation_parameters(
+            &self.context,
+            &self.id,
+            consts::TEXTURE_2D,
+            min_filter,
+            mag_filter,
+            self.mip_map_filter,
+        );
+    }
+
+    ///
+    /// Copies the content of this texture into the destination texture using a framebuffer blit.
+    /// If the size of the two textures differ, the content is scaled to fit the destination using the given interpolation.
+    /// The mip maps of the destination texture are regenerated afterwards.
+    ///
+    /// # Errors
+    /// Returns an error if the two textures do not have the same [format](crate::Format) and data type,
+    /// for example if one texture is RGB and the other is RGBA or if one contains `u8` and the other `f32` values.
+    ///
+    pub fn copy_to(&self, destination: &mut Texture2D, filter: Interpolation) -> Result<(), Error> {
+        if self.format != destination.format || self.internal_format != destination.internal_format
+        {
+            Err(Error::TextureError {
+                message: "Cannot copy between textures with different formats.".to_owned(),
+            })?;
+        }
+        let read_framebuffer = new_framebuffer(&self.context)?;
+        let draw_framebuffer = new_framebuffer(&self.context)?;
+        self.context
+            .bind_framebuffer(consts::READ_FRAMEBUFFER, Some(&read_framebuffer));
+        self.context.framebuffer_texture_2d(
+            consts::READ_FRAMEBUFFER,
+            consts::COLOR_ATTACHMENT0,
+            consts::TEXTURE_2D,
+            &self.id,
+            0,
+        );
+        self.context
+            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, Some(&draw_framebuffer));
+        self.context.framebuffer_texture_2d(
+            consts::DRAW_FRAMEBUFFER,
+            consts::COLOR_ATTACHMENT0,
+            consts::TEXTURE_2D,
+            &destination.id,
+            0,
+        );
+        self.context.blit_framebuffer(
+            0,
+            0,
+            self.width,
+            self.height,
+            0,
+            0,
+            destination.width,
+            destination.height,
+            consts::COLOR_BUFFER_BIT,
+            interpolation_from(filter) as u32,
+        );
+        self.context
+            .bind_framebuffer(consts::READ_FRAMEBUFFER, None);
+        self.context
+            .bind_framebuffer(consts::DRAW_FRAMEBUFFER, None);
+        self.context.delete_framebuffer(Some(&read_framebuffer));
+        self.context.delete_framebuffer(Some(&draw_framebuffer));
+        destination.generate_mip_maps();
+        Ok(())
+    }
+
+    ///
+    /// Sets a label on the texture which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.context.set_texture_label(&self.id, label);
+    }
+
     pub(crate) fn generate_mip_maps(&self) {
         if self.number_of_mip_maps > 1 {
             self.context.bind_texture(consts::TEXTURE_2D, &self.id);
@@ -93,6 +220,14 @@ impl Texture2D {
     }
 }
 
+fn new_framebuffer(context: &Context) -> Result<crate::context::Framebuffer, Error> {
+    context
+        .create_framebuffer()
+        .ok_or_else(|| Error::TextureError {
+            message: "Failed to create framebuffer for copying the texture".to_string(),
+        })
+}
+
 impl Texture for Texture2D {
     fn bind(&self, location: u32) {
         bind_at(&self.context, &self.id, consts::TEXTURE_2D, location);
@@ -103,10 +238,17 @@ impl Texture for Texture2D {
     fn height(&self) -> u32 {
         self.height
     }
+    fn is_srgb(&self) -> bool {
+        is_srgb_internal_format(self.internal_format)
+    }
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
 }
 
 impl Drop for Texture2D {
     fn drop(&mut self) {
+        remove_texture_memory(&self.context, self.bytes);
         self.context.delete_texture(&self.id);
     }
 }
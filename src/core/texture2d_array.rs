@@ -0,0 +1,216 @@
+use crate::context::{consts, Context};
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// An array of 2D textures with the same size and format, where each layer is an image that is transferred to the GPU, for example the images of a texture atlas.
+/// For a texture array that can be rendered into, see [ColorTargetTexture2DArray](crate::ColorTargetTexture2DArray).
+///
+pub struct Texture2DArray {
+    context: Context,
+    id: crate::context::Texture,
+    width: u32,
+    height: u32,
+    depth: u32,
+    format: Format,
+    number_of_mip_maps: u32,
+    mip_map_filter: Option<Interpolation>,
+    bytes: u64,
+}
+
+impl Texture2DArray {
+    ///
+    /// Constructs a new texture array with the given number of layers, where each layer is filled with the corresponding data in the `data` parameter.
+    /// The texture array uses linear interpolation, mip mapping and clamps to the edge.
+    ///
+    /// # Errors
+    /// Returns an error if the number of data arrays does not match the number of layers
+    /// or if the length of any of the data arrays does not match `width * height * number of color channels in the format`.
+    ///
+    pub fn new_with_data(
+        context: &Context,
+        width: u32,
+        height: u32,
+        layers: u32,
+        format: Format,
+        data: &[&[u8]],
+    ) -> Result<Self, Error> {
+        if data.len() != layers as usize {
+            Err(Error::TextureError {
+                message: format!(
+                    "Wrong number of layers of data for the texture array (got {} layers but expected {} layers)",
+                    data.len(),
+                    layers
+                ),
+            })?;
+        }
+        let id = generate(context)?;
+        let mip_map_filter = Some(Interpolation::Linear);
+        let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, width, height, 1);
+        set_parameters(
+            context,
+            &id,
+            consts::TEXTURE_2D_ARRAY,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            if number_of_mip_maps == 1 {
+                None
+            } else {
+                mip_map_filter
+            },
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            None,
+        );
+        context.tex_storage_3d(
+            consts::TEXTURE_2D_ARRAY,
+            number_of_mip_maps,
+            <u8 as internal::TextureDataTypeExtension>::internal_format(format)?,
+            width,
+            height,
+            layers,
+        );
+        let bytes = texture_bytes(
+            width,
+            height,
+            layers,
+            color_texel_bytes::<u8>(format),
+            number_of_mip_maps,
+        );
+        add_texture_memory(context, bytes);
+        let mut texture = Self {
+            context: context.clone(),
+            id,
+            width,
+            height,
+            depth: layers,
+            format,
+            number_of_mip_maps,
+            mip_map_filter: if number_of_mip_maps == 1 {
+                None
+            } else {
+                mip_map_filter
+            },
+            bytes,
+        };
+        for (layer, layer_data) in data.iter().enumerate() {
+            texture.fill_layer_internal(layer as u32, layer_data)?;
+        }
+        texture.generate_mip_maps();
+        Ok(texture)
+    }
+
+    ///
+    /// Fills the given layer of this texture array with the given data.
+    ///
+    /// # Errors
+    /// Returns an error if the layer is out of bounds or if the length of the data array does not match
+    /// `width * height * number of color channels in the format`.
+    ///
+    pub fn fill_layer(&mut self, layer: u32, data: &[u8]) -> Result<(), Error> {
+        self.fill_layer_internal(layer, data)?;
+        self.generate_mip_maps();
+        Ok(())
+    }
+
+    fn fill_layer_internal(&mut self, layer: u32, data: &[u8]) -> Result<(), Error> {
+        if layer >= self.depth {
+            Err(Error::TextureError {
+                message: format!(
+                    "Cannot fill layer {} of a texture array with {} layers",
+                    layer, self.depth
+                ),
+            })?;
+        }
+        check_data_length(self.width, self.height, 1, self.format, data.len())?;
+        self.context
+            .bind_texture(consts::TEXTURE_2D_ARRAY, &self.id);
+        self.context.tex_sub_image_3d_with_u8_data(
+            consts::TEXTURE_2D_ARRAY,
+            0,
+            0,
+            0,
+            layer,
+            self.width,
+            self.height,
+            1,
+            internal::format_from(self.format),
+            consts::UNSIGNED_BYTE,
+            data,
+        );
+        Ok(())
+    }
+
+    ///
+    /// Sets how each 2D layer of the texture array is sampled outside the range `[0, 1]` of the texture coordinates in the s (u) and t (v) directions.
+    /// The wrapping applies to all layers, the layer index itself is never wrapped but clamped to the number of layers.
+    /// Takes effect the next time the texture array is used in a draw call.
+    ///
+    pub fn set_wrapping(&mut self, wrap_s: Wrapping, wrap_t: Wrapping) {
+        set_wrapping_parameters(
+            &self.context,
+            &self.id,
+            consts::TEXTURE_2D_ARRAY,
+            wrap_s,
+            wrap_t,
+            None,
+        );
+    }
+
+    ///
+    /// Sets the interpolation used when the layers of the texture array are minified and magnified.
+    /// The mip map filter specified when the texture array was created is kept.
+    /// Takes effect the next time the texture array is used in a draw call.
+    ///
+    pub fn set_interpolation(&mut self, min_filter: Interpolation, mag_filter: Interpolation) {
+        set_interpolation_parameters(
+            &self.context,
+            &self.id,
+            consts::TEXTURE_2D_ARRAY,
+            min_filter,
+            mag_filter,
+            self.mip_map_filter,
+        );
+    }
+
+    ///
+    /// Sets a label on the texture array which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.context.set_texture_label(&self.id, label);
+    }
+
+    pub(crate) fn generate_mip_maps(&self) {
+        if self.number_of_mip_maps > 1 {
+            self.context
+                .bind_texture(consts::TEXTURE_2D_ARRAY, &self.id);
+            self.context.generate_mipmap(consts::TEXTURE_2D_ARRAY);
+        }
+    }
+}
+
+impl TextureArray for Texture2DArray {
+    fn bind(&self, location: u32) {
+        bind_at(&self.context, &self.id, consts::TEXTURE_2D_ARRAY, location);
+    }
+    fn width(&self) -> u32 {
+        self.width
+    }
+    fn height(&self) -> u32 {
+        self.height
+    }
+    fn depth(&self) -> u32 {
+        self.depth
+    }
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
+}
+
+impl Drop for Texture2DArray {
+    fn drop(&mut self) {
+        remove_texture_memory(&self.context, self.bytes);
+        self.context.delete_texture(&self.id);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::context::{consts, Context};
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// A 3D texture, basically a volume of data that is transferred to the GPU, for example density data for volume rendering.
+/// The texture uses trilinear interpolation and clamps to the edge in all three directions.
+/// Use it in a shader by declaring a `uniform sampler3D` and binding it with [use_texture](crate::Program::use_texture).
+///
+/// Single channel data, for example a density, is stored using [Format::R], which is a 8 bit (R8) texture with `u8` data
+/// and a 32 bit floating point (R32F) texture with `f32` data, or using [Format::R16F], which is a 16 bit floating point (R16F) texture
+/// that uses half the memory of R32F and requires `f32` data.
+///
+pub struct Texture3D {
+    context: Context,
+    id: crate::context::Texture,
+    width: u32,
+    height: u32,
+    depth: u32,
+    format: Format,
+    bytes: u64,
+}
+
+impl Texture3D {
+    ///
+    /// Constructs a new 3D texture with the given size and fills it with the given data, which is ordered by x, then y and finally z.
+    ///
+    /// # Errors
+    /// Returns an error if the length of the data array does not match `width * height * depth * number of color channels in the format`.
+    ///
+    pub fn new<T: TextureDataType>(
+        context: &Context,
+        width: u32,
+        height: u32,
+        depth: u32,
+        format: Format,
+        data: &[T],
+    ) -> Result<Self, Error> {
+        let id = generate(context)?;
+        set_parameters(
+            context,
+            &id,
+            consts::TEXTURE_3D,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Some(Wrapping::ClampToEdge),
+        );
+        context.tex_storage_3d(
+            consts::TEXTURE_3D,
+            1,
+            T::internal_format(format)?,
+            width,
+            height,
+            depth,
+        );
+        let bytes = texture_bytes(width, height, depth, color_texel_bytes::<T>(format), 1);
+        add_texture_memory(context, bytes);
+        let mut texture = Self {
+            context: context.clone(),
+            id,
+            width,
+            height,
+            depth,
+            format,
+            bytes,
+        };
+        texture.fill(data)?;
+        Ok(texture)
+    }
+
+    ///
+    /// Fills this texture with the given data.
+    ///
+    /// # Errors
+    /// Return an error if the length of the data array is smaller or bigger than the necessary number of values to fill the entire texture.
+    ///
+    pub fn fill<T: TextureDataType>(&mut self, data: &[T]) -> Result<(), Error> {
+        check_data_length(self.width, self.height, self.depth, self.format, data.len())?;
+        self.context.bind_texture(consts::TEXTURE_3D, &self.id);
+        T::fill_3d(
+            &self.context,
+            consts::TEXTURE_3D,
+            self.width,
+            self.height,
+            self.depth,
+            self.format,
+            data,
+        );
+        Ok(())
+    }
+
+    ///
+    /// The depth of this texture.
+    ///
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    ///
+    /// Sets a label on the texture which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.context.set_texture_label(&self.id, label);
+    }
+}
+
+impl Texture for Texture3D {
+    fn bind(&self, location: u32) {
+        bind_at(&self.context, &self.id, consts::TEXTURE_3D, location);
+    }
+    fn width(&self) -> u32 {
+        self.width
+    }
+    fn height(&self) -> u32 {
+        self.height
+    }
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
+}
+
+impl Drop for Texture3D {
+    fn drop(&mut self) {
+        remove_texture_memory(&self.context, self.bytes);
+        self.context.delete_texture(&self.id);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::context::Context;
+use crate::core::*;
+use crate::definition::*;
+
+///
+/// Builds a [ColorTargetTexture2D] using named methods instead of the many positional parameters of [ColorTargetTexture2D::new].
+/// The defaults are a size of 1x1 pixels, linear minification and magnification, no mip maps, clamping to the edge and the [RGBA](Format::RGBA) format.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorTargetTexture2DBuilder {
+    width: u32,
+    height: u32,
+    min_filter: Interpolation,
+    mag_filter: Interpolation,
+    mip_map_filter: Option<Interpolation>,
+    wrap_s: Wrapping,
+    wrap_t: Wrapping,
+    format: Format,
+}
+
+impl ColorTargetTexture2DBuilder {
+    ///
+    /// Constructs a new builder with the default settings.
+    ///
+    pub fn new() -> Self {
+        Self {
+            width: 1,
+            height: 1,
+            min_filter: Interpolation::Linear,
+            mag_filter: Interpolation::Linear,
+            mip_map_filter: None,
+            wrap_s: Wrapping::ClampToEdge,
+            wrap_t: Wrapping::ClampToEdge,
+            format: Format::RGBA,
+        }
+    }
+
+    ///
+    /// Sets the width and height of the texture.
+    ///
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    ///
+    /// Sets the interpolation used when the texture is minified and magnified.
+    ///
+    pub fn filter(mut self, min_filter: Interpolation, mag_filter: Interpolation) -> Self {
+        self.min_filter = min_filter;
+        self.mag_filter = mag_filter;
+        self
+    }
+
+    ///
+    /// Sets the interpolation between mip maps or `None` to create the texture without mip maps.
+    ///
+    pub fn mipmaps(mut self, mip_map_filter: Option<Interpolation>) -> Self {
+        self.mip_map_filter = mip_map_filter;
+        self
+    }
+
+    ///
+    /// Sets how the texture is sampled outside the range `[0, 1]` of the texture coordinates in the s (u) and t (v) directions.
+    ///
+    pub fn wrap(mut self, wrap_s: Wrapping, wrap_t: Wrapping) -> Self {
+        self.wrap_s = wrap_s;
+        self.wrap_t = wrap_t;
+        self
+    }
+
+    ///
+    /// Sets the format of the texture.
+    ///
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    ///
+    /// Constructs the texture with the current settings.
+    ///
+    pub fn build<T: TextureDataType>(
+        &self,
+        context: &Context,
+    ) -> Result<ColorTargetTexture2D<T>, Error> {
+        ColorTargetTexture2D::new(
+            context,
+            self.width,
+            self.height,
+            self.min_filter,
+            self.mag_filter,
+            self.mip_map_filter,
+            self.wrap_s,
+            self.wrap_t,
+            self.format,
+        )
+    }
+}
+
+impl Default for ColorTargetTexture2DBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+///
+/// Builds a [ColorTargetTexture2DArray] using named methods instead of the many positional parameters of [ColorTargetTexture2DArray::new].
+/// The defaults are the same as for [ColorTargetTexture2DBuilder] with a single layer.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorTargetTexture2DArrayBuilder {
+    layers: u32,
+    texture: ColorTargetTexture2DBuilder,
+}
+
+impl ColorTargetTexture2DArrayBuilder {
+    ///
+    /// Constructs a new builder with the default settings.
+    ///
+    pub fn new() -> Self {
+        Self {
+            layers: 1,
+            texture: ColorTargetTexture2DBuilder::new(),
+        }
+    }
+
+    ///
+    /// Sets the width and height of each layer and the number of layers of the texture array.
+    ///
+    pub fn size(mut self, width: u32, height: u32, layers: u32) -> Self {
+        self.texture = self.texture.size(width, height);
+        self.layers = layers;
+        self
+    }
+
+    ///
+    /// Sets the interpolation used when the layers are minified and magnified.
+    ///
+    pub fn filter(mut self, min_filter: Interpolation, mag_filter: Interpolation) -> Self {
+        self.texture = self.texture.filter(min_filter, mag_filter);
+        self
+    }
+
+    ///
+    /// Sets the interpolation between mip maps or `None` to create the texture array without mip maps.
+    ///
+    pub fn mipmaps(mut self, mip_map_filter: Option<Interpolation>) -> Self {
+        self.texture = self.texture.mipmaps(mip_map_filter);
+        self
+    }
+
+    ///
+    /// Sets how each layer is sampled outside the range `[0, 1]` of the texture coordinates in the s (u) and t (v) directions.
+    ///
+    pub fn wrap(mut self, wrap_s: Wrapping, wrap_t: Wrapping) -> Self {
+        self.texture = self.texture.wrap(wrap_s, wrap_t);
+        self
+    }
+
+    ///
+    /// Sets the format of the texture array.
+    ///
+    pub fn format(mut self, format: Format) -> Self {
+        self.texture = self.texture.format(format);
+        self
+    }
+
+    ///
+    /// Constructs the texture array with the current settings.
+    ///
+    pub fn build<T: TextureDataType>(
+        &self,
+        context: &Context,
+    ) -> Result<ColorTargetTexture2DArray<T>, Error> {
+        let texture = &self.texture;
+        ColorTargetTexture2DArray::new(
+            context,
+            texture.width,
+            texture.height,
+            self.layers,
+            texture.min_filter,
+            texture.mag_filter,
+            texture.mip_map_filter,
+            texture.wrap_s,
+            texture.wrap_t,
+            texture.format,
+        )
+    }
+}
+
+impl Default for ColorTargetTexture2DArrayBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let builder = ColorTargetTexture2DBuilder::new();
+        assert_eq!(builder, ColorTargetTexture2DBuilder::default());
+        assert_eq!((builder.width, builder.height), (1, 1));
+        assert_eq!(builder.min_filter, Interpolation::Linear);
+        assert_eq!(builder.mag_filter, Interpolation::Linear);
+        assert_eq!(builder.mip_map_filter, None);
+        assert_eq!(builder.wrap_s, Wrapping::ClampToEdge);
+        assert_eq!(builder.wrap_t, Wrapping::ClampToEdge);
+        assert_eq!(builder.format, Format::RGBA);
+
+        let builder = ColorTargetTexture2DArrayBuilder::new();
+        assert_eq!(builder, ColorTargetTexture2DArrayBuilder::default());
+        assert_eq!(builder.layers, 1);
+        assert_eq!(builder.texture, ColorTargetTexture2DBuilder::new());
+    }
+
+    #[test]
+    fn setters() {
+        let builder = ColorTargetTexture2DArrayBuilder::new()
+            .size(4, 2, 3)
+            .filter(Interpolation::Nearest, Interpolation::Linear)
+            .mipmaps(Some(Interpolation::Nearest))
+            .wrap(Wrapping::Repeat, Wrapping::MirroredRepeat)
+            .format(Format::RG);
+        assert_eq!(builder.layers, 3);
+        assert_eq!(
+            builder.texture,
+            ColorTargetTexture2DBuilder {
+                width: 4,
+                height: 2,
+                min_filter: Interpolation::Nearest,
+                mag_filter: Interpolation::Linear,
+                mip_map_filter: Some(Interpolation::Nearest),
+                wrap_s: Wrapping::Repeat,
+                wrap_t: Wrapping::MirroredRepeat,
+                format: Format::RG,
+            }
+        );
+    }
+}
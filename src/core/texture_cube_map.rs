@@ -12,6 +12,7 @@ pub struct TextureCubeMap {
     height: u32,
     format: Format,
     number_of_mip_maps: u32,
+    bytes: u64,
 }
 
 impl TextureCubeMap {
@@ -49,6 +50,14 @@ impl TextureCubeMap {
             cpu_texture.width,
             cpu_texture.height,
         );
+        let bytes = texture_bytes(
+            cpu_texture.width,
+            cpu_texture.height,
+            6,
+            color_texel_bytes::<T>(cpu_texture.format),
+            number_of_mip_maps,
+        );
+        add_texture_memory(context, bytes);
         let mut texture = Self {
             context: context.clone(),
             id,
@@ -56,11 +65,66 @@ impl TextureCubeMap {
             height: cpu_texture.height,
             format: cpu_texture.format,
             number_of_mip_maps,
+            bytes,
         };
         texture.fill(&cpu_texture.data)?;
         Ok(texture)
     }
 
+    ///
+    /// Constructs a new cube map texture where each side has the given size and no data, for example to render into using a
+    /// [RenderTargetCubeMap](crate::RenderTargetCubeMap). The texture uses linear interpolation and clamps to the edge.
+    ///
+    pub fn new_empty<T: TextureDataType>(
+        context: &Context,
+        size: u32,
+        format: Format,
+        mip_map_filter: Option<Interpolation>,
+    ) -> Result<TextureCubeMap, Error> {
+        let id = generate(context)?;
+        let number_of_mip_maps = calculate_number_of_mip_maps(mip_map_filter, size, size, 1);
+        set_parameters(
+            context,
+            &id,
+            consts::TEXTURE_CUBE_MAP,
+            Interpolation::Linear,
+            Interpolation::Linear,
+            if number_of_mip_maps == 1 {
+                None
+            } else {
+                mip_map_filter
+            },
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Some(Wrapping::ClampToEdge),
+        );
+        context.bind_texture(consts::TEXTURE_CUBE_MAP, &id);
+        context.tex_storage_2d(
+            consts::TEXTURE_CUBE_MAP,
+            number_of_mip_maps,
+            T::internal_format(format)?,
+            size,
+            size,
+        );
+        let bytes = texture_bytes(
+            size,
+            size,
+            6,
+            color_texel_bytes::<T>(format),
+            number_of_mip_maps,
+        );
+        add_texture_memory(context, bytes);
+        Ok(Self {
+            context: context.clone(),
+            id,
+            width: size,
+            height: size,
+            format,
+            number_of_mip_maps,
+            bytes,
+        })
+    }
+
     // data contains 6 images in the following order; right, left, top, bottom, front, back
     pub fn fill<T: TextureDataType>(&mut self, data: &[T]) -> Result<(), Error> {
         let offset = data.len() / 6;
@@ -88,6 +152,16 @@ impl TextureCubeMap {
             self.context.generate_mipmap(consts::TEXTURE_CUBE_MAP);
         }
     }
+
+    pub(super) fn bind_as_color_target(&self, side: CubeMapSide, mip_level: u32) {
+        self.context.framebuffer_texture_2d(
+            consts::FRAMEBUFFER,
+            consts::COLOR_ATTACHMENT0,
+            consts::TEXTURE_CUBE_MAP_POSITIVE_X + side as u32,
+            &self.id,
+            mip_level,
+        );
+    }
 }
 
 impl TextureCube for TextureCubeMap {
@@ -102,10 +176,14 @@ impl TextureCube for TextureCubeMap {
     fn height(&self) -> u32 {
         self.height
     }
+    fn context(&self) -> Option<&Context> {
+        Some(&self.context)
+    }
 }
 
 impl Drop for TextureCubeMap {
     fn drop(&mut self) {
+        remove_texture_memory(&self.context, self.bytes);
         self.context.delete_texture(&self.id);
     }
 }
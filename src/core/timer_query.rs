@@ -0,0 +1,121 @@
+use crate::context::{consts, Context};
+use crate::core::Error;
+
+// Same value for GL_TIME_ELAPSED (desktop) and GL_TIME_ELAPSED_EXT (EXT_disjoint_timer_query_webgl2)
+const TIME_ELAPSED: u32 = 0x88BF;
+
+///
+/// Measures the time the GPU spends on the graphics commands issued between a call to [begin](TimerQuery::begin) and [end](TimerQuery::end),
+/// for example a call to [generate_shadow_map](crate::DirectionalLight::generate_shadow_map) or the render closure given to [Screen::write](crate::Screen::write).
+/// The result is read back when it is available, usually one or more frames later, to avoid stalling the GPU.
+///
+/// **Note:** Only one timer query can be active at a time, so timer queries cannot overlap.
+/// To measure a pass containing another measured pass, measure the parts before, inside and after the inner pass separately and add them up.
+///
+pub struct TimerQuery {
+    context: Context,
+    id: crate::context::Query,
+    active: bool,
+    pending: bool,
+    elapsed_ms: Option<f64>,
+}
+
+impl TimerQuery {
+    ///
+    /// Creates a new timer query.
+    /// Returns an error if timer queries are not supported, which is the case on web if the `EXT_disjoint_timer_query_webgl2` extension is not available.
+    ///
+    pub fn new(context: &Context) -> Result<TimerQuery, Error> {
+        if !context.supports_timer_queries() {
+            Err(Error::QueryError {
+                message: "Timer queries are not supported by this graphics context.".to_string(),
+            })?;
+        }
+        let id = context.create_query().ok_or_else(|| Error::QueryError {
+            message: "Failed to create query".to_string(),
+        })?;
+        Ok(TimerQuery {
+            context: context.clone(),
+            id,
+            active: false,
+            pending: false,
+            elapsed_ms: None,
+        })
+    }
+
+    ///
+    /// Starts measuring. Must be followed by a call to [end](TimerQuery::end).
+    /// If the result of the previous measurement is not yet available, it is discarded.
+    ///
+    pub fn begin(&mut self) -> Result<(), Error> {
+        if self.active {
+            Err(Error::QueryError {
+                message: "The timer query is already active.".to_string(),
+            })?;
+        }
+        self.poll();
+        self.context.begin_query(TIME_ELAPSED, &self.id);
+        self.active = true;
+        Ok(())
+    }
+
+    ///
+    /// Stops measuring.
+    ///
+    pub fn end(&mut self) -> Result<(), Error> {
+        if !self.active {
+            Err(Error::QueryError {
+                message: "The timer query is not active.".to_string(),
+            })?;
+        }
+        self.context.end_query(TIME_ELAPSED);
+        self.active = false;
+        self.pending = true;
+        Ok(())
+    }
+
+    ///
+    /// Measures the graphics commands issued in the `measure` closure.
+    /// Equivalent to calling [begin](TimerQuery::begin), the closure and then [end](TimerQuery::end).
+    ///
+    pub fn measure<F: FnOnce() -> Result<(), Error>>(&mut self, measure: F) -> Result<(), Error> {
+        self.begin()?;
+        let result = measure();
+        self.end()?;
+        result
+    }
+
+    ///
+    /// Returns the GPU time in milliseconds of the latest measurement which result is available
+    /// or `None` if no result is available yet.
+    /// Never waits for the GPU to finish.
+    ///
+    pub fn elapsed_ms(&mut self) -> Option<f64> {
+        self.poll();
+        self.elapsed_ms
+    }
+
+    fn poll(&mut self) {
+        if self.pending
+            && self
+                .context
+                .get_query_parameter_u32(&self.id, consts::QUERY_RESULT_AVAILABLE)
+                != 0
+        {
+            let nanoseconds = self
+                .context
+                .get_query_parameter_u64(&self.id, consts::QUERY_RESULT);
+            self.pending = false;
+            // The result is invalid if the GPU was interrupted while measuring
+            if !self.context.is_timer_query_disjoint() {
+                self.elapsed_ms = Some(nanoseconds as f64 / 1_000_000.0);
+            }
+        }
+    }
+}
+
+impl Drop for TimerQuery {
+    fn drop(&mut self) {
+        self.context.delete_query(&self.id);
+    }
+}
@@ -22,12 +22,22 @@ impl UniformBuffer {
             offsets.push(length);
             length += *size as usize;
         }
-        Ok(UniformBuffer {
+        context.update_memory_stats(|stats| {
+            stats.uniform_buffers += 1;
+            stats.uniform_buffer_bytes += (length * std::mem::size_of::<f32>()) as u64;
+        });
+        let buffer = UniformBuffer {
             context: context.clone(),
             id,
             offsets,
             data: vec![0.0; length as usize],
-        })
+        };
+        buffer.send();
+        Ok(buffer)
+    }
+
+    pub(crate) fn context(&self) -> &Context {
+        &self.context
     }
 
     pub(crate) fn bind(&self, id: u32) {
@@ -36,9 +46,61 @@ impl UniformBuffer {
     }
 
     pub fn update(&mut self, index: u32, data: &[f32]) -> Result<(), Error> {
+        let (offset, length) = self.checked_offset_length(index, data)?;
+        self.data[offset..offset + length].copy_from_slice(data);
+        self.send_range(offset, length);
+        Ok(())
+    }
+
+    ///
+    /// Updates several of the uniform variables at once, where each element in `updates` is the index of a variable and the new data for that variable.
+    /// The updates are transferred to the GPU afterwards with one upload per contiguous range of updated variables,
+    /// which is cheaper than calling [update](Self::update) for each variable.
+    ///
+    /// # Errors
+    /// Returns an error, without updating any of the variables, if one of the indices is out of range or if the length of the data does not match.
+    ///
+    pub fn update_many(&mut self, updates: &[(u32, &[f32])]) -> Result<(), Error> {
+        let mut ranges = Vec::with_capacity(updates.len());
+        for (index, data) in updates {
+            ranges.push(self.checked_offset_length(*index, data)?);
+        }
+        for ((offset, length), (_, data)) in ranges.iter().zip(updates.iter()) {
+            self.data[*offset..*offset + *length].copy_from_slice(data);
+        }
+        ranges.sort_unstable();
+        let mut ranges = ranges.into_iter();
+        if let Some((mut start, length)) = ranges.next() {
+            let mut end = start + length;
+            for (offset, length) in ranges {
+                if offset > end {
+                    self.send_range(start, end - start);
+                    start = offset;
+                }
+                end = end.max(offset + length);
+            }
+            self.send_range(start, end - start);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, index: u32) -> Result<&[f32], Error> {
+        let (offset, length) = self.offset_length(index as usize)?;
+        Ok(&self.data[offset..offset + length])
+    }
+
+    ///
+    /// Sets a label on the buffer which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.context.set_buffer_label(&self.id, label);
+    }
+
+    fn checked_offset_length(&self, index: u32, data: &[f32]) -> Result<(usize, usize), Error> {
         let (offset, length) = self.offset_length(index as usize)?;
         if data.len() != length {
-            return Err(Error::BufferError {
+            return Err(Error::BufferOverflow {
                 message: format!(
                     "The uniform buffer data for index {} has length {} but it must be {}.",
                     index,
@@ -47,21 +109,12 @@ impl UniformBuffer {
                 ),
             });
         }
-        self.data
-            .splice(offset..offset + length, data.iter().cloned());
-        self.send();
-        //TODO: Send to GPU (contextBufferSubData)
-        Ok(())
-    }
-
-    pub fn get(&self, index: u32) -> Result<&[f32], Error> {
-        let (offset, length) = self.offset_length(index as usize)?;
-        Ok(&self.data[offset..offset + length])
+        Ok((offset, length))
     }
 
     fn offset_length(&self, index: usize) -> Result<(usize, usize), Error> {
         if index >= self.offsets.len() {
-            return Err(Error::BufferError {
+            return Err(Error::BufferOverflow {
                 message: format!(
                     "The uniform buffer index {} is outside the range 0-{}",
                     index,
@@ -84,10 +137,25 @@ impl UniformBuffer {
             .buffer_data_f32(consts::UNIFORM_BUFFER, &self.data, consts::STATIC_DRAW);
         self.context.unbind_buffer(consts::UNIFORM_BUFFER);
     }
+
+    fn send_range(&self, offset: usize, length: usize) {
+        self.context.bind_buffer(consts::UNIFORM_BUFFER, &self.id);
+        self.context.buffer_sub_data_f32(
+            consts::UNIFORM_BUFFER,
+            (offset * std::mem::size_of::<f32>()) as u32,
+            &self.data[offset..offset + length],
+        );
+        self.context.unbind_buffer(consts::UNIFORM_BUFFER);
+    }
 }
 
 impl Drop for UniformBuffer {
     fn drop(&mut self) {
+        let bytes = std::mem::size_of_val(&self.data[..]) as u64;
+        self.context.update_memory_stats(|stats| {
+            stats.uniform_buffers -= 1;
+            stats.uniform_buffer_bytes -= bytes;
+        });
         self.context.delete_buffer(&self.id);
     }
 }
@@ -10,6 +10,7 @@ pub struct VertexBuffer {
     id: crate::context::Buffer,
     count: usize,
     data_type: u32,
+    bytes: u64,
 }
 
 impl VertexBuffer {
@@ -17,11 +18,13 @@ impl VertexBuffer {
     /// Creates a new empty vertex buffer.
     ///
     pub fn new(context: &Context) -> Result<VertexBuffer, Error> {
+        context.update_memory_stats(|stats| stats.vertex_buffers += 1);
         Ok(VertexBuffer {
             context: context.clone(),
             id: context.create_buffer().unwrap(),
             count: 0,
             data_type: consts::FLOAT,
+            bytes: 0,
         })
     }
 
@@ -57,6 +60,7 @@ impl VertexBuffer {
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ARRAY_BUFFER);
         self.count = data.len();
+        self.set_bytes(std::mem::size_of_val(data) as u64);
     }
 
     ///
@@ -91,6 +95,41 @@ impl VertexBuffer {
         self.data_type = T::data_type();
         self.context.unbind_buffer(consts::ARRAY_BUFFER);
         self.count = data.len();
+        self.set_bytes(std::mem::size_of_val(data) as u64);
+    }
+
+    ///
+    /// Replaces the values in the buffer starting at the given offset (in number of values, not bytes) with the given data,
+    /// without reallocating the buffer. This is cheaper than filling the entire buffer when only a few values change.
+    ///
+    /// # Errors
+    /// Returns an error if the data does not fit in the buffer or if the buffer does not contain `f32` values.
+    ///
+    pub fn fill_subset_f32(&mut self, offset: usize, data: &[f32]) -> Result<(), Error> {
+        if self.data_type != consts::FLOAT {
+            Err(Error::BufferError {
+                message: "Cannot fill a part of a vertex buffer which does not contain f32 values"
+                    .to_owned(),
+            })?;
+        }
+        if offset + data.len() > self.count {
+            Err(Error::BufferOverflow {
+                message: format!(
+                    "Cannot fill {} values at offset {} in a vertex buffer with {} values",
+                    data.len(),
+                    offset,
+                    self.count
+                ),
+            })?;
+        }
+        self.bind();
+        self.context.buffer_sub_data_f32(
+            consts::ARRAY_BUFFER,
+            (offset * std::mem::size_of::<f32>()) as u32,
+            data,
+        );
+        self.context.unbind_buffer(consts::ARRAY_BUFFER);
+        Ok(())
     }
 
     ///
@@ -100,17 +139,49 @@ impl VertexBuffer {
         self.count
     }
 
+    ///
+    /// Sets a label on the buffer which is shown in graphics debuggers like RenderDoc.
+    /// Only has an effect when the `debug-labels` feature is enabled and supported by the graphics driver.
+    ///
+    pub fn set_label(&self, label: &str) {
+        self.bind();
+        self.context.set_buffer_label(&self.id, label);
+    }
+
+    fn set_bytes(&mut self, bytes: u64) {
+        let old_bytes = self.bytes;
+        self.context.update_memory_stats(|stats| {
+            stats.vertex_buffer_bytes = stats.vertex_buffer_bytes - old_bytes + bytes
+        });
+        self.bytes = bytes;
+    }
+
     pub(crate) fn data_type(&self) -> u32 {
         self.data_type
     }
 
+    pub(crate) fn context(&self) -> &Context {
+        &self.context
+    }
+
     pub(crate) fn bind(&self) {
         self.context.bind_buffer(consts::ARRAY_BUFFER, &self.id);
     }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn bind_as_transform_feedback(&self, index: u32) {
+        self.context
+            .bind_transform_feedback_buffer(index, Some(&self.id));
+    }
 }
 
 impl Drop for VertexBuffer {
     fn drop(&mut self) {
+        let bytes = self.bytes;
+        self.context.update_memory_stats(|stats| {
+            stats.vertex_buffers -= 1;
+            stats.vertex_buffer_bytes -= bytes;
+        });
         self.context.delete_buffer(&self.id);
     }
 }
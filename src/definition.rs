@@ -15,3 +15,7 @@ pub use cpu_material::*;
 mod cpu_texture;
 #[doc(inline)]
 pub use cpu_texture::*;
+
+mod bvh;
+#[doc(inline)]
+pub use bvh::*;
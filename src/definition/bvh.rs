@@ -0,0 +1,212 @@
+use crate::definition::*;
+use crate::math::*;
+
+const MAX_TRIANGLES_IN_LEAF: usize = 4;
+
+struct Node {
+    aabb: AxisAlignedBoundingBox,
+    // For a leaf, the range of triangles, otherwise the index of the first child followed by the index of the second child.
+    start: usize,
+    end: usize,
+    is_leaf: bool,
+}
+
+///
+/// A bounding volume hierarchy over the triangles of a [CPUMesh] which speeds up ray queries, for example picking, placing objects on a surface or simple collision tests,
+/// compared to testing all triangles using [CPUMesh::raycast].
+/// The hierarchy is built once using [build](Bvh::build) and must be rebuilt if the positions or indices of the mesh change.
+///
+pub struct Bvh {
+    nodes: Vec<Node>,
+    triangles: Vec<(usize, [Vec3; 3])>,
+}
+
+impl Bvh {
+    ///
+    /// Builds a bounding volume hierarchy over the triangles of the given mesh by recursively splitting the triangles at the median along the longest axis.
+    ///
+    pub fn build(cpu_mesh: &CPUMesh) -> Self {
+        let position = |index: u32| {
+            let i = 3 * index as usize;
+            vec3(
+                cpu_mesh.positions[i],
+                cpu_mesh.positions[i + 1],
+                cpu_mesh.positions[i + 2],
+            )
+        };
+        let mut triangles = cpu_mesh
+            .triangle_indices()
+            .chunks_exact(3)
+            .enumerate()
+            .map(|(i, t)| (i, [position(t[0]), position(t[1]), position(t[2])]))
+            .collect::<Vec<_>>();
+        let mut nodes = Vec::new();
+        if !triangles.is_empty() {
+            let count = triangles.len();
+            build_node(&mut nodes, &mut triangles, 0, count);
+        }
+        Self { nodes, triangles }
+    }
+
+    ///
+    /// Finds the closest intersection between the given ray and the triangles of the mesh.
+    /// The ray must be in the same space as the positions of the mesh, use [Ray::transformed] with the inverse transformation of the mesh to
+    /// transform a ray in world space; the distance along the ray is the same in both spaces.
+    /// The triangle index of the hit is the same as for [CPUMesh::raycast].
+    /// Returns `None` if the mesh is not hit.
+    ///
+    pub fn raycast(&self, ray: &Ray) -> Option<Hit> {
+        let mut closest: Option<Hit> = None;
+        let mut stack = Vec::new();
+        if let Some(distance) = self.nodes.first().and_then(|n| ray.intersect_aabb(&n.aabb)) {
+            stack.push((0, distance));
+        }
+        while let Some((index, distance)) = stack.pop() {
+            if closest.map(|hit| hit.distance < distance).unwrap_or(false) {
+                continue;
+            }
+            let node = &self.nodes[index];
+            if node.is_leaf {
+                for (triangle_index, [a, b, c]) in self.triangles[node.start..node.end].iter() {
+                    if let Some((distance, u, v)) = ray.intersect_triangle(*a, *b, *c) {
+                        if closest.map(|hit| distance < hit.distance).unwrap_or(true) {
+                            closest = Some(Hit {
+                                distance,
+                                position: ray.at(distance),
+                                triangle_index: *triangle_index,
+                                barycentric: vec3(1.0 - u - v, u, v),
+                            });
+                        }
+                    }
+                }
+            } else {
+                let first = ray
+                    .intersect_aabb(&self.nodes[node.start].aabb)
+                    .map(|d| (node.start, d));
+                let second = ray
+                    .intersect_aabb(&self.nodes[node.end].aabb)
+                    .map(|d| (node.end, d));
+                // Push the furthest child first so the nearest child is visited first
+                match (first, second) {
+                    (Some(f), Some(s)) if f.1 < s.1 => {
+                        stack.push(s);
+                        stack.push(f);
+                    }
+                    (Some(f), Some(s)) => {
+                        stack.push(f);
+                        stack.push(s);
+                    }
+                    (Some(f), None) => stack.push(f),
+                    (None, Some(s)) => stack.push(s),
+                    (None, None) => {}
+                }
+            }
+        }
+        closest
+    }
+}
+
+fn build_node(
+    nodes: &mut Vec<Node>,
+    triangles: &mut [(usize, [Vec3; 3])],
+    start: usize,
+    end: usize,
+) -> usize {
+    let mut aabb = AxisAlignedBoundingBox::new_with_positions(&[]);
+    let mut centers = AxisAlignedBoundingBox::new_with_positions(&[]);
+    for (_, [a, b, c]) in triangles[start..end].iter() {
+        aabb.expand(&[a.x, a.y, a.z, b.x, b.y, b.z, c.x, c.y, c.z]);
+        let center = (a + b + c) / 3.0;
+        centers.expand(&[center.x, center.y, center.z]);
+    }
+    let index = nodes.len();
+    nodes.push(Node {
+        aabb,
+        start,
+        end,
+        is_leaf: true,
+    });
+    if end - start > MAX_TRIANGLES_IN_LEAF {
+        let size = centers.max() - centers.min();
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+        let middle = (start + end) / 2;
+        triangles[start..end].select_nth_unstable_by(middle - start, |(_, t0), (_, t1)| {
+            let c0 = t0[0][axis] + t0[1][axis] + t0[2][axis];
+            let c1 = t1[0][axis] + t1[1][axis] + t1[2][axis];
+            c0.partial_cmp(&c1).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let first = build_node(nodes, triangles, start, middle);
+        let second = build_node(nodes, triangles, middle, end);
+        nodes[index].start = first;
+        nodes[index].end = second;
+        nodes[index].is_leaf = false;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn raycast_same_as_brute_force_on_random_mesh() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut point = |scale: f32| {
+            vec3(
+                rng.gen_range(-scale, scale),
+                rng.gen_range(-scale, scale),
+                rng.gen_range(-scale, scale),
+            )
+        };
+        let mut positions = Vec::new();
+        for _ in 0..500 {
+            let center = point(1.0);
+            for _ in 0..3 {
+                let corner = center + 0.2 * point(1.0);
+                positions.extend_from_slice(&[corner.x, corner.y, corner.z]);
+            }
+        }
+        let rays = (0..500)
+            .map(|_| {
+                let origin = point(3.0);
+                Ray::new(origin, point(1.0) - origin)
+            })
+            .collect::<Vec<_>>();
+        let mesh = CPUMesh {
+            positions,
+            ..Default::default()
+        };
+        let bvh = Bvh::build(&mesh);
+
+        let mut hits = 0;
+        for ray in rays.iter() {
+            let expected = mesh.raycast(ray, &Mat4::identity());
+            let actual = bvh.raycast(ray);
+            match (expected, actual) {
+                (Some(expected), Some(actual)) => {
+                    assert_eq!(expected.triangle_index, actual.triangle_index);
+                    assert!((expected.distance - actual.distance).abs() < 1.0e-5);
+                    assert!((expected.barycentric - actual.barycentric).magnitude() < 1.0e-5);
+                    hits += 1;
+                }
+                (None, None) => {}
+                _ => panic!("expected {:?} but got {:?} for {:?}", expected, actual, ray),
+            }
+        }
+        assert!(hits > 100, "only {} of the rays hit the mesh", hits);
+    }
+
+    #[test]
+    fn raycast_empty_mesh() {
+        let bvh = Bvh::build(&CPUMesh::default());
+        let ray = Ray::new(vec3(0.0, 0.0, 1.0), vec3(0.0, 0.0, -1.0));
+        assert_eq!(bvh.raycast(&ray), None);
+    }
+}
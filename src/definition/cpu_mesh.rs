@@ -1,3 +1,4 @@
+use crate::core::{Error, PrimitiveType};
 use crate::math::*;
 
 #[derive(Debug)]
@@ -15,6 +16,17 @@ impl Indices {
             Self::U32(ind) => ind.clone(),
         }
     }
+
+    ///
+    /// The index which restarts a [triangle strip](crate::PrimitiveType::TriangleStrip), ie. the maximum value of the index type.
+    ///
+    pub fn primitive_restart_index(&self) -> u32 {
+        match self {
+            Self::U8(_) => u8::MAX as u32,
+            Self::U16(_) => u16::MAX as u32,
+            Self::U32(_) => u32::MAX,
+        }
+    }
 }
 
 ///
@@ -28,9 +40,45 @@ pub struct CPUMesh {
     pub material_name: Option<String>,
     pub positions: Vec<f32>,
     pub indices: Option<Indices>,
+    ///
+    /// Defines how the vertices (or indices if present) are assembled into triangles.
+    /// A [triangle strip](crate::PrimitiveType::TriangleStrip) with indices can be split into several strips
+    /// by inserting the [primitive restart index](Indices::primitive_restart_index).
+    /// Most of the functionality on [CPUMesh] and the default objects, except [Mesh](crate::Mesh), only support [PrimitiveType::Triangles].
+    ///
+    pub primitive_type: PrimitiveType,
     pub normals: Option<Vec<f32>>,
     pub uvs: Option<Vec<f32>>,
+    ///
+    /// The per vertex colors as four `u8` values (red, green, blue and alpha) per vertex in gamma color space (sRGBA),
+    /// see for example [Mesh::render_color](crate::Mesh::render_color).
+    ///
     pub colors: Option<Vec<u8>>,
+    ///
+    /// The indices of the (up to) four joints which influence each vertex, used for [skinning](crate::SkinnedMesh).
+    ///
+    pub joint_indices: Option<Vec<u32>>,
+    ///
+    /// The weights of the (up to) four joints which influence each vertex, used for [skinning](crate::SkinnedMesh).
+    /// The weights of a vertex should sum to one.
+    ///
+    pub joint_weights: Option<Vec<f32>>,
+    ///
+    /// The morph targets (blend shapes) of the mesh, see [MorphMesh](crate::MorphMesh).
+    ///
+    pub morph_targets: Vec<MorphTarget>,
+}
+
+///
+/// A morph target (blend shape) of a [CPUMesh] which defines a displacement of each vertex position and optionally of each vertex normal.
+/// The displaced vertex position is given by `position + weight * position_delta` where the weight is set when rendering,
+/// see [MorphMesh](crate::MorphMesh).
+///
+#[derive(Default, Debug)]
+pub struct MorphTarget {
+    pub name: String,
+    pub position_deltas: Vec<f32>,
+    pub normal_deltas: Option<Vec<f32>>,
 }
 
 impl CPUMesh {
@@ -53,6 +101,47 @@ impl CPUMesh {
         }
     }
 
+    pub fn cube(size: f32) -> Self {
+        let halfsize = 0.5 * size;
+        let mut positions = Vec::with_capacity(72);
+        let mut normals = Vec::with_capacity(72);
+        let mut uvs = Vec::with_capacity(48);
+        let mut indices = Vec::with_capacity(36);
+        for axis in 0..3 {
+            for &sign in [-1.0f32, 1.0].iter() {
+                let mut normal = vec3(0.0, 0.0, 0.0);
+                normal[axis] = sign;
+                let mut tangent = vec3(0.0, 0.0, 0.0);
+                tangent[(axis + 1) % 3] = 1.0;
+                let bitangent = normal.cross(tangent);
+                let start = positions.len() as u8 / 3;
+                for (u, v) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].iter() {
+                    let p = halfsize
+                        * (normal + (2.0 * u - 1.0) * tangent + (2.0 * v - 1.0) * bitangent);
+                    positions.extend_from_slice(&[p.x, p.y, p.z]);
+                    normals.extend_from_slice(&[normal.x, normal.y, normal.z]);
+                    uvs.extend_from_slice(&[*u, *v]);
+                }
+                indices.extend_from_slice(&[
+                    start,
+                    start + 1,
+                    start + 2,
+                    start + 2,
+                    start + 3,
+                    start,
+                ]);
+            }
+        }
+        CPUMesh {
+            name: "cube".to_string(),
+            indices: Some(Indices::U8(indices)),
+            positions,
+            normals: Some(normals),
+            uvs: Some(uvs),
+            ..Default::default()
+        }
+    }
+
     pub fn circle(radius: f32, angle_subdivisions: u32) -> Self {
         let mut positions = Vec::new();
         let mut indices = Vec::new();
@@ -198,17 +287,653 @@ impl CPUMesh {
         arrow
     }
 
+    ///
+    /// Constructs a terrain mesh from a grid of `width` times `depth` height samples given in row-major order,
+    /// ie. the height at grid coordinate `(x, z)` is `heights[z * width + x]`.
+    /// The vertex at grid coordinate `(x, z)` is the vertex with index `z * width + x` and is placed at
+    /// `(x * scale.x, height * scale.y, z * scale.z)`, so `scale.x` and `scale.z` are the distances between neighbouring samples.
+    /// The normals are computed from the height differences to the neighbouring samples (one-sided at the edges of the grid)
+    /// and the uv coordinates span `[0, 1]` across the grid. The triangles are facing up (positive y).
+    ///
+    /// # Errors
+    /// Returns a [MeshError](crate::Error::MeshError) if the width or depth is less than 2 or if the number of heights is not `width * depth`.
+    ///
+    pub fn from_heightmap(
+        heights: &[f32],
+        width: u32,
+        depth: u32,
+        scale: Vec3,
+    ) -> Result<Self, Error> {
+        Self::from_heightmap_with_primitive_type(
+            heights,
+            width,
+            depth,
+            scale,
+            PrimitiveType::Triangles,
+        )
+    }
+
+    ///
+    /// Same as [from_heightmap](Self::from_heightmap), except that the mesh can be constructed as a [triangle strip](crate::PrimitiveType::TriangleStrip)
+    /// with one strip per row of the grid, separated by the [primitive restart index](Indices::primitive_restart_index),
+    /// or as [points](crate::PrimitiveType::Points) without indices.
+    ///
+    pub fn from_heightmap_with_primitive_type(
+        heights: &[f32],
+        width: u32,
+        depth: u32,
+        scale: Vec3,
+        primitive_type: PrimitiveType,
+    ) -> Result<Self, Error> {
+        if width < 2 || depth < 2 {
+            Err(mesh_error(
+                "heightmap",
+                format!(
+                    "the size of the height map ({}x{}) must be at least 2x2",
+                    width, depth
+                ),
+            ))?;
+        }
+        if heights.len() != (width * depth) as usize {
+            Err(mesh_error(
+                "heightmap",
+                format!(
+                    "the number of heights ({}) does not match the size of the height map ({}x{})",
+                    heights.len(),
+                    width,
+                    depth
+                ),
+            ))?;
+        }
+        let (w, d) = (width as usize, depth as usize);
+        let height = |x: usize, z: usize| heights[z * w + x] * scale.y;
+        let vertex_count = w * d;
+        let mut positions = Vec::with_capacity(vertex_count * 3);
+        let mut normals = Vec::with_capacity(vertex_count * 3);
+        let mut uvs = Vec::with_capacity(vertex_count * 2);
+        for z in 0..d {
+            for x in 0..w {
+                positions.extend_from_slice(&[
+                    x as f32 * scale.x,
+                    height(x, z),
+                    z as f32 * scale.z,
+                ]);
+                let (x0, x1) = (x.saturating_sub(1), (x + 1).min(w - 1));
+                let (z0, z1) = (z.saturating_sub(1), (z + 1).min(d - 1));
+                let dx = (height(x1, z) - height(x0, z)) / ((x1 - x0) as f32 * scale.x);
+                let dz = (height(x, z1) - height(x, z0)) / ((z1 - z0) as f32 * scale.z);
+                let normal = vec3(-dx, 1.0, -dz).normalize();
+                normals.extend_from_slice(&[normal.x, normal.y, normal.z]);
+                uvs.extend_from_slice(&[x as f32 / (w - 1) as f32, z as f32 / (d - 1) as f32]);
+            }
+        }
+
+        let index = |x: usize, z: usize| (z * w + x) as u32;
+        let mut indices = Vec::new();
+        match primitive_type {
+            PrimitiveType::Triangles => {
+                for z in 0..d - 1 {
+                    for x in 0..w - 1 {
+                        indices.extend_from_slice(&[
+                            index(x, z),
+                            index(x, z + 1),
+                            index(x + 1, z),
+                            index(x + 1, z),
+                            index(x, z + 1),
+                            index(x + 1, z + 1),
+                        ]);
+                    }
+                }
+            }
+            PrimitiveType::TriangleStrip => {
+                for z in 0..d - 1 {
+                    if z > 0 {
+                        indices.push(u32::MAX);
+                    }
+                    for x in 0..w {
+                        indices.push(index(x, z));
+                        indices.push(index(x, z + 1));
+                    }
+                }
+            }
+            PrimitiveType::Points => {}
+        }
+        let indices = if primitive_type == PrimitiveType::Points {
+            None
+        } else if vertex_count < u16::MAX as usize {
+            Some(Indices::U16(indices.iter().map(|i| *i as u16).collect()))
+        } else {
+            Some(Indices::U32(indices))
+        };
+        Ok(CPUMesh {
+            name: "heightmap".to_string(),
+            indices,
+            primitive_type,
+            positions,
+            normals: Some(normals),
+            uvs: Some(uvs),
+            ..Default::default()
+        })
+    }
+
     ///
     /// Computes the per vertex normals and updates the normals of the mesh.
     /// It will override the current normals if they already exist.
+    /// Does nothing for [points](crate::PrimitiveType::Points) since they have no surface.
     ///
     pub fn compute_normals(&mut self) {
-        self.normals = Some(
-            self.indices
+        self.normals = Some(match self.primitive_type {
+            PrimitiveType::Triangles => self
+                .indices
                 .as_ref()
                 .map(|indices| compute_normals_with_indices(&indices.into_u32(), &self.positions))
                 .unwrap_or(compute_normals(&self.positions)),
+            PrimitiveType::TriangleStrip => {
+                compute_normals_with_indices(&self.triangle_indices(), &self.positions)
+            }
+            PrimitiveType::Points => return,
+        });
+    }
+
+    ///
+    /// Computes texture coordinates by projecting the positions onto the plane orthogonal to the given axis and updates the uvs of the mesh.
+    /// The texture coordinates are scaled so that they span the range `[0, 1]` in both directions.
+    /// It will override the current uvs if they already exist.
+    ///
+    pub fn generate_planar_uvs(&mut self, axis: Vec3) {
+        let axis = axis.normalize();
+        let helper = if axis.x.abs() < 0.9 {
+            vec3(1.0, 0.0, 0.0)
+        } else {
+            vec3(0.0, 1.0, 0.0)
+        };
+        let tangent = helper.cross(axis).normalize();
+        let bitangent = axis.cross(tangent);
+
+        let projected = self
+            .positions
+            .chunks(3)
+            .map(|p| {
+                let p = vec3(p[0], p[1], p[2]);
+                vec2(p.dot(tangent), p.dot(bitangent))
+            })
+            .collect::<Vec<_>>();
+        let mut min = vec2(f32::INFINITY, f32::INFINITY);
+        let mut max = vec2(f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for p in projected.iter() {
+            min = vec2(min.x.min(p.x), min.y.min(p.y));
+            max = vec2(max.x.max(p.x), max.y.max(p.y));
+        }
+        let size = vec2(
+            (max.x - min.x).max(f32::EPSILON),
+            (max.y - min.y).max(f32::EPSILON),
+        );
+        let mut uvs = Vec::with_capacity(projected.len() * 2);
+        for p in projected {
+            uvs.push((p.x - min.x) / size.x);
+            uvs.push((p.y - min.y) / size.y);
+        }
+        self.uvs = Some(uvs);
+    }
+
+    ///
+    /// Computes texture coordinates by projecting the positions onto a sphere around the center of the [axis aligned bounding box](CPUMesh::compute_aabb)
+    /// and updates the uvs of the mesh. The u coordinate is given by the longitude around the y-axis and the v coordinate by the latitude.
+    /// It will override the current uvs if they already exist.
+    ///
+    /// **Note:** Triangles crossing the seam where the longitude wraps around (at the negative x-axis) will interpolate
+    /// across the whole texture, since vertices are not duplicated along the seam.
+    ///
+    pub fn generate_spherical_uvs(&mut self) {
+        let aabb = self.compute_aabb();
+        let center = 0.5 * (aabb.min() + aabb.max());
+        let mut uvs = Vec::with_capacity(self.positions.len() / 3 * 2);
+        for p in self.positions.chunks(3) {
+            let d = vec3(p[0], p[1], p[2]) - center;
+            let length = d.magnitude();
+            let (u, v) = if length > f32::EPSILON {
+                (
+                    0.5 + d.z.atan2(d.x) / (2.0 * std::f32::consts::PI),
+                    1.0 - (d.y / length).clamp(-1.0, 1.0).acos() / std::f32::consts::PI,
+                )
+            } else {
+                (0.5, 0.5)
+            };
+            uvs.push(u);
+            uvs.push(v);
+        }
+        self.uvs = Some(uvs);
+    }
+
+    ///
+    /// Reverses the winding order of all triangles, ie. swaps the second and third vertex of each triangle, and negates the normals if present.
+    /// Use this to fix meshes which are invisible or lit from the wrong side because of inverted winding order, for example when using [CullType::Back](crate::CullType::Back).
+    /// A [triangle strip](crate::PrimitiveType::TriangleStrip) is flipped by repeating the first index of each strip, which generates indices if the mesh has none.
+    /// Does nothing for [points](crate::PrimitiveType::Points).
+    ///
+    pub fn flip_winding(&mut self) {
+        if self.primitive_type == PrimitiveType::Points {
+            return;
+        }
+        if self.primitive_type == PrimitiveType::TriangleStrip {
+            let mut indices = Vec::new();
+            let restart = self.restart_index();
+            for strip in self.strip_indices().split(|i| *i == restart) {
+                if !indices.is_empty() {
+                    indices.push(restart);
+                }
+                if let Some(first) = strip.first() {
+                    indices.push(*first);
+                }
+                indices.extend_from_slice(strip);
+            }
+            self.indices = Some(match self.indices {
+                Some(Indices::U8(_)) => Indices::U8(indices.iter().map(|i| *i as u8).collect()),
+                Some(Indices::U32(_)) => Indices::U32(indices),
+                _ if restart == u16::MAX as u32 => {
+                    Indices::U16(indices.iter().map(|i| *i as u16).collect())
+                }
+                _ => Indices::U32(indices),
+            });
+            self.flip_normals();
+            return;
+        }
+        match self.indices {
+            Some(Indices::U8(ref mut indices)) => flip_triangles(indices, 1),
+            Some(Indices::U16(ref mut indices)) => flip_triangles(indices, 1),
+            Some(Indices::U32(ref mut indices)) => flip_triangles(indices, 1),
+            None => {
+                flip_triangles(&mut self.positions, 3);
+                if let Some(ref mut normals) = self.normals {
+                    flip_triangles(normals, 3);
+                }
+                if let Some(ref mut uvs) = self.uvs {
+                    flip_triangles(uvs, 2);
+                }
+                if let Some(ref mut colors) = self.colors {
+                    flip_triangles(colors, 4);
+                }
+                if let Some(ref mut joint_indices) = self.joint_indices {
+                    flip_triangles(joint_indices, 4);
+                }
+                if let Some(ref mut joint_weights) = self.joint_weights {
+                    flip_triangles(joint_weights, 4);
+                }
+                for target in self.morph_targets.iter_mut() {
+                    flip_triangles(&mut target.position_deltas, 3);
+                    if let Some(ref mut normal_deltas) = target.normal_deltas {
+                        flip_triangles(normal_deltas, 3);
+                    }
+                }
+            }
+        }
+        self.flip_normals();
+    }
+
+    ///
+    /// Negates the normals of the mesh if present, without changing the winding order of the triangles.
+    ///
+    pub fn flip_normals(&mut self) {
+        if let Some(ref mut normals) = self.normals {
+            normals.iter_mut().for_each(|n| *n = -*n);
+        }
+        for target in self.morph_targets.iter_mut() {
+            if let Some(ref mut normal_deltas) = target.normal_deltas {
+                normal_deltas.iter_mut().for_each(|n| *n = -*n);
+            }
+        }
+    }
+
+    ///
+    /// Appends the given mesh to this mesh after transforming it by the given transformation.
+    /// The positions of the other mesh are transformed by the transformation and the normals by the inverse transpose of the upper 3x3 part of the transformation,
+    /// while the uvs and colors are copied as is. The indices of the other mesh are offset by the number of vertices in this mesh.
+    /// If only one of the meshes has indices, indices are generated for the other mesh.
+    ///
+    /// # Errors
+    /// Returns a [MeshError](crate::Error::MeshError) if one of the meshes is not [valid](CPUMesh::validate) or is not a list of [triangles](crate::PrimitiveType::Triangles),
+    /// if the meshes do not have the same vertex attributes and morph targets (unless this mesh is empty, in which case it adopts the attributes of the other mesh)
+    /// or if normals are present and the transformation is not invertible.
+    ///
+    pub fn append(&mut self, other: &CPUMesh, transform: &Mat4) -> Result<(), Error> {
+        self.validate()?;
+        other.validate()?;
+        self.validate_triangles()?;
+        other.validate_triangles()?;
+        let vertex_count = self.positions.len() / 3;
+        if vertex_count == 0 {
+            self.normals = other.normals.as_ref().map(|_| Vec::new());
+            self.uvs = other.uvs.as_ref().map(|_| Vec::new());
+            self.colors = other.colors.as_ref().map(|_| Vec::new());
+            self.joint_indices = other.joint_indices.as_ref().map(|_| Vec::new());
+            self.joint_weights = other.joint_weights.as_ref().map(|_| Vec::new());
+            self.morph_targets = other
+                .morph_targets
+                .iter()
+                .map(|target| MorphTarget {
+                    name: target.name.clone(),
+                    position_deltas: Vec::new(),
+                    normal_deltas: target.normal_deltas.as_ref().map(|_| Vec::new()),
+                })
+                .collect();
+            self.indices = None;
+        }
+        let check_attribute = |name: &str, this: bool, other_has: bool| {
+            if this != other_has {
+                Err(mesh_error(
+                    &self.name,
+                    format!(
+                        "cannot append the mesh '{}' since only one of the meshes has {}",
+                        other.name, name
+                    ),
+                ))?;
+            }
+            Ok(())
+        };
+        check_attribute("normals", self.normals.is_some(), other.normals.is_some())?;
+        check_attribute("uvs", self.uvs.is_some(), other.uvs.is_some())?;
+        check_attribute("colors", self.colors.is_some(), other.colors.is_some())?;
+        check_attribute(
+            "joints",
+            self.joint_indices.is_some(),
+            other.joint_indices.is_some(),
+        )?;
+        if self.morph_targets.len() != other.morph_targets.len() {
+            Err(mesh_error(
+                &self.name,
+                format!(
+                    "cannot append the mesh '{}' since the meshes have a different number of morph targets",
+                    other.name
+                ),
+            ))?;
+        }
+        for (target, other_target) in self.morph_targets.iter().zip(other.morph_targets.iter()) {
+            check_attribute(
+                "normal deltas for a morph target",
+                target.normal_deltas.is_some(),
+                other_target.normal_deltas.is_some(),
+            )?;
+        }
+
+        let linear = Mat3::from_cols(
+            transform.x.truncate(),
+            transform.y.truncate(),
+            transform.z.truncate(),
         );
+        let normal_matrix = linear.invert().map(|m| m.transpose());
+        let has_normals = other.normals.is_some()
+            || other
+                .morph_targets
+                .iter()
+                .any(|target| target.normal_deltas.is_some());
+        if has_normals && normal_matrix.is_none() {
+            Err(mesh_error(
+                &self.name,
+                "cannot transform the normals since the transformation is not invertible"
+                    .to_string(),
+            ))?;
+        }
+        for (target, other_target) in self
+            .morph_targets
+            .iter_mut()
+            .zip(other.morph_targets.iter())
+        {
+            for d in other_target.position_deltas.chunks(3) {
+                let delta = linear * vec3(d[0], d[1], d[2]);
+                target.position_deltas.extend_from_slice(&delta.to_slice());
+            }
+            if let Some(ref other_normal_deltas) = other_target.normal_deltas {
+                let normal_matrix = normal_matrix.unwrap();
+                let normal_deltas = target.normal_deltas.as_mut().unwrap();
+                for d in other_normal_deltas.chunks(3) {
+                    let delta = normal_matrix * vec3(d[0], d[1], d[2]);
+                    normal_deltas.extend_from_slice(&delta.to_slice());
+                }
+            }
+        }
+        if let Some(ref other_normals) = other.normals {
+            let normal_matrix = normal_matrix.unwrap();
+            let normals = self.normals.as_mut().unwrap();
+            for n in other_normals.chunks(3) {
+                let normal = (normal_matrix * vec3(n[0], n[1], n[2])).normalize();
+                normals.extend_from_slice(&normal.to_slice());
+            }
+        }
+        for p in other.positions.chunks(3) {
+            let position = transform * vec4(p[0], p[1], p[2], 1.0);
+            self.positions
+                .extend_from_slice(&[position.x, position.y, position.z]);
+        }
+        if let Some(ref other_uvs) = other.uvs {
+            self.uvs.as_mut().unwrap().extend_from_slice(other_uvs);
+        }
+        if let Some(ref other_colors) = other.colors {
+            self.colors
+                .as_mut()
+                .unwrap()
+                .extend_from_slice(other_colors);
+        }
+        if let Some(ref other_joint_indices) = other.joint_indices {
+            self.joint_indices
+                .as_mut()
+                .unwrap()
+                .extend_from_slice(other_joint_indices);
+        }
+        if let Some(ref other_joint_weights) = other.joint_weights {
+            self.joint_weights
+                .as_mut()
+                .unwrap()
+                .extend_from_slice(other_joint_weights);
+        }
+
+        if self.indices.is_some() || other.indices.is_some() {
+            let other_vertex_count = other.positions.len() / 3;
+            let mut indices = self
+                .indices
+                .as_ref()
+                .map(|indices| indices.into_u32())
+                .unwrap_or_else(|| (0..vertex_count as u32).collect());
+            let other_indices = other
+                .indices
+                .as_ref()
+                .map(|indices| indices.into_u32())
+                .unwrap_or_else(|| (0..other_vertex_count as u32).collect());
+            indices.extend(other_indices.iter().map(|i| i + vertex_count as u32));
+            self.indices = Some(
+                if vertex_count + other_vertex_count <= u16::MAX as usize + 1 {
+                    Indices::U16(indices.iter().map(|i| *i as u16).collect())
+                } else {
+                    Indices::U32(indices)
+                },
+            );
+        }
+        Ok(())
+    }
+
+    ///
+    /// Checks that the mesh data is consistent, ie. that the number of positions is a multiple of 3,
+    /// that the normals, uvs, colors, joints and morph targets have the same number of vertices as the positions,
+    /// that the number of indices is a multiple of 3 (or the number of vertices if there are no indices) for a list of [triangles](crate::PrimitiveType::Triangles)
+    /// and that all indices, except the [primitive restart index](Indices::primitive_restart_index) of a triangle strip, refer to an existing vertex.
+    ///
+    /// # Errors
+    /// Returns a [MeshError](crate::Error::MeshError) describing the first inconsistency found.
+    ///
+    // usize::is_multiple_of requires a newer compiler than supported by this crate
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.positions.len() % 3 != 0 {
+            Err(mesh_error(
+                &self.name,
+                format!(
+                    "the number of position values ({}) is not a multiple of 3",
+                    self.positions.len()
+                ),
+            ))?;
+        }
+        let vertex_count = self.positions.len() / 3;
+        let check_attribute = |name: &str, length: Option<usize>, size: usize| {
+            if let Some(length) = length {
+                if length != vertex_count * size {
+                    Err(mesh_error(
+                        &self.name,
+                        format!(
+                            "the number of {} values ({}) does not match the number of vertices ({} vertices with {} values each)",
+                            name, length, vertex_count, size
+                        ),
+                    ))?;
+                }
+            }
+            Ok(())
+        };
+        check_attribute("normal", self.normals.as_ref().map(|v| v.len()), 3)?;
+        check_attribute("uv", self.uvs.as_ref().map(|v| v.len()), 2)?;
+        check_attribute("color", self.colors.as_ref().map(|v| v.len()), 4)?;
+        check_attribute(
+            "joint index",
+            self.joint_indices.as_ref().map(|v| v.len()),
+            4,
+        )?;
+        check_attribute(
+            "joint weight",
+            self.joint_weights.as_ref().map(|v| v.len()),
+            4,
+        )?;
+        for target in self.morph_targets.iter() {
+            check_attribute(
+                "morph target position delta",
+                Some(target.position_deltas.len()),
+                3,
+            )?;
+            check_attribute(
+                "morph target normal delta",
+                target.normal_deltas.as_ref().map(|v| v.len()),
+                3,
+            )?;
+        }
+        if self.joint_indices.is_some() != self.joint_weights.is_some() {
+            Err(mesh_error(
+                &self.name,
+                "joint indices and joint weights must either both be defined or both be undefined"
+                    .to_string(),
+            ))?;
+        }
+
+        if let Some(ref indices) = self.indices {
+            let restart = indices.primitive_restart_index();
+            let indices = indices.into_u32();
+            if self.primitive_type == PrimitiveType::Triangles && indices.len() % 3 != 0 {
+                Err(mesh_error(
+                    &self.name,
+                    format!(
+                        "the number of indices ({}) is not a multiple of 3",
+                        indices.len()
+                    ),
+                ))?;
+            }
+            if let Some(index) = indices.iter().find(|i| {
+                **i as usize >= vertex_count
+                    && !(self.primitive_type == PrimitiveType::TriangleStrip && **i == restart)
+            }) {
+                Err(mesh_error(
+                    &self.name,
+                    format!(
+                        "the index {} is out of range for a mesh with {} vertices",
+                        index, vertex_count
+                    ),
+                ))?;
+            }
+        } else if self.primitive_type == PrimitiveType::Triangles && vertex_count % 3 != 0 {
+            Err(mesh_error(
+                &self.name,
+                format!(
+                    "the number of vertices ({}) is not a multiple of 3 and no indices are defined",
+                    vertex_count
+                ),
+            ))?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn validate_triangles(&self) -> Result<(), Error> {
+        if self.primitive_type != PrimitiveType::Triangles {
+            Err(mesh_error(
+                &self.name,
+                format!(
+                    "the primitive type {:?} is not supported, only triangles are",
+                    self.primitive_type
+                ),
+            ))?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns the vertex indices of all triangles as a triangle list, ie. three indices per triangle,
+    /// regardless of the primitive type and whether or not the mesh has indices.
+    ///
+    pub(crate) fn triangle_indices(&self) -> Vec<u32> {
+        match self.primitive_type {
+            PrimitiveType::Triangles => self.strip_indices(),
+            PrimitiveType::TriangleStrip => {
+                strip_to_triangles(&self.strip_indices(), self.restart_index())
+            }
+            PrimitiveType::Points => Vec::new(),
+        }
+    }
+
+    fn restart_index(&self) -> u32 {
+        match self.indices {
+            Some(ref indices) => indices.primitive_restart_index(),
+            None if self.positions.len() / 3 < u16::MAX as usize => u16::MAX as u32,
+            None => u32::MAX,
+        }
+    }
+
+    fn strip_indices(&self) -> Vec<u32> {
+        self.indices
+            .as_ref()
+            .map(|indices| indices.into_u32())
+            .unwrap_or_else(|| (0..self.positions.len() as u32 / 3).collect())
+    }
+
+    ///
+    /// Finds the closest intersection between the given ray and the triangles of this mesh placed in the world using the given transformation.
+    /// The triangle index of the hit is the index of the triangle in the list of triangles, ie. the triangle defined by the indices at `3 * triangle_index` to `3 * triangle_index + 2`
+    /// for a mesh with [triangles](crate::PrimitiveType::Triangles).
+    /// Returns `None` if the mesh is not hit or if the mesh consists of points.
+    ///
+    /// This tests all triangles, for repeated tests against large meshes, consider using a [bounding volume hierarchy](crate::Bvh).
+    ///
+    pub fn raycast(&self, ray: &Ray, transformation: &Mat4) -> Option<Hit> {
+        let inverse = transformation.invert()?;
+        let local_ray = ray.transformed(&inverse);
+        let position = |index: u32| {
+            let i = 3 * index as usize;
+            vec3(
+                self.positions[i],
+                self.positions[i + 1],
+                self.positions[i + 2],
+            )
+        };
+        let mut closest: Option<Hit> = None;
+        for (triangle_index, triangle) in self.triangle_indices().chunks_exact(3).enumerate() {
+            if let Some((distance, u, v)) = local_ray.intersect_triangle(
+                position(triangle[0]),
+                position(triangle[1]),
+                position(triangle[2]),
+            ) {
+                if closest.map(|hit| distance < hit.distance).unwrap_or(true) {
+                    closest = Some(Hit {
+                        distance,
+                        position: ray.at(distance),
+                        triangle_index,
+                        barycentric: vec3(1.0 - u - v, u, v),
+                    });
+                }
+            }
+        }
+        closest
     }
 
     ///
@@ -219,8 +944,35 @@ impl CPUMesh {
     }
 }
 
+fn mesh_error(name: &str, message: String) -> Error {
+    Error::MeshError {
+        message: format!("Invalid mesh '{}': {}.", name, message),
+    }
+}
+
+fn strip_to_triangles(indices: &[u32], restart: u32) -> Vec<u32> {
+    let mut triangles = Vec::new();
+    for strip in indices.split(|i| *i == restart) {
+        for i in 2..strip.len() {
+            if i % 2 == 0 {
+                triangles.extend_from_slice(&[strip[i - 2], strip[i - 1], strip[i]]);
+            } else {
+                triangles.extend_from_slice(&[strip[i - 1], strip[i - 2], strip[i]]);
+            }
+        }
+    }
+    triangles
+}
+
+fn flip_triangles<T>(values: &mut [T], size: usize) {
+    for triangle in values.chunks_exact_mut(3 * size) {
+        let (first, rest) = triangle.split_at_mut(2 * size);
+        first[size..].swap_with_slice(rest);
+    }
+}
+
 fn compute_normals_with_indices(indices: &[u32], positions: &[f32]) -> Vec<f32> {
-    let mut normals = vec![0.0f32; positions.len() * 3];
+    let mut normals = vec![0.0f32; positions.len()];
     for face in 0..indices.len() / 3 {
         let index0 = indices[face * 3] as usize;
         let p0 = vec3(
@@ -304,3 +1056,284 @@ fn compute_normals(positions: &[f32]) -> Vec<f32> {
     }
     normals
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle() -> CPUMesh {
+        CPUMesh {
+            name: "triangle".to_string(),
+            positions: vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+            indices: Some(Indices::U8(vec![0, 1, 2])),
+            normals: Some(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0]),
+            ..Default::default()
+        }
+    }
+
+    fn assert_invalid(mesh: &CPUMesh, expected: &str) {
+        match mesh.validate() {
+            Err(Error::MeshError { message }) => assert!(
+                message.contains(expected),
+                "expected an error containing '{}', got '{}'",
+                expected,
+                message
+            ),
+            result => panic!("expected a mesh error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn validate_valid_meshes() {
+        triangle().validate().unwrap();
+        CPUMesh::square(1.0).validate().unwrap();
+        CPUMesh::cube(1.0).validate().unwrap();
+        CPUMesh::sphere(1.0).validate().unwrap();
+    }
+
+    #[test]
+    fn validate_positions_not_multiple_of_three() {
+        let mut mesh = triangle();
+        mesh.positions.pop();
+        assert_invalid(
+            &mesh,
+            "number of position values (8) is not a multiple of 3",
+        );
+    }
+
+    #[test]
+    fn validate_attribute_length_mismatch() {
+        let mut mesh = triangle();
+        mesh.normals.as_mut().unwrap().truncate(6);
+        assert_invalid(&mesh, "number of normal values (6)");
+
+        let mut mesh = triangle();
+        mesh.uvs = Some(vec![0.0; 4]);
+        assert_invalid(&mesh, "number of uv values (4)");
+
+        let mut mesh = triangle();
+        mesh.colors = Some(vec![255; 8]);
+        assert_invalid(&mesh, "number of color values (8)");
+
+        let mut mesh = triangle();
+        mesh.joint_indices = Some(vec![0; 4]);
+        mesh.joint_weights = Some(vec![1.0; 12]);
+        assert_invalid(&mesh, "number of joint index values (4)");
+
+        let mut mesh = triangle();
+        mesh.joint_indices = Some(vec![0; 12]);
+        mesh.joint_weights = Some(vec![1.0; 8]);
+        assert_invalid(&mesh, "number of joint weight values (8)");
+
+        let mut mesh = triangle();
+        mesh.morph_targets.push(MorphTarget {
+            position_deltas: vec![0.0; 6],
+            ..Default::default()
+        });
+        assert_invalid(&mesh, "number of morph target position delta values (6)");
+
+        let mut mesh = triangle();
+        mesh.morph_targets.push(MorphTarget {
+            position_deltas: vec![0.0; 9],
+            normal_deltas: Some(vec![0.0; 3]),
+            ..Default::default()
+        });
+        assert_invalid(&mesh, "number of morph target normal delta values (3)");
+    }
+
+    #[test]
+    fn validate_joint_indices_without_weights() {
+        let mut mesh = triangle();
+        mesh.joint_indices = Some(vec![0; 12]);
+        assert_invalid(
+            &mesh,
+            "joint indices and joint weights must either both be defined",
+        );
+    }
+
+    #[test]
+    fn validate_indices_not_multiple_of_three() {
+        let mut mesh = triangle();
+        mesh.indices = Some(Indices::U16(vec![0, 1, 2, 0]));
+        assert_invalid(&mesh, "number of indices (4) is not a multiple of 3");
+    }
+
+    #[test]
+    fn validate_index_out_of_range() {
+        let mut mesh = triangle();
+        mesh.indices = Some(Indices::U32(vec![0, 1, 3]));
+        assert_invalid(&mesh, "index 3 is out of range for a mesh with 3 vertices");
+    }
+
+    #[test]
+    fn validate_vertex_count_not_multiple_of_three_without_indices() {
+        let mut mesh = triangle();
+        mesh.indices = None;
+        mesh.positions.extend_from_slice(&[1.0, 1.0, 0.0]);
+        mesh.normals = None;
+        assert_invalid(&mesh, "number of vertices (4) is not a multiple of 3");
+    }
+
+    #[test]
+    fn validate_triangle_strip_allows_restart_index() {
+        let mut mesh = triangle();
+        mesh.primitive_type = PrimitiveType::TriangleStrip;
+        mesh.indices = Some(Indices::U8(vec![0, 1, 2, u8::MAX, 0, 1]));
+        mesh.validate().unwrap();
+    }
+
+    #[test]
+    fn append_offsets_indices() {
+        let mut mesh = triangle();
+        mesh.append(&triangle(), &Mat4::from_translation(vec3(0.0, 0.0, 1.0)))
+            .unwrap();
+        assert_eq!(
+            mesh.indices.as_ref().unwrap().into_u32(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(mesh.positions.len(), 18);
+        assert_eq!(
+            &mesh.positions[9..],
+            &[0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0, 1.0]
+        );
+        assert_eq!(mesh.normals.as_ref().unwrap().len(), 18);
+        mesh.validate().unwrap();
+    }
+
+    #[test]
+    fn append_generates_indices_for_mesh_without_indices() {
+        let mut mesh = triangle();
+        let mut other = triangle();
+        other.indices = None;
+        mesh.append(&other, &Mat4::identity()).unwrap();
+        assert_eq!(
+            mesh.indices.as_ref().unwrap().into_u32(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+
+        let mut mesh = triangle();
+        mesh.indices = Some(Indices::U8(vec![2, 1, 0]));
+        mesh.append(&triangle(), &Mat4::identity()).unwrap();
+        assert_eq!(
+            mesh.indices.as_ref().unwrap().into_u32(),
+            vec![2, 1, 0, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn append_transforms_normals() {
+        let mut mesh = triangle();
+        mesh.append(&triangle(), &Mat4::from_angle_x(degrees(90.0)))
+            .unwrap();
+        let normals = mesh.normals.as_ref().unwrap();
+        assert!(
+            (vec3(normals[9], normals[10], normals[11]) - vec3(0.0, -1.0, 0.0)).magnitude()
+                < 0.0001
+        );
+    }
+
+    #[test]
+    fn append_mismatching_attributes() {
+        let mut mesh = triangle();
+        let mut other = triangle();
+        other.normals = None;
+        assert!(mesh.append(&other, &Mat4::identity()).is_err());
+
+        let mut mesh = CPUMesh::default();
+        mesh.append(&other, &Mat4::identity()).unwrap();
+        assert!(mesh.normals.is_none());
+        assert_eq!(mesh.positions, other.positions);
+    }
+
+    #[test]
+    fn flip_winding_with_indices() {
+        let mut mesh = triangle();
+        mesh.flip_winding();
+        assert_eq!(mesh.indices.as_ref().unwrap().into_u32(), vec![0, 2, 1]);
+        assert_eq!(
+            mesh.normals.unwrap(),
+            vec![-0.0, -0.0, -1.0, -0.0, -0.0, -1.0, -0.0, -0.0, -1.0]
+        );
+    }
+
+    #[test]
+    fn flip_winding_without_indices() {
+        let mut mesh = triangle();
+        mesh.indices = None;
+        mesh.uvs = Some(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+        mesh.flip_winding();
+        assert_eq!(
+            mesh.positions,
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0]
+        );
+        assert_eq!(mesh.uvs.unwrap(), vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0]);
+        assert_eq!(mesh.normals.unwrap()[2], -1.0);
+    }
+
+    #[test]
+    fn flip_normals_keeps_winding() {
+        let mut mesh = triangle();
+        mesh.flip_normals();
+        assert_eq!(mesh.indices.as_ref().unwrap().into_u32(), vec![0, 1, 2]);
+        assert_eq!(mesh.normals.unwrap()[2], -1.0);
+    }
+
+    #[test]
+    fn planar_uvs_span_unit_square() {
+        let mut mesh = CPUMesh::square(4.0);
+        mesh.uvs = None;
+        mesh.generate_planar_uvs(vec3(0.0, 0.0, 1.0));
+        let uvs = mesh.uvs.unwrap();
+        assert_eq!(uvs.len(), 8);
+        for uv in uvs.chunks(2) {
+            assert!(uv.iter().all(|v| (0.0..=1.0).contains(v)));
+        }
+        let min_max = |coordinate: usize| {
+            let values = uvs.iter().skip(coordinate).step_by(2);
+            (
+                values.clone().cloned().fold(f32::INFINITY, f32::min),
+                values.cloned().fold(f32::NEG_INFINITY, f32::max),
+            )
+        };
+        assert_eq!(min_max(0), (0.0, 1.0));
+        assert_eq!(min_max(1), (0.0, 1.0));
+    }
+
+    #[test]
+    fn spherical_uvs_in_unit_square() {
+        let mut mesh = CPUMesh::sphere(2.0);
+        mesh.generate_spherical_uvs();
+        let uvs = mesh.uvs.unwrap();
+        assert_eq!(uvs.len(), mesh.positions.len() / 3 * 2);
+        assert!(uvs.iter().all(|v| (0.0..=1.0).contains(v)));
+    }
+
+    #[test]
+    fn raycast_single_triangle() {
+        let mesh = triangle();
+        let ray = Ray::new(vec3(0.25, 0.25, 2.0), vec3(0.0, 0.0, -1.0));
+        let hit = mesh.raycast(&ray, &Mat4::identity()).unwrap();
+        assert_eq!(hit.distance, 2.0);
+        assert_eq!(hit.position, vec3(0.25, 0.25, 0.0));
+        assert_eq!(hit.triangle_index, 0);
+        assert_eq!(hit.barycentric, vec3(0.5, 0.25, 0.25));
+
+        let hit = mesh
+            .raycast(&ray, &Mat4::from_translation(vec3(0.0, 0.0, -1.0)))
+            .unwrap();
+        assert_eq!(hit.distance, 3.0);
+        assert_eq!(hit.position, vec3(0.25, 0.25, -1.0));
+        assert_eq!(hit.barycentric, vec3(0.5, 0.25, 0.25));
+    }
+
+    #[test]
+    fn raycast_misses_single_triangle() {
+        let mesh = triangle();
+        let outside = Ray::new(vec3(0.75, 0.75, 2.0), vec3(0.0, 0.0, -1.0));
+        assert_eq!(mesh.raycast(&outside, &Mat4::identity()), None);
+        let away = Ray::new(vec3(0.25, 0.25, 2.0), vec3(0.0, 0.0, 1.0));
+        assert_eq!(mesh.raycast(&away, &Mat4::identity()), None);
+        let parallel = Ray::new(vec3(-1.0, 0.25, 0.0), vec3(1.0, 0.0, 0.0));
+        assert_eq!(mesh.raycast(&parallel, &Mat4::identity()), None);
+    }
+}
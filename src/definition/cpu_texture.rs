@@ -16,6 +16,12 @@ pub enum Wrapping {
     Repeat,
     MirroredRepeat,
     ClampToEdge,
+    ///
+    /// Samples outside the [0..1] range return the border color of the texture, see for example [DepthTargetTexture2D::set_border_color](crate::DepthTargetTexture2D::set_border_color).
+    ///
+    /// **Note:** Clamping to the border is not supported in OpenGL ES and WebGL 2, so on web this falls back to [Wrapping::ClampToEdge].
+    ///
+    ClampToBorder,
 }
 
 pub trait TextureDataType:
@@ -28,11 +34,23 @@ impl TextureDataType for u32 {}
 
 ///
 /// Possible formats for pixels in a texture.
+/// The precision of each color channel is given by the data type of the texture, for example [R](Format::R) is a single channel 8 bit (R8) texture
+/// with `u8` data and a single channel 32 bit floating point (R32F) texture with `f32` data. Similarly, [RG](Format::RG) with `u8` data is an RG8 texture.
+/// The single and dual channel formats return the value in the red channel, and the green channel for the second value, when sampled in a shader.
 ///
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum Format {
     R,
     RG,
+    ///
+    /// A single channel 16 bit floating point (R16F) format, which can only be used with `f32` data that is converted to half precision when transferred to the GPU.
+    /// Uses half the memory of [R](Format::R) with `f32` data and can, unlike 32 bit floating point textures, be linearly interpolated on all platforms.
+    ///
+    R16F,
+    ///
+    /// A dual channel 16 bit floating point (RG16F) format, see [R16F](Format::R16F).
+    ///
+    RG16F,
     RGB,
     SRGB,
     RGBA,
@@ -44,12 +62,34 @@ impl Format {
         match self {
             Format::R => 1,
             Format::RG => 2,
+            Format::R16F => 1,
+            Format::RG16F => 2,
             Format::RGB => 3,
             Format::SRGB => 3,
             Format::RGBA => 4,
             Format::SRGBA => 4,
         }
     }
+
+    ///
+    /// Returns true if this is a format in gamma color space ([SRGB](Format::SRGB) or [SRGBA](Format::SRGBA)).
+    ///
+    pub fn is_srgb(&self) -> bool {
+        *self == Format::SRGB || *self == Format::SRGBA
+    }
+
+    ///
+    /// Returns the format with the same color channels in linear color space,
+    /// ie. [RGB](Format::RGB) for [SRGB](Format::SRGB) and [RGBA](Format::RGBA) for [SRGBA](Format::SRGBA).
+    /// Use this for textures containing data, for example normal maps, which should not be decoded from sRGB when sampled.
+    ///
+    pub fn linear(&self) -> Format {
+        match self {
+            Format::SRGB => Format::RGB,
+            Format::SRGBA => Format::RGBA,
+            _ => *self,
+        }
+    }
 }
 
 ///
@@ -94,6 +134,73 @@ impl<T: TextureDataType> CPUTexture<T> {
     }
 }
 
+impl CPUTexture<u8> {
+    ///
+    /// Multiplies the color channels of each pixel by the alpha channel, which is needed for blending with
+    /// [premultiplied alpha](crate::BlendParameters::PREMULTIPLIED_ALPHA).
+    /// With straight alpha, the color of transparent pixels, which is often black, bleeds into the visible pixels when the texture is
+    /// interpolated or mip mapped, which shows as dark halos around the edges. Premultiplied textures do not have this problem.
+    /// For example, a white pixel with 50% alpha becomes `(0.5, 0.5, 0.5, 0.5)` in linear color space.
+    /// Textures in gamma color space ([Format::SRGBA]) are converted to linear color space before the multiplication and back again afterwards.
+    /// Does nothing if the format does not have an alpha channel.
+    ///
+    pub fn premultiply_alpha(&mut self) {
+        match self.format {
+            Format::RGBA => {
+                for pixel in self.data.chunks_mut(4) {
+                    let alpha = pixel[3] as f32 / 255.0;
+                    for channel in pixel.iter_mut().take(3) {
+                        *channel = (*channel as f32 * alpha).round() as u8;
+                    }
+                }
+            }
+            Format::SRGBA => {
+                for pixel in self.data.chunks_mut(4) {
+                    let alpha = pixel[3] as f32 / 255.0;
+                    for channel in pixel.iter_mut().take(3) {
+                        let linear = rgb_from_srgb(*channel as f32 / 255.0) * alpha;
+                        *channel = (srgb_from_rgb(linear) * 255.0).round() as u8;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl CPUTexture<f32> {
+    ///
+    /// Multiplies the color channels of each pixel by the alpha channel, see [CPUTexture::<u8>::premultiply_alpha].
+    /// Does nothing if the format does not have an alpha channel.
+    ///
+    pub fn premultiply_alpha(&mut self) {
+        if self.format.color_channel_count() == 4 {
+            for pixel in self.data.chunks_mut(4) {
+                let alpha = pixel[3];
+                for channel in pixel.iter_mut().take(3) {
+                    *channel *= alpha;
+                }
+            }
+        }
+    }
+}
+
+fn rgb_from_srgb(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn srgb_from_rgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
 impl<T: TextureDataType> Default for CPUTexture<T> {
     fn default() -> Self {
         Self {
@@ -129,3 +236,44 @@ impl<T: TextureDataType> std::fmt::Debug for CPUTexture<T> {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texture<T: TextureDataType>(data: Vec<T>, format: Format) -> CPUTexture<T> {
+        CPUTexture {
+            data,
+            width: 1,
+            height: 1,
+            format,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn premultiply_half_transparent_white() {
+        let mut t = texture(vec![1.0, 1.0, 1.0, 0.5], Format::RGBA);
+        t.premultiply_alpha();
+        assert_eq!(t.data, vec![0.5, 0.5, 0.5, 0.5]);
+
+        let mut t = texture(vec![255u8, 255, 255, 128], Format::RGBA);
+        t.premultiply_alpha();
+        assert_eq!(t.data, vec![128, 128, 128, 128]);
+    }
+
+    #[test]
+    fn premultiply_in_gamma_color_space() {
+        let mut t = texture(vec![255u8, 255, 255, 128], Format::SRGBA);
+        t.premultiply_alpha();
+        let expected = (srgb_from_rgb(128.0 / 255.0) * 255.0).round() as u8;
+        assert_eq!(t.data, vec![expected, expected, expected, 128]);
+    }
+
+    #[test]
+    fn premultiply_without_alpha_does_nothing() {
+        let mut t = texture(vec![255u8, 255, 255], Format::RGB);
+        t.premultiply_alpha();
+        assert_eq!(t.data, vec![255, 255, 255]);
+    }
+}
@@ -13,3 +13,23 @@ pub use fog::*;
 mod fxaa;
 #[doc(inline)]
 pub use fxaa::*;
+
+mod taa;
+#[doc(inline)]
+pub use taa::*;
+
+mod motion_blur;
+#[doc(inline)]
+pub use motion_blur::*;
+
+mod tone_mapping;
+#[doc(inline)]
+pub use tone_mapping::*;
+
+mod gaussian_blur;
+#[doc(inline)]
+pub use gaussian_blur::*;
+
+mod anti_aliasing;
+#[doc(inline)]
+pub use anti_aliasing::*;
@@ -0,0 +1,196 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::effect::*;
+use crate::math::*;
+
+///
+/// The anti-aliasing method used by [AntiAliasingRenderer] to remove jagged edges from the rendered image.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum AntiAliasing {
+    ///
+    /// No anti-aliasing, the scene is rendered directly to the screen.
+    ///
+    #[default]
+    None,
+    ///
+    /// Multisample anti-aliasing with the given number of samples per pixel, see [RenderTargetMultisample].
+    /// The number of samples is clamped to the maximum supported by the graphics driver and zero or one sample is the same as [AntiAliasing::None].
+    /// The screen itself should not be multisampled, ie. [WindowSettings::multisamples](crate::WindowSettings::multisamples) should be zero.
+    ///
+    Msaa(u8),
+    ///
+    /// Fast approximate anti-aliasing applied to the rendered image as a post process, see [FXAAEffect].
+    ///
+    Fxaa,
+    ///
+    /// Temporal anti-aliasing which accumulates jittered frames, see [TAAEffect].
+    /// The previous frames are reprojected using the depth and the camera only, since there are no motion vectors,
+    /// so moving objects leave a trail and this mode works best for static scenes.
+    ///
+    Taa,
+}
+
+///
+/// Renders a scene to the screen using the chosen [AntiAliasing] method.
+/// The effects and the intermediate render targets are created when first needed and reused as long as the viewport size is unchanged.
+///
+pub struct AntiAliasingRenderer {
+    context: Context,
+    ///
+    /// The anti-aliasing method used in the next call to [render](Self::render).
+    ///
+    pub anti_aliasing: AntiAliasing,
+    fxaa: Option<FXAAEffect>,
+    taa: Option<TAAEffect>,
+    multisample_target: Option<RenderTargetMultisample>,
+    color_texture: Option<ColorTargetTexture2D<u8>>,
+    depth_texture: Option<DepthTargetTexture2D>,
+}
+
+impl AntiAliasingRenderer {
+    ///
+    /// Constructs a new renderer using the given anti-aliasing method.
+    ///
+    pub fn new(context: &Context, anti_aliasing: AntiAliasing) -> Self {
+        Self {
+            context: context.clone(),
+            anti_aliasing,
+            fxaa: None,
+            taa: None,
+            multisample_target: None,
+            color_texture: None,
+            depth_texture: None,
+        }
+    }
+
+    ///
+    /// Renders whatever rendered in the `render` closure to the screen, which is cleared based on the given clear state before rendering.
+    /// The closure is given the camera to render with, which is jittered a fraction of a pixel when using [AntiAliasing::Taa].
+    /// The jitter is removed from the camera again before this method returns.
+    /// Except for [AntiAliasing::None], the scene is rendered into intermediate targets with the size of the viewport.
+    ///
+    // Option::is_none_or requires a newer compiler than supported by this crate
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn render<F: FnOnce(&Camera) -> Result<(), Error>>(
+        &mut self,
+        camera: &mut Camera,
+        viewport: Viewport,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        match self.anti_aliasing {
+            AntiAliasing::Msaa(samples) => {
+                let samples = (samples as u32).min(self.context.get_max_samples());
+                if samples <= 1 {
+                    return Screen::write(&self.context, clear_state, || render(camera));
+                }
+                if self.multisample_target.as_ref().map_or(true, |target| {
+                    target.width() != viewport.width
+                        || target.height() != viewport.height
+                        || target.samples() != samples
+                }) {
+                    self.multisample_target = None;
+                    self.multisample_target = Some(RenderTargetMultisample::new::<u8>(
+                        &self.context,
+                        viewport.width,
+                        viewport.height,
+                        samples,
+                        Format::RGBA,
+                        DepthFormat::Depth32F,
+                    )?);
+                }
+                let target = self.multisample_target.as_ref().unwrap();
+                target.write(clear_state, || render(camera))?;
+                target.resolve_to_screen()
+            }
+            AntiAliasing::Fxaa => {
+                if self.fxaa.is_none() {
+                    self.fxaa = Some(FXAAEffect::new(&self.context)?);
+                }
+                self.render_to_textures(viewport, clear_state, || render(camera))?;
+                let fxaa = self.fxaa.as_ref().unwrap();
+                let color_texture = self.color_texture.as_ref().unwrap();
+                Screen::write(&self.context, ClearState::none(), || {
+                    fxaa.apply(viewport, color_texture)
+                })
+            }
+            AntiAliasing::Taa => {
+                if self.taa.is_none() {
+                    self.taa = Some(TAAEffect::new(&self.context)?);
+                }
+                let result = self.render_taa(camera, viewport, clear_state, render);
+                // The jitter is removed on all paths, also if rendering fails, so the camera is left unchanged
+                let reset = camera.set_jitter(vec2(0.0, 0.0));
+                result.and(reset)
+            }
+            AntiAliasing::None => Screen::write(&self.context, clear_state, || render(camera)),
+        }
+    }
+
+    ///
+    /// Discards the frames accumulated by [AntiAliasing::Taa], for example when the camera is cut to a new view.
+    ///
+    pub fn reset(&mut self) {
+        if let Some(ref mut taa) = self.taa {
+            taa.reset();
+        }
+    }
+
+    fn render_taa<F: FnOnce(&Camera) -> Result<(), Error>>(
+        &mut self,
+        camera: &mut Camera,
+        viewport: Viewport,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        self.taa.as_mut().unwrap().jitter(camera, viewport)?;
+        self.render_to_textures(viewport, clear_state, || render(camera))?;
+        let result = self.taa.as_mut().unwrap().apply(
+            viewport,
+            camera,
+            self.color_texture.as_ref().unwrap(),
+            self.depth_texture.as_ref().unwrap(),
+        )?;
+        result.copy_to(CopyDestination::Screen, viewport, WriteMask::COLOR)
+    }
+
+    // Option::is_none_or requires a newer compiler than supported by this crate
+    #[allow(clippy::unnecessary_map_or)]
+    fn render_to_textures<F: FnOnce() -> Result<(), Error>>(
+        &mut self,
+        viewport: Viewport,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        if self.color_texture.as_ref().map_or(true, |texture| {
+            texture.width() != viewport.width || texture.height() != viewport.height
+        }) {
+            self.color_texture = Some(ColorTargetTexture2D::new(
+                &self.context,
+                viewport.width,
+                viewport.height,
+                Interpolation::Linear,
+                Interpolation::Linear,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )?);
+            self.depth_texture = Some(DepthTargetTexture2D::new(
+                &self.context,
+                viewport.width,
+                viewport.height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )?);
+        }
+        RenderTarget::new(
+            &self.context,
+            self.color_texture.as_ref().unwrap(),
+            self.depth_texture.as_ref().unwrap(),
+        )?
+        .write(clear_state, render)
+    }
+}
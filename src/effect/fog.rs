@@ -43,7 +43,7 @@ impl FogEffect {
         self.image_effect.use_texture(depth_texture, "depthMap")?;
         self.image_effect.use_uniform_mat4(
             "viewProjectionInverse",
-            &(camera.projection() * camera.view()).invert().unwrap(),
+            &camera.view_projection().invert().unwrap(),
         )?;
         self.image_effect
             .use_uniform_vec3("fogColor", &self.color)?;
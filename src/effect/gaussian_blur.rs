@@ -0,0 +1,119 @@
+use crate::core::*;
+use crate::definition::*;
+use crate::effect::*;
+use crate::math::*;
+
+///
+/// A separable Gaussian blur which blurs a texture in two passes, first horizontally and then vertically,
+/// for example to blur the result of a bloom, ambient occlusion or soft shadow pass.
+/// The input can be any texture, for example a single channel ([Format::R]) or an RGBA texture,
+/// and all four channels are blurred and written to the output texture with its own format.
+///
+pub struct GaussianBlur {
+    context: Context,
+    image_effect: ImageEffect,
+    radius: u32,
+    sigma: f32,
+    temp_texture: Option<ColorTargetTexture2D<f32>>,
+}
+
+impl GaussianBlur {
+    ///
+    /// Creates a new Gaussian blur which samples `radius` pixels on each side of a pixel in each direction.
+    /// The weights of the samples are computed from the standard deviation `sigma` (in pixels) and normalized so they sum to one.
+    /// A `sigma` of about a third of the radius is a good choice, since the weights beyond three standard deviations are almost zero.
+    ///
+    pub fn new(context: &Context, radius: u32, sigma: f32) -> Result<Self, Error> {
+        let sigma = sigma.max(f32::EPSILON);
+        let mut weights: Vec<f32> = (0..=radius)
+            .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let sum = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+        weights.iter_mut().for_each(|w| *w /= sum);
+        let weights = weights
+            .iter()
+            .map(|w| format!("{:.8}", w))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(Self {
+            context: context.clone(),
+            image_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "#define RADIUS {}\nconst float weights[{}] = float[]({});\n{}",
+                    radius,
+                    radius + 1,
+                    weights,
+                    include_str!("shaders/gaussian_blur.frag")
+                ),
+            )?,
+            radius,
+            sigma,
+            temp_texture: None,
+        })
+    }
+
+    ///
+    /// The number of pixels sampled on each side of a pixel in each direction.
+    ///
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    ///
+    /// The standard deviation (in pixels) of the Gaussian used to compute the weights.
+    ///
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+
+    ///
+    /// Blurs the input texture and writes the result to the output texture, which should have the same size as the input texture.
+    /// The intermediate result of the horizontal pass is stored in a temporary texture which is reused as long as the size and format of the output does not change.
+    ///
+    pub fn apply<T: TextureDataType>(
+        &mut self,
+        input: &impl Texture,
+        output: &ColorTargetTexture2D<T>,
+    ) -> Result<(), Error> {
+        let width = output.width();
+        let height = output.height();
+        let format = output.format();
+        if self
+            .temp_texture
+            .as_ref()
+            .map(|t| t.width() != width || t.height() != height || t.format() != format)
+            .unwrap_or(true)
+        {
+            self.temp_texture = Some(ColorTargetTexture2D::new(
+                &self.context,
+                width,
+                height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                format,
+            )?);
+        }
+        let temp_texture = self.temp_texture.as_ref().unwrap();
+        let viewport = Viewport::new_at_origo(width, height);
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTestType::Always,
+            ..Default::default()
+        };
+        let image_effect = &self.image_effect;
+        temp_texture.write(ClearState::none(), || {
+            image_effect.use_texture(input, "inputMap")?;
+            image_effect.use_uniform_vec2("direction", &vec2(1.0 / input.width() as f32, 0.0))?;
+            image_effect.apply(render_states, viewport)
+        })?;
+        output.write(ClearState::none(), || {
+            image_effect.use_texture(temp_texture, "inputMap")?;
+            image_effect.use_uniform_vec2("direction", &vec2(0.0, 1.0 / height as f32))?;
+            image_effect.apply(render_states, viewport)
+        })
+    }
+}
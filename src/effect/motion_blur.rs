@@ -0,0 +1,57 @@
+use crate::core::*;
+use crate::effect::*;
+use crate::math::*;
+
+///
+/// An effect that blurs each pixel along its motion since the previous frame, which gives fast moving objects and camera pans a cinematic look.
+/// The motion is given by a texture containing motion vectors, see for example [Mesh::render_motion_vectors](crate::Mesh::render_motion_vectors).
+///
+pub struct MotionBlurEffect {
+    /// The maximum number of color samples along the motion of each pixel, default is 8.
+    pub max_samples: u32,
+    /// A multiplier applied to the motion, for example to simulate a longer or shorter exposure time, default is 1.0.
+    pub intensity: f32,
+    /// The maximum length of the blur in uv coordinates, which avoids extreme smearing of very fast objects, default is 0.05.
+    pub max_velocity: f32,
+    image_effect: ImageEffect,
+}
+
+impl MotionBlurEffect {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            max_samples: 8,
+            intensity: 1.0,
+            max_velocity: 0.05,
+            image_effect: ImageEffect::new(context, include_str!("shaders/motion_blur.frag"))?,
+        })
+    }
+
+    ///
+    /// Applies the motion blur to the given color texture using the motion vectors in the red and green channels of the motion texture.
+    /// Pixels with no motion are left unchanged.
+    ///
+    pub fn apply(
+        &self,
+        viewport: Viewport,
+        color_texture: &impl Texture,
+        motion_texture: &impl Texture,
+    ) -> Result<(), Error> {
+        let render_states = RenderStates {
+            write_mask: WriteMask::COLOR,
+            depth_test: DepthTestType::Always,
+            ..Default::default()
+        };
+
+        self.image_effect.use_texture(color_texture, "colorMap")?;
+        self.image_effect.use_texture(motion_texture, "motionMap")?;
+        self.image_effect
+            .use_uniform_int("samples", &(self.max_samples.max(1) as i32))?;
+        self.image_effect
+            .use_uniform_float("intensity", &self.intensity)?;
+        self.image_effect
+            .use_uniform_float("maxVelocity", &self.max_velocity)?;
+
+        self.image_effect.apply(render_states, viewport)?;
+        Ok(())
+    }
+}
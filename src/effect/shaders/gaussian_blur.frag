@@ -0,0 +1,17 @@
+
+uniform sampler2D inputMap;
+uniform vec2 direction;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    vec4 sum = texture(inputMap, uv) * weights[0];
+    for (int i = 1; i <= RADIUS; i++) {
+        vec2 offset = float(i) * direction;
+        sum += (texture(inputMap, uv + offset) + texture(inputMap, uv - offset)) * weights[i];
+    }
+    color = sum;
+}
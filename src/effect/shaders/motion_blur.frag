@@ -0,0 +1,28 @@
+
+uniform sampler2D colorMap;
+uniform sampler2D motionMap;
+
+uniform int samples;
+uniform float intensity;
+uniform float maxVelocity;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    vec2 velocity = intensity * texture(motionMap, uv).rg;
+    float speed = length(velocity);
+    if (speed > maxVelocity) {
+        velocity *= maxVelocity / speed;
+    }
+
+    // Sample backwards along the motion of the pixel since the previous frame
+    vec4 result = texture(colorMap, uv);
+    for (int i = 1; i < samples; i++) {
+        vec2 offset = velocity * (float(i) / float(samples - 1) - 0.5);
+        result += texture(colorMap, uv - offset);
+    }
+    color = result / float(max(samples, 1));
+}
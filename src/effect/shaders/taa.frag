@@ -0,0 +1,45 @@
+
+uniform sampler2D colorMap;
+uniform sampler2D depthMap;
+uniform sampler2D historyMap;
+
+uniform mat4 viewProjectionInverse;
+uniform mat4 previousViewProjection;
+uniform float blendFactor;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    vec4 current = texture(colorMap, uv);
+
+    // Reproject the pixel into the previous frame using the depth
+    float depth = texture(depthMap, uv).r;
+    vec4 clipPosition = vec4(2.0 * uv - 1.0, 2.0 * depth - 1.0, 1.0);
+    vec4 worldPosition = viewProjectionInverse * clipPosition;
+    worldPosition /= worldPosition.w;
+    vec4 previousClipPosition = previousViewProjection * worldPosition;
+    vec2 previousUv = 0.5 * previousClipPosition.xy / previousClipPosition.w + 0.5;
+
+    if (previousUv.x < 0.0 || previousUv.x > 1.0 || previousUv.y < 0.0 || previousUv.y > 1.0) {
+        color = current;
+        return;
+    }
+
+    // Clamp the history to the color range of the 3x3 neighborhood to reduce ghosting
+    vec2 texelSize = 1.0 / vec2(textureSize(colorMap, 0));
+    vec4 minColor = current;
+    vec4 maxColor = current;
+    for (int x = -1; x <= 1; x++) {
+        for (int y = -1; y <= 1; y++) {
+            vec4 neighbor = texture(colorMap, uv + vec2(float(x), float(y)) * texelSize);
+            minColor = min(minColor, neighbor);
+            maxColor = max(maxColor, neighbor);
+        }
+    }
+    vec4 history = clamp(texture(historyMap, previousUv), minColor, maxColor);
+
+    color = mix(history, current, blendFactor);
+}
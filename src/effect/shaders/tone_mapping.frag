@@ -0,0 +1,15 @@
+
+uniform sampler2D colorMap;
+uniform float exposure;
+uniform float gamma;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    vec4 c = texture(colorMap, uv);
+    vec3 linear = pow(rgb_from_srgb(c.rgb) * exposure, vec3(1.0 / gamma));
+    color = vec4(srgb_from_rgb(linear), c.a);
+}
@@ -0,0 +1,150 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::effect::*;
+use crate::math::*;
+
+const JITTER_SEQUENCE_LENGTH: u32 = 8;
+
+///
+/// Temporal anti-aliasing which removes jagged edges by accumulating the rendered images of several frames.
+/// Each frame, the camera projection is [jittered](Self::jitter) a fraction of a pixel according to a Halton sequence,
+/// then the scene is rendered and finally the image is blended with the result of the previous frames using [apply](Self::apply).
+/// The result of the previous frames is reprojected using the depth and the previous view and projection
+/// and clamped to the color range of the neighbourhood of each pixel to reduce ghosting.
+///
+/// Call [reset](Self::reset) when the camera is cut to a new view to avoid blending unrelated images.
+///
+pub struct TAAEffect {
+    context: Context,
+    image_effect: ImageEffect,
+    /// The weight of the current frame when blending with the result of the previous frames.
+    /// Lower values give a smoother result but more ghosting, default is 0.1.
+    pub blend_factor: f32,
+    history: Vec<ColorTargetTexture2D<u8>>,
+    history_index: usize,
+    history_valid: bool,
+    previous_view_projection: Mat4,
+    frame_index: u32,
+}
+
+impl TAAEffect {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            context: context.clone(),
+            image_effect: ImageEffect::new(context, include_str!("shaders/taa.frag"))?,
+            blend_factor: 0.1,
+            history: Vec::new(),
+            history_index: 0,
+            history_valid: false,
+            previous_view_projection: Mat4::identity(),
+            frame_index: 0,
+        })
+    }
+
+    ///
+    /// Jitters the projection of the camera to the next sub-pixel offset in the Halton sequence.
+    /// Should be called each frame before rendering the scene.
+    ///
+    pub fn jitter(&mut self, camera: &mut Camera, viewport: Viewport) -> Result<(), Error> {
+        self.frame_index = (self.frame_index + 1) % JITTER_SEQUENCE_LENGTH;
+        let jitter = vec2(
+            (halton(self.frame_index + 1, 2) - 0.5) * 2.0 / viewport.width as f32,
+            (halton(self.frame_index + 1, 3) - 0.5) * 2.0 / viewport.height as f32,
+        );
+        camera.set_jitter(jitter)
+    }
+
+    ///
+    /// Blends the given color texture, which contains the scene rendered with a [jittered](Self::jitter) camera,
+    /// with the result of the previous frames and returns the anti-aliased result.
+    /// The depth texture must contain the depth of the scene rendered with the same camera.
+    /// The returned texture can be copied to the screen and is valid until the next call to this method.
+    ///
+    pub fn apply(
+        &mut self,
+        viewport: Viewport,
+        camera: &Camera,
+        color_texture: &impl Texture,
+        depth_texture: &DepthTargetTexture2D,
+    ) -> Result<&ColorTargetTexture2D<u8>, Error> {
+        let width = color_texture.width();
+        let height = color_texture.height();
+        if self.history.len() != 2
+            || self.history[0].width() != width
+            || self.history[0].height() != height
+        {
+            self.history = Vec::new();
+            for _ in 0..2 {
+                self.history.push(ColorTargetTexture2D::new(
+                    &self.context,
+                    width,
+                    height,
+                    Interpolation::Linear,
+                    Interpolation::Linear,
+                    None,
+                    Wrapping::ClampToEdge,
+                    Wrapping::ClampToEdge,
+                    Format::RGBA,
+                )?);
+            }
+            self.history_valid = false;
+        }
+
+        let view_projection = camera.view_projection();
+        let jitter = camera.jitter();
+        let unjittered_view_projection =
+            Mat4::from_translation(vec3(-jitter.x, -jitter.y, 0.0)) * view_projection;
+        let blend_factor = if self.history_valid {
+            self.blend_factor
+        } else {
+            1.0
+        };
+
+        let history = &self.history[self.history_index];
+        let target = &self.history[1 - self.history_index];
+        let image_effect = &self.image_effect;
+        let previous_view_projection = &self.previous_view_projection;
+        target.write(ClearState::none(), || {
+            image_effect.use_texture(color_texture, "colorMap")?;
+            image_effect.use_texture(depth_texture, "depthMap")?;
+            image_effect.use_texture(history, "historyMap")?;
+            image_effect
+                .use_uniform_mat4("viewProjectionInverse", &view_projection.invert().unwrap())?;
+            image_effect.use_uniform_mat4("previousViewProjection", previous_view_projection)?;
+            image_effect.use_uniform_float("blendFactor", &blend_factor)?;
+            image_effect.apply(
+                RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    depth_test: DepthTestType::Always,
+                    ..Default::default()
+                },
+                viewport,
+            )?;
+            Ok(())
+        })?;
+
+        self.previous_view_projection = unjittered_view_projection;
+        self.history_valid = true;
+        self.history_index = 1 - self.history_index;
+        Ok(&self.history[self.history_index])
+    }
+
+    ///
+    /// Discards the result of the previous frames, for example when the camera is cut to a new view.
+    /// The next call to [apply](Self::apply) returns the current frame without blending.
+    ///
+    pub fn reset(&mut self) {
+        self.history_valid = false;
+    }
+}
+
+fn halton(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0;
+    let mut fraction = 1.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
@@ -0,0 +1,135 @@
+use crate::core::*;
+use crate::effect::*;
+use crate::math::*;
+
+///
+/// Applies an exposure multiplier and a gamma correction to the colors of a rendered image, usually as the final step before presenting the image on the screen,
+/// see [Screen::write_with_tone_mapping].
+///
+/// The shaders already encode the output colors in gamma color space (sRGB) since the screen is not an sRGB framebuffer,
+/// so the colors are decoded to linear color space, multiplied by the exposure, raised to the power of `1 / gamma` and encoded in sRGB again.
+/// This means that the default exposure and gamma of 1 does not change the image.
+///
+pub struct ToneMappingEffect {
+    ///
+    /// The multiplier applied to the colors in linear color space. Default is 1.
+    ///
+    pub exposure: f32,
+    ///
+    /// The gamma correction applied on top of the sRGB encoding. Values above 1 brightens the dark colors. Default is 1.
+    ///
+    pub gamma: f32,
+    image_effect: ImageEffect,
+    color_texture: Option<ColorTargetTexture2D<u8>>,
+    depth_texture: Option<DepthTargetTexture2D>,
+}
+
+impl ToneMappingEffect {
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            exposure: 1.0,
+            gamma: 1.0,
+            image_effect: ImageEffect::new(
+                context,
+                &format!(
+                    "{}{}",
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/tone_mapping.frag")
+                ),
+            )?,
+            color_texture: None,
+            depth_texture: None,
+        })
+    }
+
+    ///
+    /// Returns true if the exposure and gamma are both 1, in which case applying this effect does not change the image.
+    ///
+    pub fn is_identity(&self) -> bool {
+        self.exposure == 1.0 && self.gamma == 1.0
+    }
+
+    ///
+    /// Applies the tone mapping to the given color texture and writes the result to the current render target.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn apply(&self, viewport: Viewport, color_texture: &impl Texture) -> Result<(), Error> {
+        self.image_effect.use_texture(color_texture, "colorMap")?;
+        self.image_effect
+            .use_uniform_float("exposure", &self.exposure)?;
+        self.image_effect.use_uniform_float("gamma", &self.gamma)?;
+        self.image_effect.apply(
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTestType::Always,
+                ..Default::default()
+            },
+            viewport,
+        )
+    }
+
+    // Option::is_none_or requires a newer compiler than supported by this crate
+    #[allow(clippy::unnecessary_map_or)]
+    fn render_to_textures<F: FnOnce() -> Result<(), Error>>(
+        &mut self,
+        context: &Context,
+        viewport: Viewport,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        if self.color_texture.as_ref().map_or(true, |texture| {
+            texture.width() != viewport.width || texture.height() != viewport.height
+        }) {
+            self.color_texture = Some(ColorTargetTexture2D::<u8>::new(
+                context,
+                viewport.width,
+                viewport.height,
+                Interpolation::Nearest,
+                Interpolation::Nearest,
+                None,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                Format::RGBA,
+            )?);
+            self.depth_texture = Some(DepthTargetTexture2D::new(
+                context,
+                viewport.width,
+                viewport.height,
+                Wrapping::ClampToEdge,
+                Wrapping::ClampToEdge,
+                DepthFormat::Depth32F,
+            )?);
+        }
+        RenderTarget::new(
+            context,
+            self.color_texture.as_ref().unwrap(),
+            self.depth_texture.as_ref().unwrap(),
+        )?
+        .write(clear_state, render)
+    }
+}
+
+impl Screen {
+    ///
+    /// Same as [Screen::write], except that the given tone mapping is applied to the rendered image before it is presented on the screen.
+    /// If the tone mapping does not change the image (see [ToneMappingEffect::is_identity]), this renders directly to the screen,
+    /// otherwise the image is rendered into an intermediate color and depth texture with the size of the viewport.
+    /// The intermediate textures are kept in the tone mapping effect and only created again when the size of the viewport changes.
+    ///
+    pub fn write_with_tone_mapping<F: FnOnce() -> Result<(), Error>>(
+        context: &Context,
+        viewport: Viewport,
+        clear_state: ClearState,
+        tone_mapping: &mut ToneMappingEffect,
+        render: F,
+    ) -> Result<(), Error> {
+        if tone_mapping.is_identity() {
+            return Screen::write(context, clear_state, render);
+        }
+        tone_mapping.render_to_textures(context, viewport, clear_state, render)?;
+        Screen::write(context, ClearState::none(), || {
+            tone_mapping.apply(viewport, tone_mapping.color_texture.as_ref().unwrap())
+        })
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::camera::*;
 use crate::core::*;
+use crate::definition::*;
 use crate::math::*;
 use crate::object::*;
 
@@ -15,20 +16,7 @@ pub fn ray_intersect(
     geometries: &[&dyn Geometry],
 ) -> Result<Option<Vec3>, Error> {
     let viewport = Viewport::new_at_origo(1, 1);
-    let up = if direction.dot(vec3(1.0, 0.0, 0.0)).abs() > 0.99 {
-        direction.cross(vec3(0.0, 1.0, 0.0))
-    } else {
-        direction.cross(vec3(1.0, 0.0, 0.0))
-    };
-    let camera = Camera::new_orthographic(
-        context,
-        position,
-        position + direction * max_depth,
-        up,
-        0.01,
-        0.01,
-        max_depth,
-    )?;
+    let camera = ray_camera(context, position, direction, max_depth)?;
     let texture = ColorTargetTexture2D::<f32>::new(
         context,
         viewport.width,
@@ -85,3 +73,110 @@ pub fn ray_intersect(
         None
     })
 }
+
+///
+/// Returns a camera which sees a tiny area around the ray with the given position and direction, used for rendering into a single pixel when picking.
+///
+pub(crate) fn ray_camera(
+    context: &Context,
+    position: Vec3,
+    direction: Vec3,
+    max_depth: f32,
+) -> Result<Camera, Error> {
+    let up = if direction.dot(vec3(1.0, 0.0, 0.0)).abs() > 0.99 {
+        direction.cross(vec3(0.0, 1.0, 0.0))
+    } else {
+        direction.cross(vec3(1.0, 0.0, 0.0))
+    };
+    Camera::new_orthographic(
+        context,
+        position,
+        position + direction * max_depth,
+        up,
+        0.01,
+        0.01,
+        max_depth,
+    )
+}
+
+///
+/// Renders the depth of the given opaque geometries in a depth pre-pass and then calls the `draw` closure with render states
+/// which only write color and use the [DepthTestType::Equal](crate::DepthTestType::Equal) depth test.
+/// Rendering the geometries with these render states in the `draw` closure then shades each pixel only once,
+/// which reduces the cost of expensive fragment shaders in scenes with a lot of overdraw.
+/// Must be called in a render target render function where the depth is cleared,
+/// for example in the callback function of [RenderTarget::write](crate::RenderTarget::write).
+///
+/// **Note:** The equal depth test requires the depth computed in the pre-pass and in the main pass to be identical,
+/// so the geometries must be rendered with exactly the same transformations and camera in both passes.
+/// Geometries where the depth is computed differently, for example with another vertex shader, may flicker or disappear.
+///
+pub fn render_with_depth_prepass<F: FnOnce(RenderStates) -> Result<(), Error>>(
+    viewport: Viewport,
+    camera: &Camera,
+    geometries: &[&dyn Geometry],
+    draw: F,
+) -> Result<(), Error> {
+    let depth_render_states = RenderStates {
+        write_mask: WriteMask::DEPTH,
+        depth_test: DepthTestType::Less,
+        ..Default::default()
+    };
+    for geometry in geometries {
+        if geometry
+            .aabb()
+            .map(|aabb| camera.in_frustum(&aabb))
+            .unwrap_or(true)
+        {
+            geometry.render_depth(depth_render_states, viewport, camera)?;
+        }
+    }
+    draw(RenderStates {
+        write_mask: WriteMask::COLOR,
+        depth_test: DepthTestType::Equal,
+        ..Default::default()
+    })
+}
+
+///
+/// Finds the closest of the given objects that is hit by the ray from the camera through the given pixel, computed on the CPU.
+/// The pixel is given in physical pixels from the top left corner of the window and the viewport is the viewport the camera is rendered into.
+/// Each object is a geometry, which [bounding box](Geometry::aabb) is used for quickly skipping objects that cannot be hit,
+/// together with the CPU mesh the geometry was created from and the transformation of the geometry, which is used for the exact test using [CPUMesh::raycast].
+/// Returns the index of the closest hit object and the hit position in world space, or `None` if no object is hit.
+///
+/// **Note:** This requires the CPU-side mesh data to be kept after the geometry is created.
+/// Use [Camera::pick] for picking on the GPU without the CPU-side mesh data.
+///
+pub fn pick(
+    camera: &Camera,
+    pixel: (f32, f32),
+    viewport: Viewport,
+    objects: &[(&dyn Geometry, &CPUMesh, &Mat4)],
+) -> Option<(usize, Vec3)> {
+    let ray = camera.ray_at((
+        (pixel.0 - viewport.x as f32) / viewport.width as f32,
+        (pixel.1 - viewport.y as f32) / viewport.height as f32,
+    ));
+    let mut closest: Option<(usize, Hit)> = None;
+    for (index, (geometry, cpu_mesh, transformation)) in objects.iter().enumerate() {
+        if let Some(aabb) = geometry.aabb() {
+            match ray.intersect_aabb(&aabb) {
+                Some(distance)
+                    if closest
+                        .map(|(_, hit)| distance <= hit.distance)
+                        .unwrap_or(true) => {}
+                _ => continue,
+            }
+        }
+        if let Some(hit) = cpu_mesh.raycast(&ray, transformation) {
+            if closest
+                .map(|(_, closest_hit)| hit.distance < closest_hit.distance)
+                .unwrap_or(true)
+            {
+                closest = Some((index, hit));
+            }
+        }
+    }
+    closest.map(|(index, hit)| (index, hit.position))
+}
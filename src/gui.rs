@@ -7,3 +7,15 @@ mod egui_gui;
 #[doc(inline)]
 #[cfg(feature = "egui-gui")]
 pub use egui_gui::*;
+
+mod gui_renderer;
+#[doc(inline)]
+pub use gui_renderer::*;
+
+mod shape_renderer;
+#[doc(inline)]
+pub use shape_renderer::*;
+
+mod sdf_text;
+#[doc(inline)]
+pub use sdf_text::*;
@@ -10,7 +10,7 @@ pub struct GUI {
     egui_context: egui::CtxRef,
     width: u32,
     height: u32,
-    program: Program,
+    renderer: GuiRenderer,
     texture_version: u64,
     texture: Option<Texture2D>,
 }
@@ -27,19 +27,7 @@ impl GUI {
             height: 0,
             texture_version: 0,
             texture: None,
-            program: Program::from_source(
-                context,
-                &format!(
-                    "{}{}",
-                    include_str!("../core/shared.frag"),
-                    include_str!("shaders/egui.vert")
-                ),
-                &format!(
-                    "{}{}",
-                    include_str!("../core/shared.frag"),
-                    include_str!("shaders/egui.frag")
-                ),
-            )?,
+            renderer: GuiRenderer::new(context)?,
         })
     }
 
@@ -132,76 +120,42 @@ impl GUI {
             self.texture_version = egui_texture.version;
         };
 
-        for egui::ClippedMesh(_, mesh) in clipped_meshes {
-            self.paint_mesh(
-                self.width,
-                self.height,
-                self.egui_context.pixels_per_point(),
-                &mesh,
-                self.texture.as_ref().unwrap(),
-            )?;
-        }
-        Ok(())
-    }
-
-    fn paint_mesh(
-        &self,
-        width: u32,
-        height: u32,
-        pixels_per_point: f32,
-        mesh: &egui::paint::Mesh,
-        texture: &Texture2D,
-    ) -> Result<(), Error> {
-        debug_assert!(mesh.is_valid());
-
-        let mut positions = Vec::new();
-        let mut colors = Vec::new();
-        let mut uvs = Vec::new();
-        for v in mesh.vertices.iter() {
-            positions.push(v.pos.x);
-            positions.push(v.pos.y);
-            uvs.push(v.uv.x);
-            uvs.push(v.uv.y);
-            colors.push(v.color[0] as f32);
-            colors.push(v.color[1] as f32);
-            colors.push(v.color[2] as f32);
-            colors.push(v.color[3] as f32);
-        }
-        let indices: Vec<u32> = mesh.indices.iter().map(|idx| *idx as u32).collect();
-
-        let position_buffer = VertexBuffer::new_with_static(&self.context, &positions)?;
-        let uv_buffer = VertexBuffer::new_with_static(&self.context, &uvs)?;
-        let color_buffer = VertexBuffer::new_with_static(&self.context, &colors)?;
-        let index_buffer = ElementBuffer::new(&self.context, &indices)?;
-
-        let viewport = Viewport::new_at_origo(
-            (width as f32 * pixels_per_point).round() as u32,
-            (height as f32 * pixels_per_point).round() as u32,
-        );
-
-        let render_states = RenderStates {
-            blend: Some(BlendParameters {
-                source_rgb_multiplier: BlendMultiplierType::One,
-                destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
-                source_alpha_multiplier: BlendMultiplierType::OneMinusDstAlpha,
-                destination_alpha_multiplier: BlendMultiplierType::One,
-                ..Default::default()
-            }),
-            depth_test: DepthTestType::Always,
-            ..Default::default()
-        };
-
-        self.program.use_texture(texture, "u_sampler")?;
-        self.program
-            .use_uniform_vec2("u_screen_size", &vec2(width as f32, height as f32))?;
-
-        self.program.use_attribute_vec2(&position_buffer, "a_pos")?;
-        self.program.use_attribute_vec4(&color_buffer, "a_srgba")?;
-        self.program.use_attribute_vec2(&uv_buffer, "a_tc")?;
-
-        self.program
-            .draw_elements(render_states, CullType::None, viewport, &index_buffer);
-        Ok(())
+        let vertices: Vec<Vec<GuiVertex>> = clipped_meshes
+            .iter()
+            .map(|egui::ClippedMesh(_, mesh)| {
+                debug_assert!(mesh.is_valid());
+                mesh.vertices
+                    .iter()
+                    .map(|v| GuiVertex {
+                        position: vec2(v.pos.x, v.pos.y),
+                        uv: vec2(v.uv.x, v.uv.y),
+                        color: v.color.to_array(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let texture = self.texture.as_ref().unwrap();
+        let meshes: Vec<GuiMesh> = clipped_meshes
+            .iter()
+            .zip(vertices.iter())
+            .map(|(egui::ClippedMesh(clip_rect, mesh), vertices)| GuiMesh {
+                clip_rect: [
+                    clip_rect.min.x,
+                    clip_rect.min.y,
+                    clip_rect.max.x,
+                    clip_rect.max.y,
+                ],
+                vertices,
+                indices: &mesh.indices,
+                texture,
+            })
+            .collect();
+        self.renderer.render(
+            self.width,
+            self.height,
+            self.egui_context.pixels_per_point(),
+            &meshes,
+        )
     }
 }
 
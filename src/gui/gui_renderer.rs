@@ -0,0 +1,162 @@
+use crate::context::consts;
+use crate::core::*;
+use crate::math::*;
+
+///
+/// A vertex of a [GuiMesh].
+///
+#[derive(Debug, Copy, Clone)]
+pub struct GuiVertex {
+    /// The position in points with the origin in the top left corner of the screen.
+    pub position: Vec2,
+    /// The uv coordinates used for looking up the color in the texture of the mesh.
+    pub uv: Vec2,
+    /// The color in gamma color space (sRGBA), which is multiplied with the texture color.
+    pub color: [u8; 4],
+}
+
+///
+/// A textured and colored triangle mesh which is only rendered inside a clip rectangle, for example the output of an immediate mode GUI like [egui](https://crates.io/crates/egui).
+///
+pub struct GuiMesh<'a> {
+    /// The rectangle in points, given as `[min x, min y, max x, max y]` with the origin in the top left corner of the screen, outside which nothing is rendered.
+    pub clip_rect: [f32; 4],
+    /// The vertices of the mesh.
+    pub vertices: &'a [GuiVertex],
+    /// Three indices into the vertices per triangle.
+    pub indices: &'a [u32],
+    /// The texture which is multiplied with the vertex colors. It is assumed to be in sRGBA format, ie. with [Format::SRGBA](crate::Format::SRGBA).
+    pub texture: &'a Texture2D,
+}
+
+///
+/// Renders the triangle meshes produced by an immediate mode GUI, see [GuiMesh], in screen space with the correct blending and clipping.
+/// This makes it possible to use any immediate mode GUI which outputs clipped triangle meshes with positions, uv coordinates and sRGBA colors,
+/// without depending on that GUI crate.
+///
+pub struct GuiRenderer {
+    context: Context,
+    program: Program,
+}
+
+impl GuiRenderer {
+    ///
+    /// Creates a new GUI renderer.
+    ///
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            context: context.clone(),
+            program: Program::from_source(
+                context,
+                &format!(
+                    "{}{}",
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/egui.vert")
+                ),
+                &format!(
+                    "{}{}",
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/egui.frag")
+                ),
+            )?,
+        })
+    }
+
+    ///
+    /// Render the given meshes on top of everything else, where the screen has the given width and height in points
+    /// and a point has the given size in pixels.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(
+        &self,
+        width: u32,
+        height: u32,
+        pixels_per_point: f32,
+        meshes: &[GuiMesh],
+    ) -> Result<(), Error> {
+        let viewport = Viewport::new_at_origo(
+            (width as f32 * pixels_per_point).round() as u32,
+            (height as f32 * pixels_per_point).round() as u32,
+        );
+        for mesh in meshes {
+            self.render_mesh(width, height, pixels_per_point, viewport, mesh)?;
+        }
+        Ok(())
+    }
+
+    fn render_mesh(
+        &self,
+        width: u32,
+        height: u32,
+        pixels_per_point: f32,
+        viewport: Viewport,
+        mesh: &GuiMesh,
+    ) -> Result<(), Error> {
+        if mesh.indices.is_empty() {
+            return Ok(());
+        }
+        let mut positions = Vec::with_capacity(2 * mesh.vertices.len());
+        let mut colors = Vec::with_capacity(4 * mesh.vertices.len());
+        let mut uvs = Vec::with_capacity(2 * mesh.vertices.len());
+        for v in mesh.vertices.iter() {
+            positions.push(v.position.x);
+            positions.push(v.position.y);
+            uvs.push(v.uv.x);
+            uvs.push(v.uv.y);
+            colors.push(v.color[0] as f32);
+            colors.push(v.color[1] as f32);
+            colors.push(v.color[2] as f32);
+            colors.push(v.color[3] as f32);
+        }
+
+        let position_buffer = VertexBuffer::new_with_static(&self.context, &positions)?;
+        let uv_buffer = VertexBuffer::new_with_static(&self.context, &uvs)?;
+        let color_buffer = VertexBuffer::new_with_static(&self.context, &colors)?;
+        let index_buffer = ElementBuffer::new(&self.context, mesh.indices)?;
+
+        let render_states = RenderStates {
+            blend: Some(BlendParameters {
+                source_rgb_multiplier: BlendMultiplierType::One,
+                destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+                source_alpha_multiplier: BlendMultiplierType::OneMinusDstAlpha,
+                destination_alpha_multiplier: BlendMultiplierType::One,
+                ..Default::default()
+            }),
+            depth_test: DepthTestType::Always,
+            ..Default::default()
+        };
+
+        self.program.use_texture(mesh.texture, "u_sampler")?;
+        self.program
+            .use_uniform_vec2("u_screen_size", &vec2(width as f32, height as f32))?;
+
+        self.program.use_attribute_vec2(&position_buffer, "a_pos")?;
+        self.program.use_attribute_vec4(&color_buffer, "a_srgba")?;
+        self.program.use_attribute_vec2(&uv_buffer, "a_tc")?;
+
+        // The clip rectangle is given in points from the top left corner, while the scissor box is in pixels from the bottom left corner
+        let min_x = (mesh.clip_rect[0] * pixels_per_point).round().max(0.0) as i32;
+        let max_x = (mesh.clip_rect[2] * pixels_per_point)
+            .round()
+            .min(viewport.width as f32) as i32;
+        let min_y = (viewport.height as f32 - mesh.clip_rect[3] * pixels_per_point)
+            .round()
+            .max(0.0) as i32;
+        let max_y = (viewport.height as f32 - mesh.clip_rect[1] * pixels_per_point)
+            .round()
+            .min(viewport.height as f32) as i32;
+        if max_x <= min_x || max_y <= min_y {
+            return Ok(());
+        }
+        self.context.enable(consts::SCISSOR_TEST);
+        self.context
+            .scissor(min_x, min_y, max_x - min_x, max_y - min_y);
+        let result =
+            self.program
+                .draw_elements(render_states, CullType::None, viewport, &index_buffer);
+        // Disable the scissor test before propagating an error, otherwise it would affect all later draws
+        self.context.disable(consts::SCISSOR_TEST);
+        result
+    }
+}
@@ -0,0 +1,283 @@
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use std::collections::HashMap;
+
+///
+/// The metrics of a glyph in a [SdfFont], given in pixels of the atlas, which are also the pixels of the font at the [font size](SdfFont::font_size) of the atlas.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Glyph {
+    /// The top left corner of the glyph in the atlas, with the origin in the top left corner of the atlas image.
+    pub atlas_min: Vec2,
+    /// The bottom right corner of the glyph in the atlas, with the origin in the top left corner of the atlas image.
+    pub atlas_max: Vec2,
+    /// The offset from the pen position on the baseline to the top left corner of the glyph, x to the right and y upwards.
+    pub bearing: Vec2,
+    /// How far the pen position moves to the right after this glyph.
+    pub advance: f32,
+}
+
+///
+/// A font consisting of a precomputed signed distance field atlas and glyph metrics, used for rendering crisp text at any size with a [SdfTextRenderer].
+///
+/// The atlas is a single channel texture ([Format::R](crate::Format::R) with `u8` data) where a value of 0.5 (128) is on the edge of a glyph,
+/// larger values are inside and smaller values are outside the glyph. The distance is scaled such that the values from 0 to 1
+/// cover [distance_range](SdfFont::distance_range) pixels of the atlas. This matches the output of common tools, for example msdfgen in single channel mode.
+/// The glyphs should be separated by at least half the distance range in the atlas.
+///
+pub struct SdfFont {
+    texture: Texture2D,
+    width: f32,
+    height: f32,
+    glyphs: HashMap<char, Glyph>,
+    ///
+    /// The font size in pixels at which the atlas is generated, ie. the size which the glyph metrics are given in.
+    ///
+    pub font_size: f32,
+    ///
+    /// The distance between two lines of text in pixels at the font size of the atlas.
+    ///
+    pub line_height: f32,
+    ///
+    /// The number of atlas pixels covered by the distance values from 0 to 1.
+    ///
+    pub distance_range: f32,
+}
+
+impl SdfFont {
+    ///
+    /// Creates a new font from a signed distance field atlas and the metrics of the glyphs in the atlas.
+    ///
+    /// # Errors
+    /// Returns an error if the atlas does not have the [Format::R](crate::Format::R) format.
+    ///
+    pub fn new(
+        context: &Context,
+        atlas: &CPUTexture<u8>,
+        glyphs: HashMap<char, Glyph>,
+        font_size: f32,
+        line_height: f32,
+        distance_range: f32,
+    ) -> Result<Self, Error> {
+        if atlas.format != Format::R {
+            Err(Error::TextureError {
+                message: "The signed distance field atlas of a font must have the R format."
+                    .to_string(),
+            })?;
+        }
+        let mut texture = Texture2D::new(context, atlas)?;
+        texture.set_interpolation(Interpolation::Linear, Interpolation::Linear);
+        texture.set_wrapping(Wrapping::ClampToEdge, Wrapping::ClampToEdge);
+        Ok(Self {
+            texture,
+            width: atlas.width as f32,
+            height: atlas.height as f32,
+            glyphs,
+            font_size,
+            line_height,
+            distance_range,
+        })
+    }
+
+    ///
+    /// Returns the metrics of the glyph for the given character, if it is in the atlas.
+    ///
+    pub fn glyph(&self, character: char) -> Option<&Glyph> {
+        self.glyphs.get(&character)
+    }
+
+    ///
+    /// Returns the width in pixels of the widest line of the given text when rendered with the given size in pixels.
+    ///
+    pub fn text_width(&self, text: &str, size: f32) -> f32 {
+        let scale = size / self.font_size;
+        text.lines()
+            .map(|line| {
+                line.chars()
+                    .filter_map(|c| self.glyph(c))
+                    .map(|glyph| glyph.advance)
+                    .sum::<f32>()
+            })
+            .fold(0.0, f32::max)
+            * scale
+    }
+}
+
+///
+/// The style of the text rendered by a [SdfTextRenderer].
+///
+#[derive(Debug, Copy, Clone)]
+pub struct SdfTextStyle {
+    /// The color of the text.
+    pub color: Vec4,
+    /// The color of the outline.
+    pub outline_color: Vec4,
+    /// The width of the outline in pixels, a width of zero means no outline.
+    pub outline_width: f32,
+    /// The color of the shadow, an alpha value of zero means no shadow.
+    pub shadow_color: Vec4,
+    /// The offset of the shadow in pixels, x to the right and y upwards.
+    pub shadow_offset: Vec2,
+    /// The width in pixels of the soft edge of the shadow.
+    pub shadow_softness: f32,
+}
+
+impl Default for SdfTextStyle {
+    fn default() -> Self {
+        Self {
+            color: vec4(1.0, 1.0, 1.0, 1.0),
+            outline_color: vec4(0.0, 0.0, 0.0, 1.0),
+            outline_width: 0.0,
+            shadow_color: vec4(0.0, 0.0, 0.0, 0.0),
+            shadow_offset: vec2(2.0, -2.0),
+            shadow_softness: 2.0,
+        }
+    }
+}
+
+///
+/// Renders text using a [signed distance field font](SdfFont), which gives crisp edges at any size and supports outlines and soft shadows.
+/// The text is positioned in pixel coordinates of the viewport with the origin in the bottom left corner and is rendered on top of everything else.
+///
+pub struct SdfTextRenderer {
+    program: Program,
+    position_buffer: VertexBuffer,
+    uv_buffer: VertexBuffer,
+    uv_rectangle_buffer: VertexBuffer,
+}
+
+impl SdfTextRenderer {
+    ///
+    /// Creates a new text renderer.
+    ///
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            program: Program::from_source(
+                context,
+                include_str!("shaders/sdf_text.vert"),
+                include_str!("shaders/sdf_text.frag"),
+            )?,
+            position_buffer: VertexBuffer::new(context)?,
+            uv_buffer: VertexBuffer::new(context)?,
+            uv_rectangle_buffer: VertexBuffer::new(context)?,
+        })
+    }
+
+    ///
+    /// Render the text with the given font, size in pixels and style, starting at the given position on the baseline of the first line.
+    /// A new line is started at each line break and characters which are not in the font are skipped.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(
+        &mut self,
+        viewport: Viewport,
+        font: &SdfFont,
+        text: &str,
+        position: Vec2,
+        size: f32,
+        style: &SdfTextStyle,
+    ) -> Result<(), Error> {
+        let scale = size / font.font_size;
+        // Expand the quads to make room for the antialiased edge, the outline and the shadow
+        let padding = 1.0
+            + style.outline_width.max(0.0)
+            + if style.shadow_color.w > 0.0 {
+                style.shadow_offset.x.abs().max(style.shadow_offset.y.abs())
+                    + style.shadow_softness.max(1.0)
+            } else {
+                0.0
+            };
+        let atlas_padding = padding / scale;
+
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut uv_rectangles = Vec::new();
+        let mut pen = position;
+        for c in text.chars() {
+            if c == '\n' {
+                pen = vec2(position.x, pen.y - font.line_height * scale);
+                continue;
+            }
+            let glyph = if let Some(glyph) = font.glyph(c) {
+                glyph
+            } else {
+                continue;
+            };
+            let glyph_size = glyph.atlas_max - glyph.atlas_min;
+            if glyph_size.x > 0.0 && glyph_size.y > 0.0 {
+                let top_left = pen + scale * glyph.bearing;
+                let x0 = top_left.x - padding;
+                let x1 = top_left.x + scale * glyph_size.x + padding;
+                let y0 = top_left.y - scale * glyph_size.y - padding;
+                let y1 = top_left.y + padding;
+                let u0 = (glyph.atlas_min.x - atlas_padding) / font.width;
+                let u1 = (glyph.atlas_max.x + atlas_padding) / font.width;
+                // The first row of the atlas image is at v = 0
+                let v0 = (glyph.atlas_max.y + atlas_padding) / font.height;
+                let v1 = (glyph.atlas_min.y - atlas_padding) / font.height;
+                positions.extend_from_slice(&[x0, y0, x1, y0, x1, y1, x0, y0, x1, y1, x0, y1]);
+                uvs.extend_from_slice(&[u0, v0, u1, v0, u1, v1, u0, v0, u1, v1, u0, v1]);
+                let rectangle = [
+                    glyph.atlas_min.x / font.width,
+                    glyph.atlas_min.y / font.height,
+                    glyph.atlas_max.x / font.width,
+                    glyph.atlas_max.y / font.height,
+                ];
+                for _ in 0..6 {
+                    uv_rectangles.extend_from_slice(&rectangle);
+                }
+            }
+            pen.x += glyph.advance * scale;
+        }
+        if positions.is_empty() {
+            return Ok(());
+        }
+        self.position_buffer.fill_with_dynamic(&positions);
+        self.uv_buffer.fill_with_dynamic(&uvs);
+        self.uv_rectangle_buffer.fill_with_dynamic(&uv_rectangles);
+
+        self.program.use_uniform_vec2(
+            "resolution",
+            &vec2(viewport.width as f32, viewport.height as f32),
+        )?;
+        self.program.use_texture(&font.texture, "atlas")?;
+        self.program
+            .use_uniform_float("distanceScale", &(font.distance_range * scale))?;
+        self.program.use_uniform_vec4("color", &style.color)?;
+        self.program
+            .use_uniform_vec4("outlineColor", &style.outline_color)?;
+        self.program
+            .use_uniform_float("outlineWidth", &style.outline_width)?;
+        self.program
+            .use_uniform_vec4("shadowColor", &style.shadow_color)?;
+        self.program.use_uniform_vec2(
+            "shadowOffset",
+            &vec2(
+                style.shadow_offset.x / (scale * font.width),
+                -style.shadow_offset.y / (scale * font.height),
+            ),
+        )?;
+        self.program
+            .use_uniform_float("shadowSoftness", &style.shadow_softness)?;
+        self.program
+            .use_attribute_vec2(&self.position_buffer, "position")?;
+        self.program
+            .use_attribute_vec2(&self.uv_buffer, "uv_coordinates")?;
+        self.program
+            .use_attribute_vec4(&self.uv_rectangle_buffer, "uv_rectangle")?;
+        self.program.draw_arrays(
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTestType::Always,
+                blend: Some(BlendParameters::TRANSPARENCY),
+                ..Default::default()
+            },
+            CullType::None,
+            viewport,
+            positions.len() as u32 / 2,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+uniform sampler2D atlas;
+// The number of screen pixels covered by the distance range of the atlas
+uniform float distanceScale;
+uniform vec4 color;
+uniform vec4 outlineColor;
+uniform float outlineWidth;
+uniform vec4 shadowColor;
+uniform vec2 shadowOffset;
+uniform float shadowSoftness;
+
+in vec2 uv;
+flat in vec4 uvRectangle;
+
+layout (location = 0) out vec4 outColor;
+
+// The signed distance in screen pixels to the edge of the glyph, positive inside
+float signedDistance(vec2 uv)
+{
+    float d = texture(atlas, clamp(uv, uvRectangle.xy, uvRectangle.zw)).r;
+    return (d - 0.5) * distanceScale;
+}
+
+void main()
+{
+    float distance = signedDistance(uv);
+    float fill = clamp(distance + 0.5, 0.0, 1.0);
+    vec4 text = vec4(color.rgb, color.a * fill);
+    if (outlineWidth > 0.0) {
+        float outline = clamp(distance + outlineWidth + 0.5, 0.0, 1.0);
+        text = vec4(mix(outlineColor.rgb, color.rgb, fill), mix(outlineColor.a * outline, color.a, fill));
+    }
+
+    if (shadowColor.a > 0.0) {
+        float shadowDistance = signedDistance(uv - shadowOffset) + max(outlineWidth, 0.0);
+        float softness = max(shadowSoftness, 1.0);
+        float shadow = shadowColor.a * clamp((shadowDistance + 0.5 * softness) / softness, 0.0, 1.0);
+        // Composite the text over the shadow
+        float alpha = text.a + shadow * (1.0 - text.a);
+        vec3 rgb = alpha > 0.0 ? (text.rgb * text.a + shadowColor.rgb * shadow * (1.0 - text.a)) / alpha : text.rgb;
+        text = vec4(rgb, alpha);
+    }
+    if (text.a <= 0.0) {
+        discard;
+    }
+    outColor = text;
+}
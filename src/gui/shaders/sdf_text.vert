@@ -0,0 +1,15 @@
+uniform vec2 resolution;
+
+in vec2 position;
+in vec2 uv_coordinates;
+in vec4 uv_rectangle;
+
+out vec2 uv;
+flat out vec4 uvRectangle;
+
+void main()
+{
+    uv = uv_coordinates;
+    uvRectangle = uv_rectangle;
+    gl_Position = vec4(2.0 * position / resolution - 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,30 @@
+uniform vec2 shapeHalfSize;
+uniform vec4 cornerRadii;
+uniform vec4 fillColor;
+uniform vec4 borderColor;
+uniform float borderWidth;
+
+in vec2 p;
+
+layout (location = 0) out vec4 outColor;
+
+// Signed distance to a rectangle with the given half size and the radius of each corner
+// (x is top right, y is bottom right, z is top left and w is bottom left)
+float roundedBoxDistance(vec2 p, vec2 halfSize, vec4 radii)
+{
+    radii.xy = p.x > 0.0 ? radii.xy : radii.zw;
+    float radius = p.y > 0.0 ? radii.x : radii.y;
+    vec2 q = abs(p) - halfSize + radius;
+    return min(max(q.x, q.y), 0.0) + length(max(q, 0.0)) - radius;
+}
+
+void main()
+{
+    float distance = roundedBoxDistance(p, shapeHalfSize, cornerRadii);
+    float coverage = clamp(0.5 - distance, 0.0, 1.0);
+    vec4 color = fillColor;
+    if (borderWidth > 0.0) {
+        color = mix(fillColor, borderColor, clamp(distance + borderWidth + 0.5, 0.0, 1.0));
+    }
+    outColor = vec4(color.rgb, color.a * coverage);
+}
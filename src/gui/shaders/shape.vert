@@ -0,0 +1,15 @@
+uniform vec2 resolution;
+uniform vec2 shapeCenter;
+uniform vec2 shapeHalfSize;
+
+in vec2 quad_position;
+
+out vec2 p;
+
+void main()
+{
+    // Expand the quad by a pixel to make room for the antialiased edge
+    p = (2.0 * quad_position - 1.0) * (shapeHalfSize + 1.0);
+    vec2 pixel = shapeCenter + p;
+    gl_Position = vec4(2.0 * pixel / resolution - 1.0, 0.0, 1.0);
+}
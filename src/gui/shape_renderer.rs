@@ -0,0 +1,143 @@
+use crate::core::*;
+use crate::math::*;
+
+///
+/// The colors and border of a shape rendered by the [ShapeRenderer].
+///
+#[derive(Debug, Copy, Clone)]
+pub struct ShapeStyle {
+    /// The color inside the shape.
+    pub fill_color: Vec4,
+    /// The color of the border.
+    pub border_color: Vec4,
+    /// The width of the border in pixels, a width of zero means no border.
+    pub border_width: f32,
+}
+
+impl Default for ShapeStyle {
+    fn default() -> Self {
+        Self {
+            fill_color: vec4(1.0, 1.0, 1.0, 1.0),
+            border_color: vec4(0.0, 0.0, 0.0, 1.0),
+            border_width: 0.0,
+        }
+    }
+}
+
+///
+/// Renders antialiased 2D shapes, ie. rectangles, rounded rectangles and circles, for building a simple user interface, for example panels and sliders.
+/// The shapes are defined in pixel coordinates of the viewport with the origin in the bottom left corner and are rendered on top of everything else
+/// using a signed distance field, which gives smooth edges at any size.
+///
+pub struct ShapeRenderer {
+    program: Program,
+    quad_buffer: VertexBuffer,
+}
+
+impl ShapeRenderer {
+    ///
+    /// Creates a new shape renderer.
+    ///
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            program: Program::from_source(
+                context,
+                include_str!("shaders/shape.vert"),
+                include_str!("shaders/shape.frag"),
+            )?,
+            quad_buffer: VertexBuffer::new_with_static(
+                context,
+                &[
+                    0.0f32, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0,
+                ],
+            )?,
+        })
+    }
+
+    ///
+    /// Render a rectangle with the given position of the bottom left corner and size in pixels.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_rectangle(
+        &self,
+        viewport: Viewport,
+        position: Vec2,
+        size: Vec2,
+        style: &ShapeStyle,
+    ) -> Result<(), Error> {
+        self.render_rounded_rectangle(viewport, position, size, [0.0; 4], style)
+    }
+
+    ///
+    /// Render a rectangle with rounded corners with the given position of the bottom left corner and size in pixels.
+    /// The radius of each corner is given in pixels in the order bottom left, bottom right, top right and top left
+    /// and is limited to half the width and height of the rectangle.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_rounded_rectangle(
+        &self,
+        viewport: Viewport,
+        position: Vec2,
+        size: Vec2,
+        corner_radii: [f32; 4],
+        style: &ShapeStyle,
+    ) -> Result<(), Error> {
+        let half_size = 0.5 * size;
+        let max_radius = half_size.x.min(half_size.y);
+        let radius = |i: usize| corner_radii[i].clamp(0.0, max_radius);
+        self.program.use_uniform_vec2(
+            "resolution",
+            &vec2(viewport.width as f32, viewport.height as f32),
+        )?;
+        self.program
+            .use_uniform_vec2("shapeCenter", &(position + half_size))?;
+        self.program.use_uniform_vec2("shapeHalfSize", &half_size)?;
+        self.program.use_uniform_vec4(
+            "cornerRadii",
+            &vec4(radius(2), radius(1), radius(3), radius(0)),
+        )?;
+        self.program
+            .use_uniform_vec4("fillColor", &style.fill_color)?;
+        self.program
+            .use_uniform_vec4("borderColor", &style.border_color)?;
+        self.program
+            .use_uniform_float("borderWidth", &style.border_width)?;
+        self.program
+            .use_attribute_vec2(&self.quad_buffer, "quad_position")?;
+        self.program.draw_arrays(
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTestType::Always,
+                blend: Some(BlendParameters::TRANSPARENCY),
+                ..Default::default()
+            },
+            CullType::None,
+            viewport,
+            6,
+        );
+        Ok(())
+    }
+
+    ///
+    /// Render a circle with the given center and radius in pixels.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_circle(
+        &self,
+        viewport: Viewport,
+        center: Vec2,
+        radius: f32,
+        style: &ShapeStyle,
+    ) -> Result<(), Error> {
+        self.render_rounded_rectangle(
+            viewport,
+            center - vec2(radius, radius),
+            vec2(2.0 * radius, 2.0 * radius),
+            [radius; 4],
+            style,
+        )
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::PrimitiveType;
 use crate::definition::*;
 use crate::io::*;
 use ::gltf::Gltf;
@@ -130,7 +131,10 @@ fn parse_tree<'a>(
                     };
                     let metallic_roughness_texture =
                         if let Some(info) = pbr.metallic_roughness_texture() {
-                            Some(parse_texture(loaded, path, buffers, info)?)
+                            // The metallic and roughness values are linear data, not colors
+                            let mut texture = parse_texture(loaded, path, buffers, info)?;
+                            texture.format = texture.format.linear();
+                            Some(texture)
                         } else {
                             None
                         };
@@ -146,10 +150,11 @@ fn parse_tree<'a>(
 
                 let colors = reader.read_colors(0).map(|values| {
                     let mut cols = Vec::new();
-                    for value in values.into_rgb_u8() {
+                    for value in values.into_rgba_u8() {
                         cols.push(value[0]);
                         cols.push(value[1]);
                         cols.push(value[2]);
+                        cols.push(value[3]);
                     }
                     cols
                 });
@@ -163,13 +168,53 @@ fn parse_tree<'a>(
                     uvs
                 });
 
+                let joint_indices = reader.read_joints(0).map(|values| {
+                    let mut joints = Vec::new();
+                    for value in values.into_u16() {
+                        joints.extend(value.iter().map(|j| *j as u32));
+                    }
+                    joints
+                });
+
+                let joint_weights = reader.read_weights(0).map(|values| {
+                    let mut weights = Vec::new();
+                    for value in values.into_f32() {
+                        weights.extend_from_slice(&value);
+                    }
+                    weights
+                });
+
+                let mut morph_targets = Vec::new();
+                for (i, (position_deltas, normal_deltas, _)) in
+                    reader.read_morph_targets().enumerate()
+                {
+                    let position_deltas = position_deltas
+                        .map(|values| values.flat_map(|v| v.to_vec()).collect())
+                        .unwrap_or_else(|| vec![0.0; positions.len()]);
+                    let normal_deltas =
+                        normal_deltas.map(|values| values.flat_map(|v| v.to_vec()).collect());
+                    morph_targets.push(MorphTarget {
+                        name: format!("target {}", i),
+                        position_deltas,
+                        normal_deltas,
+                    });
+                }
+
+                let primitive_type = match primitive.mode() {
+                    ::gltf::mesh::Mode::TriangleStrip => PrimitiveType::TriangleStrip,
+                    _ => PrimitiveType::Triangles,
+                };
                 cpu_meshes.push(CPUMesh {
                     name: name.clone(),
                     positions,
                     normals,
                     indices,
+                    primitive_type,
                     colors,
                     uvs,
+                    joint_indices,
+                    joint_weights,
+                    morph_targets,
                     material_name: Some(material_name),
                 });
             }
@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
-type RefLoaded = Rc<RefCell<HashMap<PathBuf, Result<Vec<u8>, std::io::Error>>>>;
+type RefLoaded = Rc<RefCell<HashMap<PathBuf, Option<Result<Vec<u8>, std::io::Error>>>>>;
 
 ///
 /// The resources loaded using the [Loader](crate::Loader).
@@ -70,6 +70,9 @@ impl<'a> Loaded<'a> {
 
 ///
 /// Functionality for loading any type of resource runtime on both desktop and web.
+/// On desktop, the resources are read from the file system and the loading blocks until all resources are loaded.
+/// On web, the resources are fetched from the given urls without blocking, so the `on_done` callback is called later when all resources are loaded.
+/// In both cases, resources which failed to load are reported as an error when getting the bytes from the [loaded resources](crate::Loaded).
 ///
 pub struct Loader {}
 
@@ -102,9 +105,7 @@ impl Loader {
     {
         let loads = Rc::new(RefCell::new(HashMap::new()));
         for path in paths {
-            loads
-                .borrow_mut()
-                .insert(path.as_ref().to_path_buf(), Ok(Vec::new()));
+            loads.borrow_mut().insert(path.as_ref().to_path_buf(), None);
             Self::load_file(path, loads.clone());
         }
         info!("Loading started...");
@@ -116,31 +117,31 @@ impl Loader {
         G: 'static + Fn(f32),
         F: 'static + FnOnce(&mut Loaded),
     {
-        Self::sleep(100, move || {
-            let is_loading = match loads.try_borrow() {
-                Ok(map) => {
-                    let total_count = map.len();
-                    let mut count = 0;
-                    for bytes in map.values() {
-                        if bytes.is_err() || bytes.as_ref().unwrap().len() > 0 {
-                            count = count + 1;
-                        }
-                    }
-                    progress_callback(count as f32 / total_count as f32);
-                    count < total_count
-                }
-                Err(_) => true,
-            };
-
-            if is_loading {
-                Self::wait_local(loads, progress_callback, on_done);
-            } else {
-                info!("Loading done.");
-                on_done(&mut Loaded {
-                    loaded: &mut loads.borrow_mut(),
-                });
+        let is_loading = match loads.try_borrow() {
+            Ok(map) => {
+                let total_count = map.len();
+                let count = map.values().filter(|bytes| bytes.is_some()).count();
+                progress_callback(count as f32 / total_count as f32);
+                count < total_count
             }
-        });
+            Err(_) => true,
+        };
+
+        if is_loading {
+            Self::sleep(100, move || {
+                Self::wait_local(loads, progress_callback, on_done);
+            });
+        } else {
+            info!("Loading done.");
+            let mut loaded = loads
+                .borrow_mut()
+                .drain()
+                .map(|(path, bytes)| (path, bytes.unwrap()))
+                .collect();
+            on_done(&mut Loaded {
+                loaded: &mut loaded,
+            });
+        }
     }
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -172,12 +173,12 @@ impl Loader {
                 let result = f.read_to_end(&mut bytes).and(Ok(bytes));
                 loads
                     .borrow_mut()
-                    .insert(path.as_ref().to_path_buf(), result);
+                    .insert(path.as_ref().to_path_buf(), Some(result));
             }
             Err(e) => {
                 loads
                     .borrow_mut()
-                    .insert(path.as_ref().to_path_buf(), Err(e));
+                    .insert(path.as_ref().to_path_buf(), Some(Err(e)));
             }
         }
     }
@@ -192,6 +193,16 @@ impl Loader {
 
     #[cfg(target_arch = "wasm32")]
     async fn load_file_async<P: AsRef<Path>>(path: P, loads: RefLoaded) {
+        let result = Self::fetch(path.as_ref())
+            .await
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("{:?}", e)));
+        loads
+            .borrow_mut()
+            .insert(path.as_ref().to_path_buf(), Some(result));
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn fetch(path: &Path) -> Result<Vec<u8>, wasm_bindgen::JsValue> {
         use wasm_bindgen::prelude::*;
         use wasm_bindgen::JsCast;
         use wasm_bindgen_futures::JsFuture;
@@ -201,24 +212,27 @@ impl Loader {
         opts.method("GET");
         opts.mode(RequestMode::Cors);
 
-        let request =
-            Request::new_with_str_and_init(path.as_ref().to_str().unwrap(), &opts).unwrap();
+        let url = path
+            .to_str()
+            .ok_or_else(|| JsValue::from_str("The url is not valid unicode"))?;
+        let request = Request::new_with_str_and_init(url, &opts)?;
         request
             .headers()
-            .set("Accept", "application/octet-stream")
-            .unwrap();
-
-        let window = web_sys::window().unwrap();
-        let resp_value = JsFuture::from(window.fetch_with_request(&request))
-            .await
-            .unwrap();
-        let resp: Response = resp_value.dyn_into().unwrap();
+            .set("Accept", "application/octet-stream")?;
+
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+        let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+        let resp: Response = resp_value.dyn_into()?;
+        if !resp.ok() {
+            Err(JsValue::from_str(&format!(
+                "Request failed with status {} {}",
+                resp.status(),
+                resp.status_text()
+            )))?;
+        }
 
         // Convert this other `Promise` into a rust `Future`.
-        let data: JsValue = JsFuture::from(resp.array_buffer().unwrap()).await.unwrap();
-        loads.borrow_mut().insert(
-            path.as_ref().to_path_buf(),
-            Ok(js_sys::Uint8Array::new(&data).to_vec()),
-        );
+        let data: JsValue = JsFuture::from(resp.array_buffer()?).await?;
+        Ok(js_sys::Uint8Array::new(&data).to_vec())
     }
 }
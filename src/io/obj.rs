@@ -1,3 +1,4 @@
+use crate::core::PrimitiveType;
 use crate::definition::*;
 use crate::io::*;
 use std::collections::HashMap;
@@ -152,9 +153,13 @@ impl<'a> Loaded<'a> {
                     material_name: mesh.material_name.clone(),
                     positions,
                     indices: Some(Indices::U32(indices)),
+                    primitive_type: PrimitiveType::Triangles,
                     normals: Some(normals),
                     uvs: Some(uvs),
                     colors: None,
+                    joint_indices: None,
+                    joint_weights: None,
+                    morph_targets: Vec::new(),
                 });
             }
         }
@@ -15,6 +15,26 @@ impl<'a> Loaded<'a> {
         image_from_bytes(&self.get_bytes(path)?)
     }
 
+    ///
+    /// Deserialize the loaded image resource at the given path into a [CPUTexture](crate::CPUTexture), see [image](Self::image).
+    /// If `premultiply` is true, the color channels are multiplied by the alpha channel (see [CPUTexture::premultiply_alpha])
+    /// so that the resulting texture can be blended using [premultiplied alpha](crate::BlendParameters::PREMULTIPLIED_ALPHA).
+    ///
+    /// # Feature
+    /// Only available when the `image-io` feature is enabled.
+    ///
+    pub fn image_with_alpha<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        premultiply: bool,
+    ) -> Result<CPUTexture<u8>, IOError> {
+        let mut texture = self.image(path)?;
+        if premultiply {
+            texture.premultiply_alpha();
+        }
+        Ok(texture)
+    }
+
     ///
     /// Deserialize the 6 loaded image resources at the given paths into a [CPUTexture](crate::CPUTexture) using
     /// the [image](https://crates.io/crates/image/main.rs) crate.
@@ -1,3 +1,4 @@
+use crate::core::PrimitiveType;
 use crate::definition::*;
 use crate::io::*;
 use std::path::Path;
@@ -41,9 +42,13 @@ impl<'a> Loaded<'a> {
                 material_name: mesh.material_name,
                 positions: mesh.positions,
                 indices: mesh.indices.map(|i| Indices::U32(i)),
+                primitive_type: PrimitiveType::Triangles,
                 normals: mesh.normals,
                 uvs: mesh.uvs,
                 colors: None,
+                joint_indices: None,
+                joint_weights: None,
+                morph_targets: Vec::new(),
             });
         }
 
@@ -1,6 +1,6 @@
 //!
 //! A collection of light types.
-//! Currently implemented light types are ambient light, directional light, spot light and point light.
+//! Currently implemented light types are ambient light, hemisphere light, directional light, spot light and point light.
 //! Directional and spot lights can cast shadows.
 //!
 
@@ -19,3 +19,11 @@ pub use point_light::*;
 mod ambient_light;
 #[doc(inline)]
 pub use ambient_light::*;
+
+mod hemisphere_light;
+#[doc(inline)]
+pub use hemisphere_light::*;
+
+mod lights;
+#[doc(inline)]
+pub use lights::*;
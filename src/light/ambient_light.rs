@@ -1,4 +1,6 @@
+use crate::core::*;
 use crate::math::*;
+use std::rc::Rc;
 
 ///
 /// A light which shines equally on all parts of any surface.
@@ -6,6 +8,37 @@ use crate::math::*;
 pub struct AmbientLight {
     pub color: Vec3,
     pub intensity: f32,
+    occlusion_texture: Option<Rc<Texture2D>>,
+}
+
+impl AmbientLight {
+    ///
+    /// Constructs a new ambient light with the given color and intensity and without an [occlusion texture](Self::set_occlusion_texture).
+    ///
+    pub fn new(color: Vec3, intensity: f32) -> Self {
+        Self {
+            color,
+            intensity,
+            occlusion_texture: None,
+        }
+    }
+
+    ///
+    /// Sets an optional ambient occlusion texture, for example the output of a screen space ambient occlusion pass,
+    /// which darkens the ambient light in creases and corners.
+    /// The texture is mapped onto the viewport of the render call and the red channel is multiplied with the ambient light.
+    /// If `None`, the ambient light is not occluded anywhere.
+    ///
+    pub fn set_occlusion_texture(&mut self, occlusion_texture: Option<Rc<Texture2D>>) {
+        self.occlusion_texture = occlusion_texture;
+    }
+
+    ///
+    /// Returns the ambient occlusion texture, see [set_occlusion_texture](Self::set_occlusion_texture).
+    ///
+    pub fn occlusion_texture(&self) -> Option<&Rc<Texture2D>> {
+        self.occlusion_texture.as_ref()
+    }
 }
 
 impl Default for AmbientLight {
@@ -13,6 +46,7 @@ impl Default for AmbientLight {
         Self {
             color: vec3(1.0, 1.0, 1.0),
             intensity: 1.0,
+            occlusion_texture: None,
         }
     }
 }
@@ -4,6 +4,26 @@ use crate::definition::*;
 use crate::math::*;
 use crate::object::*;
 
+///
+/// The shadow frustum and shadow map size used when generating shadow maps for several lights at once, see [DirectionalLight::generate_shadow_maps_for].
+/// The frustum is an orthographic box centered at the target and oriented along the direction of each light.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShadowFrustum {
+    /// The center of the shadow frustum.
+    pub target: Vec3,
+    /// The width of the shadow frustum orthogonal to the light direction.
+    pub width: f32,
+    /// The height of the shadow frustum orthogonal to the light direction.
+    pub height: f32,
+    /// The depth of the shadow frustum along the light direction.
+    pub depth: f32,
+    /// The width of the shadow map texture in texels.
+    pub texture_width: u32,
+    /// The height of the shadow map texture in texels.
+    pub texture_height: u32,
+}
+
 ///
 /// A light which shines in the given direction.
 /// The light will cast shadows if you [generate a shadow map](DirectionalLight::generate_shadow_map).
@@ -13,6 +33,9 @@ pub struct DirectionalLight {
     light_buffer: UniformBuffer,
     shadow_texture: DepthTargetTexture2D,
     shadow_camera: Option<Camera>,
+    shadow_layer_mask: u32,
+    hardware_pcf: bool,
+    debug_effect: Option<crate::ImageEffect>,
 }
 
 impl DirectionalLight {
@@ -25,15 +48,11 @@ impl DirectionalLight {
         let mut light = DirectionalLight {
             context: context.clone(),
             light_buffer: UniformBuffer::new(context, &[3u32, 1, 3, 1, 16])?,
-            shadow_texture: DepthTargetTexture2D::new(
-                context,
-                1,
-                1,
-                Wrapping::ClampToEdge,
-                Wrapping::ClampToEdge,
-                DepthFormat::Depth32F,
-            )?,
+            shadow_texture: new_shadow_texture(context, 1, 1, true)?,
             shadow_camera: None,
+            shadow_layer_mask: u32::MAX,
+            hardware_pcf: true,
+            debug_effect: None,
         };
 
         light.set_intensity(intensity);
@@ -63,18 +82,51 @@ impl DirectionalLight {
 
     pub fn clear_shadow_map(&mut self) {
         self.shadow_camera = None;
-        self.shadow_texture = DepthTargetTexture2D::new(
-            &self.context,
-            1,
-            1,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            DepthFormat::Depth32F,
-        )
-        .unwrap();
+        self.shadow_texture = new_shadow_texture(&self.context, 1, 1, self.hardware_pcf).unwrap();
         self.light_buffer.update(3, &[0.0]).unwrap();
     }
 
+    ///
+    /// Sets the render layers that cast shadows from this light, see [Geometry::layer_mask](crate::Geometry::layer_mask).
+    /// Geometries that do not share any layer with this mask are skipped when [generating the shadow map](Self::generate_shadow_map).
+    /// The default is all layers.
+    ///
+    pub fn set_shadow_layer_mask(&mut self, layer_mask: u32) {
+        self.shadow_layer_mask = layer_mask;
+    }
+
+    ///
+    /// The render layers that cast shadows from this light, see [set_shadow_layer_mask](Self::set_shadow_layer_mask).
+    ///
+    pub fn shadow_layer_mask(&self) -> u32 {
+        self.shadow_layer_mask
+    }
+
+    ///
+    /// Sets whether the shadow map is sampled using a comparison sampler which gives hardware 2x2 percentage closer filtering
+    /// of the shadow edges (see [DepthTargetTexture2D::set_depth_comparison]). This is the default.
+    /// Disable it to fall back to comparing the depth in the shader, for example on drivers where linear filtering of depth textures is not working correctly.
+    ///
+    pub fn set_hardware_pcf(&mut self, enabled: bool) {
+        self.hardware_pcf = enabled;
+        self.shadow_texture
+            .set_depth_comparison(shadow_comparison(enabled));
+    }
+
+    ///
+    /// Whether the shadow map is sampled using a comparison sampler, see [set_hardware_pcf](Self::set_hardware_pcf).
+    ///
+    pub fn hardware_pcf(&self) -> bool {
+        self.hardware_pcf
+    }
+
+    ///
+    /// Renders the depth of the given geometries, as seen from the light, into the shadow map of this light.
+    /// The depth of shadow casters outside the depth of the shadow frustum is clamped (see [RenderStates::depth_clamp]),
+    /// so they still cast shadows instead of leaving holes in the shadow.
+    /// Geometries that are not in the [shadow layers](Self::set_shadow_layer_mask) of this light are skipped
+    /// and the rest are rendered in the order of their [depth program key](crate::Geometry::depth_program_key) to minimize the number of program switches.
+    ///
     pub fn generate_shadow_map(
         &mut self,
         target: &Vec3,
@@ -84,54 +136,139 @@ impl DirectionalLight {
         texture_width: u32,
         texture_height: u32,
         geometries: &[&dyn Geometry],
+    ) -> Result<(), Error> {
+        let aabbs: Vec<_> = geometries.iter().map(|geometry| geometry.aabb()).collect();
+        self.generate_shadow_map_internal(
+            &ShadowFrustum {
+                target: *target,
+                width: frustrum_width,
+                height: frustrum_height,
+                depth: frustrum_depth,
+                texture_width,
+                texture_height,
+            },
+            geometries,
+            &aabbs,
+        )
+    }
+
+    ///
+    /// Same as [generate_shadow_map](Self::generate_shadow_map) for each of the given lights, using the same [shadow frustum](ShadowFrustum) for all of them.
+    /// Each light still gets its own shadow matrix and shadow map, but the bounding boxes of the geometries are only computed once
+    /// and the shadow map textures are reused when their size is unchanged, which reduces the overhead in scenes with several lights.
+    ///
+    pub fn generate_shadow_maps_for(
+        lights: &mut [&mut DirectionalLight],
+        frustum: &ShadowFrustum,
+        geometries: &[&dyn Geometry],
+    ) -> Result<(), Error> {
+        let aabbs: Vec<_> = geometries.iter().map(|geometry| geometry.aabb()).collect();
+        for light in lights.iter_mut() {
+            light.generate_shadow_map_internal(frustum, geometries, &aabbs)?;
+        }
+        Ok(())
+    }
+
+    fn generate_shadow_map_internal(
+        &mut self,
+        frustum: &ShadowFrustum,
+        geometries: &[&dyn Geometry],
+        aabbs: &[Option<AxisAlignedBoundingBox>],
     ) -> Result<(), Error> {
         let direction = self.direction();
         let up = compute_up_direction(direction);
+        let (texture_width, texture_height) = (frustum.texture_width, frustum.texture_height);
 
-        self.shadow_camera = Some(Camera::new_orthographic(
+        let shadow_camera = Camera::new_orthographic(
             &self.context,
-            target - direction.normalize() * 0.5 * frustrum_depth,
-            *target,
+            frustum.target - direction.normalize() * 0.5 * frustum.depth,
+            frustum.target,
             up,
-            frustrum_width,
-            frustrum_height,
-            frustrum_depth,
-        )?);
-        self.light_buffer.update(
-            4,
-            &shadow_matrix(self.shadow_camera.as_ref().unwrap()).to_slice(),
+            frustum.width,
+            frustum.height,
+            frustum.depth,
         )?;
+        self.light_buffer
+            .update(4, &shadow_matrix(&shadow_camera).to_slice())?;
 
-        self.shadow_texture = DepthTargetTexture2D::new(
-            &self.context,
-            texture_width,
-            texture_height,
-            Wrapping::ClampToEdge,
-            Wrapping::ClampToEdge,
-            DepthFormat::Depth32F,
-        )
-        .unwrap();
+        if self.shadow_texture.width() != texture_width
+            || self.shadow_texture.height() != texture_height
+        {
+            self.shadow_texture = new_shadow_texture(
+                &self.context,
+                texture_width,
+                texture_height,
+                self.hardware_pcf,
+            )?;
+        }
+        let mut visible_geometries: Vec<_> = geometries
+            .iter()
+            .zip(aabbs)
+            .filter(|(geometry, aabb)| {
+                geometry.layer_mask() & self.shadow_layer_mask != 0
+                    && aabb
+                        .as_ref()
+                        .map(|aabb| shadow_camera.in_frustum(aabb))
+                        .unwrap_or(true)
+            })
+            .map(|(geometry, _)| *geometry)
+            .collect();
+        visible_geometries.sort_by_key(|geometry| geometry.depth_program_key());
         self.shadow_texture.write(Some(1.0), || {
             let viewport = Viewport::new_at_origo(texture_width, texture_height);
-            for geometry in geometries {
-                if geometry
-                    .aabb()
-                    .map(|aabb| self.shadow_camera.as_ref().unwrap().in_frustum(&aabb))
-                    .unwrap_or(true)
-                {
-                    geometry.render_depth(
-                        RenderStates::default(),
-                        viewport,
-                        self.shadow_camera.as_ref().unwrap(),
-                    )?;
-                }
+            for geometry in visible_geometries {
+                geometry.render_depth(
+                    RenderStates {
+                        depth_clamp: true,
+                        ..Default::default()
+                    },
+                    viewport,
+                    &shadow_camera,
+                )?;
             }
             Ok(())
         })?;
+        self.shadow_camera = Some(shadow_camera);
         self.light_buffer.update(3, &[1.0])?;
         Ok(())
     }
 
+    ///
+    /// Renders the content of the shadow map into the given viewport of the current render target,
+    /// where black is close to the light and white is far away from the light or not covered by any shadow caster.
+    /// Must be called in a render target render function, for example in the callback function of [Screen::write](crate::Screen::write),
+    /// typically after rendering the scene and with a small viewport in a corner of the screen.
+    ///
+    /// **Note:** Only intended for debugging, for example when tuning the shadow frustum and the shadow map size.
+    /// The shader is only created the first time this is called, so there is no cost when not using it.
+    ///
+    pub fn debug_render_shadow_map(&mut self, viewport: Viewport) -> Result<(), Error> {
+        if self.debug_effect.is_none() {
+            self.debug_effect = Some(crate::ImageEffect::new(
+                &self.context,
+                include_str!("shaders/shadow_map_debug.frag"),
+            )?);
+        }
+        // The stored depth cannot be read while the shadow map is sampled as a comparison sampler.
+        self.shadow_texture.set_depth_comparison(None);
+        let effect = self.debug_effect.as_ref().unwrap();
+        let result = effect
+            .use_texture(&self.shadow_texture, "shadowMap")
+            .and_then(|_| {
+                effect.apply(
+                    RenderStates {
+                        write_mask: WriteMask::COLOR,
+                        depth_test: DepthTestType::Always,
+                        ..Default::default()
+                    },
+                    viewport,
+                )
+            });
+        self.shadow_texture
+            .set_depth_comparison(shadow_comparison(self.hardware_pcf));
+        result
+    }
+
     pub fn shadow_map(&self) -> &DepthTargetTexture2D {
         &self.shadow_texture
     }
@@ -141,11 +278,42 @@ impl DirectionalLight {
     }
 }
 
+///
+/// Creates a shadow map which clamps to a white border, ie. the maximum depth, so lookups outside the shadow map are unshadowed.
+/// On web, where clamping to the border is not supported, it clamps to the edge instead.
+///
+fn new_shadow_texture(
+    context: &Context,
+    width: u32,
+    height: u32,
+    hardware_pcf: bool,
+) -> Result<DepthTargetTexture2D, Error> {
+    let mut texture = DepthTargetTexture2D::new(
+        context,
+        width,
+        height,
+        Wrapping::ClampToBorder,
+        Wrapping::ClampToBorder,
+        DepthFormat::Depth32F,
+    )?;
+    texture.set_border_color(vec4(1.0, 1.0, 1.0, 1.0));
+    texture.set_depth_comparison(shadow_comparison(hardware_pcf));
+    Ok(texture)
+}
+
+fn shadow_comparison(hardware_pcf: bool) -> Option<DepthTestType> {
+    if hardware_pcf {
+        Some(DepthTestType::LessOrEqual)
+    } else {
+        None
+    }
+}
+
 fn shadow_matrix(camera: &Camera) -> Mat4 {
     let bias_matrix = crate::Mat4::new(
         0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.5, 0.5, 0.5, 1.0,
     );
-    bias_matrix * camera.projection() * camera.view()
+    bias_matrix * camera.view_projection()
 }
 
 fn compute_up_direction(direction: Vec3) -> Vec3 {
@@ -0,0 +1,78 @@
+use crate::core::*;
+use crate::math::*;
+
+///
+/// An ambient light which shines with the sky color from above and with the ground color from below.
+/// The color on a surface is interpolated between the two colors based on the angle between the surface normal and the up direction.
+/// Use it by adding it to a [light collection](crate::Lights::set_hemisphere).
+///
+pub struct HemisphereLight {
+    light_buffer: UniformBuffer,
+}
+
+impl HemisphereLight {
+    ///
+    /// Creates a new hemisphere light with the positive y-axis as the up direction.
+    ///
+    pub fn new(
+        context: &Context,
+        intensity: f32,
+        sky_color: &Vec3,
+        ground_color: &Vec3,
+    ) -> Result<HemisphereLight, Error> {
+        Self::new_with_up(
+            context,
+            intensity,
+            sky_color,
+            ground_color,
+            &vec3(0.0, 1.0, 0.0),
+        )
+    }
+
+    ///
+    /// Creates a new hemisphere light with the given up direction, for scenes where the y-axis is not pointing up.
+    ///
+    pub fn new_with_up(
+        context: &Context,
+        intensity: f32,
+        sky_color: &Vec3,
+        ground_color: &Vec3,
+        up: &Vec3,
+    ) -> Result<HemisphereLight, Error> {
+        let mut light = HemisphereLight {
+            light_buffer: UniformBuffer::new(context, &[3u32, 1, 3, 1, 3, 1])?,
+        };
+        light.set_intensity(intensity);
+        light.set_sky_color(sky_color);
+        light.set_ground_color(ground_color);
+        light.set_up(up);
+        Ok(light)
+    }
+
+    pub fn set_sky_color(&mut self, color: &Vec3) {
+        self.light_buffer.update(0, &color.to_slice()).unwrap();
+    }
+
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.light_buffer.update(1, &[intensity]).unwrap();
+    }
+
+    pub fn set_ground_color(&mut self, color: &Vec3) {
+        self.light_buffer.update(2, &color.to_slice()).unwrap();
+    }
+
+    pub fn set_up(&mut self, up: &Vec3) {
+        self.light_buffer
+            .update(4, &up.normalize().to_slice())
+            .unwrap();
+    }
+
+    pub fn up(&self) -> Vec3 {
+        let u = self.light_buffer.get(4).unwrap();
+        vec3(u[0], u[1], u[2])
+    }
+
+    pub fn buffer(&self) -> &UniformBuffer {
+        &self.light_buffer
+    }
+}
@@ -0,0 +1,212 @@
+use crate::core::*;
+use crate::light::*;
+
+const DIRECTIONAL_LIGHT_SIZE: usize = 24;
+const POINT_LIGHT_SIZE: usize = 12;
+const HEMISPHERE_LIGHT_SIZE: usize = 12;
+
+///
+/// A collection of directional and point lights and optionally a hemisphere light which are packed into arrays in a single uniform buffer,
+/// so that any number of lights up to the maximum can be used in a shader which loops over the active lights.
+/// This is an alternative to sending each light to the shader separately, see for example
+/// [render_with_lights](crate::ShadedGeometry::render_with_lights).
+///
+/// The maximum number of lights of each type is given at construction ([Lights::DEFAULT_MAX_DIRECTIONAL_LIGHTS] directional lights
+/// and [Lights::DEFAULT_MAX_POINT_LIGHTS] point lights when using [Lights::new]).
+/// To raise the limits, use [Lights::new_with_max] instead. Note that the size of a uniform buffer
+/// is limited by the graphics driver (at least 16 KB is guaranteed, which is room for roughly 150 directional lights or 300 point lights)
+/// and that a higher maximum increases the cost of compiling the shader.
+///
+/// **Note:** The lights in the collection do not cast shadows, since shadow maps cannot be packed into a uniform buffer.
+///
+pub struct Lights {
+    light_buffer: UniformBuffer,
+    max_directional_lights: usize,
+    max_point_lights: usize,
+    directional_light_count: usize,
+    point_light_count: usize,
+    has_hemisphere_light: bool,
+}
+
+impl Lights {
+    /// The maximum number of directional lights when using [Lights::new].
+    pub const DEFAULT_MAX_DIRECTIONAL_LIGHTS: usize = 4;
+    /// The maximum number of point lights when using [Lights::new].
+    pub const DEFAULT_MAX_POINT_LIGHTS: usize = 16;
+
+    ///
+    /// Creates a new empty collection of lights with room for [Lights::DEFAULT_MAX_DIRECTIONAL_LIGHTS] directional lights
+    /// and [Lights::DEFAULT_MAX_POINT_LIGHTS] point lights.
+    ///
+    pub fn new(context: &Context) -> Result<Lights, Error> {
+        Self::new_with_max(
+            context,
+            Self::DEFAULT_MAX_DIRECTIONAL_LIGHTS,
+            Self::DEFAULT_MAX_POINT_LIGHTS,
+        )
+    }
+
+    ///
+    /// Creates a new empty collection of lights with room for the given number of directional and point lights.
+    ///
+    pub fn new_with_max(
+        context: &Context,
+        max_directional_lights: usize,
+        max_point_lights: usize,
+    ) -> Result<Lights, Error> {
+        // GLSL does not allow arrays of size zero
+        let max_directional_lights = max_directional_lights.max(1);
+        let max_point_lights = max_point_lights.max(1);
+        let mut lights = Lights {
+            light_buffer: UniformBuffer::new(
+                context,
+                &[
+                    4,
+                    (DIRECTIONAL_LIGHT_SIZE * max_directional_lights) as u32,
+                    (POINT_LIGHT_SIZE * max_point_lights) as u32,
+                    HEMISPHERE_LIGHT_SIZE as u32,
+                ],
+            )?,
+            max_directional_lights,
+            max_point_lights,
+            directional_light_count: 0,
+            point_light_count: 0,
+            has_hemisphere_light: false,
+        };
+        lights.update_counts()?;
+        Ok(lights)
+    }
+
+    ///
+    /// Adds a copy of the current parameters of the given directional light to the collection.
+    /// Returns an error if the collection already contains the maximum number of directional lights.
+    ///
+    pub fn add_directional(&mut self, light: &DirectionalLight) -> Result<(), Error> {
+        if self.directional_light_count >= self.max_directional_lights {
+            Err(Error::BufferOverflow {
+                message: format!(
+                    "Cannot add more than {} directional lights to the light collection.",
+                    self.max_directional_lights
+                ),
+            })?;
+        }
+        let mut data = light_data(light.buffer(), DIRECTIONAL_LIGHT_SIZE)?;
+        data[7] = 0.0; // Shadows are not supported
+        self.set_light_data(
+            1,
+            self.directional_light_count * DIRECTIONAL_LIGHT_SIZE,
+            &data,
+        )?;
+        self.directional_light_count += 1;
+        self.update_counts()
+    }
+
+    ///
+    /// Adds a copy of the current parameters of the given point light to the collection.
+    /// Returns an error if the collection already contains the maximum number of point lights.
+    ///
+    pub fn add_point(&mut self, light: &PointLight) -> Result<(), Error> {
+        if self.point_light_count >= self.max_point_lights {
+            Err(Error::BufferOverflow {
+                message: format!(
+                    "Cannot add more than {} point lights to the light collection.",
+                    self.max_point_lights
+                ),
+            })?;
+        }
+        let data = light_data(light.buffer(), POINT_LIGHT_SIZE)?;
+        self.set_light_data(2, self.point_light_count * POINT_LIGHT_SIZE, &data)?;
+        self.point_light_count += 1;
+        self.update_counts()
+    }
+
+    ///
+    /// Sets the hemisphere light of the collection to a copy of the current parameters of the given hemisphere light
+    /// or removes the hemisphere light if `None`. The collection can only contain one hemisphere light.
+    ///
+    pub fn set_hemisphere(&mut self, light: Option<&HemisphereLight>) -> Result<(), Error> {
+        if let Some(light) = light {
+            let data = light_data(light.buffer(), HEMISPHERE_LIGHT_SIZE)?;
+            self.light_buffer.update(3, &data)?;
+        }
+        self.has_hemisphere_light = light.is_some();
+        self.update_counts()
+    }
+
+    ///
+    /// Removes all lights from the collection.
+    ///
+    pub fn clear(&mut self) -> Result<(), Error> {
+        self.directional_light_count = 0;
+        self.point_light_count = 0;
+        self.has_hemisphere_light = false;
+        self.update_counts()
+    }
+
+    ///
+    /// The number of directional lights in the collection.
+    ///
+    pub fn directional_light_count(&self) -> usize {
+        self.directional_light_count
+    }
+
+    ///
+    /// The number of point lights in the collection.
+    ///
+    pub fn point_light_count(&self) -> usize {
+        self.point_light_count
+    }
+
+    ///
+    /// Binds the lights to the `Lights` uniform block in the given program.
+    /// The program must include the [fragment_shader_source](Lights::fragment_shader_source).
+    ///
+    pub fn bind(&self, program: &Program) -> Result<(), Error> {
+        program.use_uniform_block(&self.light_buffer, "Lights")?;
+        Ok(())
+    }
+
+    ///
+    /// Returns fragment shader source which defines the `Lights` uniform block and the function
+    /// `vec3 calculate_lights(vec3 surface_color, vec3 position, vec3 normal, float metallic, float roughness)`
+    /// which returns the sum of the contributions of all the lights in the collection.
+    /// The hemisphere light is included in the sum, but other ambient light is not.
+    /// Must be included after the shared light shader source which defines the light structs and lighting functions.
+    ///
+    pub fn fragment_shader_source(&self) -> String {
+        format!(
+            "#define MAX_DIRECTIONAL_LIGHTS {}\n#define MAX_POINT_LIGHTS {}\n{}",
+            self.max_directional_lights,
+            self.max_point_lights,
+            include_str!("shaders/lights.frag")
+        )
+    }
+
+    fn set_light_data(&mut self, index: u32, offset: usize, data: &[f32]) -> Result<(), Error> {
+        let mut all = self.light_buffer.get(index)?.to_vec();
+        all.splice(offset..offset + data.len(), data.iter().cloned());
+        self.light_buffer.update(index, &all)
+    }
+
+    fn update_counts(&mut self) -> Result<(), Error> {
+        self.light_buffer.update(
+            0,
+            &[
+                self.directional_light_count as f32,
+                self.point_light_count as f32,
+                if self.has_hemisphere_light { 1.0 } else { 0.0 },
+                0.0,
+            ],
+        )
+    }
+}
+
+fn light_data(buffer: &UniformBuffer, size: usize) -> Result<Vec<f32>, Error> {
+    let mut data = Vec::with_capacity(size);
+    let mut index = 0;
+    while data.len() < size {
+        data.extend_from_slice(buffer.get(index)?);
+        index += 1;
+    }
+    Ok(data)
+}
@@ -42,9 +42,9 @@ impl PointLight {
     }
 
     pub fn set_attenuation(&mut self, constant: f32, linear: f32, exponential: f32) {
-        self.light_buffer.update(2, &[constant]).unwrap();
-        self.light_buffer.update(3, &[linear]).unwrap();
-        self.light_buffer.update(4, &[exponential]).unwrap();
+        self.light_buffer
+            .update_many(&[(2, &[constant]), (3, &[linear]), (4, &[exponential])])
+            .unwrap();
     }
 
     pub fn set_position(&mut self, position: &Vec3) {
@@ -0,0 +1,30 @@
+
+layout (std140) uniform Lights
+{
+    vec4 lightCounts;
+    DirectionalLight directionalLights[MAX_DIRECTIONAL_LIGHTS];
+    PointLight pointLights[MAX_POINT_LIGHTS];
+    HemisphereLight hemisphereLight;
+};
+
+vec3 calculate_lights(vec3 surface_color, vec3 position, vec3 normal, float metallic, float roughness)
+{
+    vec3 color = vec3(0.0);
+    int directional_light_count = int(lightCounts.x);
+    for (int i = 0; i < directional_light_count; i++)
+    {
+        DirectionalLight light = directionalLights[i];
+        vec3 light_color = light.base.intensity * light.base.color;
+        color += calculate_light(light_color, -light.direction, surface_color, position, normal, metallic, roughness);
+    }
+    int point_light_count = int(lightCounts.y);
+    for (int i = 0; i < point_light_count; i++)
+    {
+        color += calculate_point_light(pointLights[i], surface_color, position, normal, metallic, roughness);
+    }
+    if (lightCounts.z > 0.5)
+    {
+        color += calculate_hemisphere_light(hemisphereLight, surface_color, normal, metallic);
+    }
+    return color;
+}
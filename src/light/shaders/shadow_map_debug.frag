@@ -0,0 +1,11 @@
+uniform sampler2D shadowMap;
+
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+    float depth = texture(shadowMap, uv).x;
+    color = vec4(depth, depth, depth, 1.0);
+}
@@ -13,6 +13,7 @@ pub struct SpotLight {
     light_buffer: UniformBuffer,
     shadow_texture: DepthTargetTexture2D,
     shadow_camera: Option<Camera>,
+    shadow_layer_mask: u32,
 }
 
 impl SpotLight {
@@ -40,6 +41,7 @@ impl SpotLight {
                 DepthFormat::Depth32F,
             )?,
             shadow_camera: None,
+            shadow_layer_mask: u32::MAX,
         };
         light.set_intensity(intensity);
         light.set_color(color);
@@ -63,9 +65,9 @@ impl SpotLight {
     }
 
     pub fn set_attenuation(&mut self, constant: f32, linear: f32, exponential: f32) {
-        self.light_buffer.update(2, &[constant]).unwrap();
-        self.light_buffer.update(3, &[linear]).unwrap();
-        self.light_buffer.update(4, &[exponential]).unwrap();
+        self.light_buffer
+            .update_many(&[(2, &[constant]), (3, &[linear]), (4, &[exponential])])
+            .unwrap();
     }
 
     pub fn set_position(&mut self, position: &Vec3) {
@@ -106,6 +108,22 @@ impl SpotLight {
         self.light_buffer.update(9, &[0.0]).unwrap();
     }
 
+    ///
+    /// Sets the render layers that cast shadows from this light, see [Geometry::layer_mask](crate::Geometry::layer_mask).
+    /// Geometries that do not share any layer with this mask are skipped when [generating the shadow map](Self::generate_shadow_map).
+    /// The default is all layers.
+    ///
+    pub fn set_shadow_layer_mask(&mut self, layer_mask: u32) {
+        self.shadow_layer_mask = layer_mask;
+    }
+
+    ///
+    /// The render layers that cast shadows from this light, see [set_shadow_layer_mask](Self::set_shadow_layer_mask).
+    ///
+    pub fn shadow_layer_mask(&self) -> u32 {
+        self.shadow_layer_mask
+    }
+
     pub fn generate_shadow_map(
         &mut self,
         frustrum_depth: f32,
@@ -143,6 +161,9 @@ impl SpotLight {
         self.shadow_texture.write(Some(1.0), || {
             let viewport = Viewport::new_at_origo(texture_size, texture_size);
             for geometry in geometries {
+                if geometry.layer_mask() & self.shadow_layer_mask == 0 {
+                    continue;
+                }
                 if geometry
                     .aabb()
                     .map(|aabb| self.shadow_camera.as_ref().unwrap().in_frustum(&aabb))
@@ -174,7 +195,7 @@ fn shadow_matrix(camera: &Camera) -> Mat4 {
     let bias_matrix = crate::Mat4::new(
         0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.5, 0.5, 0.5, 1.0,
     );
-    bias_matrix * camera.projection() * camera.view()
+    bias_matrix * camera.view_projection()
 }
 
 fn compute_up_direction(direction: Vec3) -> Vec3 {
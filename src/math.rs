@@ -10,6 +10,14 @@ mod aabb;
 #[doc(inline)]
 pub use aabb::*;
 
+mod ray;
+#[doc(inline)]
+pub use ray::*;
+
 mod viewport;
 #[doc(inline)]
 pub use viewport::*;
+
+mod animation;
+#[doc(inline)]
+pub use animation::*;
@@ -55,6 +55,13 @@ impl AxisAlignedBoundingBox {
         &self.max
     }
 
+    ///
+    /// Get the center of the bounding box.
+    ///
+    pub fn center(&self) -> Vec3 {
+        0.5 * (self.min + self.max)
+    }
+
     ///
     /// Expands the bounding box such that all of the given positions are contained inside the bounding box.
     /// A position consisting of an x, y and z coordinate corresponds to three consecutive value in the positions array.
@@ -0,0 +1,175 @@
+use crate::math::*;
+
+///
+/// A key frame in an [AnimationTrack] which defines the translation, rotation and scale at the given time.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyFrame {
+    pub time: f32,
+    pub position: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl KeyFrame {
+    ///
+    /// Returns the transformation which first scales, then rotates and finally translates.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        Mat4::from_translation(self.position)
+            * Mat4::from(self.rotation)
+            * Mat4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+}
+
+///
+/// Defines what happens when an [AnimationTrack] is sampled outside the time range of its key frames.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationMode {
+    /// Before the first key frame the first key frame is used and after the last key frame the last key frame is used.
+    Clamp,
+    /// The animation is repeated, ie. the time is wrapped into the time range of the key frames.
+    Loop,
+}
+
+///
+/// A sequence of time stamped [key frames](KeyFrame) which can be sampled at any time to get an interpolated transformation, for example
+/// to animate a [Mesh](crate::Mesh), a [scene node](crate::Node) or a bone of a [SkinnedMesh](crate::SkinnedMesh).
+/// The position and scale are linearly interpolated and the rotation is spherically interpolated (slerp) between the two nearest key frames.
+///
+#[derive(Debug, Clone)]
+pub struct AnimationTrack {
+    key_frames: Vec<KeyFrame>,
+    pub mode: AnimationMode,
+}
+
+impl AnimationTrack {
+    ///
+    /// Creates a new animation track with the given key frames, which do not need to be sorted by time.
+    /// Key frames with a time which is not a number (NaN) are sorted after all other key frames.
+    ///
+    pub fn new(mut key_frames: Vec<KeyFrame>, mode: AnimationMode) -> Self {
+        key_frames.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Self { key_frames, mode }
+    }
+
+    ///
+    /// Adds a key frame to the track.
+    ///
+    pub fn add_key_frame(&mut self, key_frame: KeyFrame) {
+        let index = self
+            .key_frames
+            .iter()
+            .position(|k| k.time > key_frame.time)
+            .unwrap_or(self.key_frames.len());
+        self.key_frames.insert(index, key_frame);
+    }
+
+    ///
+    /// Returns the key frames sorted by time.
+    ///
+    pub fn key_frames(&self) -> &[KeyFrame] {
+        &self.key_frames
+    }
+
+    ///
+    /// Returns the time between the first and the last key frame or zero if the track has less than two key frames.
+    ///
+    pub fn duration(&self) -> f32 {
+        match (self.key_frames.first(), self.key_frames.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        }
+    }
+
+    ///
+    /// Returns the interpolated key frame at the given time.
+    /// A track with a single key frame always returns that key frame and an empty track returns the identity transformation.
+    ///
+    pub fn sample_key_frame(&self, time: f32) -> KeyFrame {
+        let first = match self.key_frames.first() {
+            Some(first) => first,
+            None => {
+                return KeyFrame {
+                    time,
+                    position: vec3(0.0, 0.0, 0.0),
+                    rotation: Quat::one(),
+                    scale: vec3(1.0, 1.0, 1.0),
+                }
+            }
+        };
+        let last = self.key_frames.last().unwrap();
+        let duration = self.duration();
+        let t = if duration <= 0.0 {
+            first.time
+        } else {
+            match self.mode {
+                AnimationMode::Clamp => time.clamp(first.time, last.time),
+                AnimationMode::Loop => first.time + (time - first.time).rem_euclid(duration),
+            }
+        };
+
+        let next = self
+            .key_frames
+            .iter()
+            .position(|k| k.time > t)
+            .unwrap_or(self.key_frames.len() - 1);
+        if next == 0 {
+            return *first;
+        }
+        let k0 = &self.key_frames[next - 1];
+        let k1 = &self.key_frames[next];
+        if k1.time <= k0.time || t >= k1.time {
+            return *k1;
+        }
+        let s = (t - k0.time) / (k1.time - k0.time);
+        let r1 = if k0.rotation.dot(k1.rotation) < 0.0 {
+            -k1.rotation
+        } else {
+            k1.rotation
+        };
+        KeyFrame {
+            time: t,
+            position: k0.position.lerp(k1.position, s),
+            rotation: k0.rotation.slerp(r1, s).normalize(),
+            scale: k0.scale.lerp(k1.scale, s),
+        }
+    }
+
+    ///
+    /// Returns the interpolated transformation at the given time, see [sample_key_frame](Self::sample_key_frame).
+    ///
+    pub fn sample(&self, time: f32) -> Mat4 {
+        self.sample_key_frame(time).transformation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_frame(time: f32) -> KeyFrame {
+        KeyFrame {
+            time,
+            position: vec3(time, 0.0, 0.0),
+            rotation: Quat::one(),
+            scale: vec3(1.0, 1.0, 1.0),
+        }
+    }
+
+    #[test]
+    fn new_sorts_key_frames_with_nan_last() {
+        let track = AnimationTrack::new(
+            vec![key_frame(2.0), key_frame(f32::NAN), key_frame(0.0)],
+            AnimationMode::Clamp,
+        );
+        let times = track
+            .key_frames()
+            .iter()
+            .map(|k| k.time)
+            .collect::<Vec<_>>();
+        assert_eq!(&times[..2], &[0.0, 2.0]);
+        assert!(times[2].is_nan());
+    }
+}
@@ -2,7 +2,7 @@ pub(crate) use cgmath::ortho;
 pub(crate) use cgmath::perspective;
 #[doc(hidden)]
 pub use cgmath::prelude::*;
-use cgmath::{Deg, Matrix2, Matrix3, Matrix4, Point3, Rad, Vector2, Vector3, Vector4};
+use cgmath::{Deg, Matrix2, Matrix3, Matrix4, Point3, Quaternion, Rad, Vector2, Vector3, Vector4};
 
 pub type Vec2 = Vector2<f32>;
 pub type Vec3 = Vector3<f32>;
@@ -10,6 +10,7 @@ pub type Vec4 = Vector4<f32>;
 pub type Mat2 = Matrix2<f32>;
 pub type Mat3 = Matrix3<f32>;
 pub type Mat4 = Matrix4<f32>;
+pub type Quat = Quaternion<f32>;
 pub type Point = Point3<f32>;
 pub type Degrees = Deg<f32>;
 pub type Radians = Rad<f32>;
@@ -99,6 +100,28 @@ pub fn radians(v: f32) -> Radians {
     Rad(v)
 }
 
+///
+/// Parts of the rendering assume that the y axis points up, for example the capture angles of the [Imposters](crate::Imposters) and the default camera.
+/// Returns the transformation which converts positions and directions from a coordinate system where the z axis points up,
+/// which is common in CAD and GIS data, to a coordinate system where the y axis points up, ie. `(x, y, z)` is mapped to `(x, z, -y)`.
+/// Use it as (part of) the transformation of an imported mesh, see also [convert_y_up_to_z_up].
+///
+pub fn convert_z_up_to_y_up() -> Mat4 {
+    Mat4::new(
+        1.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+///
+/// The inverse of [convert_z_up_to_y_up], ie. `(x, y, z)` is mapped to `(x, -z, y)`,
+/// for example to convert the positions of a picked point back to the coordinate system of the imported data.
+///
+pub fn convert_y_up_to_z_up() -> Mat4 {
+    Mat4::new(
+        1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    )
+}
+
 pub fn rotation_matrix_from_dir_to_dir(source_dir: Vec3, target_dir: Vec3) -> Mat4 {
     let c = source_dir.dot(target_dir);
     if c > 0.99999 {
@@ -131,3 +154,35 @@ pub fn rotation_matrix_from_dir_to_dir(source_dir: Vec3, target_dir: Vec3) -> Ma
     )
     .transpose();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_z_up_to_y_up_maps_the_up_axis() {
+        let convert = convert_z_up_to_y_up();
+        assert_eq!(convert * vec4(0.0, 0.0, 1.0, 0.0), vec4(0.0, 1.0, 0.0, 0.0));
+        assert_eq!(
+            convert * vec4(0.0, 1.0, 0.0, 0.0),
+            vec4(0.0, 0.0, -1.0, 0.0)
+        );
+        assert_eq!(convert * vec4(1.0, 0.0, 0.0, 1.0), vec4(1.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn convert_y_up_to_z_up_is_the_inverse() {
+        assert_eq!(
+            convert_y_up_to_z_up() * convert_z_up_to_y_up(),
+            Mat4::identity()
+        );
+        assert_eq!(
+            convert_z_up_to_y_up() * convert_y_up_to_z_up(),
+            Mat4::identity()
+        );
+        assert_eq!(
+            convert_y_up_to_z_up() * vec4(0.0, 1.0, 0.0, 0.0),
+            vec4(0.0, 0.0, 1.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use crate::math::*;
+
+///
+/// A ray, ie. a half line starting at the origin and going in the direction, used for intersection tests on the CPU,
+/// see for example [CPUMesh::raycast](crate::CPUMesh::raycast).
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Ray {
+    /// The start point of the ray.
+    pub origin: Vec3,
+    /// The direction of the ray. Distances along the ray are measured in units of the length of this direction, so normalize it to get distances in world units.
+    pub direction: Vec3,
+}
+
+impl Ray {
+    ///
+    /// Constructs a new ray with the given origin and direction.
+    ///
+    pub fn new(origin: Vec3, direction: Vec3) -> Self {
+        Self { origin, direction }
+    }
+
+    ///
+    /// Returns the point at the given distance along the ray.
+    ///
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + distance * self.direction
+    }
+
+    ///
+    /// Returns the ray transformed by the given transformation.
+    /// Distances along the transformed ray corresponds to the same points as the distances along this ray, since the direction is not normalized.
+    ///
+    pub fn transformed(&self, transformation: &Mat4) -> Self {
+        Self {
+            origin: (transformation * self.origin.extend(1.0)).truncate(),
+            direction: (transformation * self.direction.extend(0.0)).truncate(),
+        }
+    }
+
+    ///
+    /// Returns the distance along the ray and the barycentric coordinates `(u, v)` of the intersection with the triangle with the given corners,
+    /// such that the intersection point is `(1 - u - v) * a + u * b + v * c`.
+    /// Both sides of the triangle are hit. Returns `None` if the ray does not hit the triangle.
+    ///
+    pub fn intersect_triangle(&self, a: Vec3, b: Vec3, c: Vec3) -> Option<(f32, f32, f32)> {
+        // Möller–Trumbore intersection
+        let edge0 = b - a;
+        let edge1 = c - a;
+        let p = self.direction.cross(edge1);
+        let determinant = edge0.dot(p);
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+        let inverse_determinant = 1.0 / determinant;
+        let s = self.origin - a;
+        let u = s.dot(p) * inverse_determinant;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(edge0);
+        let v = self.direction.dot(q) * inverse_determinant;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let distance = edge1.dot(q) * inverse_determinant;
+        if distance < 0.0 {
+            return None;
+        }
+        Some((distance, u, v))
+    }
+
+    ///
+    /// Returns the distance along the ray to where it enters the given bounding box, which is zero if the origin of the ray is inside the box.
+    /// Returns `None` if the ray does not hit the box.
+    ///
+    pub fn intersect_aabb(&self, aabb: &AxisAlignedBoundingBox) -> Option<f32> {
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+        for i in 0..3 {
+            let inverse_direction = 1.0 / self.direction[i];
+            let mut t0 = (aabb.min()[i] - self.origin[i]) * inverse_direction;
+            let mut t1 = (aabb.max()[i] - self.origin[i]) * inverse_direction;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            // Handles the NaN produced when the origin lies on a slab plane and the direction is parallel to it
+            near = if t0 > near { t0 } else { near };
+            far = if t1 < far { t1 } else { far };
+            if near > far {
+                return None;
+            }
+        }
+        Some(near)
+    }
+}
+
+///
+/// The result of a ray intersection test, see for example [CPUMesh::raycast](crate::CPUMesh::raycast).
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Hit {
+    /// The distance along the ray to the intersection point, see [Ray::direction].
+    pub distance: f32,
+    /// The position of the intersection point.
+    pub position: Vec3,
+    /// The index of the hit triangle.
+    pub triangle_index: usize,
+    /// The barycentric coordinates of the intersection point in the hit triangle, ie. the weights of the three corners of the triangle, which sums to one.
+    pub barycentric: Vec3,
+}
@@ -10,6 +10,14 @@ mod instanced_mesh;
 #[doc(inline)]
 pub use instanced_mesh::*;
 
+mod skinned_mesh;
+#[doc(inline)]
+pub use skinned_mesh::*;
+
+mod morph_mesh;
+#[doc(inline)]
+pub use morph_mesh::*;
+
 mod skybox;
 #[doc(inline)]
 pub use skybox::*;
@@ -26,6 +34,50 @@ mod axes;
 #[doc(inline)]
 pub use axes::*;
 
+mod orientation_gizmo;
+#[doc(inline)]
+pub use orientation_gizmo::*;
+
+mod decal;
+#[doc(inline)]
+pub use decal::*;
+
+mod point_cloud;
+#[doc(inline)]
+pub use point_cloud::*;
+
+mod reflection_probe;
+#[doc(inline)]
+pub use reflection_probe::*;
+
+mod grid;
+#[doc(inline)]
+pub use grid::*;
+
+mod render_list;
+#[doc(inline)]
+pub use render_list::*;
+
+mod scene;
+#[doc(inline)]
+pub use scene::*;
+
+mod occlusion_culler;
+#[doc(inline)]
+pub use occlusion_culler::*;
+
+mod lines;
+#[doc(inline)]
+pub use lines::*;
+
+mod lod;
+#[doc(inline)]
+pub use lod::*;
+
+mod instanced_lod;
+#[doc(inline)]
+pub use instanced_lod::*;
+
 use crate::camera::*;
 use crate::core::*;
 use crate::math::*;
@@ -56,5 +108,71 @@ pub trait Geometry {
         max_depth: f32,
     ) -> Result<(), Error>;
 
+    ///
+    /// Render the geometry with the given shader program, which makes it possible to use a custom shader for any geometry.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// The following vertex attributes and uniforms are bound if the program uses them:
+    /// - `in vec3 position;` the vertex positions (required)
+    /// - `in vec3 normal;` the vertex normals
+    /// - `in vec2 uv_coordinates;` the vertex uv coordinates
+    /// - `in vec4 color;` the vertex colors
+    /// - `in mat4 instance_transformation;` the per instance transformation (only for instanced geometry)
+    /// - `uniform mat4 modelMatrix;` the transformation of the geometry
+    /// - `uniform mat4 normalMatrix;` the inverse transpose of the transformation of the geometry
+    /// - the `Camera` uniform block, see the mesh vertex shader for the layout.
+    ///
+    /// # Errors
+    /// Will return an error if the program uses a vertex attribute which the geometry does not have.
+    /// The default implementation always returns an error, since a geometry must bind its own vertex attributes.
+    ///
+    fn render_with_program(
+        &self,
+        _program: &Program,
+        _render_states: RenderStates,
+        _viewport: Viewport,
+        _camera: &Camera,
+    ) -> Result<(), Error> {
+        Err(Error::MeshError {
+            message: "Rendering with a custom program is not supported by this geometry."
+                .to_string(),
+        })
+    }
+
     fn aabb(&self) -> Option<AxisAlignedBoundingBox>;
+
+    ///
+    /// The render layers this geometry belongs to as a bit mask, where each of the 32 bits is a layer.
+    /// Passes that render a list of geometries, for example [DirectionalLight::generate_shadow_map](crate::DirectionalLight::generate_shadow_map),
+    /// skip the geometries that do not share any layer with the layer mask of the pass.
+    /// The default is all layers (`u32::MAX`).
+    ///
+    fn layer_mask(&self) -> u32 {
+        u32::MAX
+    }
+
+    ///
+    /// A key identifying the shader program used by [render_depth](Self::render_depth), where geometries with the same key use the same program.
+    /// Passes that render the depth of a list of geometries, for example [DirectionalLight::generate_shadow_map](crate::DirectionalLight::generate_shadow_map),
+    /// sort the geometries by this key to minimize the number of program switches, which does not change the result since the depth test is independent of the draw order.
+    /// The default is 0.
+    ///
+    fn depth_program_key(&self) -> usize {
+        0
+    }
+}
+
+///
+/// Returns a [depth program key](Geometry::depth_program_key) which is the same for all geometries that render their depth
+/// with a program of type `P` compiled from the given key in the given context.
+/// The key is computed from the context and program identifiers, so it does not depend on whether the program is created yet.
+///
+pub(crate) fn depth_program_key<P>(context: &Context, program_key: &str) -> usize {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    context.id().hash(&mut hasher);
+    std::any::type_name::<P>().hash(&mut hasher);
+    program_key.hash(&mut hasher);
+    hasher.finish() as usize
 }
@@ -23,8 +23,8 @@ impl Axes {
         let x = Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?;
         let mut y = Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?;
         let mut z = Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?;
-        y.transformation = Mat4::from_angle_z(degrees(90.0));
-        z.transformation = Mat4::from_angle_y(degrees(-90.0));
+        y.set_transformation(Mat4::from_angle_z(degrees(90.0)));
+        z.set_transformation(Mat4::from_angle_y(degrees(-90.0)));
         Ok(Self { x, y, z })
     }
 
@@ -35,25 +35,129 @@ impl Axes {
     /// The transformation can be used to position, orientate and scale the axes.
     ///
     pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
-        self.x.render_with_color(
-            &vec4(1.0, 0.0, 0.0, 1.0),
-            RenderStates::default(),
-            viewport,
-            camera,
-        )?;
-        self.y.render_with_color(
-            &vec4(0.0, 1.0, 0.0, 1.0),
-            RenderStates::default(),
-            viewport,
-            camera,
-        )?;
-        self.z.render_with_color(
-            &vec4(0.0, 0.0, 1.0, 1.0),
-            RenderStates::default(),
-            viewport,
-            camera,
-        )?;
+        self.render_with_states(RenderStates::default(), viewport, camera)
+    }
+
+    ///
+    /// Render the axes with the given render states, for example with [DepthTestType::Always](crate::DepthTestType::Always)
+    /// to draw the axes on top of everything else.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_with_states(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        self.x
+            .render_with_color(&vec4(1.0, 0.0, 0.0, 1.0), render_states, viewport, camera)?;
+        self.y
+            .render_with_color(&vec4(0.0, 1.0, 0.0, 1.0), render_states, viewport, camera)?;
+        self.z
+            .render_with_color(&vec4(0.0, 0.0, 1.0, 1.0), render_states, viewport, camera)?;
+
+        Ok(())
+    }
+}
 
+///
+/// Three arrows for each of many coordinate frames, for example the joints of a skeleton, indicating the three main axes of each frame.
+/// In contrast to rendering an [Axes] for each frame, all frames are rendered in three instanced draw calls, one for each axis.
+///
+pub struct InstancedAxes {
+    x: InstancedMesh,
+    y: InstancedMesh,
+    z: InstancedMesh,
+    /// The color of the arrows along the x-axis of the frames, default is red.
+    pub x_color: Vec4,
+    /// The color of the arrows along the y-axis of the frames, default is green.
+    pub y_color: Vec4,
+    /// The color of the arrows along the z-axis of the frames, default is blue.
+    pub z_color: Vec4,
+}
+
+impl InstancedAxes {
+    ///
+    /// Creates new axes with the given radius and length for each of the coordinate frames given by the transformations.
+    ///
+    pub fn new(
+        context: &Context,
+        radius: f32,
+        length: f32,
+        transformations: &[Mat4],
+    ) -> Result<Self, Error> {
+        // The three instanced meshes share the vertex buffers of the arrow
+        let arrow = Mesh::new(context, &CPUMesh::arrow(radius, length, 16))?;
+        let mut axes = Self {
+            x: arrow.instanced(&[])?,
+            y: arrow.instanced(&[])?,
+            z: arrow.instanced(&[])?,
+            x_color: vec4(1.0, 0.0, 0.0, 1.0),
+            y_color: vec4(0.0, 1.0, 0.0, 1.0),
+            z_color: vec4(0.0, 0.0, 1.0, 1.0),
+        };
+        axes.update_transformations(transformations);
+        Ok(axes)
+    }
+
+    ///
+    /// Updates the coordinate frames, one set of axes is rendered for each of the given transformations.
+    ///
+    pub fn update_transformations(&mut self, transformations: &[Mat4]) {
+        let y_rotation = Mat4::from_angle_z(degrees(90.0));
+        let z_rotation = Mat4::from_angle_y(degrees(-90.0));
+        self.x.update_transformations(transformations);
+        self.y.update_transformations(
+            &transformations
+                .iter()
+                .map(|t| t * y_rotation)
+                .collect::<Vec<_>>(),
+        );
+        self.z.update_transformations(
+            &transformations
+                .iter()
+                .map(|t| t * z_rotation)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    ///
+    /// The number of coordinate frames.
+    ///
+    pub fn instance_count(&self) -> u32 {
+        self.x.instance_count()
+    }
+
+    ///
+    /// Render the axes of all the coordinate frames.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        self.render_with_states(RenderStates::default(), viewport, camera)
+    }
+
+    ///
+    /// Render the axes of all the coordinate frames with the given render states, see [Axes::render_with_states].
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_with_states(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if self.instance_count() == 0 {
+            return Ok(());
+        }
+        self.x
+            .render_with_color(&self.x_color, render_states, viewport, camera)?;
+        self.y
+            .render_with_color(&self.y_color, render_states, viewport, camera)?;
+        self.z
+            .render_with_color(&self.z_color, render_states, viewport, camera)?;
         Ok(())
     }
 }
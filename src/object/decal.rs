@@ -0,0 +1,128 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use std::rc::Rc;
+
+///
+/// A decal which projects a texture onto the existing geometry inside an oriented box, for example a bullet hole or a surface detail.
+/// The box is the unit cube centered at the origin transformed by the [transformation](Decal::transformation)
+/// and the texture is projected along the local y-axis of the box, ie. the u coordinate follows the local x-axis and the v coordinate the local z-axis.
+///
+/// The decal is rendered by drawing the box and reconstructing the world position of the underlying surface from a depth texture,
+/// so the scene must first be rendered into a [DepthTargetTexture2D](crate::DepthTargetTexture2D),
+/// for example in a [deferred pipeline](crate::DeferredPipeline) or a depth pre-pass.
+/// The decal is alpha blended with the underlying surface.
+///
+pub struct Decal {
+    program: Program,
+    position_buffer: VertexBuffer,
+    index_buffer: ElementBuffer,
+    ///
+    /// The transformation of the unit cube to the decal box in world space.
+    ///
+    pub transformation: Mat4,
+    ///
+    /// The texture projected onto the geometry. The alpha channel is used for blending with the underlying surface.
+    ///
+    pub texture: Rc<Texture2D>,
+}
+
+impl Decal {
+    ///
+    /// Creates a new decal projecting the given texture onto the geometry inside the box given by the transformation.
+    ///
+    pub fn new(
+        context: &Context,
+        texture: Rc<Texture2D>,
+        transformation: Mat4,
+    ) -> Result<Self, Error> {
+        let cube = CPUMesh::cube(1.0);
+        let index_buffer = match cube.indices {
+            Some(Indices::U8(ref indices)) => ElementBuffer::new(context, indices)?,
+            _ => unreachable!(),
+        };
+        let program = Program::from_source(
+            context,
+            include_str!("shaders/decal.vert"),
+            &format!(
+                "{}{}",
+                include_str!("../core/shared.frag"),
+                include_str!("shaders/decal.frag")
+            ),
+        )?;
+        Ok(Self {
+            program,
+            position_buffer: VertexBuffer::new_with_static(context, &cube.positions)?,
+            index_buffer,
+            transformation,
+            texture,
+        })
+    }
+
+    ///
+    /// Render the decal onto the geometry whose depth, as seen from the given camera, is stored in the depth texture.
+    /// The depth texture must have the same size as the viewport and must not be the depth target that is currently written to.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if the transformation is not invertible.
+    ///
+    pub fn render(
+        &self,
+        viewport: Viewport,
+        camera: &Camera,
+        depth_texture: &DepthTargetTexture2D,
+    ) -> Result<(), Error> {
+        let inverse = self
+            .transformation
+            .invert()
+            .ok_or_else(|| Error::MeshError {
+                message: "The transformation of the decal is not invertible.".to_string(),
+            })?;
+        let view_projection_inverse =
+            camera
+                .view_projection()
+                .invert()
+                .ok_or_else(|| Error::MeshError {
+                    message: "The view projection of the camera is not invertible.".to_string(),
+                })?;
+        self.program.use_texture(depth_texture, "depthMap")?;
+        self.program.use_texture(self.texture.as_ref(), "tex")?;
+        self.program
+            .use_uniform_int("isSrgb", &(self.texture.is_srgb() as i32))?;
+        self.program
+            .use_uniform_mat4("viewProjectionInverse", &view_projection_inverse)?;
+        self.program
+            .use_uniform_mat4("modelMatrix", &self.transformation)?;
+        self.program
+            .use_uniform_mat4("modelMatrixInverse", &inverse)?;
+        self.program.use_uniform_vec4(
+            "viewport",
+            &vec4(
+                viewport.x as f32,
+                viewport.y as f32,
+                viewport.width as f32,
+                viewport.height as f32,
+            ),
+        )?;
+        self.program
+            .use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        self.program
+            .use_attribute_vec3(&self.position_buffer, "position")?;
+        // The back faces are rendered without depth test, so the decal is also visible when the camera is inside the box
+        self.program.draw_elements(
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test: DepthTestType::Always,
+                blend: Some(BlendParameters::TRANSPARENCY),
+                ..Default::default()
+            },
+            CullType::Front,
+            viewport,
+            &self.index_buffer,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+
+///
+/// A grid of antialiased lines in the xz-plane which follows the camera and fades out with the distance to the camera, for example as the floor in an editor.
+/// The grid has minor lines with the given spacing and major lines at every [major_interval](Grid::major_interval) minor line.
+///
+pub struct Grid {
+    program: Program,
+    position_buffer: VertexBuffer,
+    ///
+    /// The distance between two minor lines.
+    ///
+    pub spacing: f32,
+    ///
+    /// The number of minor lines between two major lines.
+    ///
+    pub major_interval: u32,
+    ///
+    /// The color of the minor lines in linear color space.
+    ///
+    pub minor_color: Vec4,
+    ///
+    /// The color of the major lines in linear color space.
+    ///
+    pub major_color: Vec4,
+    ///
+    /// The distance from the camera at which the grid is completely faded out. The grid starts fading at half this distance.
+    ///
+    pub fade_distance: f32,
+    ///
+    /// The height of the grid along the y-axis.
+    ///
+    pub height: f32,
+}
+
+impl Grid {
+    ///
+    /// Creates a new grid with the given spacing between the minor lines and a major line at every tenth minor line.
+    ///
+    pub fn new(context: &Context, spacing: f32) -> Result<Self, Error> {
+        let positions = vec![
+            -1.0, 0.0, -1.0, 1.0, 0.0, 1.0, 1.0, 0.0, -1.0, -1.0, 0.0, -1.0, -1.0, 0.0, 1.0, 1.0,
+            0.0, 1.0,
+        ];
+        Ok(Self {
+            program: Program::from_source(
+                context,
+                include_str!("shaders/grid.vert"),
+                &format!(
+                    "{}{}",
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/grid.frag")
+                ),
+            )?,
+            position_buffer: VertexBuffer::new_with_static(context, &positions)?,
+            spacing,
+            major_interval: 10,
+            minor_color: vec4(0.3, 0.3, 0.3, 0.5),
+            major_color: vec4(0.5, 0.5, 0.5, 0.8),
+            fade_distance: 1000.0 * spacing,
+            height: 0.0,
+        })
+    }
+
+    ///
+    /// Render the grid.
+    /// Must be called in a render target render function after rendering the opaque objects,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        self.render_with_depth_test(DepthTestType::Less, viewport, camera)
+    }
+
+    ///
+    /// Render the grid with the given depth test instead of the default [DepthTestType::Less](crate::DepthTestType::Less),
+    /// for example [DepthTestType::Always](crate::DepthTestType::Always) to render the grid on top of everything else.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_with_depth_test(
+        &self,
+        depth_test: DepthTestType,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let eye = *camera.position();
+        // Snap the center to the major lines so the plane does not move relative to the lines
+        let major_spacing = self.spacing * self.major_interval.max(1) as f32;
+        let center = vec3(
+            (eye.x / major_spacing).round() * major_spacing,
+            self.height,
+            (eye.z / major_spacing).round() * major_spacing,
+        );
+        self.program.use_uniform_vec3("center", &center)?;
+        self.program
+            .use_uniform_float("extent", &self.fade_distance)?;
+        self.program.use_uniform_vec3("eyePosition", &eye)?;
+        self.program.use_uniform_float("spacing", &self.spacing)?;
+        self.program
+            .use_uniform_float("majorInterval", &(self.major_interval.max(1) as f32))?;
+        self.program
+            .use_uniform_vec4("minorColor", &self.minor_color)?;
+        self.program
+            .use_uniform_vec4("majorColor", &self.major_color)?;
+        self.program
+            .use_uniform_float("fadeDistance", &self.fade_distance)?;
+        self.program
+            .use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        self.program
+            .use_attribute_vec3(&self.position_buffer, "position")?;
+        self.program.draw_arrays(
+            RenderStates {
+                write_mask: WriteMask::COLOR,
+                depth_test,
+                blend: Some(BlendParameters::TRANSPARENCY),
+                ..Default::default()
+            },
+            CullType::None,
+            viewport,
+            6,
+        );
+        Ok(())
+    }
+}
@@ -2,9 +2,69 @@ use crate::camera::*;
 use crate::core::*;
 use crate::definition::*;
 use crate::math::*;
+use crate::ImageEffect;
 use std::f32::consts::PI;
 
 const NO_VIEW_ANGLES: u32 = 8;
+const ATLAS_COLUMNS: u32 = 4;
+const ATLAS_ROWS: u32 = NO_VIEW_ANGLES / ATLAS_COLUMNS;
+
+///
+/// Settings used when capturing the textures of [Imposters], see [Imposters::settings].
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImposterSettings {
+    ///
+    /// The format of the depth texture used while rendering the mesh into the imposter textures.
+    /// The depth is only used during the capture, so a lower precision format like [DepthFormat::Depth16]
+    /// saves memory on mobile devices when the mesh does not have fine depth details. Only depth formats can be given since this is a [DepthFormat].
+    /// Default is [DepthFormat::Depth32F].
+    ///
+    pub depth_format: DepthFormat,
+    ///
+    /// The textures are captured with a transparent black background, so the pixels at the edge of the silhouette are mixed with black
+    /// when interpolating between two view angles, which shows as a dark fringe when blended on top of a bright background.
+    /// If true, the colors are multiplied by alpha before the interpolation and blended using [premultiplied alpha](crate::BlendParameters::PREMULTIPLIED_ALPHA),
+    /// which avoids the fringe.
+    /// Default is false.
+    ///
+    pub premultiply_alpha: bool,
+    ///
+    /// Pixels with an alpha value below this threshold are discarded, which gives a hard edge that does not need sorting.
+    /// The threshold is baked into the captured textures, where pixels below the threshold are stored as transparent black,
+    /// and applied again when rendering, since interpolating between two view angles gives alpha values in between.
+    /// If `None`, no pixels are discarded and the edge is blended, which looks smoother but requires rendering the imposters after opaque objects.
+    /// Default is `Some(0.5)`.
+    ///
+    pub alpha_cutout: Option<f32>,
+    ///
+    /// The cull type used for all geometry rendered while capturing the textures, regardless of the cull type of the geometry itself.
+    /// Culling back faces avoids capturing interior faces of meshes that are not watertight, which otherwise shows through in the imposters.
+    /// Use [CullType::None] for double-sided meshes, for example leaves modelled as single planes, where both sides must be captured.
+    /// Default is [CullType::Back].
+    ///
+    pub cull: CullType,
+    ///
+    /// If true, the view angles are captured into a grid in a single 2D texture (an atlas) instead of one layer for each view angle in a texture array.
+    /// The atlas is easier to inspect in a graphics debugger and can be used where array textures are limited,
+    /// but the atlas is four times wider and two times higher than the size given to [update_texture](Imposters::update_texture), which must be below the maximum texture size.
+    /// Takes effect the next time the textures are captured.
+    /// Default is false.
+    ///
+    pub atlas: bool,
+}
+
+impl Default for ImposterSettings {
+    fn default() -> Self {
+        Self {
+            depth_format: DepthFormat::Depth32F,
+            premultiply_alpha: false,
+            alpha_cutout: Some(0.5),
+            cull: CullType::Back,
+            atlas: false,
+        }
+    }
+}
 
 ///
 /// A level-of-detail technique to replace rendering high-poly meshes at a distance.
@@ -20,6 +80,14 @@ pub struct Imposters {
     uvs_buffer: VertexBuffer,
     instance_count: u32,
     texture: ColorTargetTexture2DArray<u8>,
+    atlas_program: Option<Program>,
+    atlas_texture: Option<ColorTargetTexture2D<u8>>,
+    cutout_effect: Option<ImageEffect>,
+    atlas_cutout_effect: Option<ImageEffect>,
+    ///
+    /// The settings used when capturing the textures using [update_texture](Self::update_texture) and when rendering the imposters.
+    ///
+    pub settings: ImposterSettings,
 }
 
 impl Imposters {
@@ -62,6 +130,11 @@ impl Imposters {
             positions_buffer,
             uvs_buffer,
             instance_count: 0,
+            atlas_program: None,
+            atlas_texture: None,
+            cutout_effect: None,
+            atlas_cutout_effect: None,
+            settings: ImposterSettings::default(),
         })
     }
 
@@ -87,6 +160,55 @@ impl Imposters {
 
         let texture_width = (max_texture_size as f32 * (width / height).min(1.0)) as u32;
         let texture_height = (max_texture_size as f32 * (height / width).min(1.0)) as u32;
+        let view_angle = |camera: &mut Camera, i: u32| {
+            let angle = i as f32 * 2.0 * PI / NO_VIEW_ANGLES as f32;
+            camera.set_view(
+                center + width * vec3(f32::sin(-angle), 0.0, f32::cos(-angle)),
+                center,
+                vec3(0.0, 1.0, 0.0),
+            )
+        };
+        Program::set_cull_override(&self.context, Some(self.settings.cull));
+        let result = if self.settings.atlas {
+            self.capture_atlas(
+                &render,
+                &mut camera,
+                view_angle,
+                texture_width,
+                texture_height,
+            )
+        } else {
+            self.capture_array(
+                &render,
+                &mut camera,
+                view_angle,
+                texture_width,
+                texture_height,
+            )
+        };
+        Program::set_cull_override(&self.context, None);
+        result?;
+
+        let xmin = center.x - 0.5 * width;
+        let xmax = center.x + 0.5 * width;
+        let ymin = min.y;
+        let ymax = max.y;
+        self.positions_buffer.fill_with_dynamic(&vec![
+            xmin, ymin, 0.0, xmax, ymin, 0.0, xmax, ymax, 0.0, xmax, ymax, 0.0, xmin, ymax, 0.0,
+            xmin, ymin, 0.0,
+        ]);
+        Ok(())
+    }
+
+    fn capture_array<F: Fn(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        render: &F,
+        camera: &mut Camera,
+        view_angle: impl Fn(&mut Camera, u32) -> Result<(), Error>,
+        texture_width: u32,
+        texture_height: u32,
+    ) -> Result<(), Error> {
+        self.atlas_texture = None;
         self.texture = ColorTargetTexture2DArray::<u8>::new(
             &self.context,
             texture_width,
@@ -106,42 +228,193 @@ impl Imposters {
             NO_VIEW_ANGLES,
             Wrapping::ClampToEdge,
             Wrapping::ClampToEdge,
-            DepthFormat::Depth32F,
+            self.settings.depth_format,
         )?;
         let render_target = RenderTargetArray::new(&self.context, &self.texture, &depth_texture)?;
+        for i in 0..NO_VIEW_ANGLES {
+            self.context
+                .push_debug_group(&format!("Imposter view angle {}", i));
+            let result = view_angle(camera, i).and_then(|_| {
+                render_target.write(
+                    &[i],
+                    0,
+                    ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0),
+                    || {
+                        render(
+                            Viewport::new_at_origo(texture_width, texture_height),
+                            camera,
+                        )
+                    },
+                )
+            });
+            self.context.pop_debug_group();
+            result?;
+        }
+        drop(render_target);
+        if let Some(alpha_cutout) = self.settings.alpha_cutout {
+            self.bake_alpha_cutout_array(alpha_cutout, texture_width, texture_height)?;
+        }
+        Ok(())
+    }
 
+    fn bake_alpha_cutout_array(
+        &mut self,
+        alpha_cutout: f32,
+        texture_width: u32,
+        texture_height: u32,
+    ) -> Result<(), Error> {
+        if self.cutout_effect.is_none() {
+            self.cutout_effect = Some(ImageEffect::new(
+                &self.context,
+                include_str!("shaders/imposter_cutout.frag"),
+            )?);
+        }
+        let effect = self.cutout_effect.as_ref().unwrap();
+        let texture = ColorTargetTexture2DArray::<u8>::new(
+            &self.context,
+            texture_width,
+            texture_height,
+            NO_VIEW_ANGLES,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        effect.use_texture_array(&self.texture, "tex")?;
+        effect.use_uniform_float("alphaCutout", &alpha_cutout)?;
         for i in 0..NO_VIEW_ANGLES {
-            let angle = i as f32 * 2.0 * PI / NO_VIEW_ANGLES as f32;
-            camera.set_view(
-                center + width * vec3(f32::sin(-angle), 0.0, f32::cos(-angle)),
-                center,
-                vec3(0.0, 1.0, 0.0),
-            )?;
-            render_target.write(
-                &[i],
-                0,
-                ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0),
-                || {
-                    render(
-                        Viewport::new_at_origo(texture_width, texture_height),
-                        &camera,
-                    )?;
-                    Ok(())
-                },
-            )?;
+            effect.use_uniform_int("layer", &(i as i32))?;
+            texture.write(&[i], ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+                effect.apply(
+                    RenderStates {
+                        depth_test: DepthTestType::Always,
+                        ..Default::default()
+                    },
+                    Viewport::new_at_origo(texture_width, texture_height),
+                )
+            })?;
         }
+        self.texture = texture;
+        Ok(())
+    }
 
-        let xmin = center.x - 0.5 * width;
-        let xmax = center.x + 0.5 * width;
-        let ymin = min.y;
-        let ymax = max.y;
-        self.positions_buffer.fill_with_dynamic(&vec![
-            xmin, ymin, 0.0, xmax, ymin, 0.0, xmax, ymax, 0.0, xmax, ymax, 0.0, xmin, ymax, 0.0,
-            xmin, ymin, 0.0,
-        ]);
+    fn capture_atlas<F: Fn(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        render: &F,
+        camera: &mut Camera,
+        view_angle: impl Fn(&mut Camera, u32) -> Result<(), Error>,
+        texture_width: u32,
+        texture_height: u32,
+    ) -> Result<(), Error> {
+        if self.atlas_program.is_none() {
+            self.atlas_program = Some(Program::from_source(
+                &self.context,
+                include_str!("shaders/imposter.vert"),
+                &format!(
+                    "#define ATLAS\n{}{}",
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/imposter.frag")
+                ),
+            )?);
+        }
+        let atlas_texture = ColorTargetTexture2D::<u8>::new(
+            &self.context,
+            ATLAS_COLUMNS * texture_width,
+            ATLAS_ROWS * texture_height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        let depth_texture = DepthTargetTexture2D::new(
+            &self.context,
+            ATLAS_COLUMNS * texture_width,
+            ATLAS_ROWS * texture_height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            self.settings.depth_format,
+        )?;
+        let render_target = RenderTarget::new(&self.context, &atlas_texture, &depth_texture)?;
+        render_target.write(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0), || {
+            for i in 0..NO_VIEW_ANGLES {
+                self.context
+                    .push_debug_group(&format!("Imposter view angle {}", i));
+                let result = view_angle(camera, i).and_then(|_| {
+                    render(
+                        Viewport {
+                            x: ((i % ATLAS_COLUMNS) * texture_width) as i32,
+                            y: ((i / ATLAS_COLUMNS) * texture_height) as i32,
+                            width: texture_width,
+                            height: texture_height,
+                        },
+                        camera,
+                    )
+                });
+                self.context.pop_debug_group();
+                result?;
+            }
+            Ok(())
+        })?;
+        drop(render_target);
+        let atlas_texture = match self.settings.alpha_cutout {
+            Some(alpha_cutout) => self.bake_alpha_cutout_atlas(
+                &atlas_texture,
+                alpha_cutout,
+                ATLAS_COLUMNS * texture_width,
+                ATLAS_ROWS * texture_height,
+            )?,
+            None => atlas_texture,
+        };
+        self.atlas_texture = Some(atlas_texture);
         Ok(())
     }
 
+    fn bake_alpha_cutout_atlas(
+        &mut self,
+        atlas_texture: &ColorTargetTexture2D<u8>,
+        alpha_cutout: f32,
+        width: u32,
+        height: u32,
+    ) -> Result<ColorTargetTexture2D<u8>, Error> {
+        if self.atlas_cutout_effect.is_none() {
+            self.atlas_cutout_effect = Some(ImageEffect::new(
+                &self.context,
+                &format!(
+                    "#define ATLAS\n{}",
+                    include_str!("shaders/imposter_cutout.frag")
+                ),
+            )?);
+        }
+        let effect = self.atlas_cutout_effect.as_ref().unwrap();
+        let texture = ColorTargetTexture2D::<u8>::new(
+            &self.context,
+            width,
+            height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        effect.use_texture(atlas_texture, "tex")?;
+        effect.use_uniform_float("alphaCutout", &alpha_cutout)?;
+        texture.write(ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+            effect.apply(
+                RenderStates {
+                    depth_test: DepthTestType::Always,
+                    ..Default::default()
+                },
+                Viewport::new_at_origo(width, height),
+            )
+        })?;
+        Ok(texture)
+    }
+
     pub fn update_positions(&mut self, positions: &[f32], angles_in_radians: &[f32]) {
         self.center_buffer.fill_with_dynamic(positions);
         self.rotation_buffer.fill_with_dynamic(angles_in_radians);
@@ -154,34 +427,61 @@ impl Imposters {
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     ///
     pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        self.render_with_depth_test(DepthTestType::Less, viewport, camera)
+    }
+
+    ///
+    /// Render the imposters with the given depth test instead of the default [DepthTestType::Less](crate::DepthTestType::Less),
+    /// for example [DepthTestType::Always](crate::DepthTestType::Always) to render the imposters on top of everything else.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_with_depth_test(
+        &self,
+        depth_test: DepthTestType,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
         let render_states = RenderStates {
-            blend: Some(BlendParameters {
-                source_rgb_multiplier: BlendMultiplierType::SrcAlpha,
-                source_alpha_multiplier: BlendMultiplierType::Zero,
-                destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
-                destination_alpha_multiplier: BlendMultiplierType::One,
-                rgb_equation: BlendEquationType::Add,
-                alpha_equation: BlendEquationType::Add,
+            blend: Some(if self.settings.premultiply_alpha {
+                BlendParameters::PREMULTIPLIED_ALPHA
+            } else {
+                BlendParameters {
+                    source_rgb_multiplier: BlendMultiplierType::SrcAlpha,
+                    source_alpha_multiplier: BlendMultiplierType::Zero,
+                    destination_rgb_multiplier: BlendMultiplierType::OneMinusSrcAlpha,
+                    destination_alpha_multiplier: BlendMultiplierType::One,
+                    rgb_equation: BlendEquationType::Add,
+                    alpha_equation: BlendEquationType::Add,
+                }
             }),
+            depth_test,
             ..Default::default()
         };
-        self.program
-            .use_uniform_int("no_views", &(NO_VIEW_ANGLES as i32))?;
-        self.program
-            .use_uniform_block(camera.uniform_buffer(), "Camera");
-
-        self.program.use_texture_array(&self.texture, "tex")?;
-
-        self.program
-            .use_attribute_vec3(&self.positions_buffer, "position")?;
-        self.program
-            .use_attribute_vec2(&self.uvs_buffer, "uv_coordinate")?;
-
-        self.program
-            .use_attribute_vec3_divisor(&self.center_buffer, "center", 1)?;
-        self.program
-            .use_attribute_divisor(&self.rotation_buffer, "theta", 1)?;
-        self.program.draw_arrays_instanced(
+        let program = if let Some(ref atlas_texture) = self.atlas_texture {
+            let program = self.atlas_program.as_ref().unwrap();
+            program.use_texture(atlas_texture, "tex")?;
+            program
+                .use_uniform_vec2("atlasGrid", &vec2(ATLAS_COLUMNS as f32, ATLAS_ROWS as f32))?;
+            program
+        } else {
+            self.program.use_texture_array(&self.texture, "tex")?;
+            &self.program
+        };
+        program.use_uniform_int("no_views", &(NO_VIEW_ANGLES as i32))?;
+        program.use_uniform_int(
+            "premultiplyAlpha",
+            &(self.settings.premultiply_alpha as i32),
+        )?;
+        program.use_uniform_float("alphaCutout", &self.settings.alpha_cutout.unwrap_or(0.0))?;
+        program.use_uniform_block(camera.uniform_buffer(), "Camera")?;
+
+        program.use_attribute_vec3(&self.positions_buffer, "position")?;
+        program.use_attribute_vec2(&self.uvs_buffer, "uv_coordinate")?;
+
+        program.use_attribute_vec3_divisor(&self.center_buffer, "center", 1)?;
+        program.use_attribute_divisor(&self.rotation_buffer, "theta", 1)?;
+        program.draw_arrays_instanced(
             render_states,
             CullType::Back,
             viewport,
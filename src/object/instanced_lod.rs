@@ -0,0 +1,131 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// Renders many instances of the same mesh, where the instances close to the camera are rendered as a full [InstancedMesh](crate::InstancedMesh)
+/// and the instances further away than the [transition distance](InstancedLod::transition_distance) are rendered as [Imposters](crate::Imposters).
+/// This is for example useful for rendering vegetation.
+///
+/// The instances are partitioned on the CPU each time the instances are rendered, based on the distance from the camera to the translation of each instance.
+/// To avoid instances popping back and forth between the two representations when the camera moves around the transition distance,
+/// an instance only changes representation when it is more than [hysteresis](InstancedLod::hysteresis) times the transition distance past the transition distance.
+/// The imposters only support instances which are translated and rotated around the y-axis, any scaling or other rotation is ignored for the far instances.
+///
+pub struct InstancedLod {
+    mesh: InstancedMesh,
+    imposters: Imposters,
+    aabb: AxisAlignedBoundingBox,
+    transformations: Vec<Mat4>,
+    is_far: Vec<bool>,
+    ///
+    /// The distance from the camera beyond which instances are rendered as imposters.
+    ///
+    pub transition_distance: f32,
+    ///
+    /// The fraction of the transition distance an instance needs to move past the transition distance before it changes representation. Default is 0.1.
+    ///
+    pub hysteresis: f32,
+}
+
+impl InstancedLod {
+    ///
+    /// Constructs a new instanced level-of-detail object from the given [CPUMesh](crate::CPUMesh) with one instance per transformation.
+    /// The imposter textures are empty until they are updated with [update_imposter_texture](Self::update_imposter_texture).
+    ///
+    pub fn new(
+        context: &Context,
+        transformations: &[Mat4],
+        cpu_mesh: &CPUMesh,
+        transition_distance: f32,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            mesh: InstancedMesh::new(context, &[], cpu_mesh)?,
+            imposters: Imposters::new(context)?,
+            aabb: cpu_mesh.compute_aabb(),
+            transformations: transformations.to_vec(),
+            is_far: vec![false; transformations.len()],
+            transition_distance,
+            hysteresis: 0.1,
+        })
+    }
+
+    ///
+    /// Updates the imposter textures by rendering the mesh from different angles using the given render function,
+    /// which should render the instance at the origin, for example using a [Mesh](crate::Mesh) constructed from the same [CPUMesh](crate::CPUMesh).
+    /// See [Imposters::update_texture].
+    ///
+    pub fn update_imposter_texture<F: Fn(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        render: F,
+        max_texture_size: u32,
+    ) -> Result<(), Error> {
+        self.imposters.update_texture(
+            render,
+            (*self.aabb.min(), *self.aabb.max()),
+            max_texture_size,
+        )
+    }
+
+    ///
+    /// Updates the transformations of the instances.
+    ///
+    pub fn update_transformations(&mut self, transformations: &[Mat4]) {
+        self.transformations = transformations.to_vec();
+        self.is_far = vec![false; transformations.len()];
+    }
+
+    ///
+    /// Returns the mesh used for rendering the near instances, for example to change its material.
+    ///
+    pub fn mesh_mut(&mut self) -> &mut InstancedMesh {
+        &mut self.mesh
+    }
+
+    ///
+    /// Partitions the instances based on the distance to the camera and renders the near instances using the given render function,
+    /// for example calling [render_with_lighting](crate::ShadedGeometry::render_with_lighting) on the given mesh,
+    /// and the far instances as imposters.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render<F: FnOnce(&InstancedMesh) -> Result<(), Error>>(
+        &mut self,
+        viewport: Viewport,
+        camera: &Camera,
+        render_near: F,
+    ) -> Result<(), Error> {
+        let near_limit = self.transition_distance * (1.0 - self.hysteresis);
+        let far_limit = self.transition_distance * (1.0 + self.hysteresis);
+        let mut near = Vec::new();
+        let mut far_positions = Vec::new();
+        let mut far_angles = Vec::new();
+        for (transformation, is_far) in self.transformations.iter().zip(self.is_far.iter_mut()) {
+            let position = transformation.w.truncate();
+            let distance = camera.position().distance(position);
+            if distance > far_limit {
+                *is_far = true;
+            } else if distance < near_limit {
+                *is_far = false;
+            }
+            if *is_far {
+                far_positions.extend_from_slice(&[position.x, position.y, position.z]);
+                far_angles.push(f32::atan2(-transformation.x.z, transformation.x.x));
+            } else {
+                near.push(*transformation);
+            }
+        }
+
+        if !near.is_empty() {
+            self.mesh.update_transformations(&near);
+            render_near(&self.mesh)?;
+        }
+        if !far_angles.is_empty() {
+            self.imposters.update_positions(&far_positions, &far_angles);
+            self.imposters.render(viewport, camera)?;
+        }
+        Ok(())
+    }
+}
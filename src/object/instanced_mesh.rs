@@ -5,6 +5,7 @@ use crate::math::*;
 use crate::object::mesh::*;
 use crate::object::*;
 use crate::shading::*;
+use std::rc::Rc;
 
 ///
 /// A shader program used for rendering one or more instances of a [InstancedMesh](InstancedMesh). It has a fixed vertex shader and
@@ -21,7 +22,11 @@ impl InstancedMeshProgram {
     ///
     pub fn new(context: &Context, fragment_shader_source: &str) -> Result<Self, Error> {
         Ok(Self {
-            mesh_program: MeshProgram::new_internal(context, fragment_shader_source, true)?,
+            mesh_program: MeshProgram::new_internal(
+                context,
+                fragment_shader_source,
+                "#define INSTANCED\n",
+            )?,
         })
     }
 }
@@ -39,20 +44,32 @@ impl std::ops::Deref for InstancedMeshProgram {
 ///
 pub struct InstancedMesh {
     context: Context,
-    position_buffer: VertexBuffer,
-    normal_buffer: Option<VertexBuffer>,
-    index_buffer: Option<ElementBuffer>,
-    uv_buffer: Option<VertexBuffer>,
-    color_buffer: Option<VertexBuffer>,
+    position_buffer: Rc<VertexBuffer>,
+    normal_buffer: Option<Rc<VertexBuffer>>,
+    index_buffer: Option<Rc<ElementBuffer>>,
+    uv_buffer: Option<Rc<VertexBuffer>>,
+    color_buffer: Option<Rc<VertexBuffer>>,
     instance_count: u32,
-    instance_buffer1: VertexBuffer,
-    instance_buffer2: VertexBuffer,
-    instance_buffer3: VertexBuffer,
+    instance_buffer: VertexBuffer,
+    instance_transformations: Vec<Mat4>,
+    instance_enabled: Vec<bool>,
+    culled_instance_buffer: Option<VertexBuffer>,
+    aabb: AxisAlignedBoundingBox,
     pub name: String,
+    ///
+    /// The [CullType](crate::CullType) applied in all render calls, default is [CullType::None](crate::CullType::None).
+    ///
     pub cull: CullType,
     pub transformation: Mat4,
+    /// The transformation in the previous frame which is used when rendering [motion vectors](Self::render_motion_vectors).
+    /// The per instance transformations are assumed to be unchanged since the previous frame.
+    pub previous_transformation: Mat4,
     pub material: Material,
     pub lighting_model: LightingModel,
+    ///
+    /// The render layers this geometry belongs to, see [Geometry::layer_mask](crate::Geometry::layer_mask). The default is all layers.
+    ///
+    pub layer_mask: u32,
 }
 
 impl InstancedMesh {
@@ -67,48 +84,45 @@ impl InstancedMesh {
         transformations: &[Mat4],
         cpu_mesh: &CPUMesh,
     ) -> Result<Self, Error> {
-        let position_buffer = VertexBuffer::new_with_static(context, &cpu_mesh.positions)?;
-        let normal_buffer = if let Some(ref normals) = cpu_mesh.normals {
-            Some(VertexBuffer::new_with_static(context, normals)?)
-        } else {
-            None
-        };
-        let index_buffer = if let Some(ref indices) = cpu_mesh.indices {
-            Some(match indices {
-                Indices::U8(ind) => ElementBuffer::new(context, ind)?,
-                Indices::U16(ind) => ElementBuffer::new(context, ind)?,
-                Indices::U32(ind) => ElementBuffer::new(context, ind)?,
-            })
-        } else {
-            None
-        };
-        let uv_buffer = if let Some(ref uvs) = cpu_mesh.uvs {
-            Some(VertexBuffer::new_with_static(context, uvs)?)
-        } else {
-            None
-        };
-        let color_buffer = if let Some(ref colors) = cpu_mesh.colors {
-            Some(VertexBuffer::new_with_static(context, colors)?)
-        } else {
-            None
-        };
+        cpu_mesh.validate()?;
+        cpu_mesh.validate_triangles()?;
+        let buffers = MeshBuffers::new(context, cpu_mesh)?;
+        let mut mesh = Self::new_with_shared_buffers(
+            context,
+            transformations,
+            buffers.into(),
+            cpu_mesh.compute_aabb(),
+        )?;
+        mesh.name = cpu_mesh.name.clone();
+        Ok(mesh)
+    }
 
+    pub(in crate::object) fn new_with_shared_buffers(
+        context: &Context,
+        transformations: &[Mat4],
+        buffers: SharedMeshBuffers,
+        aabb: AxisAlignedBoundingBox,
+    ) -> Result<Self, Error> {
         let mut mesh = Self {
-            name: cpu_mesh.name.clone(),
+            name: String::new(),
             context: context.clone(),
             instance_count: 0,
-            position_buffer,
-            normal_buffer,
-            index_buffer,
-            uv_buffer,
-            color_buffer,
-            instance_buffer1: VertexBuffer::new(context)?,
-            instance_buffer2: VertexBuffer::new(context)?,
-            instance_buffer3: VertexBuffer::new(context)?,
+            position_buffer: buffers.position_buffer,
+            normal_buffer: buffers.normal_buffer,
+            index_buffer: buffers.index_buffer,
+            uv_buffer: buffers.uv_buffer,
+            color_buffer: buffers.color_buffer,
+            instance_buffer: VertexBuffer::new(context)?,
+            instance_transformations: Vec::new(),
+            instance_enabled: Vec::new(),
+            culled_instance_buffer: None,
+            aabb,
             cull: CullType::None,
             transformation: Mat4::identity(),
+            previous_transformation: Mat4::identity(),
             material: Material::default(),
             lighting_model: LightingModel::Blinn,
+            layer_mask: u32::MAX,
         };
         mesh.update_transformations(transformations);
         unsafe {
@@ -129,7 +143,8 @@ impl InstancedMesh {
     }
 
     ///
-    /// Render the instanced mesh with a color per triangle vertex. The colors are defined when constructing the instanced mesh.
+    /// Render the instanced mesh with a color per triangle vertex. The colors are defined by [CPUMesh::colors](crate::CPUMesh::colors) when constructing the instanced mesh
+    /// and are assumed to be in gamma color space (sRGBA), ie. four `u8` values per vertex, see [Mesh::render_color](crate::Mesh::render_color).
     /// Must be called in a render target render function,
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     /// The transformation can be used to position, orientate and scale the instanced mesh.
@@ -143,7 +158,16 @@ impl InstancedMesh {
         viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
-        let program = self.get_or_insert_program(include_str!("shaders/mesh_vertex_color.frag"))?;
+        if self.color_buffer.is_none() {
+            Err(Error::MeshError {
+                message: format!("Cannot render the vertex colors of the instanced mesh '{}' since it has no colors. The colors are defined by CPUMesh::colors.", self.name),
+            })?;
+        }
+        let program = self.get_or_insert_program(&format!(
+            "{}{}",
+            include_str!("../core/shared.frag"),
+            include_str!("shaders/mesh_vertex_color.frag")
+        ))?;
         self.render(program, render_states, viewport, camera)
     }
 
@@ -166,7 +190,7 @@ impl InstancedMesh {
     }
 
     ///
-    /// Render the instanced mesh with the given texture.
+    /// Render the instanced mesh with the given texture, see [Mesh::render_with_texture](crate::Mesh::render_with_texture) for how sRGB and linear textures are handled.
     /// Must be called in a render target render function,
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     /// The transformation can be used to position, orientate and scale the instanced mesh.
@@ -181,8 +205,29 @@ impl InstancedMesh {
         viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
-        let program = self.get_or_insert_program(include_str!("shaders/mesh_texture.frag"))?;
+        let program = self.get_or_insert_program(&format!(
+            "{}{}",
+            include_str!("../core/shared.frag"),
+            include_str!("shaders/mesh_texture.frag")
+        ))?;
         program.use_texture(texture, "tex")?;
+        program.use_uniform_int("isSrgb", &(texture.is_srgb() as i32))?;
+        self.render(program, render_states, viewport, camera)
+    }
+
+    ///
+    /// Render the screen space motion of the instanced mesh since the previous frame into the red (u) and green (v) channels, measured in uv coordinates of the render target.
+    /// See [Mesh::render_motion_vectors](crate::Mesh::render_motion_vectors) for more information.
+    ///
+    pub fn render_motion_vectors(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        previous_view_projection: &Mat4,
+    ) -> Result<(), Error> {
+        let program = self.get_or_insert_program(include_str!("shaders/mesh_motion.frag"))?;
+        program.use_uniform_mat4("previousViewProjection", previous_view_projection)?;
         self.render(program, render_states, viewport, camera)
     }
 
@@ -204,52 +249,68 @@ impl InstancedMesh {
         viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
-        program.use_attribute_vec4_divisor(&self.instance_buffer1, "row1", 1)?;
-        program.use_attribute_vec4_divisor(&self.instance_buffer2, "row2", 1)?;
-        program.use_attribute_vec4_divisor(&self.instance_buffer3, "row3", 1)?;
+        let (instance_buffer, instance_count) = match self.culled_instance_buffer {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(ref culled_instance_buffer) => (
+                culled_instance_buffer,
+                self.cull_instances(culled_instance_buffer, camera)?,
+            ),
+            _ => (&self.instance_buffer, self.instance_count),
+        };
+        self.render_instances(
+            program,
+            render_states,
+            viewport,
+            camera,
+            instance_buffer,
+            instance_count,
+        )
+    }
 
-        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
-        program.use_uniform_block(camera.uniform_buffer(), "Camera");
-
-        program.use_attribute_vec3(&self.position_buffer, "position")?;
-        if program.mesh_program.use_uvs {
-            let uv_buffer = self.uv_buffer.as_ref().ok_or(Error::MeshError {
-                message:
-                    "The mesh shader program needs uv coordinates, but the mesh does not have any."
-                        .to_string(),
-            })?;
-            program.use_attribute_vec2(uv_buffer, "uv_coordinates")?;
-        }
-        if program.mesh_program.use_normals {
-            let normal_buffer = self.normal_buffer.as_ref().ok_or(
-                Error::MeshError {message: "The mesh shader program needs normals, but the mesh does not have any. Consider calculating the normals on the CPUMesh.".to_string()})?;
-            program.use_uniform_mat4(
-                "normalMatrix",
-                &self.transformation.invert().unwrap().transpose(),
-            )?;
-            program.use_attribute_vec3(normal_buffer, "normal")?;
+    fn render_instances(
+        &self,
+        program: &InstancedMeshProgram,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        instance_buffer: &VertexBuffer,
+        instance_count: u32,
+    ) -> Result<(), Error> {
+        if instance_count == 0 {
+            return Ok(());
         }
-        if program.mesh_program.use_colors {
-            let color_buffer = self.color_buffer.as_ref().ok_or(
-                Error::MeshError {message: "The mesh shader program needs per vertex colors, but the mesh does not have any.".to_string()})?;
-            program.use_attribute_vec4(color_buffer, "color")?;
+        program.use_attribute_mat4_divisor(instance_buffer, "instance_transformation", 1)?;
+
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+        program.use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        if program.mesh_program.use_motion {
+            program.use_uniform_mat4("previousModelMatrix", &self.previous_transformation)?;
         }
 
+        use_mesh_program_attributes(
+            &program.mesh_program,
+            &normal_matrix(&self.transformation),
+            &self.position_buffer,
+            self.normal_buffer.as_deref(),
+            self.uv_buffer.as_deref(),
+            self.color_buffer.as_deref(),
+        )?;
+
         if let Some(ref index_buffer) = self.index_buffer {
             program.draw_elements_instanced(
                 render_states,
                 self.cull,
                 viewport,
                 index_buffer,
-                self.instance_count,
-            );
+                instance_count,
+            )?;
         } else {
             program.draw_arrays_instanced(
                 render_states,
                 self.cull,
                 viewport,
                 self.position_buffer.count() as u32 / 3,
-                self.instance_count,
+                instance_count,
             );
         }
         Ok(())
@@ -261,28 +322,212 @@ impl InstancedMesh {
     ///
     pub fn update_transformations(&mut self, transformations: &[Mat4]) {
         self.instance_count = transformations.len() as u32;
-        let mut row1 = Vec::new();
-        let mut row2 = Vec::new();
-        let mut row3 = Vec::new();
-        for transform in transformations {
-            row1.push(transform.x.x);
-            row1.push(transform.y.x);
-            row1.push(transform.z.x);
-            row1.push(transform.w.x);
-
-            row2.push(transform.x.y);
-            row2.push(transform.y.y);
-            row2.push(transform.z.y);
-            row2.push(transform.w.y);
-
-            row3.push(transform.x.z);
-            row3.push(transform.y.z);
-            row3.push(transform.z.z);
-            row3.push(transform.w.z);
+        let mut data = Vec::with_capacity(16 * transformations.len());
+        self.instance_enabled.resize(transformations.len(), true);
+        for (transform, enabled) in transformations.iter().zip(self.instance_enabled.iter()) {
+            if *enabled {
+                data.extend_from_slice(&transform.to_slice());
+            } else {
+                data.extend_from_slice(&[0.0; 16]);
+            }
+        }
+        self.instance_buffer.fill_with_dynamic(&data);
+        self.instance_transformations = transformations.to_vec();
+        if let Some(ref mut culled_instance_buffer) = self.culled_instance_buffer {
+            culled_instance_buffer.fill_with_dynamic(&vec![0.0f32; data.len()]);
+        }
+    }
+
+    ///
+    /// Enables or disables frustum culling of the instances on the GPU, which is disabled by default.
+    /// When enabled, each render call first tests the bounding sphere of each instance against the frustum of the camera used for rendering,
+    /// for example the shadow camera when rendering a shadow map, and compacts the transformations of the visible instances into a second instance buffer,
+    /// so only the visible instances are drawn. This is useful for a very large number of instances that are spread out, since culling them on the CPU becomes the bottleneck.
+    ///
+    /// The compaction uses a geometry shader and transform feedback, since there are no compute shaders, which requires OpenGL 3.2 or higher.
+    /// The number of visible instances is read back before drawing, which waits for the GPU to finish the culling,
+    /// since drawing with a count written by the GPU (indirect drawing) is not available in OpenGL 3.3.
+    /// The second instance buffer costs another 64 bytes per instance.
+    ///
+    /// **Note:** Has no effect on web, since WebGL 2 does not support geometry shaders, so all instances are drawn.
+    ///
+    /// # Errors
+    /// Will return an error if the second instance buffer could not be created, in which case GPU culling stays disabled.
+    ///
+    pub fn set_gpu_culling(&mut self, enabled: bool) -> Result<(), Error> {
+        if !enabled || cfg!(target_arch = "wasm32") {
+            self.culled_instance_buffer = None;
+        } else if self.culled_instance_buffer.is_none() {
+            self.culled_instance_buffer = Some(VertexBuffer::new_with_dynamic(
+                &self.context,
+                &vec![0.0f32; 16 * self.instance_count as usize],
+            )?);
         }
-        self.instance_buffer1.fill_with_dynamic(&row1);
-        self.instance_buffer2.fill_with_dynamic(&row2);
-        self.instance_buffer3.fill_with_dynamic(&row3);
+        Ok(())
+    }
+
+    ///
+    /// Returns whether the instances are culled on the GPU, see [set_gpu_culling](Self::set_gpu_culling).
+    ///
+    pub fn gpu_culling(&self) -> bool {
+        self.culled_instance_buffer.is_some()
+    }
+
+    ///
+    /// Enables or disables rendering of the instance with the given index, which is the index in the transformations given to [update_transformations](Self::update_transformations).
+    /// All instances are enabled by default and the enabled state is kept when the transformations are updated.
+    ///
+    /// A disabled instance is collapsed by replacing its transformation in the instance buffer with a degenerate (all zero) transformation,
+    /// so only the 64 bytes of that instance are uploaded and the other instances are left untouched.
+    /// The vertices of a disabled instance are still processed by the vertex shader, but the triangles are degenerate and produce no fragments,
+    /// so this is best for scripted visibility of a few instances that changes rarely. If most instances are hidden,
+    /// it is cheaper to call [update_transformations](Self::update_transformations) with only the visible transformations (compaction),
+    /// which costs an upload of all visible transformations but skips the hidden instances entirely.
+    ///
+    /// # Errors
+    /// Returns an error if the index is not smaller than the [instance count](Self::instance_count).
+    ///
+    pub fn set_instance_enabled(&mut self, index: u32, enabled: bool) -> Result<(), Error> {
+        let i = index as usize;
+        if i >= self.instance_enabled.len() {
+            Err(Error::MeshError {
+                message: format!(
+                    "Cannot enable or disable instance {} of an instanced mesh with {} instances",
+                    index,
+                    self.instance_enabled.len()
+                ),
+            })?;
+        }
+        if self.instance_enabled[i] != enabled {
+            self.instance_enabled[i] = enabled;
+            let transformation = if enabled {
+                self.instance_transformations[i]
+            } else {
+                Mat4::zero()
+            };
+            self.instance_buffer
+                .fill_subset_f32(16 * i, &transformation.to_slice())?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Returns whether the instance with the given index is rendered, see [set_instance_enabled](Self::set_instance_enabled).
+    /// Returns false if the index is out of range.
+    ///
+    pub fn is_instance_enabled(&self, index: u32) -> bool {
+        self.instance_enabled
+            .get(index as usize)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    ///
+    /// The number of instances that are rendered.
+    ///
+    pub fn instance_count(&self) -> u32 {
+        self.instance_count
+    }
+
+    ///
+    /// The transformations applied to each mesh instance, as given in the last call to [update_transformations](Self::update_transformations).
+    /// A copy of the transformations is kept on the CPU side for this purpose, which costs 64 bytes per instance.
+    ///
+    pub fn instance_transformations(&self) -> &[Mat4] {
+        &self.instance_transformations
+    }
+
+    ///
+    /// Finds the closest instance hit by a ray from the given camera in the direction of the given screen coordinates, see [Camera::pick](crate::Camera::pick).
+    /// The index of each instance is rendered into a color target and the index of the instance covering the center of the ray is read back.
+    /// Returns the index of the instance, ie. the index in the transformations given to [update_transformations](Self::update_transformations),
+    /// or `None` if no instance was hit before the given maximum depth. Disabled instances are never hit.
+    /// The instances are not [culled on the GPU](Self::set_gpu_culling) when picking, since the compacted instances do not keep their original index.
+    ///
+    /// **Note:** The instance index is encoded in the 24 bits of the red, green and blue color channels, so only the first 16777215 (2^24 - 1) instances can be picked.
+    ///
+    pub fn pick_instance(
+        &self,
+        camera: &Camera,
+        screen_coordinates: (f32, f32),
+        max_depth: f32,
+    ) -> Result<Option<u32>, Error> {
+        let viewport = Viewport::new_at_origo(1, 1);
+        let pick_camera = crate::function::ray_camera(
+            &self.context,
+            camera.position_at(screen_coordinates),
+            camera.view_direction_at(screen_coordinates),
+            max_depth,
+        )?;
+        let texture = ColorTargetTexture2D::<u8>::new(
+            &self.context,
+            viewport.width,
+            viewport.height,
+            Interpolation::Nearest,
+            Interpolation::Nearest,
+            None,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            Format::RGBA,
+        )?;
+        let depth_texture = DepthTargetTexture2D::new(
+            &self.context,
+            viewport.width,
+            viewport.height,
+            Wrapping::ClampToEdge,
+            Wrapping::ClampToEdge,
+            DepthFormat::Depth32F,
+        )?;
+        let program = self.get_or_insert_program(include_str!("shaders/mesh_instance_id.frag"))?;
+        RenderTarget::new(&self.context, &texture, &depth_texture)?.write(
+            ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0),
+            || {
+                self.render_instances(
+                    program,
+                    RenderStates {
+                        depth_test: DepthTestType::Less,
+                        ..Default::default()
+                    },
+                    viewport,
+                    &pick_camera,
+                    &self.instance_buffer,
+                    self.instance_count,
+                )
+            },
+        )?;
+        let pixel = texture.read(viewport)?;
+        let id = pixel[0] as u32 | (pixel[1] as u32) << 8 | (pixel[2] as u32) << 16;
+        Ok(if id > 0 { Some(id - 1) } else { None })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn cull_instances(&self, output: &VertexBuffer, camera: &Camera) -> Result<u32, Error> {
+        let program = unsafe {
+            get_or_insert_cached_program(
+                std::ptr::addr_of_mut!(CULLING_PROGRAMS),
+                &self.context,
+                "",
+                || {
+                    Program::from_transform_feedback_source(
+                        &self.context,
+                        include_str!("shaders/instance_culling.vert"),
+                        include_str!("shaders/instance_culling.geom"),
+                        &["culledTransformation"],
+                    )
+                },
+            )?
+        };
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+        program.use_uniform_vec3("boundingCenter", &self.aabb.center())?;
+        program.use_uniform_float(
+            "boundingRadius",
+            &(0.5 * (self.aabb.max() - self.aabb.min()).magnitude()),
+        )?;
+        for (i, plane) in camera.frustum_planes().iter().enumerate() {
+            program.use_uniform_vec4(&format!("frustumPlane{}", i), plane)?;
+        }
+        program.use_attribute_mat4_divisor(&self.instance_buffer, "instance_transformation", 0)?;
+        program.transform_feedback_points(output, self.instance_count)
     }
 
     pub(crate) fn get_or_insert_program(
@@ -290,24 +535,12 @@ impl InstancedMesh {
         fragment_shader_source: &str,
     ) -> Result<&InstancedMeshProgram, Error> {
         unsafe {
-            if PROGRAMS.is_none() {
-                PROGRAMS = Some(std::collections::HashMap::new());
-            }
-            if !PROGRAMS
-                .as_ref()
-                .unwrap()
-                .contains_key(fragment_shader_source)
-            {
-                PROGRAMS.as_mut().unwrap().insert(
-                    fragment_shader_source.to_string(),
-                    InstancedMeshProgram::new(&self.context, fragment_shader_source)?,
-                );
-            };
-            Ok(PROGRAMS
-                .as_ref()
-                .unwrap()
-                .get(fragment_shader_source)
-                .unwrap())
+            get_or_insert_cached_program(
+                std::ptr::addr_of_mut!(PROGRAMS),
+                &self.context,
+                fragment_shader_source,
+                || InstancedMeshProgram::new(&self.context, fragment_shader_source),
+            )
         }
     }
 }
@@ -326,6 +559,10 @@ impl Geometry for InstancedMesh {
         Ok(())
     }
 
+    fn depth_program_key(&self) -> usize {
+        crate::object::depth_program_key::<InstancedMeshProgram>(&self.context, "void main() {}")
+    }
+
     fn render_depth(
         &self,
         render_states: RenderStates,
@@ -336,8 +573,71 @@ impl Geometry for InstancedMesh {
         self.render(program, render_states, viewport, camera)
     }
 
+    fn render_with_program(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if program.requires_attribute("instance_transformation") {
+            program.use_attribute_mat4_divisor(
+                &self.instance_buffer,
+                "instance_transformation",
+                1,
+            )?;
+        }
+
+        use_mesh_uniforms_and_attributes(
+            program,
+            &self.transformation,
+            Some(camera),
+            &self.position_buffer,
+            self.normal_buffer.as_deref(),
+            self.uv_buffer.as_deref(),
+            self.color_buffer.as_deref(),
+        )?;
+
+        if let Some(ref index_buffer) = self.index_buffer {
+            program.draw_elements_instanced(
+                render_states,
+                self.cull,
+                viewport,
+                index_buffer,
+                self.instance_count,
+            )?;
+        } else {
+            program.draw_arrays_instanced(
+                render_states,
+                self.cull,
+                viewport,
+                self.position_buffer.count() as u32 / 3,
+                self.instance_count,
+            );
+        }
+        Ok(())
+    }
+
+    fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
+
     fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
-        None // TODO: Compute bounding box
+        let mut instances = self
+            .instance_transformations
+            .iter()
+            .zip(self.instance_enabled.iter())
+            .filter(|(_, enabled)| **enabled)
+            .map(|(transformation, _)| {
+                let mut aabb = self.aabb.clone();
+                aabb.transform(&(self.transformation * transformation));
+                aabb
+            });
+        let mut aabb = instances.next()?;
+        for other in instances {
+            aabb.expand_with_aabb(&other);
+        }
+        Some(aabb)
     }
 }
 
@@ -347,10 +647,16 @@ impl Drop for InstancedMesh {
             MESH_COUNT -= 1;
             if MESH_COUNT == 0 {
                 PROGRAMS = None;
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    CULLING_PROGRAMS = None;
+                }
             }
         }
     }
 }
 
-static mut PROGRAMS: Option<std::collections::HashMap<String, InstancedMeshProgram>> = None;
+static mut PROGRAMS: ProgramCache<InstancedMeshProgram> = None;
+#[cfg(not(target_arch = "wasm32"))]
+static mut CULLING_PROGRAMS: ProgramCache<Program> = None;
 static mut MESH_COUNT: u32 = 0;
@@ -0,0 +1,116 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+
+///
+/// A set of thick line segments in 3D with a constant width in pixels on the screen regardless of the distance to the camera.
+/// Each line segment is rendered as an instance of a quad which is expanded in the vertex shader, so no geometry shader is needed.
+///
+pub struct Lines {
+    program: Program,
+    quad_buffer: VertexBuffer,
+    start_buffer: VertexBuffer,
+    end_buffer: VertexBuffer,
+    line_count: u32,
+    /// The width of the lines in pixels.
+    pub width: f32,
+    /// Whether or not to extend each line segment by half the width at both ends.
+    /// This closes the gaps at the joints between connected line segments, however, there are no miter joins so sharp angles look square.
+    pub square_caps: bool,
+}
+
+impl Lines {
+    ///
+    /// Creates line segments between the given pairs of start and end points with the given width in pixels.
+    ///
+    pub fn new(context: &Context, lines: &[(Vec3, Vec3)], width: f32) -> Result<Self, Error> {
+        let program = Program::from_source(
+            context,
+            include_str!("shaders/lines.vert"),
+            &format!(
+                "{}{}",
+                include_str!("../core/shared.frag"),
+                include_str!("shaders/mesh_color.frag")
+            ),
+        )?;
+        let quad_buffer = VertexBuffer::new_with_static(
+            context,
+            &[
+                0.0f32, -1.0, 1.0, -1.0, 1.0, 1.0, 0.0, -1.0, 1.0, 1.0, 0.0, 1.0,
+            ],
+        )?;
+        let mut lines_object = Self {
+            program,
+            quad_buffer,
+            start_buffer: VertexBuffer::new(context)?,
+            end_buffer: VertexBuffer::new(context)?,
+            line_count: 0,
+            width,
+            square_caps: true,
+        };
+        lines_object.update(lines);
+        Ok(lines_object)
+    }
+
+    ///
+    /// Updates the line segments to be between the given pairs of start and end points.
+    ///
+    pub fn update(&mut self, lines: &[(Vec3, Vec3)]) {
+        let mut starts = Vec::with_capacity(3 * lines.len());
+        let mut ends = Vec::with_capacity(3 * lines.len());
+        for (start, end) in lines {
+            starts.extend_from_slice(&[start.x, start.y, start.z]);
+            ends.extend_from_slice(&[end.x, end.y, end.z]);
+        }
+        self.start_buffer.fill_with_dynamic(&starts);
+        self.end_buffer.fill_with_dynamic(&ends);
+        self.line_count = lines.len() as u32;
+    }
+
+    ///
+    /// Render the lines with the given color. The color is assumed to be in gamma color space (sRGBA).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_with_color(
+        &self,
+        color: &Vec4,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if self.line_count == 0 {
+            return Ok(());
+        }
+        self.program.use_uniform_vec4("color", color)?;
+        self.program.use_uniform_vec2(
+            "resolution",
+            &vec2(viewport.width as f32, viewport.height as f32),
+        )?;
+        self.program.use_uniform_float("lineWidth", &self.width)?;
+        self.program.use_uniform_float(
+            "capLength",
+            &if self.square_caps {
+                0.5 * self.width
+            } else {
+                0.0
+            },
+        )?;
+        self.program
+            .use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        self.program
+            .use_attribute_vec2(&self.quad_buffer, "quad_position")?;
+        self.program
+            .use_attribute_vec3_divisor(&self.start_buffer, "start", 1)?;
+        self.program
+            .use_attribute_vec3_divisor(&self.end_buffer, "end", 1)?;
+        self.program.draw_arrays_instanced(
+            render_states,
+            CullType::None,
+            viewport,
+            6,
+            self.line_count,
+        );
+        Ok(())
+    }
+}
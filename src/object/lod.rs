@@ -0,0 +1,215 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+use crate::object::*;
+use std::cell::Cell;
+
+///
+/// A level-of-detail geometry which holds several [meshes](crate::Mesh), each associated with a distance threshold,
+/// and renders the mesh matching the distance from the camera to the center of the [bounding box](crate::Geometry::aabb) of the object.
+///
+/// A level is used when the distance is larger than or equal to its threshold and smaller than the threshold of the next level.
+/// When the camera is closer than the smallest threshold, for example inside the object, the level with the smallest threshold is used.
+///
+/// To avoid popping when the camera moves back and forth around a threshold, the selected level only changes when the distance
+/// is more than the fraction [hysteresis](Lod::hysteresis) past the threshold. The selected level is remembered between render calls,
+/// so rendering the same object with cameras at very different distances, for example into a shadow map, shares this state.
+/// Use [level](Lod::level) to render a specific level in that case.
+///
+pub struct Lod {
+    levels: Vec<(f32, Mesh)>,
+    current: Cell<usize>,
+    ///
+    /// The fraction of the threshold distance the camera needs to move past a threshold before the level changes. Default is 0.1.
+    ///
+    pub hysteresis: f32,
+    ///
+    /// The render layers this geometry belongs to, see [Geometry::layer_mask](crate::Geometry::layer_mask). The default is all layers.
+    /// The layer masks of the meshes of the levels are not used.
+    ///
+    pub layer_mask: u32,
+}
+
+impl Lod {
+    ///
+    /// Constructs a new level-of-detail geometry from a list of distance thresholds and meshes.
+    /// The levels are sorted by their threshold, so the order of the list does not matter.
+    ///
+    pub fn new(mut levels: Vec<(f32, Mesh)>) -> Self {
+        levels.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        Self {
+            levels,
+            current: Cell::new(0),
+            hysteresis: 0.1,
+            layer_mask: u32::MAX,
+        }
+    }
+
+    ///
+    /// Returns the distance thresholds and meshes, sorted by threshold.
+    ///
+    pub fn levels(&self) -> &[(f32, Mesh)] {
+        &self.levels
+    }
+
+    ///
+    /// Returns the mesh of the given level, where level 0 is the level with the smallest threshold.
+    ///
+    pub fn level(&self, level: usize) -> Option<&Mesh> {
+        self.levels.get(level).map(|(_, mesh)| mesh)
+    }
+
+    ///
+    /// Returns a mutable reference to the mesh of the given level, for example to change its material.
+    ///
+    pub fn level_mut(&mut self, level: usize) -> Option<&mut Mesh> {
+        self.levels.get_mut(level).map(|(_, mesh)| mesh)
+    }
+
+    ///
+    /// Returns the level used in the last render call.
+    ///
+    pub fn current_level(&self) -> usize {
+        self.current.get()
+    }
+
+    ///
+    /// Set the local to world transformation of all the levels.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        for (_, mesh) in self.levels.iter_mut() {
+            mesh.set_transformation(transformation);
+        }
+    }
+
+    ///
+    /// Selects the level to render when viewed from the given camera, taking the hysteresis into account,
+    /// and returns the corresponding mesh or `None` if there are no levels.
+    ///
+    pub fn select(&self, camera: &Camera) -> Option<&Mesh> {
+        let aabb = self.aabb()?;
+        let distance = (*camera.position()).distance(aabb.center());
+        let coarsest = self.level_at((distance / (1.0 + self.hysteresis)).max(0.0));
+        let finest = self.level_at(distance / (1.0 - self.hysteresis).max(0.001));
+        let level = self.current.get().max(coarsest).min(finest);
+        self.current.set(level);
+        self.level(level)
+    }
+
+    fn level_at(&self, distance: f32) -> usize {
+        self.levels
+            .iter()
+            .rposition(|(threshold, _)| distance >= *threshold)
+            .unwrap_or(0)
+    }
+
+    ///
+    /// Render the selected level with a color per triangle vertex. The colors are defined when constructing the meshes.
+    /// See [Mesh::render_color].
+    ///
+    pub fn render_color(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.render_color(render_states, viewport, camera)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Render the selected level with the given color. See [Mesh::render_with_color].
+    ///
+    pub fn render_with_color(
+        &self,
+        color: &Vec4,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.render_with_color(color, render_states, viewport, camera)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Render the selected level with the given texture. See [Mesh::render_with_texture].
+    ///
+    pub fn render_with_texture(
+        &self,
+        texture: &impl Texture,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.render_with_texture(texture, render_states, viewport, camera)?;
+        }
+        Ok(())
+    }
+}
+
+impl Geometry for Lod {
+    fn render_depth_to_red(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        max_depth: f32,
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.render_depth_to_red(render_states, viewport, camera, max_depth)?;
+        }
+        Ok(())
+    }
+
+    fn render_depth(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.render_depth(render_states, viewport, camera)?;
+        }
+        Ok(())
+    }
+
+    fn render_with_program(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.render_with_program(program, render_states, viewport, camera)?;
+        }
+        Ok(())
+    }
+
+    fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
+
+    fn depth_program_key(&self) -> usize {
+        self.levels
+            .first()
+            .map(|(_, mesh)| mesh.depth_program_key())
+            .unwrap_or(0)
+    }
+
+    fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
+        let mut levels = self.levels.iter();
+        let mut aabb = levels.next()?.1.aabb()?;
+        for (_, mesh) in levels {
+            if let Some(other) = mesh.aabb() {
+                aabb.expand_with_aabb(&other);
+            }
+        }
+        Some(aabb)
+    }
+}
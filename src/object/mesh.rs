@@ -1,4 +1,5 @@
 use crate::camera::*;
+use crate::context::consts;
 use crate::core::*;
 use crate::definition::*;
 use crate::math::*;
@@ -15,28 +16,37 @@ pub struct MeshProgram {
     pub(in crate::object) use_normals: bool,
     pub(in crate::object) use_uvs: bool,
     pub(in crate::object) use_colors: bool,
+    pub(in crate::object) use_motion: bool,
 }
 
 impl MeshProgram {
     ///
     /// Constructs a new shader program for rendering meshes. The fragment shader can use the fragments position in world space by adding `in vec3 pos;`,
     /// its normal by `in vec3 nor;`, its uv coordinates by `in vec2 uvs;` and its per vertex color by `in vec4 col;` to the shader source code.
+    /// Adding `in vec4 currentClipPosition;` and `in vec4 previousClipPosition;` gives access to the clip position in the current and previous frame,
+    /// which is used for rendering [motion vectors](Mesh::render_motion_vectors).
     ///
     pub fn new(context: &Context, fragment_shader_source: &str) -> Result<Self, Error> {
-        Self::new_internal(context, fragment_shader_source, false)
+        Self::new_internal(context, fragment_shader_source, "")
     }
 
     pub(in crate::object) fn new_internal(
         context: &Context,
         fragment_shader_source: &str,
-        instanced: bool,
+        vertex_shader_defines: &str,
     ) -> Result<Self, Error> {
         let use_positions = fragment_shader_source.find("in vec3 pos;").is_some();
         let use_normals = fragment_shader_source.find("in vec3 nor;").is_some();
         let use_uvs = fragment_shader_source.find("in vec2 uvs;").is_some();
         let use_colors = fragment_shader_source.find("in vec4 col;").is_some();
+        let use_motion = fragment_shader_source
+            .find("in vec4 previousClipPosition;")
+            .is_some();
+        let use_instance_id = fragment_shader_source
+            .find("flat in int instanceId;")
+            .is_some();
         let vertex_shader_source = &format!(
-            "{}{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}{}",
             if use_positions {
                 "#define USE_POSITIONS\n"
             } else {
@@ -53,7 +63,17 @@ impl MeshProgram {
             } else {
                 ""
             },
-            if instanced { "#define INSTANCED\n" } else { "" },
+            if use_motion {
+                "#define USE_MOTION\n"
+            } else {
+                ""
+            },
+            if use_instance_id {
+                "#define USE_INSTANCE_ID\n"
+            } else {
+                ""
+            },
+            vertex_shader_defines,
             include_str!("../core/shared.frag"),
             include_str!("shaders/mesh.vert"),
         );
@@ -64,6 +84,7 @@ impl MeshProgram {
             use_normals,
             use_uvs,
             use_colors,
+            use_motion,
         })
     }
 }
@@ -88,10 +109,29 @@ pub struct Mesh {
     color_buffer: Option<Rc<VertexBuffer>>,
     aabb: AxisAlignedBoundingBox,
     pub name: String,
+    ///
+    /// The [CullType](crate::CullType) applied in all render calls, default is [CullType::None](crate::CullType::None).
+    ///
     pub cull: CullType,
-    pub transformation: Mat4,
+    primitive_type: PrimitiveType,
+    transformation: Mat4,
+    previous_transformation: Mat4,
+    normal_matrix: Mat4,
+    mirrored: bool,
+    decomposition: Option<Decomposition>,
     pub material: Material,
     pub lighting_model: LightingModel,
+    ///
+    /// The render layers this geometry belongs to, see [Geometry::layer_mask](crate::Geometry::layer_mask). The default is all layers.
+    ///
+    pub layer_mask: u32,
+    wireframe: Option<Rc<Wireframe>>,
+}
+
+struct Wireframe {
+    program: Program,
+    position_buffer: VertexBuffer,
+    barycentric_buffer: VertexBuffer,
 }
 
 impl Mesh {
@@ -100,47 +140,31 @@ impl Mesh {
     /// making it possible to render the mesh.
     ///
     pub fn new(context: &Context, cpu_mesh: &CPUMesh) -> Result<Self, Error> {
-        let position_buffer = Rc::new(VertexBuffer::new_with_static(context, &cpu_mesh.positions)?);
-        let normal_buffer = if let Some(ref normals) = cpu_mesh.normals {
-            Some(Rc::new(VertexBuffer::new_with_static(context, normals)?))
-        } else {
-            None
-        };
-        let index_buffer = if let Some(ref indices) = cpu_mesh.indices {
-            Some(Rc::new(match indices {
-                Indices::U8(ind) => ElementBuffer::new(context, ind)?,
-                Indices::U16(ind) => ElementBuffer::new(context, ind)?,
-                Indices::U32(ind) => ElementBuffer::new(context, ind)?,
-            }))
-        } else {
-            None
-        };
-        let uv_buffer = if let Some(ref uvs) = cpu_mesh.uvs {
-            Some(Rc::new(VertexBuffer::new_with_static(context, uvs)?))
-        } else {
-            None
-        };
-        let color_buffer = if let Some(ref colors) = cpu_mesh.colors {
-            Some(Rc::new(VertexBuffer::new_with_static(context, colors)?))
-        } else {
-            None
-        };
+        cpu_mesh.validate()?;
+        let buffers = MeshBuffers::new(context, cpu_mesh)?;
         unsafe {
             MESH_COUNT += 1;
         }
         Ok(Mesh {
             context: context.clone(),
-            position_buffer,
-            normal_buffer,
-            index_buffer,
-            uv_buffer,
-            color_buffer,
+            position_buffer: Rc::new(buffers.position_buffer),
+            normal_buffer: buffers.normal_buffer.map(Rc::new),
+            index_buffer: buffers.index_buffer.map(Rc::new),
+            uv_buffer: buffers.uv_buffer.map(Rc::new),
+            color_buffer: buffers.color_buffer.map(Rc::new),
             aabb: cpu_mesh.compute_aabb(),
             name: cpu_mesh.name.clone(),
             transformation: Mat4::identity(),
+            previous_transformation: Mat4::identity(),
+            normal_matrix: Mat4::identity(),
+            mirrored: false,
+            decomposition: None,
             cull: CullType::None,
+            primitive_type: cpu_mesh.primitive_type,
             material: Material::default(),
             lighting_model: LightingModel::Blinn,
+            layer_mask: u32::MAX,
+            wireframe: None,
         })
     }
 
@@ -155,7 +179,151 @@ impl Mesh {
     }
 
     ///
-    /// Render the mesh with a color per triangle vertex. The colors are defined when constructing the mesh and are assumed to be in gamma color space (sRGBA).
+    /// Returns how the vertices of this mesh are assembled into triangles, as specified by [CPUMesh::primitive_type](crate::CPUMesh::primitive_type).
+    ///
+    pub fn primitive_type(&self) -> PrimitiveType {
+        self.primitive_type
+    }
+
+    ///
+    /// Returns the local to world transformation applied to this mesh.
+    ///
+    pub fn transformation(&self) -> Mat4 {
+        self.transformation
+    }
+
+    ///
+    /// Set the local to world transformation applied to this mesh.
+    /// Use this for transformations which cannot be expressed as a translation, rotation and scale, for example a shear,
+    /// otherwise consider using [set_position](Self::set_position), [set_rotation](Self::set_rotation) and [set_scale](Self::set_scale).
+    ///
+    /// The transformation is not baked into the vertex data, instead it is sent to the shader as the `modelMatrix` uniform
+    /// together with the `normalMatrix` uniform (the inverse transpose, which is computed here and not in every render call).
+    /// Changing the transformation each frame is therefore cheap and never re-uploads the vertex buffers.
+    ///
+    pub fn set_transformation(&mut self, transformation: Mat4) {
+        self.decomposition = None;
+        self.update_transformation(transformation);
+    }
+
+    ///
+    /// Returns the local to world transformation of this mesh in the previous frame, which is used when rendering [motion vectors](Self::render_motion_vectors).
+    ///
+    pub fn previous_transformation(&self) -> Mat4 {
+        self.previous_transformation
+    }
+
+    ///
+    /// Stores the current transformation as the transformation in the previous frame, see [previous_transformation](Self::previous_transformation).
+    /// Call this once each frame after the motion vectors are rendered, also when the mesh has not moved, otherwise the motion of the last transformation change is repeated.
+    ///
+    pub fn update_previous_transformation(&mut self) {
+        self.previous_transformation = self.transformation;
+    }
+
+    ///
+    /// Returns the position (translation) part of the transformation of this mesh.
+    ///
+    pub fn position(&self) -> Vec3 {
+        self.decomposition().position
+    }
+
+    ///
+    /// Sets the position (translation) part of the transformation of this mesh, while keeping the rotation and scale.
+    ///
+    pub fn set_position(&mut self, position: Vec3) {
+        let mut decomposition = self.decomposition();
+        decomposition.position = position;
+        self.set_decomposition(decomposition);
+    }
+
+    ///
+    /// Returns the rotation part of the transformation of this mesh.
+    /// If the transformation was set using [set_transformation](Self::set_transformation) and contains a shear, the rotation is approximate.
+    ///
+    pub fn rotation(&self) -> Quat {
+        self.decomposition().rotation
+    }
+
+    ///
+    /// Sets the rotation part of the transformation of this mesh, while keeping the position and scale.
+    ///
+    pub fn set_rotation(&mut self, rotation: Quat) {
+        let mut decomposition = self.decomposition();
+        decomposition.rotation = rotation;
+        self.set_decomposition(decomposition);
+    }
+
+    ///
+    /// Returns the scale part of the transformation of this mesh.
+    /// If the transformation was set using [set_transformation](Self::set_transformation) and contains a shear, the scale is approximate.
+    ///
+    pub fn scale(&self) -> Vec3 {
+        self.decomposition().scale
+    }
+
+    ///
+    /// Sets the (non-uniform) scale part of the transformation of this mesh, while keeping the position and rotation.
+    ///
+    pub fn set_scale(&mut self, scale: Vec3) {
+        let mut decomposition = self.decomposition();
+        decomposition.scale = scale;
+        self.set_decomposition(decomposition);
+    }
+
+    fn decomposition(&self) -> Decomposition {
+        self.decomposition
+            .unwrap_or_else(|| Decomposition::new(&self.transformation))
+    }
+
+    fn set_decomposition(&mut self, decomposition: Decomposition) {
+        self.decomposition = Some(decomposition);
+        self.update_transformation(decomposition.transformation());
+    }
+
+    fn update_transformation(&mut self, transformation: Mat4) {
+        self.transformation = transformation;
+        self.normal_matrix = normal_matrix(&transformation);
+        self.mirrored = transformation.determinant() < 0.0;
+    }
+
+    ///
+    /// Creates an [InstancedMesh](crate::InstancedMesh) which renders this mesh in as many instances as there are transformation matrices
+    /// in the transformations parameter. The name, material, lighting model, cull type, layer mask and transformation are copied from this mesh.
+    ///
+    /// The vertex buffers (positions, normals, uvs, colors and indices) are shared between this mesh and the instanced mesh,
+    /// so no geometry is copied and the memory is only released when both are dropped.
+    /// Only the per instance transformations are stored in new buffers.
+    ///
+    pub fn instanced(&self, transformations: &[Mat4]) -> Result<InstancedMesh, Error> {
+        let mut instanced_mesh = InstancedMesh::new_with_shared_buffers(
+            &self.context,
+            transformations,
+            SharedMeshBuffers {
+                position_buffer: self.position_buffer.clone(),
+                normal_buffer: self.normal_buffer.clone(),
+                index_buffer: self.index_buffer.clone(),
+                uv_buffer: self.uv_buffer.clone(),
+                color_buffer: self.color_buffer.clone(),
+            },
+            self.aabb,
+        )?;
+        instanced_mesh.name = self.name.clone();
+        instanced_mesh.cull = self.cull;
+        instanced_mesh.transformation = self.transformation;
+        instanced_mesh.previous_transformation = self.previous_transformation;
+        instanced_mesh.material = self.material.clone();
+        instanced_mesh.lighting_model = self.lighting_model;
+        instanced_mesh.layer_mask = self.layer_mask;
+        Ok(instanced_mesh)
+    }
+
+    ///
+    /// Render the mesh with a color per triangle vertex. The colors are defined by [CPUMesh::colors](crate::CPUMesh::colors) when constructing the mesh
+    /// and are assumed to be in gamma color space (sRGBA), ie. four `u8` values per vertex.
+    /// The colors are converted to linear color space before they are interpolated across the triangles
+    /// and converted back to gamma color space when written to the render target.
+    /// This can for example be used for vertex painted terrain or debug heat maps.
     /// Must be called in a render target render function,
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     ///
@@ -168,6 +336,11 @@ impl Mesh {
         viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
+        if self.color_buffer.is_none() {
+            Err(Error::MeshError {
+                message: format!("Cannot render the vertex colors of the mesh '{}' since it has no colors. The colors are defined by CPUMesh::colors.", self.name),
+            })?;
+        }
         let program = self.get_or_insert_program(&format!(
             "{}{}",
             include_str!("../core/shared.frag"),
@@ -197,6 +370,91 @@ impl Mesh {
         self.render(program, render_states, viewport, camera)
     }
 
+    ///
+    /// Creates the data needed for rendering this mesh with a wireframe overlay using [render_with_wireframe](Self::render_with_wireframe).
+    /// The given [CPUMesh](crate::CPUMesh) should be the one used for constructing this mesh.
+    /// The data is shared with clones of this mesh.
+    ///
+    pub fn create_wireframe(&mut self, cpu_mesh: &CPUMesh) -> Result<(), Error> {
+        cpu_mesh.validate()?;
+        let indices = cpu_mesh.triangle_indices();
+        let mut positions = Vec::with_capacity(indices.len() * 3);
+        let mut barycentrics = Vec::with_capacity(indices.len() * 3);
+        for (i, index) in indices.iter().enumerate() {
+            let index = *index as usize;
+            positions.extend_from_slice(&cpu_mesh.positions[index * 3..index * 3 + 3]);
+            barycentrics.extend_from_slice(match i % 3 {
+                0 => &[1.0, 0.0, 0.0],
+                1 => &[0.0, 1.0, 0.0],
+                _ => &[0.0, 0.0, 1.0],
+            });
+        }
+        let program = Program::from_source(
+            &self.context,
+            include_str!("shaders/wireframe.vert"),
+            &format!(
+                "{}{}",
+                include_str!("../core/shared.frag"),
+                include_str!("shaders/wireframe.frag")
+            ),
+        )?;
+        self.wireframe = Some(Rc::new(Wireframe {
+            program,
+            position_buffer: VertexBuffer::new_with_static(&self.context, &positions)?,
+            barycentric_buffer: VertexBuffer::new_with_static(&self.context, &barycentrics)?,
+        }));
+        Ok(())
+    }
+
+    ///
+    /// Render the mesh with the given color and overlay the edges of the triangles with lines of the given color and width in pixels.
+    /// The filled triangles are rendered with a polygon offset which pushes them slightly away from the camera,
+    /// so the lines are rendered on top without z-fighting, also when the camera moves.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if [create_wireframe](Self::create_wireframe) has not been called.
+    ///
+    pub fn render_with_wireframe(
+        &self,
+        color: &Vec4,
+        wire_color: &Vec4,
+        wire_width: f32,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let wireframe = self.wireframe.as_ref().ok_or(Error::MeshError {
+            message: "The wireframe of the mesh has not been created, see Mesh::create_wireframe."
+                .to_string(),
+        })?;
+        self.context.enable(consts::POLYGON_OFFSET_FILL);
+        self.context.polygon_offset(1.0, 1.0);
+        let result = self.render_with_color(color, render_states, viewport, camera);
+        self.context.disable(consts::POLYGON_OFFSET_FILL);
+        result?;
+
+        let program = &wireframe.program;
+        program.use_uniform_vec4("color", wire_color)?;
+        program.use_uniform_float("lineWidth", &wire_width)?;
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+        program.use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        program.use_attribute_vec3(&wireframe.position_buffer, "position")?;
+        program.use_attribute_vec3(&wireframe.barycentric_buffer, "barycentric")?;
+        program.draw_arrays(
+            RenderStates {
+                depth_test: DepthTestType::LessOrEqual,
+                blend: Some(BlendParameters::TRANSPARENCY),
+                ..render_states
+            },
+            CullType::None,
+            viewport,
+            wireframe.position_buffer.count() as u32 / 3,
+        );
+        Ok(())
+    }
+
     ///
     /// Render the uv coordinates of the mesh in red (u) and green (v).
     /// Must be called in a render target render function,
@@ -235,6 +493,8 @@ impl Mesh {
 
     ///
     /// Render the mesh with the given texture.
+    /// The colors of an [sRGB texture](crate::Texture::is_srgb), for example an albedo texture loaded from an image, are written as seen in the image,
+    /// and so are the values of a linear texture, for example a normal map, ie. the values are not gamma corrected.
     /// Must be called in a render target render function,
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     ///
@@ -254,6 +514,28 @@ impl Mesh {
             include_str!("shaders/mesh_texture.frag")
         ))?;
         program.use_texture(texture, "tex")?;
+        program.use_uniform_int("isSrgb", &(texture.is_srgb() as i32))?;
+        self.render(program, render_states, viewport, camera)
+    }
+
+    ///
+    /// Render the screen space motion of the mesh since the previous frame into the red (u) and green (v) channels, measured in uv coordinates of the render target.
+    /// The motion is computed from the current transformation and camera and the [previous transformation](Self::previous_transformation)
+    /// and the given view-projection matrix of the camera in the previous frame.
+    /// Render into a [ColorTargetTexture2D](crate::ColorTargetTexture2D) with `f32` data and [Format::RG](crate::Format::RG) to keep the precision and sign of the motion.
+    ///
+    /// **Note:** Static geometry still produces motion when the camera moves.
+    /// If the camera is [jittered](crate::Camera::set_jitter), the jitter is part of the motion unless the previous view-projection matrix contains the same jitter.
+    ///
+    pub fn render_motion_vectors(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        previous_view_projection: &Mat4,
+    ) -> Result<(), Error> {
+        let program = self.get_or_insert_program(include_str!("shaders/mesh_motion.frag"))?;
+        program.use_uniform_mat4("previousViewProjection", previous_view_projection)?;
         self.render(program, render_states, viewport, camera)
     }
 
@@ -274,69 +556,216 @@ impl Mesh {
         viewport: Viewport,
         camera: &Camera,
     ) -> Result<(), Error> {
-        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
-        program.use_uniform_block(camera.uniform_buffer(), "Camera");
-
-        program.use_attribute_vec3(&self.position_buffer, "position")?;
-        if program.use_uvs {
-            let uv_buffer = self.uv_buffer.as_ref().ok_or(Error::MeshError {
-                message:
-                    "The mesh shader program needs uv coordinates, but the mesh does not have any."
-                        .to_string(),
+        self.render_internal(program, render_states, viewport, camera, None)
+    }
+
+    ///
+    /// Render only the part of the mesh given by `index_count` indices, starting at the index `index_start`, with the given [MeshProgram](MeshProgram).
+    /// This is for example useful for progressively revealing a path without creating a new mesh for each step.
+    /// With [PrimitiveType::Triangles](crate::PrimitiveType::Triangles), the start and count should be multiples of three to draw whole triangles.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if the mesh does not have indices or if the range is outside the indices of the mesh.
+    /// Also returns the same errors as [render](Self::render).
+    ///
+    pub fn render_range(
+        &self,
+        program: &MeshProgram,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        index_start: u32,
+        index_count: u32,
+    ) -> Result<(), Error> {
+        let index_buffer = self.index_buffer.as_ref().ok_or(Error::MeshError {
+            message: "Cannot render a range of the indices of a mesh without indices.".to_string(),
+        })?;
+        if index_start as usize + index_count as usize > index_buffer.count() {
+            Err(Error::MeshError {
+                message: format!(
+                    "Cannot render {} indices starting at index {} of a mesh with {} indices.",
+                    index_count,
+                    index_start,
+                    index_buffer.count()
+                ),
             })?;
-            program.use_attribute_vec2(uv_buffer, "uv_coordinates")?;
         }
-        if program.use_normals {
-            let normal_buffer = self.normal_buffer.as_ref().ok_or(
-                Error::MeshError {message: "The mesh shader program needs normals, but the mesh does not have any. Consider calculating the normals on the CPUMesh.".to_string()})?;
-            program.use_uniform_mat4(
-                "normalMatrix",
-                &self.transformation.invert().unwrap().transpose(),
-            )?;
-            program.use_attribute_vec3(normal_buffer, "normal")?;
+        self.render_internal(
+            program,
+            render_states,
+            viewport,
+            camera,
+            Some(index_start..index_start + index_count),
+        )
+    }
+
+    fn render_internal(
+        &self,
+        program: &MeshProgram,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        elements: Option<std::ops::Range<u32>>,
+    ) -> Result<(), Error> {
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+        program.use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        let render_states = self.front_face_corrected(render_states);
+        if program.use_motion {
+            program.use_uniform_mat4("previousModelMatrix", &self.previous_transformation)?;
         }
-        if program.use_colors {
-            let color_buffer = self.color_buffer.as_ref().ok_or(
-                Error::MeshError {message: "The mesh shader program needs per vertex colors, but the mesh does not have any.".to_string()})?;
-            program.use_attribute_vec4(color_buffer, "color")?;
+
+        use_mesh_program_attributes(
+            program,
+            &self.normal_matrix,
+            &self.position_buffer,
+            self.normal_buffer.as_deref(),
+            self.uv_buffer.as_deref(),
+            self.color_buffer.as_deref(),
+        )?;
+
+        if let Some(ref index_buffer) = self.index_buffer {
+            program.draw_subset_of_elements_with_primitive_type(
+                render_states,
+                self.cull,
+                viewport,
+                index_buffer,
+                elements.unwrap_or(0..index_buffer.count() as u32),
+                self.primitive_type,
+            )?;
+        } else {
+            program.draw_arrays_with_primitive_type(
+                render_states,
+                self.cull,
+                viewport,
+                self.position_buffer.count() as u32 / 3,
+                self.primitive_type,
+            );
         }
+        Ok(())
+    }
+
+    ///
+    /// Render the mesh with the given shader program without a camera, so the vertex shader must compute the position in normalized device coordinates,
+    /// typically `gl_Position = modelMatrix * vec4(position, 1.0);`, see [render_ndc_with_color](Self::render_ndc_with_color).
+    /// The same vertex attributes and uniforms as in [Geometry::render_with_program](crate::Geometry::render_with_program) are bound, except the `Camera` uniform block.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_ndc(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewport: Viewport,
+    ) -> Result<(), Error> {
+        self.render_with_program_internal(program, render_states, viewport, None)
+    }
+
+    ///
+    /// Render the mesh with the given color without a camera, where the positions of the mesh, transformed by the [transformation](Self::transformation), are interpreted directly as
+    /// normalized device coordinates, ie. `(-1, -1)` is the bottom left corner and `(1, 1)` the top right corner of the viewport and z is the depth between -1 and 1.
+    /// This is for example useful for crosshairs, vignettes and screen borders.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// **Note:** The depth test in the render states still applies, so use [DepthTestType::Always](crate::DepthTestType::Always)
+    /// and do not write depth ([WriteMask::COLOR](crate::WriteMask::COLOR)) to always render the overlay on top of the scene.
+    ///
+    pub fn render_ndc_with_color(
+        &self,
+        color: &Vec4,
+        render_states: RenderStates,
+        viewport: Viewport,
+    ) -> Result<(), Error> {
+        let program = unsafe {
+            get_or_insert_cached_program(
+                std::ptr::addr_of_mut!(NDC_PROGRAMS),
+                &self.context,
+                "",
+                || {
+                    Program::from_source(
+                        &self.context,
+                        "uniform mat4 modelMatrix;
+                        in vec3 position;
+                        void main()
+                        {
+                            gl_Position = modelMatrix * vec4(position, 1.0);
+                        }",
+                        &format!(
+                            "{}{}",
+                            include_str!("../core/shared.frag"),
+                            include_str!("shaders/mesh_color.frag")
+                        ),
+                    )
+                },
+            )?
+        };
+        program.use_uniform_vec4("color", color)?;
+        self.render_ndc(program, render_states, viewport)
+    }
+
+    fn render_with_program_internal(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: Option<&Camera>,
+    ) -> Result<(), Error> {
+        use_mesh_uniforms_and_attributes(
+            program,
+            &self.transformation,
+            camera,
+            &self.position_buffer,
+            self.normal_buffer.as_deref(),
+            self.uv_buffer.as_deref(),
+            self.color_buffer.as_deref(),
+        )?;
+        let render_states = self.front_face_corrected(render_states);
 
         if let Some(ref index_buffer) = self.index_buffer {
-            program.draw_elements(render_states, self.cull, viewport, index_buffer);
+            program.draw_elements_with_primitive_type(
+                render_states,
+                self.cull,
+                viewport,
+                index_buffer,
+                self.primitive_type,
+            )?;
         } else {
-            program.draw_arrays(
+            program.draw_arrays_with_primitive_type(
                 render_states,
                 self.cull,
                 viewport,
                 self.position_buffer.count() as u32 / 3,
+                self.primitive_type,
             );
         }
         Ok(())
     }
 
+    fn front_face_corrected(&self, render_states: RenderStates) -> RenderStates {
+        // A mirrored transformation flips the winding order of the triangles
+        if self.mirrored {
+            RenderStates {
+                front_face: render_states.front_face.flipped(),
+                ..render_states
+            }
+        } else {
+            render_states
+        }
+    }
+
     pub(crate) fn get_or_insert_program(
         &self,
         fragment_shader_source: &str,
     ) -> Result<&MeshProgram, Error> {
         unsafe {
-            if PROGRAMS.is_none() {
-                PROGRAMS = Some(std::collections::HashMap::new());
-            }
-            if !PROGRAMS
-                .as_ref()
-                .unwrap()
-                .contains_key(fragment_shader_source)
-            {
-                PROGRAMS.as_mut().unwrap().insert(
-                    fragment_shader_source.to_string(),
-                    MeshProgram::new(&self.context, fragment_shader_source)?,
-                );
-            };
-            Ok(PROGRAMS
-                .as_ref()
-                .unwrap()
-                .get(fragment_shader_source)
-                .unwrap())
+            get_or_insert_cached_program(
+                std::ptr::addr_of_mut!(PROGRAMS),
+                &self.context,
+                fragment_shader_source,
+                || MeshProgram::new(&self.context, fragment_shader_source),
+            )
         }
     }
 }
@@ -355,6 +784,10 @@ impl Geometry for Mesh {
         Ok(())
     }
 
+    fn depth_program_key(&self) -> usize {
+        crate::object::depth_program_key::<MeshProgram>(&self.context, "void main() {}")
+    }
+
     fn render_depth(
         &self,
         render_states: RenderStates,
@@ -365,6 +798,20 @@ impl Geometry for Mesh {
         self.render(program, render_states, viewport, camera)
     }
 
+    fn render_with_program(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        self.render_with_program_internal(program, render_states, viewport, Some(camera))
+    }
+
+    fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
+
     fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
         let mut aabb = self.aabb.clone();
         aabb.transform(&self.transformation);
@@ -387,9 +834,16 @@ impl Clone for Mesh {
             aabb: self.aabb.clone(),
             name: self.name.clone(),
             cull: self.cull.clone(),
-            transformation: self.transformation.clone(),
+            primitive_type: self.primitive_type,
+            wireframe: self.wireframe.clone(),
+            transformation: self.transformation,
+            previous_transformation: self.previous_transformation,
+            normal_matrix: self.normal_matrix,
+            mirrored: self.mirrored,
+            decomposition: self.decomposition,
             material: self.material.clone(),
             lighting_model: self.lighting_model,
+            layer_mask: self.layer_mask,
         }
     }
 }
@@ -400,10 +854,334 @@ impl Drop for Mesh {
             MESH_COUNT -= 1;
             if MESH_COUNT == 0 {
                 PROGRAMS = None;
+                NDC_PROGRAMS = None;
             }
         }
     }
 }
 
 static mut MESH_COUNT: u32 = 0;
-static mut PROGRAMS: Option<std::collections::HashMap<String, MeshProgram>> = None;
+
+///
+/// The vertex and index buffers constructed from a [CPUMesh], which are common to all mesh types.
+///
+pub(crate) struct MeshBuffers {
+    pub position_buffer: VertexBuffer,
+    pub normal_buffer: Option<VertexBuffer>,
+    pub index_buffer: Option<ElementBuffer>,
+    pub uv_buffer: Option<VertexBuffer>,
+    pub color_buffer: Option<VertexBuffer>,
+}
+
+///
+/// The vertex and index buffers of a mesh, which are shared between a [Mesh] and the [InstancedMesh]es created from it using [Mesh::instanced].
+///
+pub(crate) struct SharedMeshBuffers {
+    pub position_buffer: Rc<VertexBuffer>,
+    pub normal_buffer: Option<Rc<VertexBuffer>>,
+    pub index_buffer: Option<Rc<ElementBuffer>>,
+    pub uv_buffer: Option<Rc<VertexBuffer>>,
+    pub color_buffer: Option<Rc<VertexBuffer>>,
+}
+
+impl From<MeshBuffers> for SharedMeshBuffers {
+    fn from(buffers: MeshBuffers) -> Self {
+        Self {
+            position_buffer: Rc::new(buffers.position_buffer),
+            normal_buffer: buffers.normal_buffer.map(Rc::new),
+            index_buffer: buffers.index_buffer.map(Rc::new),
+            uv_buffer: buffers.uv_buffer.map(Rc::new),
+            color_buffer: buffers.color_buffer.map(Rc::new),
+        }
+    }
+}
+
+impl MeshBuffers {
+    ///
+    /// Copies the positions, normals, indices, uvs and colors of the given mesh to the GPU. The mesh is assumed to be [valid](CPUMesh::validate).
+    ///
+    pub fn new(context: &Context, cpu_mesh: &CPUMesh) -> Result<Self, Error> {
+        let position_buffer = VertexBuffer::new_with_static(context, &cpu_mesh.positions)?;
+        let normal_buffer = if let Some(ref normals) = cpu_mesh.normals {
+            Some(VertexBuffer::new_with_static(context, normals)?)
+        } else {
+            None
+        };
+        let index_buffer = if let Some(ref indices) = cpu_mesh.indices {
+            Some(match indices {
+                Indices::U8(ind) => ElementBuffer::new(context, ind)?,
+                Indices::U16(ind) => ElementBuffer::new(context, ind)?,
+                Indices::U32(ind) => ElementBuffer::new(context, ind)?,
+            })
+        } else {
+            None
+        };
+        let uv_buffer = if let Some(ref uvs) = cpu_mesh.uvs {
+            Some(VertexBuffer::new_with_static(context, uvs)?)
+        } else {
+            None
+        };
+        let color_buffer = if let Some(ref colors) = cpu_mesh.colors {
+            Some(VertexBuffer::new_with_static(context, colors)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            position_buffer,
+            normal_buffer,
+            index_buffer,
+            uv_buffer,
+            color_buffer,
+        })
+    }
+
+    ///
+    /// Binds the vertex attributes needed by the given mesh program, see [use_mesh_program_attributes].
+    ///
+    pub fn use_attributes(
+        &self,
+        program: &MeshProgram,
+        transformation: &Mat4,
+    ) -> Result<(), Error> {
+        use_mesh_program_attributes(
+            program,
+            &normal_matrix(transformation),
+            &self.position_buffer,
+            self.normal_buffer.as_ref(),
+            self.uv_buffer.as_ref(),
+            self.color_buffer.as_ref(),
+        )
+    }
+
+    ///
+    /// Binds the uniforms and vertex attributes used by the given program, see [use_mesh_uniforms_and_attributes].
+    ///
+    pub fn use_uniforms_and_attributes(
+        &self,
+        program: &Program,
+        transformation: &Mat4,
+        camera: Option<&Camera>,
+    ) -> Result<(), Error> {
+        use_mesh_uniforms_and_attributes(
+            program,
+            transformation,
+            camera,
+            &self.position_buffer,
+            self.normal_buffer.as_ref(),
+            self.uv_buffer.as_ref(),
+            self.color_buffer.as_ref(),
+        )
+    }
+
+    ///
+    /// Draws the triangles defined by the indices, or by the positions if there are no indices.
+    ///
+    pub fn draw(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        cull: CullType,
+        viewport: Viewport,
+    ) -> Result<(), Error> {
+        if let Some(ref index_buffer) = self.index_buffer {
+            program.draw_elements(render_states, cull, viewport, index_buffer)?;
+        } else {
+            program.draw_arrays(
+                render_states,
+                cull,
+                viewport,
+                self.position_buffer.count() as u32 / 3,
+            );
+        }
+        Ok(())
+    }
+}
+
+///
+/// The matrix which transforms the normals of a mesh with the given transformation, ie. the inverse transpose,
+/// or the identity if the transformation is not invertible.
+///
+pub(crate) fn normal_matrix(transformation: &Mat4) -> Mat4 {
+    transformation
+        .invert()
+        .map(|m| m.transpose())
+        .unwrap_or_else(Mat4::identity)
+}
+
+///
+/// Binds the vertex attributes needed by the given mesh program, where the normals are only bound if the program uses them,
+/// together with the normal matrix, and similarly for the uvs and colors.
+///
+pub(crate) fn use_mesh_program_attributes(
+    program: &MeshProgram,
+    normal_matrix: &Mat4,
+    position_buffer: &VertexBuffer,
+    normal_buffer: Option<&VertexBuffer>,
+    uv_buffer: Option<&VertexBuffer>,
+    color_buffer: Option<&VertexBuffer>,
+) -> Result<(), Error> {
+    program.use_attribute_vec3(position_buffer, "position")?;
+    if program.use_uvs {
+        let uv_buffer = uv_buffer.ok_or(Error::MeshError {
+            message:
+                "The mesh shader program needs uv coordinates, but the mesh does not have any."
+                    .to_string(),
+        })?;
+        program.use_attribute_vec2(uv_buffer, "uv_coordinates")?;
+    }
+    if program.use_normals {
+        let normal_buffer = normal_buffer.ok_or(
+            Error::MeshError {message: "The mesh shader program needs normals, but the mesh does not have any. Consider calculating the normals on the CPUMesh.".to_string()})?;
+        program.use_uniform_mat4("normalMatrix", normal_matrix)?;
+        program.use_attribute_vec3(normal_buffer, "normal")?;
+    }
+    if program.use_colors {
+        let color_buffer = color_buffer.ok_or(Error::MeshError {
+            message:
+                "The mesh shader program needs per vertex colors, but the mesh does not have any."
+                    .to_string(),
+        })?;
+        program.use_attribute_vec4(color_buffer, "color")?;
+    }
+    Ok(())
+}
+
+///
+/// Binds the uniforms and vertex attributes which are used by the given program and are common to all mesh types,
+/// see [Geometry::render_with_program](crate::Geometry::render_with_program) for the names.
+///
+pub(crate) fn use_mesh_uniforms_and_attributes(
+    program: &Program,
+    transformation: &Mat4,
+    camera: Option<&Camera>,
+    position_buffer: &VertexBuffer,
+    normal_buffer: Option<&VertexBuffer>,
+    uv_buffer: Option<&VertexBuffer>,
+    color_buffer: Option<&VertexBuffer>,
+) -> Result<(), Error> {
+    if program.requires_uniform("modelMatrix") {
+        program.use_uniform_mat4("modelMatrix", transformation)?;
+    }
+    if program.requires_uniform("normalMatrix") {
+        program.use_uniform_mat4("normalMatrix", &normal_matrix(transformation))?;
+    }
+    if let Some(camera) = camera {
+        if program.requires_uniform_block("Camera") {
+            program.use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        }
+    }
+
+    program.use_attribute_vec3(position_buffer, "position")?;
+    if program.requires_attribute("uv_coordinates") {
+        let uv_buffer = uv_buffer.ok_or(Error::MeshError {
+            message: "The shader program needs uv coordinates, but the mesh does not have any."
+                .to_string(),
+        })?;
+        program.use_attribute_vec2(uv_buffer, "uv_coordinates")?;
+    }
+    if program.requires_attribute("normal") {
+        let normal_buffer = normal_buffer.ok_or(
+            Error::MeshError {message: "The shader program needs normals, but the mesh does not have any. Consider calculating the normals on the CPUMesh.".to_string()})?;
+        program.use_attribute_vec3(normal_buffer, "normal")?;
+    }
+    if program.requires_attribute("color") {
+        let color_buffer = color_buffer.ok_or(Error::MeshError {
+            message: "The shader program needs per vertex colors, but the mesh does not have any."
+                .to_string(),
+        })?;
+        program.use_attribute_vec4(color_buffer, "color")?;
+    }
+    Ok(())
+}
+
+// The programs used for rendering in normalized device coordinates, one for each context.
+static mut NDC_PROGRAMS: ProgramCache<Program> = None;
+
+#[derive(Clone, Copy)]
+struct Decomposition {
+    position: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+}
+
+impl Decomposition {
+    fn new(transformation: &Mat4) -> Self {
+        let mut scale = vec3(
+            transformation.x.truncate().magnitude(),
+            transformation.y.truncate().magnitude(),
+            transformation.z.truncate().magnitude(),
+        );
+        if transformation.determinant() < 0.0 {
+            scale.x = -scale.x;
+        }
+        let rotation = if scale.x != 0.0 && scale.y != 0.0 && scale.z != 0.0 {
+            Quat::from(Mat3::from_cols(
+                transformation.x.truncate() / scale.x,
+                transformation.y.truncate() / scale.y,
+                transformation.z.truncate() / scale.z,
+            ))
+            .normalize()
+        } else {
+            Quat::one()
+        };
+        Self {
+            position: transformation.w.truncate(),
+            rotation,
+            scale,
+        }
+    }
+
+    fn transformation(&self) -> Mat4 {
+        Mat4::from_translation(self.position)
+            * Mat4::from(self.rotation)
+            * Mat4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+}
+static mut PROGRAMS: ProgramCache<MeshProgram> = None;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).magnitude() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    fn assert_same_rotation(a: Quat, b: Quat) {
+        // q and -q are the same rotation
+        assert!((a.dot(b).abs() - 1.0).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    fn assert_round_trip(decomposition: Decomposition) {
+        let result = Decomposition::new(&decomposition.transformation());
+        assert_close(result.position, decomposition.position);
+        assert_same_rotation(result.rotation, decomposition.rotation);
+        assert_close(result.scale, decomposition.scale);
+    }
+
+    #[test]
+    fn decomposition_round_trip() {
+        let rotation = Quat::from_axis_angle(vec3(1.0, 2.0, -0.5).normalize(), degrees(70.0));
+        assert_round_trip(Decomposition {
+            position: vec3(1.0, -2.0, 3.0),
+            rotation: Quat::one(),
+            scale: vec3(1.0, 1.0, 1.0),
+        });
+        assert_round_trip(Decomposition {
+            position: vec3(1.0, -2.0, 3.0),
+            rotation,
+            scale: vec3(2.0, 2.0, 2.0),
+        });
+        assert_round_trip(Decomposition {
+            position: vec3(0.0, 0.0, 0.0),
+            rotation,
+            scale: vec3(2.0, 0.5, 3.0),
+        });
+        // A mirroring is represented by a negative scale in x
+        assert_round_trip(Decomposition {
+            position: vec3(-4.0, 0.0, 1.0),
+            rotation,
+            scale: vec3(-1.5, 0.25, 4.0),
+        });
+    }
+}
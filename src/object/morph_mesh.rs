@@ -0,0 +1,362 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::mesh::*;
+use crate::object::*;
+use crate::shading::*;
+
+///
+/// A shader program used for rendering a [MorphMesh](MorphMesh) with a specific number of morph targets. It has a fixed vertex shader and
+/// customizable fragment shader for custom lighting. Use this in combination with [render](MorphMesh::render).
+///
+pub struct MorphMeshProgram {
+    mesh_program: MeshProgram,
+    morph_target_count: usize,
+    use_normal_deltas: bool,
+}
+
+impl MorphMeshProgram {
+    ///
+    /// Constructs a new shader program for rendering morph meshes with the given number of morph targets (at most [MorphMesh::MAX_MORPH_TARGETS])
+    /// and with or without normal deltas. The fragment shader can use the fragments position by adding `in vec3 pos;`,
+    /// its normal by `in vec3 nor;`, its uv coordinates by `in vec2 uvs;` and its per vertex color by `in vec4 col;` to the shader source code.
+    ///
+    pub fn new(
+        context: &Context,
+        fragment_shader_source: &str,
+        morph_target_count: usize,
+        use_normal_deltas: bool,
+    ) -> Result<Self, Error> {
+        let morph_target_count = morph_target_count.min(MorphMesh::MAX_MORPH_TARGETS);
+        Ok(Self {
+            mesh_program: MeshProgram::new_internal(
+                context,
+                fragment_shader_source,
+                &format!(
+                    "#define MORPH\n#define MORPH_TARGETS {}\n{}",
+                    morph_target_count,
+                    if use_normal_deltas {
+                        "#define MORPH_NORMALS\n"
+                    } else {
+                        ""
+                    }
+                ),
+            )?,
+            morph_target_count,
+            use_normal_deltas,
+        })
+    }
+}
+
+impl std::ops::Deref for MorphMeshProgram {
+    type Target = Program;
+
+    fn deref(&self) -> &Program {
+        &self.mesh_program
+    }
+}
+
+///
+/// Similar to [Mesh](crate::Mesh), except that the vertex positions (and normals) are blended with the [morph targets](crate::MorphTarget)
+/// of the mesh, also known as blend shapes, which is for example used for facial animation.
+/// The blended position is `position + Σ weight_i * position_delta_i` where the weights are updated each frame using [set_weights](Self::set_weights).
+///
+/// At most [MorphMesh::MAX_MORPH_TARGETS] morph targets are supported, since each morph target uses one or two vertex attributes
+/// and only 16 vertex attributes are guaranteed to be available. Additional morph targets are ignored.
+/// Normal deltas are only used if all the used morph targets have normal deltas.
+///
+pub struct MorphMesh {
+    context: Context,
+    buffers: MeshBuffers,
+    position_delta_buffers: Vec<VertexBuffer>,
+    normal_delta_buffers: Option<Vec<VertexBuffer>>,
+    weight_buffer: UniformBuffer,
+    aabb: AxisAlignedBoundingBox,
+    pub name: String,
+    ///
+    /// The [CullType](crate::CullType) applied in all render calls, default is [CullType::None](crate::CullType::None).
+    ///
+    pub cull: CullType,
+    pub transformation: Mat4,
+    pub material: Material,
+    pub lighting_model: LightingModel,
+    ///
+    /// The render layers this geometry belongs to, see [Geometry::layer_mask](crate::Geometry::layer_mask). The default is all layers.
+    ///
+    pub layer_mask: u32,
+}
+
+impl MorphMesh {
+    /// The maximum number of morph targets of a morph mesh.
+    pub const MAX_MORPH_TARGETS: usize = 4;
+
+    ///
+    /// Copies the per vertex data and morph targets defined in the given [CPUMesh](crate::CPUMesh) to the GPU, thereby
+    /// making it possible to render the mesh. All weights are initialized to zero.
+    ///
+    pub fn new(context: &Context, cpu_mesh: &CPUMesh) -> Result<Self, Error> {
+        cpu_mesh.validate()?;
+        cpu_mesh.validate_triangles()?;
+        let buffers = MeshBuffers::new(context, cpu_mesh)?;
+        let morph_targets =
+            &cpu_mesh.morph_targets[..cpu_mesh.morph_targets.len().min(Self::MAX_MORPH_TARGETS)];
+        let mut position_delta_buffers = Vec::new();
+        for target in morph_targets {
+            position_delta_buffers.push(VertexBuffer::new_with_static(
+                context,
+                &target.position_deltas,
+            )?);
+        }
+        let normal_delta_buffers = if !morph_targets.is_empty()
+            && morph_targets
+                .iter()
+                .all(|target| target.normal_deltas.is_some())
+        {
+            let mut buffers = Vec::new();
+            for target in morph_targets {
+                buffers.push(VertexBuffer::new_with_static(
+                    context,
+                    target.normal_deltas.as_ref().unwrap(),
+                )?);
+            }
+            Some(buffers)
+        } else {
+            None
+        };
+        let mesh = Self {
+            context: context.clone(),
+            buffers,
+            position_delta_buffers,
+            normal_delta_buffers,
+            weight_buffer: UniformBuffer::new(context, &[Self::MAX_MORPH_TARGETS as u32])?,
+            aabb: cpu_mesh.compute_aabb(),
+            name: cpu_mesh.name.clone(),
+            cull: CullType::None,
+            transformation: Mat4::identity(),
+            material: Material::default(),
+            lighting_model: LightingModel::Blinn,
+            layer_mask: u32::MAX,
+        };
+        unsafe {
+            MESH_COUNT += 1;
+        }
+        Ok(mesh)
+    }
+
+    ///
+    /// Returns the number of morph targets used by this mesh.
+    ///
+    pub fn morph_target_count(&self) -> usize {
+        self.position_delta_buffers.len()
+    }
+
+    ///
+    /// Sets the weight of each morph target, where the i'th weight is applied to the i'th morph target.
+    /// Call this each frame to animate the mesh. Weights for morph targets beyond [morph_target_count](Self::morph_target_count) are ignored
+    /// and morph targets without a weight get the weight zero.
+    ///
+    pub fn set_weights(&mut self, weights: &[f32]) -> Result<(), Error> {
+        let mut data = [0.0; Self::MAX_MORPH_TARGETS];
+        for (i, weight) in weights.iter().take(self.morph_target_count()).enumerate() {
+            data[i] = *weight;
+        }
+        self.weight_buffer.update(0, &data)
+    }
+
+    ///
+    /// Render the morph mesh with the given color. The color is assumed to be in gamma color space (sRGBA).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_with_color(
+        &self,
+        color: &Vec4,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let program = self.get_or_insert_program(&format!(
+            "{}{}",
+            include_str!("../core/shared.frag"),
+            include_str!("shaders/mesh_color.frag")
+        ))?;
+        program.use_uniform_vec4("color", color)?;
+        self.render(program, render_states, viewport, camera)
+    }
+
+    ///
+    /// Render the morph mesh with the given [MorphMeshProgram](MorphMeshProgram).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if the shader program requires a certain attribute and the mesh does not have that attribute
+    /// or if the program uses more morph targets than the mesh has.
+    ///
+    pub fn render(
+        &self,
+        program: &MorphMeshProgram,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if program.morph_target_count > self.morph_target_count() {
+            Err(Error::MeshError {
+                message: format!(
+                    "The mesh shader program uses {} morph targets, but the mesh only has {}.",
+                    program.morph_target_count,
+                    self.morph_target_count()
+                ),
+            })?;
+        }
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+        program.use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        program.use_uniform_block(&self.weight_buffer, "MorphWeights")?;
+
+        self.buffers
+            .use_attributes(&program.mesh_program, &self.transformation)?;
+        for (i, buffer) in self
+            .position_delta_buffers
+            .iter()
+            .take(program.morph_target_count)
+            .enumerate()
+        {
+            program.use_attribute_vec3(buffer, &format!("position_delta{}", i))?;
+        }
+        if program.mesh_program.use_normals && program.use_normal_deltas {
+            let normal_delta_buffers = self.normal_delta_buffers.as_ref().ok_or(
+                Error::MeshError {message: "The mesh shader program needs morph target normal deltas, but the mesh does not have any.".to_string()})?;
+            for (i, buffer) in normal_delta_buffers
+                .iter()
+                .take(program.morph_target_count)
+                .enumerate()
+            {
+                program.use_attribute_vec3(buffer, &format!("normal_delta{}", i))?;
+            }
+        }
+        self.buffers
+            .draw(program, render_states, self.cull, viewport)
+    }
+
+    fn program_key(&self, fragment_shader_source: &str) -> String {
+        format!(
+            "{}{}{}",
+            self.morph_target_count(),
+            self.normal_delta_buffers.is_some(),
+            fragment_shader_source
+        )
+    }
+
+    pub(crate) fn get_or_insert_program(
+        &self,
+        fragment_shader_source: &str,
+    ) -> Result<&MorphMeshProgram, Error> {
+        let use_normal_deltas = self.normal_delta_buffers.is_some();
+        let key = self.program_key(fragment_shader_source);
+        unsafe {
+            get_or_insert_cached_program(
+                std::ptr::addr_of_mut!(PROGRAMS),
+                &self.context,
+                &key,
+                || {
+                    MorphMeshProgram::new(
+                        &self.context,
+                        fragment_shader_source,
+                        self.morph_target_count(),
+                        use_normal_deltas,
+                    )
+                },
+            )
+        }
+    }
+}
+
+impl Geometry for MorphMesh {
+    fn render_depth_to_red(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        max_depth: f32,
+    ) -> Result<(), Error> {
+        let program = self.get_or_insert_program(include_str!("shaders/mesh_pick.frag"))?;
+        program.use_uniform_float("maxDistance", &max_depth)?;
+        self.render(program, render_states, viewport, camera)
+    }
+
+    fn depth_program_key(&self) -> usize {
+        crate::object::depth_program_key::<MorphMeshProgram>(
+            &self.context,
+            &self.program_key("void main() {}"),
+        )
+    }
+
+    fn render_depth(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let program = self.get_or_insert_program("void main() {}")?;
+        self.render(program, render_states, viewport, camera)
+    }
+
+    fn render_with_program(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        self.buffers
+            .use_uniforms_and_attributes(program, &self.transformation, Some(camera))?;
+        if program.requires_uniform_block("MorphWeights") {
+            program.use_uniform_block(&self.weight_buffer, "MorphWeights")?;
+        }
+        for (i, buffer) in self.position_delta_buffers.iter().enumerate() {
+            let name = format!("position_delta{}", i);
+            if program.requires_attribute(&name) {
+                program.use_attribute_vec3(buffer, &name)?;
+            }
+        }
+        if let Some(ref normal_delta_buffers) = self.normal_delta_buffers {
+            for (i, buffer) in normal_delta_buffers.iter().enumerate() {
+                let name = format!("normal_delta{}", i);
+                if program.requires_attribute(&name) {
+                    program.use_attribute_vec3(buffer, &name)?;
+                }
+            }
+        }
+
+        self.buffers
+            .draw(program, render_states, self.cull, viewport)
+    }
+
+    fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
+
+    ///
+    /// Returns the axis aligned bounding box of the mesh without morphing, which might not enclose the morphed mesh.
+    ///
+    fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
+        let mut aabb = self.aabb;
+        aabb.transform(&self.transformation);
+        Some(aabb)
+    }
+}
+
+impl Drop for MorphMesh {
+    fn drop(&mut self) {
+        unsafe {
+            MESH_COUNT -= 1;
+            if MESH_COUNT == 0 {
+                PROGRAMS = None;
+            }
+        }
+    }
+}
+
+static mut MESH_COUNT: u32 = 0;
+static mut PROGRAMS: ProgramCache<MorphMeshProgram> = None;
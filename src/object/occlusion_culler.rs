@@ -0,0 +1,94 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// Culls a geometry which is hidden behind other geometries using a hardware [occlusion query](crate::OcclusionQuery) on its bounding box.
+/// Use one occlusion culler per geometry and call [query_visible](Self::query_visible) each frame after rendering the occluders,
+/// then only render the geometry if it returns true.
+///
+/// To avoid waiting for the GPU, the returned visibility is the result of the query issued in a previous frame, usually the frame before.
+/// The tradeoff is that a geometry which comes into view is rendered one or more frames late, which can be visible as popping when the camera or occluders move fast.
+///
+pub struct OcclusionCuller {
+    query: OcclusionQuery,
+    box_mesh: Mesh,
+    visible: bool,
+}
+
+impl OcclusionCuller {
+    ///
+    /// Creates a new occlusion culler.
+    ///
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            query: OcclusionQuery::new(context)?,
+            box_mesh: Mesh::new(context, &CPUMesh::cube(2.0))?,
+            visible: true,
+        })
+    }
+
+    ///
+    /// Returns whether the given geometry was visible in the latest available occlusion test and issues a new test for this frame
+    /// by rendering the bounding box of the geometry without writing color or depth.
+    /// Returns true if the geometry has no bounding box, if the camera is inside the bounding box or if no test result is available yet.
+    /// The geometry is always considered invisible if it is outside the camera frustum.
+    /// Must be called in a render target render function after the occluders are rendered,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn query_visible(
+        &mut self,
+        geometry: &dyn Geometry,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<bool, Error> {
+        let aabb = if let Some(aabb) = geometry.aabb() {
+            aabb
+        } else {
+            return Ok(true);
+        };
+        if !camera.in_frustum(&aabb) {
+            return Ok(false);
+        }
+        let position = camera.position();
+        if position.x >= aabb.min().x
+            && position.y >= aabb.min().y
+            && position.z >= aabb.min().z
+            && position.x <= aabb.max().x
+            && position.y <= aabb.max().y
+            && position.z <= aabb.max().z
+        {
+            self.visible = true;
+            return Ok(true);
+        }
+        if let Some(visible) = self.query.is_visible() {
+            self.visible = visible;
+        }
+
+        let center = 0.5 * (aabb.min() + aabb.max());
+        let half_size = 0.5 * (aabb.max() - aabb.min());
+        self.box_mesh.set_transformation(
+            Mat4::from_translation(center)
+                * Mat4::from_nonuniform_scale(
+                    half_size.x.max(0.001),
+                    half_size.y.max(0.001),
+                    half_size.z.max(0.001),
+                ),
+        );
+        let box_mesh = &self.box_mesh;
+        self.query.test(|| {
+            box_mesh.render_depth(
+                RenderStates {
+                    write_mask: WriteMask::NONE,
+                    depth_test: DepthTestType::LessOrEqual,
+                    ..Default::default()
+                },
+                viewport,
+                camera,
+            )
+        })?;
+        Ok(self.visible)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::camera::*;
+use crate::context::consts;
+use crate::core::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// The corners of the screen (or render target) where an [OrientationGizmo] can be placed.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+///
+/// A small orientation gizmo which is rendered in a corner of the screen and shows the orientation of the main camera using [Axes],
+/// ie. the x-axis (red), the y-axis (green) and the z-axis (blue).
+/// Only the rotation of the main camera is used, so the gizmo always stays in the corner regardless of where the camera is positioned.
+///
+pub struct OrientationGizmo {
+    context: Context,
+    axes: Axes,
+    camera: Camera,
+    ///
+    /// The distance in pixels between the gizmo and the edges of the screen. Default is 10.
+    ///
+    pub margin: u32,
+}
+
+impl OrientationGizmo {
+    ///
+    /// Creates a new orientation gizmo.
+    ///
+    pub fn new(context: &Context) -> Result<Self, Error> {
+        Ok(Self {
+            context: context.clone(),
+            axes: Axes::new(context, 0.06, 1.0)?,
+            camera: Camera::new_orthographic(
+                context,
+                vec3(0.0, 0.0, 5.0),
+                vec3(0.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                2.4,
+                2.4,
+                10.0,
+            )?,
+            margin: 10,
+        })
+    }
+
+    ///
+    /// Render the gizmo in the given corner of the given viewport as a square with the given size in pixels,
+    /// oriented as seen from the main camera.
+    /// The depth in the square is cleared before rendering, so the gizmo is always visible on top of the rest of the scene.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(
+        &mut self,
+        corner: Corner,
+        size: u32,
+        viewport: Viewport,
+        main_camera: &Camera,
+    ) -> Result<(), Error> {
+        let direction = (main_camera.position() - main_camera.target()).normalize();
+        self.camera
+            .set_view(5.0 * direction, vec3(0.0, 0.0, 0.0), *main_camera.up())?;
+
+        let size = size
+            .min(viewport.width.saturating_sub(2 * self.margin))
+            .min(viewport.height.saturating_sub(2 * self.margin));
+        let margin = self.margin as i32;
+        let left = viewport.x + margin;
+        let right = viewport.x + viewport.width as i32 - margin - size as i32;
+        let bottom = viewport.y + margin;
+        let top = viewport.y + viewport.height as i32 - margin - size as i32;
+        let (x, y) = match corner {
+            Corner::TopLeft => (left, top),
+            Corner::TopRight => (right, top),
+            Corner::BottomLeft => (left, bottom),
+            Corner::BottomRight => (right, bottom),
+        };
+        let gizmo_viewport = Viewport {
+            x,
+            y,
+            width: size,
+            height: size,
+        };
+
+        let context = &self.context;
+        Program::set_write_mask(context, WriteMask::COLOR_AND_DEPTH);
+        context.enable(consts::SCISSOR_TEST);
+        context.scissor(x, y, size as i32, size as i32);
+        context.clear_depth(1.0);
+        context.clear(consts::DEPTH_BUFFER_BIT);
+        context.disable(consts::SCISSOR_TEST);
+
+        self.axes.render(gizmo_viewport, &self.camera)
+    }
+}
@@ -112,6 +112,7 @@ pub struct Particles {
 
 impl Particles {
     pub fn new(context: &Context, cpu_mesh: &CPUMesh, acceleration: &Vec3) -> Result<Self, Error> {
+        cpu_mesh.validate_triangles()?;
         let position_buffer = VertexBuffer::new_with_static(context, &cpu_mesh.positions)?;
         let normal_buffer = if let Some(ref normals) = cpu_mesh.normals {
             Some(VertexBuffer::new_with_static(context, normals)?)
@@ -186,7 +187,7 @@ impl Particles {
         program.use_uniform_mat4("modelMatrix", &self.transformation)?;
         program.use_uniform_vec3("acceleration", &self.acceleration)?;
         program.use_uniform_float("time", &time)?;
-        program.use_uniform_block(camera.uniform_buffer(), "Camera");
+        program.use_uniform_block(camera.uniform_buffer(), "Camera")?;
 
         program.use_attribute_vec3_divisor(&self.start_position_buffer, "start_position", 1)?;
         program.use_attribute_vec3_divisor(&self.start_velocity_buffer, "start_velocity", 1)?;
@@ -213,7 +214,7 @@ impl Particles {
                 viewport,
                 index_buffer,
                 self.instance_count,
-            );
+            )?;
         } else {
             program.draw_arrays_instanced(
                 render_states,
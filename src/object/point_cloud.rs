@@ -0,0 +1,141 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+
+///
+/// Defines how the size of the points in a [PointCloud] is specified.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PointSizeMode {
+    ///
+    /// The size is the diameter of the point in pixels, regardless of the distance to the camera.
+    ///
+    Pixels,
+    ///
+    /// The size is the diameter of the point in world units, so points further away from the camera are smaller.
+    ///
+    World,
+}
+
+///
+/// A set of points rendered as antialiased round dots with a size and color per point, for example a point cloud or round particles.
+///
+pub struct PointCloud {
+    program: Program,
+    position_buffer: VertexBuffer,
+    color_buffer: VertexBuffer,
+    size_buffer: VertexBuffer,
+    count: u32,
+    ///
+    /// How the sizes of the points are specified. Default is [PointSizeMode::Pixels].
+    ///
+    pub size_mode: PointSizeMode,
+    ///
+    /// The local to world transformation of the points.
+    ///
+    pub transformation: Mat4,
+}
+
+impl PointCloud {
+    ///
+    /// Creates a new point cloud with the given positions (three values per point), colors (four `u8` values per point in gamma color space (sRGBA))
+    /// and sizes (one value per point), see [PointSizeMode] for how the sizes are interpreted.
+    ///
+    /// # Errors
+    /// Returns an error if the number of colors or sizes does not match the number of positions.
+    ///
+    pub fn new(
+        context: &Context,
+        positions: &[f32],
+        colors: &[u8],
+        sizes: &[f32],
+    ) -> Result<Self, Error> {
+        let mut point_cloud = Self {
+            program: Program::from_source(
+                context,
+                &format!(
+                    "{}{}",
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/point_cloud.vert")
+                ),
+                &format!(
+                    "{}{}",
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/point_cloud.frag")
+                ),
+            )?,
+            position_buffer: VertexBuffer::new(context)?,
+            color_buffer: VertexBuffer::new(context)?,
+            size_buffer: VertexBuffer::new(context)?,
+            count: 0,
+            size_mode: PointSizeMode::Pixels,
+            transformation: Mat4::identity(),
+        };
+        point_cloud.update(positions, colors, sizes)?;
+        Ok(point_cloud)
+    }
+
+    ///
+    /// Updates the positions, colors and sizes of the points, see [new](Self::new).
+    ///
+    /// # Errors
+    /// Returns an error if the number of colors or sizes does not match the number of positions.
+    ///
+    pub fn update(&mut self, positions: &[f32], colors: &[u8], sizes: &[f32]) -> Result<(), Error> {
+        let count = positions.len() / 3;
+        if positions.len() != 3 * count || colors.len() != 4 * count || sizes.len() != count {
+            Err(Error::MeshError {
+                message: format!(
+                    "The point cloud data is inconsistent ({} position values, {} color values and {} sizes)",
+                    positions.len(),
+                    colors.len(),
+                    sizes.len()
+                ),
+            })?;
+        }
+        self.position_buffer.fill_with_dynamic(positions);
+        self.color_buffer.fill_with_dynamic(colors);
+        self.size_buffer.fill_with_dynamic(sizes);
+        self.count = count as u32;
+        Ok(())
+    }
+
+    ///
+    /// Render the points. Use [blending](crate::BlendParameters::TRANSPARENCY) in the render states to get antialiased edges.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if self.count == 0 {
+            return Ok(());
+        }
+        self.program
+            .use_uniform_mat4("modelMatrix", &self.transformation)?;
+        self.program.use_uniform_int(
+            "worldSize",
+            &((self.size_mode == PointSizeMode::World) as i32),
+        )?;
+        self.program
+            .use_uniform_float("viewportHeight", &(viewport.height as f32))?;
+        self.program
+            .use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        self.program
+            .use_attribute_vec3(&self.position_buffer, "position")?;
+        self.program
+            .use_attribute_vec4(&self.color_buffer, "color")?;
+        self.program.use_attribute(&self.size_buffer, "size")?;
+        self.program.draw_arrays_with_primitive_type(
+            render_states,
+            CullType::None,
+            viewport,
+            self.count,
+            PrimitiveType::Points,
+        );
+        Ok(())
+    }
+}
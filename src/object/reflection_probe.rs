@@ -0,0 +1,131 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+use crate::object::*;
+
+///
+/// Captures the scene surrounding a position into a [cube map](crate::TextureCubeMap) which can then be sampled to render reflections on nearby objects.
+/// The scene is only captured when calling [capture](ReflectionProbe::capture), so it can be captured once for a static scene
+/// or periodically for a dynamic scene.
+///
+/// Reflections sampled directly from a cube map appear as if the surroundings are infinitely far away.
+/// If the surroundings are a room or another box shaped volume, set the [bounding box](ReflectionProbe::bounding_box) to that volume to enable
+/// parallax corrected sampling which aligns the reflections with the nearby surfaces.
+///
+pub struct ReflectionProbe {
+    render_target: RenderTargetCubeMap,
+    program: MeshProgram,
+    ///
+    /// The box shaped volume around the probe used for parallax corrected sampling, for example the walls of a room.
+    /// If `None`, the reflections are sampled without parallax correction.
+    ///
+    pub bounding_box: Option<AxisAlignedBoundingBox>,
+}
+
+impl ReflectionProbe {
+    ///
+    /// Constructs a new reflection probe at the given position,
+    /// where each side of the cube map has the given size and the scene is captured between the `z_near` and `z_far` distances from the position.
+    ///
+    pub fn new(
+        context: &Context,
+        position: Vec3,
+        size: u32,
+        z_near: f32,
+        z_far: f32,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            render_target: RenderTargetCubeMap::new::<u8>(
+                context,
+                size,
+                Format::RGBA,
+                position,
+                z_near,
+                z_far,
+            )?,
+            program: MeshProgram::new(
+                context,
+                &format!(
+                    "{}{}",
+                    include_str!("../core/shared.frag"),
+                    include_str!("shaders/reflection_probe.frag")
+                ),
+            )?,
+            bounding_box: None,
+        })
+    }
+
+    ///
+    /// Captures the surrounding scene into the cube map by calling the `render` closure once for each side of the cube map.
+    /// Render the scene in the closure using the given viewport and camera and do not render the objects
+    /// which the reflections are rendered on, since they would block the view.
+    ///
+    pub fn capture<F: FnMut(Viewport, &Camera) -> Result<(), Error>>(
+        &mut self,
+        clear_state: ClearState,
+        render: F,
+    ) -> Result<(), Error> {
+        self.render_target.write(clear_state, render)
+    }
+
+    ///
+    /// The position the scene is captured from.
+    ///
+    pub fn position(&self) -> Vec3 {
+        self.render_target.position()
+    }
+
+    ///
+    /// Sets the position the scene is captured from the next time [capture](Self::capture) is called.
+    ///
+    pub fn set_position(&mut self, position: Vec3) {
+        self.render_target.set_position(position);
+    }
+
+    ///
+    /// The cube map containing the captured scene.
+    ///
+    pub fn texture(&self) -> &TextureCubeMap {
+        self.render_target.texture()
+    }
+
+    ///
+    /// Sends the uniforms needed for sampling this probe to the given program, which can then be used for rendering reflective materials.
+    /// The program must declare `uniform samplerCube reflectionMap;`, `uniform vec3 probePosition;`, `uniform int parallaxCorrection;`,
+    /// `uniform vec3 probeMin;` and `uniform vec3 probeMax;` in the fragment shader,
+    /// where `parallaxCorrection` is 1 if the box defined by `probeMin` and `probeMax` should be used for parallax corrected sampling.
+    ///
+    pub fn use_uniforms(&self, program: &Program) -> Result<(), Error> {
+        program.use_texture_cube(self.texture(), "reflectionMap")?;
+        program.use_uniform_vec3("probePosition", &self.position())?;
+        if let Some(ref aabb) = self.bounding_box {
+            program.use_uniform_int("parallaxCorrection", &1)?;
+            program.use_uniform_vec3("probeMin", aabb.min())?;
+            program.use_uniform_vec3("probeMax", aabb.max())?;
+        } else {
+            program.use_uniform_int("parallaxCorrection", &0)?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Renders the mesh as a perfect mirror reflecting the scene captured by this probe.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if the mesh does not have normals.
+    ///
+    pub fn render_reflection(
+        &self,
+        mesh: &Mesh,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        self.use_uniforms(&self.program)?;
+        self.program
+            .use_uniform_vec3("eyePosition", camera.position())?;
+        mesh.render(&self.program, render_states, viewport, camera)
+    }
+}
@@ -0,0 +1,147 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::math::*;
+use crate::object::*;
+
+struct RenderListItem<'a> {
+    geometry: &'a dyn Geometry,
+    program: &'a Program,
+    blend: Option<BlendParameters>,
+    priority: i32,
+}
+
+///
+/// A list of geometries, each with the shader program used for rendering it, which renders the geometries in the right order and with the right render states.
+/// The opaque geometries are rendered first, sorted front-to-back so that the depth test discards as many fragments as possible,
+/// afterwards the transparent geometries are rendered sorted back-to-front with blending and without writing depth, so that they blend correctly.
+/// The sorting uses the distance from the camera to the center of the bounding box of each geometry.
+///
+/// To override the order for special cases, use [push_with_priority](RenderList::push_with_priority),
+/// all geometries with a lower priority are rendered before the geometries with a higher priority.
+///
+pub struct RenderList<'a> {
+    items: Vec<RenderListItem<'a>>,
+}
+
+impl<'a> RenderList<'a> {
+    ///
+    /// Creates a new empty render list.
+    ///
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    ///
+    /// Adds an opaque geometry which is rendered using the given program.
+    /// See [Geometry::render_with_program] for the attributes and uniforms available in the program.
+    ///
+    pub fn push_opaque(&mut self, geometry: &'a dyn Geometry, program: &'a Program) {
+        self.push_with_priority(geometry, program, None, 0);
+    }
+
+    ///
+    /// Adds a transparent geometry which is rendered using the given program and blend parameters.
+    /// See [Geometry::render_with_program] for the attributes and uniforms available in the program.
+    ///
+    pub fn push_transparent(
+        &mut self,
+        geometry: &'a dyn Geometry,
+        program: &'a Program,
+        blend: BlendParameters,
+    ) {
+        self.push_with_priority(geometry, program, Some(blend), 0);
+    }
+
+    ///
+    /// Adds a geometry which is rendered using the given program and is transparent if blend parameters are given.
+    /// The geometries are rendered in order of increasing priority, and geometries with the same priority are sorted as described in [RenderList].
+    /// The priority of geometries added with [push_opaque](Self::push_opaque) and [push_transparent](Self::push_transparent) is zero.
+    ///
+    pub fn push_with_priority(
+        &mut self,
+        geometry: &'a dyn Geometry,
+        program: &'a Program,
+        blend: Option<BlendParameters>,
+        priority: i32,
+    ) {
+        self.items.push(RenderListItem {
+            geometry,
+            program,
+            blend,
+            priority,
+        });
+    }
+
+    ///
+    /// Removes all geometries from the list.
+    ///
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    ///
+    /// The number of geometries in the list.
+    ///
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    ///
+    /// Returns true if the list contains no geometries.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    ///
+    /// Render all the geometries in the list.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        let position = *camera.position();
+        let mut order = self
+            .items
+            .iter()
+            .map(|item| {
+                let distance = item
+                    .geometry
+                    .aabb()
+                    .map(|aabb| aabb.center().distance2(position))
+                    .unwrap_or(0.0);
+                // Transparent geometries are sorted back-to-front
+                let distance = if item.blend.is_some() {
+                    -distance
+                } else {
+                    distance
+                };
+                (item.priority, item.blend.is_some(), distance, item)
+            })
+            .collect::<Vec<_>>();
+        order.sort_by(|a, b| {
+            (a.0, a.1)
+                .cmp(&(b.0, b.1))
+                .then(a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
+        });
+        for (_, _, _, item) in order {
+            let render_states = if let Some(blend) = item.blend {
+                RenderStates {
+                    write_mask: WriteMask::COLOR,
+                    blend: Some(blend),
+                    ..Default::default()
+                }
+            } else {
+                RenderStates::default()
+            };
+            item.geometry
+                .render_with_program(item.program, render_states, viewport, camera)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for RenderList<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
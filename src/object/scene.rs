@@ -0,0 +1,191 @@
+use crate::math::*;
+use crate::object::*;
+
+///
+/// Identifies a [Node] in a [Scene].
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+///
+/// A node in a [Scene] which has a local transformation relative to its parent node, a list of child nodes and a list of attached meshes.
+///
+pub struct Node {
+    local_transformation: Mat4,
+    world_transformation: Mat4,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    meshes: Vec<Mesh>,
+}
+
+impl Node {
+    fn new(parent: Option<NodeId>, local_transformation: Mat4) -> Self {
+        Self {
+            local_transformation,
+            world_transformation: local_transformation,
+            parent,
+            children: Vec::new(),
+            meshes: Vec::new(),
+        }
+    }
+
+    ///
+    /// Returns the transformation of this node relative to its parent node.
+    ///
+    pub fn local_transformation(&self) -> Mat4 {
+        self.local_transformation
+    }
+
+    ///
+    /// Sets the transformation of this node relative to its parent node.
+    /// The world transformation of this node, its children and the attached meshes are not updated until [Scene::update_transforms] is called.
+    ///
+    pub fn set_local_transformation(&mut self, transformation: Mat4) {
+        self.local_transformation = transformation;
+    }
+
+    ///
+    /// Returns the world transformation of this node as computed in the last call to [Scene::update_transforms].
+    /// Use [Scene::world_transformation] to compute the current world transformation.
+    ///
+    pub fn world_transformation(&self) -> Mat4 {
+        self.world_transformation
+    }
+
+    ///
+    /// Returns the parent of this node or `None` if this is the root node.
+    ///
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    ///
+    /// Returns the children of this node.
+    ///
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    ///
+    /// Attaches the mesh to this node. The transformation of the mesh is overwritten by the world transformation of this node
+    /// in each call to [Scene::update_transforms].
+    ///
+    pub fn attach(&mut self, mut mesh: Mesh) {
+        mesh.set_transformation(self.world_transformation);
+        self.meshes.push(mesh);
+    }
+
+    ///
+    /// Returns the meshes attached to this node.
+    ///
+    pub fn meshes(&self) -> &[Mesh] {
+        &self.meshes
+    }
+
+    ///
+    /// Returns the meshes attached to this node as mutable, for example to change the material.
+    ///
+    pub fn meshes_mut(&mut self) -> &mut [Mesh] {
+        &mut self.meshes
+    }
+}
+
+///
+/// A tree of [Node]s where the world transformation of each node is the composition of the local transformations from the root node down to that node,
+/// which makes it easy to animate articulated objects, for example a turret on a tank.
+/// Meshes are attached to the nodes, see [Node::attach], and are then moved together with the node.
+///
+/// Using a scene is optional, meshes can also be rendered by setting their transformation directly.
+///
+pub struct Scene {
+    nodes: Vec<Node>,
+}
+
+impl Scene {
+    ///
+    /// Creates a new scene which only contains the root node with an identity transformation.
+    ///
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::new(None, Mat4::identity())],
+        }
+    }
+
+    ///
+    /// Returns the root node of the scene.
+    ///
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    ///
+    /// Adds a new node with the given transformation relative to the given parent node and returns the id of the new node.
+    ///
+    pub fn add_node(&mut self, parent: NodeId, local_transformation: Mat4) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        let mut node = Node::new(Some(parent), local_transformation);
+        node.world_transformation = self.world_transformation(parent) * local_transformation;
+        self.nodes.push(node);
+        self.nodes[parent.0].children.push(id);
+        id
+    }
+
+    ///
+    /// Returns the node with the given id.
+    ///
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    ///
+    /// Returns the node with the given id as mutable.
+    ///
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        &mut self.nodes[id.0]
+    }
+
+    ///
+    /// Computes the current world transformation of the given node by composing the local transformations from the root node down to the node.
+    ///
+    pub fn world_transformation(&self, id: NodeId) -> Mat4 {
+        let node = self.node(id);
+        match node.parent {
+            Some(parent) => self.world_transformation(parent) * node.local_transformation,
+            None => node.local_transformation,
+        }
+    }
+
+    ///
+    /// Updates the world transformation of all nodes and the transformation of all attached meshes.
+    /// Call this after changing the local transformation of one or more nodes and before rendering.
+    ///
+    pub fn update_transforms(&mut self) {
+        let mut stack = vec![(self.root(), Mat4::identity())];
+        while let Some((id, parent_transformation)) = stack.pop() {
+            let node = &mut self.nodes[id.0];
+            node.world_transformation = parent_transformation * node.local_transformation;
+            for mesh in node.meshes.iter_mut() {
+                mesh.set_transformation(node.world_transformation);
+            }
+            let world_transformation = node.world_transformation;
+            stack.extend(
+                node.children
+                    .iter()
+                    .map(|child| (*child, world_transformation)),
+            );
+        }
+    }
+
+    ///
+    /// Returns an iterator over all meshes attached to any node in the scene, for example to render them.
+    ///
+    pub fn meshes(&self) -> impl Iterator<Item = &Mesh> {
+        self.nodes.iter().flat_map(|node| node.meshes.iter())
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
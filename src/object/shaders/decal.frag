@@ -0,0 +1,26 @@
+
+uniform sampler2D depthMap;
+uniform sampler2D tex;
+uniform int isSrgb;
+uniform mat4 viewProjectionInverse;
+uniform mat4 modelMatrixInverse;
+uniform vec4 viewport;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 uv = (gl_FragCoord.xy - viewport.xy) / viewport.zw;
+    float depth = texture(depthMap, uv).x;
+    vec3 position = world_pos_from_depth(viewProjectionInverse, depth, uv);
+
+    // Discard the surface if it is outside the decal box, which is the unit cube centered at the origin in local space
+    vec3 local = (modelMatrixInverse * vec4(position, 1.0)).xyz;
+    if (any(greaterThan(abs(local), vec3(0.5)))) {
+        discard;
+    }
+
+    // The texture is projected along the local y-axis
+    vec4 col = texture(tex, local.xz + 0.5);
+    outColor = isSrgb == 1 ? vec4(srgb_from_rgb(col.rgb), col.a) : col;
+}
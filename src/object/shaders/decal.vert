@@ -0,0 +1,18 @@
+
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform mat4 modelMatrix;
+
+in vec3 position;
+
+void main()
+{
+    gl_Position = camera.viewProjection * modelMatrix * vec4(position, 1.0);
+}
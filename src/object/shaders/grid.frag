@@ -0,0 +1,40 @@
+
+uniform vec3 eyePosition;
+uniform float spacing;
+uniform float majorInterval;
+uniform vec4 minorColor;
+uniform vec4 majorColor;
+uniform float fadeDistance;
+
+in vec3 pos;
+
+layout (location = 0) out vec4 outColor;
+
+// Returns the coverage of the grid lines at the given grid coordinate and fades the lines out when the cells become smaller than a few pixels
+float grid_lines(vec2 coord)
+{
+    vec2 derivative = fwidth(coord);
+    vec2 grid = abs(fract(coord - 0.5) - 0.5) / derivative;
+    float coverage = 1.0 - min(min(grid.x, grid.y), 1.0);
+    float cellSizeInPixels = 1.0 / max(derivative.x, derivative.y);
+    return coverage * smoothstep(2.0, 8.0, cellSizeInPixels);
+}
+
+void main()
+{
+    vec2 coord = pos.xz / spacing;
+    float minor = grid_lines(coord);
+    float major = grid_lines(coord / majorInterval);
+
+    vec3 toEye = eyePosition - pos;
+    float fade = 1.0 - smoothstep(0.5 * fadeDistance, fadeDistance, length(toEye));
+    // Fade out when looking at the grid at a grazing angle to avoid moiré patterns at the horizon
+    fade *= smoothstep(0.0, 0.15, abs(normalize(toEye).y));
+
+    float alpha = max(minorColor.a * minor, majorColor.a * major) * fade;
+    if (alpha <= 0.0) {
+        discard;
+    }
+    vec3 color = mix(minorColor.rgb, majorColor.rgb, major);
+    outColor = vec4(srgb_from_rgb(color), alpha);
+}
@@ -0,0 +1,22 @@
+
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform vec3 center;
+uniform float extent;
+
+in vec3 position;
+
+out vec3 pos;
+
+void main()
+{
+    pos = center + extent * position;
+    gl_Position = camera.viewProjection * vec4(pos, 1.0);
+}
@@ -1,6 +1,24 @@
+#ifdef ATLAS
+uniform sampler2D tex;
+uniform vec2 atlasGrid;
+
+vec4 sample_view(vec2 uv, float index)
+{
+    vec2 cell = vec2(mod(index, atlasGrid.x), floor(index / atlasGrid.x));
+    return texture(tex, (cell + uv) / atlasGrid);
+}
+#else
 uniform sampler2DArray tex;
 
+vec4 sample_view(vec2 uv, float index)
+{
+    return texture(tex, vec3(uv, index));
+}
+#endif
+
 uniform int no_views;
+uniform int premultiplyAlpha;
+uniform float alphaCutout;
 in vec2 uv;
 in float t;
 
@@ -14,13 +32,21 @@ void main()
     float index1 = float((int(index0) + 1) % no_views);
     float frac = layer - index0;
 
-    vec4 color0 = texture(tex, vec3(uv, index0));
+    vec4 color0 = sample_view(uv, index0);
     color0.rgb = rgb_from_srgb(color0.rgb);
-    vec4 color1 = texture(tex, vec3(uv, index1));
+    vec4 color1 = sample_view(uv, index1);
     color1.rgb = rgb_from_srgb(color1.rgb);
-    out_color = mix(color0, color1, frac);
-    out_color = vec4(srgb_from_rgb(out_color.rgb), out_color.a);
-    if(out_color.a < 0.5) {
+    if(premultiplyAlpha == 1) {
+        // Interpolate the premultiplied colors, then encode the color before multiplying with alpha,
+        // since premultiplied alpha blending expects srgb(color) * alpha and not srgb(color * alpha)
+        vec4 color = mix(vec4(color0.rgb * color0.a, color0.a), vec4(color1.rgb * color1.a, color1.a), frac);
+        color.rgb = color.a > 0.0 ? color.rgb / color.a : vec3(0.0);
+        out_color = vec4(srgb_from_rgb(color.rgb) * color.a, color.a);
+    } else {
+        out_color = mix(color0, color1, frac);
+        out_color = vec4(srgb_from_rgb(out_color.rgb), out_color.a);
+    }
+    if(out_color.a < alphaCutout || out_color.a <= 0.0) {
         discard;
     }
 }
@@ -0,0 +1,23 @@
+#ifdef ATLAS
+uniform sampler2D tex;
+#else
+uniform sampler2DArray tex;
+uniform int layer;
+#endif
+
+uniform float alphaCutout;
+in vec2 uv;
+
+layout (location = 0) out vec4 color;
+
+void main()
+{
+#ifdef ATLAS
+    color = texture(tex, uv);
+#else
+    color = texture(tex, vec3(uv, float(layer)));
+#endif
+    if(color.a < alphaCutout) {
+        color = vec4(0.0);
+    }
+}
@@ -0,0 +1,37 @@
+uniform mat4 modelMatrix;
+uniform vec3 boundingCenter;
+uniform float boundingRadius;
+uniform vec4 frustumPlane0;
+uniform vec4 frustumPlane1;
+uniform vec4 frustumPlane2;
+uniform vec4 frustumPlane3;
+uniform vec4 frustumPlane4;
+uniform vec4 frustumPlane5;
+
+in mat4 instance_transformation;
+
+out mat4 transformation;
+out float visible;
+
+bool outside(vec4 plane, vec3 center, float radius)
+{
+    return dot(plane.xyz, center) + plane.w < -radius * length(plane.xyz);
+}
+
+void main()
+{
+    transformation = instance_transformation;
+    visible = 0.0;
+    // Disabled instances have an all zero transformation
+    if (instance_transformation[3][3] != 0.0) {
+        mat4 world = modelMatrix * instance_transformation;
+        vec3 center = (world * vec4(boundingCenter, 1.0)).xyz;
+        float scale = max(length(world[0].xyz), max(length(world[1].xyz), length(world[2].xyz)));
+        float radius = boundingRadius * scale;
+        if (!outside(frustumPlane0, center, radius) && !outside(frustumPlane1, center, radius)
+            && !outside(frustumPlane2, center, radius) && !outside(frustumPlane3, center, radius)
+            && !outside(frustumPlane4, center, radius) && !outside(frustumPlane5, center, radius)) {
+            visible = 1.0;
+        }
+    }
+}
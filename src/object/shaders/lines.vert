@@ -0,0 +1,50 @@
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform vec2 resolution;
+uniform float lineWidth;
+uniform float capLength;
+
+// x is 0 at the start and 1 at the end of the line, y is -1 and 1 at either side of the line
+in vec2 quad_position;
+in vec3 start;
+in vec3 end;
+
+void main()
+{
+    vec4 clipStart = camera.viewProjection * vec4(start, 1.0);
+    vec4 clipEnd = camera.viewProjection * vec4(end, 1.0);
+
+    // Clip the line against the near plane, otherwise the projection of an end point behind the camera is flipped
+    float startDistance = clipStart.z + clipStart.w;
+    float endDistance = clipEnd.z + clipEnd.w;
+    if (startDistance < 0.0 && endDistance < 0.0) {
+        gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+        return;
+    }
+    if (startDistance < 0.0) {
+        clipStart = mix(clipStart, clipEnd, startDistance / (startDistance - endDistance));
+    }
+    if (endDistance < 0.0) {
+        clipEnd = mix(clipEnd, clipStart, endDistance / (endDistance - startDistance));
+    }
+
+    // Expand the line in screen space (pixels) to get the same width regardless of the distance to the camera
+    vec2 screenStart = 0.5 * resolution * clipStart.xy / clipStart.w;
+    vec2 screenEnd = 0.5 * resolution * clipEnd.xy / clipEnd.w;
+    vec2 direction = screenEnd - screenStart;
+    float screenLength = length(direction);
+    // A line which is (nearly) parallel to the view direction is projected to (almost) a point, so any direction can be used
+    direction = screenLength > 0.0001 ? direction / screenLength : vec2(1.0, 0.0);
+    vec2 normal = vec2(-direction.y, direction.x);
+
+    vec4 clipPosition = mix(clipStart, clipEnd, quad_position.x);
+    vec2 offset = 0.5 * lineWidth * quad_position.y * normal + capLength * (2.0 * quad_position.x - 1.0) * direction;
+    gl_Position = clipPosition + vec4(2.0 * offset * clipPosition.w / resolution, 0.0, 0.0);
+}
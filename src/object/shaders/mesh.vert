@@ -11,15 +11,63 @@ uniform mat4 modelMatrix;
 in vec3 position;
 
 #ifdef INSTANCED
-in vec4 row1;
-in vec4 row2;
-in vec4 row3;
+in mat4 instance_transformation;
+#ifdef USE_INSTANCE_ID
+flat out int instanceId;
+#endif
+#endif
+
+#ifdef SKINNED
+layout (std140) uniform Bones
+{
+    mat4 boneMatrices[MAX_BONES];
+};
+in vec4 joint_indices;
+in vec4 joint_weights;
+#endif
+
+#ifdef MORPH
+layout (std140) uniform MorphWeights
+{
+    vec4 morphWeights;
+};
+#if MORPH_TARGETS > 0
+in vec3 position_delta0;
+#ifdef MORPH_NORMALS
+in vec3 normal_delta0;
+#endif
+#endif
+#if MORPH_TARGETS > 1
+in vec3 position_delta1;
+#ifdef MORPH_NORMALS
+in vec3 normal_delta1;
+#endif
+#endif
+#if MORPH_TARGETS > 2
+in vec3 position_delta2;
+#ifdef MORPH_NORMALS
+in vec3 normal_delta2;
+#endif
+#endif
+#if MORPH_TARGETS > 3
+in vec3 position_delta3;
+#ifdef MORPH_NORMALS
+in vec3 normal_delta3;
+#endif
+#endif
 #endif
 
 #ifdef USE_POSITIONS
 out vec3 pos;
 #endif
 
+#ifdef USE_MOTION
+uniform mat4 previousModelMatrix;
+uniform mat4 previousViewProjection;
+out vec4 currentClipPosition;
+out vec4 previousClipPosition;
+#endif
+
 #ifdef USE_NORMALS 
 uniform mat4 normalMatrix;
 in vec3 normal;
@@ -40,22 +88,74 @@ void main()
 {
     mat4 local2World = modelMatrix;
 #ifdef INSTANCED
-    mat4 transform;
-    transform[0] = vec4(row1.x, row2.x, row3.x, 0.0);
-    transform[1] = vec4(row1.y, row2.y, row3.y, 0.0);
-    transform[2] = vec4(row1.z, row2.z, row3.z, 0.0);
-    transform[3] = vec4(row1.w, row2.w, row3.w, 1.0);
+    mat4 transform = instance_transformation;
     local2World *= transform;
+#ifdef USE_INSTANCE_ID
+    instanceId = gl_InstanceID;
+#endif
 #endif
-    vec4 worldPosition = local2World * vec4(position, 1.);
+#ifdef SKINNED
+    mat4 skin = joint_weights.x * boneMatrices[int(joint_indices.x)]
+        + joint_weights.y * boneMatrices[int(joint_indices.y)]
+        + joint_weights.z * boneMatrices[int(joint_indices.z)]
+        + joint_weights.w * boneMatrices[int(joint_indices.w)];
+    local2World *= skin;
+#endif
+    vec3 morphedPosition = position;
+#ifdef MORPH
+#if MORPH_TARGETS > 0
+    morphedPosition += morphWeights.x * position_delta0;
+#endif
+#if MORPH_TARGETS > 1
+    morphedPosition += morphWeights.y * position_delta1;
+#endif
+#if MORPH_TARGETS > 2
+    morphedPosition += morphWeights.z * position_delta2;
+#endif
+#if MORPH_TARGETS > 3
+    morphedPosition += morphWeights.w * position_delta3;
+#endif
+#endif
+    vec4 worldPosition = local2World * vec4(morphedPosition, 1.);
     gl_Position = camera.viewProjection * worldPosition;
 
+#ifdef USE_MOTION
+    mat4 previousLocal2World = previousModelMatrix;
+#ifdef INSTANCED
+    previousLocal2World *= transform;
+#endif
+#ifdef SKINNED
+    previousLocal2World *= skin;
+#endif
+    currentClipPosition = gl_Position;
+    previousClipPosition = previousViewProjection * previousLocal2World * vec4(morphedPosition, 1.);
+#endif
+
 #ifdef USE_POSITIONS
     pos = worldPosition.xyz;
 #endif
 
 #ifdef USE_NORMALS 
-    nor = mat3(normalMatrix) * normal;
+    vec3 morphedNormal = normal;
+#ifdef MORPH_NORMALS
+#if MORPH_TARGETS > 0
+    morphedNormal += morphWeights.x * normal_delta0;
+#endif
+#if MORPH_TARGETS > 1
+    morphedNormal += morphWeights.y * normal_delta1;
+#endif
+#if MORPH_TARGETS > 2
+    morphedNormal += morphWeights.z * normal_delta2;
+#endif
+#if MORPH_TARGETS > 3
+    morphedNormal += morphWeights.w * normal_delta3;
+#endif
+#endif
+#ifdef SKINNED
+    nor = mat3(normalMatrix) * mat3(skin) * morphedNormal;
+#else
+    nor = mat3(normalMatrix) * morphedNormal;
+#endif
 #endif
 
 #ifdef USE_UVS 
@@ -0,0 +1,10 @@
+flat in int instanceId;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The instance index plus one is encoded in the 24 bits of the red, green and blue channels, so zero means no instance
+    int id = instanceId + 1;
+    outColor = vec4(float(id & 255), float((id >> 8) & 255), float((id >> 16) & 255), 255.0) / 255.0;
+}
@@ -0,0 +1,11 @@
+
+in vec4 currentClipPosition;
+in vec4 previousClipPosition;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec2 motion = 0.5 * (currentClipPosition.xy / currentClipPosition.w - previousClipPosition.xy / previousClipPosition.w);
+    outColor = vec4(motion, 0.0, 1.0);
+}
@@ -1,5 +1,6 @@
 
 uniform sampler2D tex;
+uniform int isSrgb;
 
 in vec2 uvs;
 
@@ -8,5 +9,6 @@ layout (location = 0) out vec4 outColor;
 void main()
 {
     vec4 col = texture(tex, vec2(uvs.x, 1.0 - uvs.y));
-    outColor = vec4(srgb_from_rgb(col.rgb), col.a);
+    // An sRGB texture is decoded to linear color space when sampled, so it is encoded again, while linear data is written as is
+    outColor = isSrgb == 1 ? vec4(srgb_from_rgb(col.rgb), col.a) : col;
 }
\ No newline at end of file
@@ -0,0 +1,16 @@
+
+in vec4 col;
+in float pointSize;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The distance in pixels from the edge of the circle, positive inside
+    float distance = (1.0 - length(2.0 * gl_PointCoord - 1.0)) * 0.5 * (pointSize + 1.0) - 0.5;
+    float coverage = clamp(distance + 0.5, 0.0, 1.0);
+    if (coverage <= 0.0) {
+        discard;
+    }
+    outColor = vec4(srgb_from_rgb(col.rgb), col.a * coverage);
+}
@@ -0,0 +1,34 @@
+
+layout (std140) uniform Camera
+{
+    mat4 viewProjection;
+    mat4 view;
+    mat4 projection;
+    vec3 position;
+    float padding;
+} camera;
+
+uniform mat4 modelMatrix;
+uniform int worldSize;
+uniform float viewportHeight;
+
+in vec3 position;
+in vec4 color;
+in float size;
+
+out vec4 col;
+out float pointSize;
+
+void main()
+{
+    col = vec4(rgb_from_srgb(color.rgb / 255.0), color.a / 255.0);
+    gl_Position = camera.viewProjection * modelMatrix * vec4(position, 1.0);
+    if (worldSize == 1) {
+        // Scale the size in world units by the perspective divide to get the size in pixels
+        pointSize = size * camera.projection[1][1] * 0.5 * viewportHeight / gl_Position.w;
+    } else {
+        pointSize = size;
+    }
+    // Add a pixel for the antialiased edge
+    gl_PointSize = pointSize + 1.0;
+}
@@ -0,0 +1,27 @@
+
+uniform samplerCube reflectionMap;
+uniform vec3 eyePosition;
+uniform vec3 probePosition;
+uniform int parallaxCorrection;
+uniform vec3 probeMin;
+uniform vec3 probeMax;
+
+in vec3 pos;
+in vec3 nor;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    vec3 direction = reflect(normalize(pos - eyePosition), normalize(nor));
+    if (parallaxCorrection == 1) {
+        // Intersect the reflected ray with the probe box and sample in the direction from the probe to the intersection
+        vec3 maxPlanes = (probeMax - pos) / direction;
+        vec3 minPlanes = (probeMin - pos) / direction;
+        vec3 furthest = max(maxPlanes, minPlanes);
+        float t = min(min(furthest.x, furthest.y), furthest.z);
+        direction = pos + direction * t - probePosition;
+    }
+    // The captured colors are already in gamma color space
+    outColor = vec4(texture(reflectionMap, direction).rgb, 1.0);
+}
@@ -0,0 +1,19 @@
+
+uniform vec4 color;
+uniform float lineWidth;
+
+in vec3 bary;
+
+layout (location = 0) out vec4 outColor;
+
+void main()
+{
+    // The width of the line in barycentric coordinates is found using the screen space derivatives, which makes the width constant in pixels
+    vec3 d = fwidth(bary);
+    vec3 a = smoothstep(d * (0.5 * lineWidth - 0.5), d * (0.5 * lineWidth + 0.5), bary);
+    float edge = 1.0 - min(min(a.x, a.y), a.z);
+    if (edge < 0.01) {
+        discard;
+    }
+    outColor = vec4(srgb_from_rgb(color.rgb), color.a * edge);
+}
@@ -0,0 +1,296 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::definition::*;
+use crate::math::*;
+use crate::object::mesh::*;
+use crate::object::*;
+use crate::shading::*;
+
+///
+/// A shader program used for rendering a [SkinnedMesh](SkinnedMesh). It has a fixed vertex shader and
+/// customizable fragment shader for custom lighting. Use this in combination with [render](SkinnedMesh::render).
+///
+pub struct SkinnedMeshProgram {
+    mesh_program: MeshProgram,
+}
+
+impl SkinnedMeshProgram {
+    ///
+    /// Constructs a new shader program for rendering skinned meshes. The fragment shader can use the fragments position by adding `in vec3 pos;`,
+    /// its normal by `in vec3 nor;`, its uv coordinates by `in vec2 uvs;` and its per vertex color by `in vec4 col;` to the shader source code.
+    ///
+    pub fn new(context: &Context, fragment_shader_source: &str) -> Result<Self, Error> {
+        Ok(Self {
+            mesh_program: MeshProgram::new_internal(
+                context,
+                fragment_shader_source,
+                &format!(
+                    "#define SKINNED\n#define MAX_BONES {}\n",
+                    SkinnedMesh::MAX_BONES
+                ),
+            )?,
+        })
+    }
+}
+
+impl std::ops::Deref for SkinnedMeshProgram {
+    type Target = Program;
+
+    fn deref(&self) -> &Program {
+        &self.mesh_program
+    }
+}
+
+///
+/// Similar to [Mesh](crate::Mesh), except that each vertex is deformed by a weighted sum of (up to) four bone (joint) transformations
+/// before the mesh transformation is applied, also known as linear blend skinning. This is used for animating characters.
+/// The joints which influence each vertex and their weights are given by [CPUMesh::joint_indices] and [CPUMesh::joint_weights]
+/// and the bone transformations are updated each frame using [set_bone_matrices](Self::set_bone_matrices).
+///
+/// At most [SkinnedMesh::MAX_BONES] bones are supported, since the bone matrices are stored in a uniform buffer.
+/// Normals are transformed by the same bone transformations as the positions, so the bone transformations should not contain a non-uniform scale.
+///
+pub struct SkinnedMesh {
+    context: Context,
+    buffers: MeshBuffers,
+    joint_index_buffer: VertexBuffer,
+    joint_weight_buffer: VertexBuffer,
+    bone_buffer: UniformBuffer,
+    aabb: AxisAlignedBoundingBox,
+    pub name: String,
+    ///
+    /// The [CullType](crate::CullType) applied in all render calls, default is [CullType::None](crate::CullType::None).
+    ///
+    pub cull: CullType,
+    pub transformation: Mat4,
+    pub material: Material,
+    pub lighting_model: LightingModel,
+    ///
+    /// The render layers this geometry belongs to, see [Geometry::layer_mask](crate::Geometry::layer_mask). The default is all layers.
+    ///
+    pub layer_mask: u32,
+}
+
+impl SkinnedMesh {
+    /// The maximum number of bones of a skinned mesh.
+    pub const MAX_BONES: usize = 64;
+
+    ///
+    /// Copies the per vertex data defined in the given [CPUMesh](crate::CPUMesh) to the GPU, thereby
+    /// making it possible to render the mesh. All bone matrices are initialized to the identity.
+    ///
+    /// # Errors
+    /// Will return an error if the mesh does not have joint indices and weights or if a joint index is not less than [SkinnedMesh::MAX_BONES].
+    ///
+    pub fn new(context: &Context, cpu_mesh: &CPUMesh) -> Result<Self, Error> {
+        cpu_mesh.validate()?;
+        cpu_mesh.validate_triangles()?;
+        let (joint_indices, joint_weights) = match (
+            cpu_mesh.joint_indices.as_ref(),
+            cpu_mesh.joint_weights.as_ref(),
+        ) {
+            (Some(joint_indices), Some(joint_weights)) => (joint_indices, joint_weights),
+            _ => Err(Error::MeshError {
+                message: format!(
+                    "The mesh '{}' needs joint indices and weights to be skinned.",
+                    cpu_mesh.name
+                ),
+            })?,
+        };
+        if let Some(index) = joint_indices
+            .iter()
+            .find(|i| **i as usize >= Self::MAX_BONES)
+        {
+            Err(Error::MeshError {
+                message: format!(
+                    "The joint index {} of the mesh '{}' exceeds the maximum number of bones ({}).",
+                    index,
+                    cpu_mesh.name,
+                    Self::MAX_BONES
+                ),
+            })?;
+        }
+
+        let buffers = MeshBuffers::new(context, cpu_mesh)?;
+        let joint_index_buffer = VertexBuffer::new_with_static(
+            context,
+            &joint_indices.iter().map(|i| *i as f32).collect::<Vec<_>>(),
+        )?;
+        let joint_weight_buffer = VertexBuffer::new_with_static(context, joint_weights)?;
+        let mut mesh = Self {
+            context: context.clone(),
+            buffers,
+            joint_index_buffer,
+            joint_weight_buffer,
+            bone_buffer: UniformBuffer::new(context, &[16 * Self::MAX_BONES as u32])?,
+            aabb: cpu_mesh.compute_aabb(),
+            name: cpu_mesh.name.clone(),
+            cull: CullType::None,
+            transformation: Mat4::identity(),
+            material: Material::default(),
+            lighting_model: LightingModel::Blinn,
+            layer_mask: u32::MAX,
+        };
+        mesh.set_bone_matrices(&vec![Mat4::identity(); Self::MAX_BONES])?;
+        unsafe {
+            MESH_COUNT += 1;
+        }
+        Ok(mesh)
+    }
+
+    ///
+    /// Sets the transformation of each bone, where the bone with index `i` in [CPUMesh::joint_indices] uses the i'th matrix.
+    /// Each matrix should transform from the bind pose of the mesh to the posed mesh in the local space of the mesh,
+    /// ie. be the world transformation of the joint multiplied by the inverse bind matrix of the joint.
+    /// Call this each frame to animate the mesh. Only the first [SkinnedMesh::MAX_BONES] matrices are used,
+    /// any additional matrices are ignored and bones without a matrix keep their previous transformation.
+    ///
+    pub fn set_bone_matrices(&mut self, bone_matrices: &[Mat4]) -> Result<(), Error> {
+        let mut data = self.bone_buffer.get(0)?.to_vec();
+        for (i, bone) in bone_matrices.iter().take(Self::MAX_BONES).enumerate() {
+            let m: &[f32; 16] = bone.as_ref();
+            data[i * 16..(i + 1) * 16].copy_from_slice(m);
+        }
+        self.bone_buffer.update(0, &data)
+    }
+
+    ///
+    /// Render the skinned mesh with the given color. The color is assumed to be in gamma color space (sRGBA).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_with_color(
+        &self,
+        color: &Vec4,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let program = self.get_or_insert_program(&format!(
+            "{}{}",
+            include_str!("../core/shared.frag"),
+            include_str!("shaders/mesh_color.frag")
+        ))?;
+        program.use_uniform_vec4("color", color)?;
+        self.render(program, render_states, viewport, camera)
+    }
+
+    ///
+    /// Render the skinned mesh with the given [SkinnedMeshProgram](SkinnedMeshProgram).
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    /// # Errors
+    /// Will return an error if the shader program requires a certain attribute and the mesh does not have that attribute.
+    ///
+    pub fn render(
+        &self,
+        program: &SkinnedMeshProgram,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        program.use_uniform_mat4("modelMatrix", &self.transformation)?;
+        program.use_uniform_block(camera.uniform_buffer(), "Camera")?;
+        program.use_uniform_block(&self.bone_buffer, "Bones")?;
+
+        self.buffers
+            .use_attributes(&program.mesh_program, &self.transformation)?;
+        program.use_attribute_vec4(&self.joint_index_buffer, "joint_indices")?;
+        program.use_attribute_vec4(&self.joint_weight_buffer, "joint_weights")?;
+        self.buffers
+            .draw(program, render_states, self.cull, viewport)
+    }
+
+    pub(crate) fn get_or_insert_program(
+        &self,
+        fragment_shader_source: &str,
+    ) -> Result<&SkinnedMeshProgram, Error> {
+        unsafe {
+            get_or_insert_cached_program(
+                std::ptr::addr_of_mut!(PROGRAMS),
+                &self.context,
+                fragment_shader_source,
+                || SkinnedMeshProgram::new(&self.context, fragment_shader_source),
+            )
+        }
+    }
+}
+
+impl Geometry for SkinnedMesh {
+    fn render_depth_to_red(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        max_depth: f32,
+    ) -> Result<(), Error> {
+        let program = self.get_or_insert_program(include_str!("shaders/mesh_pick.frag"))?;
+        program.use_uniform_float("maxDistance", &max_depth)?;
+        self.render(program, render_states, viewport, camera)
+    }
+
+    fn depth_program_key(&self) -> usize {
+        crate::object::depth_program_key::<SkinnedMeshProgram>(&self.context, "void main() {}")
+    }
+
+    fn render_depth(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        let program = self.get_or_insert_program("void main() {}")?;
+        self.render(program, render_states, viewport, camera)
+    }
+
+    fn render_with_program(
+        &self,
+        program: &Program,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        self.buffers
+            .use_uniforms_and_attributes(program, &self.transformation, Some(camera))?;
+        if program.requires_uniform_block("Bones") {
+            program.use_uniform_block(&self.bone_buffer, "Bones")?;
+        }
+        if program.requires_attribute("joint_indices") {
+            program.use_attribute_vec4(&self.joint_index_buffer, "joint_indices")?;
+        }
+        if program.requires_attribute("joint_weights") {
+            program.use_attribute_vec4(&self.joint_weight_buffer, "joint_weights")?;
+        }
+
+        self.buffers
+            .draw(program, render_states, self.cull, viewport)
+    }
+
+    fn layer_mask(&self) -> u32 {
+        self.layer_mask
+    }
+
+    ///
+    /// Returns the axis aligned bounding box of the mesh in the bind pose, which might not enclose the posed mesh.
+    ///
+    fn aabb(&self) -> Option<AxisAlignedBoundingBox> {
+        let mut aabb = self.aabb;
+        aabb.transform(&self.transformation);
+        Some(aabb)
+    }
+}
+
+impl Drop for SkinnedMesh {
+    fn drop(&mut self) {
+        unsafe {
+            MESH_COUNT -= 1;
+            if MESH_COUNT == 0 {
+                PROGRAMS = None;
+            }
+        }
+    }
+}
+
+static mut MESH_COUNT: u32 = 0;
+static mut PROGRAMS: ProgramCache<SkinnedMeshProgram> = None;
@@ -51,14 +51,29 @@ impl Skybox {
     /// for example in the callback function of [Screen::write](crate::Screen::write).
     ///
     pub fn render(&self, viewport: Viewport, camera: &Camera) -> Result<(), Error> {
+        self.render_with_depth_test(DepthTestType::LessOrEqual, viewport, camera)
+    }
+
+    ///
+    /// Render the skybox with the given depth test instead of the default [DepthTestType::LessOrEqual](crate::DepthTestType::LessOrEqual),
+    /// for example [DepthTestType::Always](crate::DepthTestType::Always) to render the skybox on top of everything else.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    pub fn render_with_depth_test(
+        &self,
+        depth_test: DepthTestType,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
         let render_states = RenderStates {
-            depth_test: DepthTestType::LessOrEqual,
+            depth_test,
             ..Default::default()
         };
 
         self.program.use_texture_cube(&self.texture, "texture0")?;
         self.program
-            .use_uniform_block(camera.uniform_buffer(), "Camera");
+            .use_uniform_block(camera.uniform_buffer(), "Camera")?;
 
         self.program
             .use_attribute_vec3(&self.vertex_buffer, "position")?;
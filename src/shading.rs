@@ -18,6 +18,8 @@ mod instanced_mesh;
 #[doc(inline)]
 pub use instanced_mesh::*;
 
+mod lod;
+
 use crate::camera::*;
 use crate::core::*;
 use crate::light::*;
@@ -51,8 +53,100 @@ pub trait ShadedGeometry: Geometry {
         spot_lights: &[&SpotLight],
         point_lights: &[&PointLight],
     ) -> Result<(), Error>;
+
+    ///
+    /// Render the object shaded with the given ambient light and [collection of lights](crate::Lights) using physically based rendering (PBR).
+    /// The same shader program is used regardless of the number of lights in the collection.
+    /// Must be called in a render target render function,
+    /// for example in the callback function of [Screen::write](crate::Screen::write).
+    ///
+    fn render_with_lights(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        ambient_light: Option<&AmbientLight>,
+        lights: &Lights,
+    ) -> Result<(), Error>;
 }
 
+// Implements ShadedGeometry for a mesh type which has a material and a lighting model and which, like Mesh, has a get_or_insert_program method
+// returning a program for the given fragment shader and a render method rendering with that program.
+macro_rules! impl_shaded_geometry {
+    ($mesh:ty) => {
+        impl ShadedGeometry for $mesh {
+            fn geometry_pass(
+                &self,
+                render_states: RenderStates,
+                viewport: Viewport,
+                camera: &Camera,
+            ) -> Result<(), Error> {
+                let fragment_shader_source = geometry_fragment_shader(&self.material);
+                let program = self.get_or_insert_program(&fragment_shader_source)?;
+                self.material.bind(program)?;
+                self.render(program, render_states, viewport, camera)
+            }
+
+            fn render_with_lighting(
+                &self,
+                render_states: RenderStates,
+                viewport: Viewport,
+                camera: &Camera,
+                ambient_light: Option<&AmbientLight>,
+                directional_lights: &[&DirectionalLight],
+                spot_lights: &[&SpotLight],
+                point_lights: &[&PointLight],
+            ) -> Result<(), Error> {
+                let fragment_shader_source = shaded_fragment_shader(
+                    self.lighting_model,
+                    Some(&self.material),
+                    uses_ambient_occlusion(ambient_light),
+                    directional_lights,
+                    spot_lights.len(),
+                    point_lights.len(),
+                );
+                let program = self.get_or_insert_program(&fragment_shader_source)?;
+
+                bind_lights(
+                    program,
+                    ambient_light,
+                    directional_lights,
+                    spot_lights,
+                    point_lights,
+                    camera.position(),
+                    viewport,
+                )?;
+                self.material.bind(program)?;
+                self.render(program, render_states, viewport, camera)?;
+                Ok(())
+            }
+
+            fn render_with_lights(
+                &self,
+                render_states: RenderStates,
+                viewport: Viewport,
+                camera: &Camera,
+                ambient_light: Option<&AmbientLight>,
+                lights: &Lights,
+            ) -> Result<(), Error> {
+                let fragment_shader_source = lights_fragment_shader(
+                    self.lighting_model,
+                    &self.material,
+                    uses_ambient_occlusion(ambient_light),
+                    lights,
+                );
+                let program = self.get_or_insert_program(&fragment_shader_source)?;
+                bind_light_collection(program, ambient_light, lights, camera.position(), viewport)?;
+                self.material.bind(program)?;
+                self.render(program, render_states, viewport, camera)
+            }
+        }
+    };
+}
+
+impl_shaded_geometry!(SkinnedMesh);
+impl_shaded_geometry!(MorphMesh);
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum LightingModel {
     Phong,
@@ -83,21 +177,29 @@ fn geometry_fragment_shader(material: &Material) -> String {
 fn shaded_fragment_shader(
     lighting_model: LightingModel,
     material: Option<&Material>,
-    directional_lights: usize,
+    ambient_occlusion: bool,
+    directional_lights: &[&DirectionalLight],
     spot_lights: usize,
     point_lights: usize,
 ) -> String {
     let mut dir_uniform = String::new();
     let mut dir_fun = String::new();
-    for i in 0..directional_lights {
+    for (i, light) in directional_lights.iter().enumerate() {
         dir_uniform.push_str(&format!(
             "
-                uniform sampler2D directionalShadowMap{};
+                uniform {} directionalShadowMap{};
                 layout (std140) uniform DirectionalLightUniform{}
                 {{
                     DirectionalLight directionalLight{};
                 }};",
-            i, i, i
+            if light.hardware_pcf() {
+                "sampler2DShadow"
+            } else {
+                "sampler2D"
+            },
+            i,
+            i,
+            i
         ));
         dir_fun.push_str(&format!("
                     color += calculate_directional_light(directionalLight{}, surface_color, position, normal, metallic, roughness, directionalShadowMap{});", i, i));
@@ -138,31 +240,21 @@ fn shaded_fragment_shader(
         ));
     }
 
-    let model = match lighting_model {
-        LightingModel::Phong => "#define PHONG",
-        LightingModel::Blinn => "#define BLINN",
-        LightingModel::Cook(normal, _) => match normal {
-            NormalDistributionFunction::Blinn => "#define COOK\n#define COOK_BLINN\n",
-            NormalDistributionFunction::Beckmann => "#define COOK\n#define COOK_BECKMANN\n",
-            NormalDistributionFunction::TrowbridgeReitzGGX => "#define COOK\n#define COOK_GGX\n",
-        },
-    };
-
     format!(
-        "{}\n{}\n{}\n{}\n{}\n{}",
-        model,
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        lighting_model_shader(lighting_model),
         include_str!("core/shared.frag"),
         include_str!("shading/shaders/light_shared.frag"),
+        ambient_shader(ambient_occlusion),
         &format!(
             "
-                uniform vec3 ambientColor;
                 {} // Directional lights
                 {} // Spot lights
                 {} // Point lights
 
                 vec3 calculate_lighting(vec3 surface_color, vec3 position, vec3 normal, float metallic, float roughness)
                 {{
-                    vec3 color = ambientColor * mix(surface_color, vec3(0.0), metallic); // Ambient light
+                    vec3 color = calculate_ambient_light(surface_color, metallic);
                     {} // Directional lights
                     {} // Spot lights
                     {} // Point lights
@@ -176,6 +268,56 @@ fn shaded_fragment_shader(
     )
 }
 
+fn lights_fragment_shader(
+    lighting_model: LightingModel,
+    material: &Material,
+    ambient_occlusion: bool,
+    lights: &Lights,
+) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        lighting_model_shader(lighting_model),
+        include_str!("core/shared.frag"),
+        include_str!("shading/shaders/light_shared.frag"),
+        ambient_shader(ambient_occlusion),
+        lights.fragment_shader_source(),
+        "
+                vec3 calculate_lighting(vec3 surface_color, vec3 position, vec3 normal, float metallic, float roughness)
+                {
+                    vec3 color = calculate_ambient_light(surface_color, metallic);
+                    color += calculate_lights(surface_color, position, normal, metallic, roughness);
+                    return color;
+                }
+                ",
+        material_shader(material),
+        include_str!("shading/shaders/lighting.frag"),
+    )
+}
+
+fn ambient_shader(ambient_occlusion: bool) -> String {
+    format!(
+        "{}{}",
+        if ambient_occlusion {
+            "#define USE_AMBIENT_OCCLUSION\n"
+        } else {
+            ""
+        },
+        include_str!("shading/shaders/ambient.frag")
+    )
+}
+
+fn lighting_model_shader(lighting_model: LightingModel) -> &'static str {
+    match lighting_model {
+        LightingModel::Phong => "#define PHONG",
+        LightingModel::Blinn => "#define BLINN",
+        LightingModel::Cook(normal, _) => match normal {
+            NormalDistributionFunction::Blinn => "#define COOK\n#define COOK_BLINN\n",
+            NormalDistributionFunction::Beckmann => "#define COOK\n#define COOK_BECKMANN\n",
+            NormalDistributionFunction::TrowbridgeReitzGGX => "#define COOK\n#define COOK_GGX\n",
+        },
+    }
+}
+
 fn material_shader(material: &Material) -> &'static str {
     match material.color_source {
         ColorSource::Color(_) => "in vec3 pos;\nin vec3 nor;\n",
@@ -192,14 +334,10 @@ fn bind_lights(
     spot_lights: &[&SpotLight],
     point_lights: &[&PointLight],
     camera_position: &Vec3,
+    viewport: Viewport,
 ) -> Result<(), Error> {
     // Ambient light
-    program.use_uniform_vec3(
-        "ambientColor",
-        &ambient_light
-            .map(|light| light.color * light.intensity)
-            .unwrap_or(vec3(0.0, 0.0, 0.0)),
-    )?;
+    bind_ambient_light(program, ambient_light, viewport)?;
 
     if !directional_lights.is_empty() || !spot_lights.is_empty() || !point_lights.is_empty() {
         program.use_uniform_vec3("eyePosition", camera_position)?;
@@ -214,18 +352,62 @@ fn bind_lights(
         program.use_uniform_block(
             directional_lights[i].buffer(),
             &format!("DirectionalLightUniform{}", i),
-        );
+        )?;
     }
 
     // Spot light
     for i in 0..spot_lights.len() {
         program.use_texture(spot_lights[i].shadow_map(), &format!("spotShadowMap{}", i))?;
-        program.use_uniform_block(spot_lights[i].buffer(), &format!("SpotLightUniform{}", i));
+        program.use_uniform_block(spot_lights[i].buffer(), &format!("SpotLightUniform{}", i))?;
     }
 
     // Point light
     for i in 0..point_lights.len() {
-        program.use_uniform_block(point_lights[i].buffer(), &format!("PointLightUniform{}", i));
+        program.use_uniform_block(point_lights[i].buffer(), &format!("PointLightUniform{}", i))?;
     }
     Ok(())
 }
+
+fn bind_light_collection(
+    program: &Program,
+    ambient_light: Option<&AmbientLight>,
+    lights: &Lights,
+    camera_position: &Vec3,
+    viewport: Viewport,
+) -> Result<(), Error> {
+    bind_ambient_light(program, ambient_light, viewport)?;
+    program.use_uniform_vec3("eyePosition", camera_position)?;
+    lights.bind(program)
+}
+
+fn bind_ambient_light(
+    program: &Program,
+    ambient_light: Option<&AmbientLight>,
+    viewport: Viewport,
+) -> Result<(), Error> {
+    program.use_uniform_vec3(
+        "ambientColor",
+        &ambient_light
+            .map(|light| light.color * light.intensity)
+            .unwrap_or(vec3(0.0, 0.0, 0.0)),
+    )?;
+    if let Some(texture) = ambient_light.and_then(|light| light.occlusion_texture()) {
+        program.use_texture(texture.as_ref(), "ambientOcclusionMap")?;
+        program.use_uniform_vec4(
+            "ambientOcclusionViewport",
+            &vec4(
+                viewport.x as f32,
+                viewport.y as f32,
+                viewport.width as f32,
+                viewport.height as f32,
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+fn uses_ambient_occlusion(ambient_light: Option<&AmbientLight>) -> bool {
+    ambient_light
+        .map(|light| light.occlusion_texture().is_some())
+        .unwrap_or(false)
+}
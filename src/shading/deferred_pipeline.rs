@@ -160,7 +160,7 @@ impl DeferredPipeline {
             }
             self.debug_effect.as_ref().unwrap().use_uniform_mat4(
                 "viewProjectionInverse",
-                &(camera.projection() * camera.view()).invert().unwrap(),
+                &camera.view_projection().invert().unwrap(),
             )?;
             self.debug_effect
                 .as_ref()
@@ -184,7 +184,8 @@ impl DeferredPipeline {
         let fragment_shader = shaded_fragment_shader(
             self.lighting_model,
             None,
-            directional_lights.len(),
+            uses_ambient_occlusion(ambient_light),
+            directional_lights,
             spot_lights.len(),
             point_lights.len(),
         );
@@ -203,6 +204,7 @@ impl DeferredPipeline {
             spot_lights,
             point_lights,
             camera.position(),
+            viewport,
         )?;
 
         effect.use_texture_array(self.geometry_pass_texture(), "gbuffer")?;
@@ -210,7 +212,7 @@ impl DeferredPipeline {
         if !directional_lights.is_empty() || !spot_lights.is_empty() || !point_lights.is_empty() {
             effect.use_uniform_mat4(
                 "viewProjectionInverse",
-                &(camera.projection() * camera.view()).invert().unwrap(),
+                &camera.view_projection().invert().unwrap(),
             )?;
         }
         effect.apply(render_states, viewport)?;
@@ -31,7 +31,8 @@ impl ShadedGeometry for InstancedMesh {
         let fragment_shader_source = shaded_fragment_shader(
             self.lighting_model,
             Some(&self.material),
-            directional_lights.len(),
+            uses_ambient_occlusion(ambient_light),
+            directional_lights,
             spot_lights.len(),
             point_lights.len(),
         );
@@ -44,9 +45,30 @@ impl ShadedGeometry for InstancedMesh {
             spot_lights,
             point_lights,
             camera.position(),
+            viewport,
         )?;
         self.material.bind(program)?;
         self.render(program, render_states, viewport, camera)?;
         Ok(())
     }
+
+    fn render_with_lights(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        ambient_light: Option<&AmbientLight>,
+        lights: &Lights,
+    ) -> Result<(), Error> {
+        let fragment_shader_source = lights_fragment_shader(
+            self.lighting_model,
+            &self.material,
+            uses_ambient_occlusion(ambient_light),
+            lights,
+        );
+        let program = self.get_or_insert_program(&fragment_shader_source)?;
+        bind_light_collection(program, ambient_light, lights, camera.position(), viewport)?;
+        self.material.bind(program)?;
+        self.render(program, render_states, viewport, camera)
+    }
 }
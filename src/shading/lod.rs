@@ -0,0 +1,58 @@
+use crate::camera::*;
+use crate::core::*;
+use crate::light::*;
+use crate::math::*;
+use crate::object::*;
+use crate::shading::*;
+
+impl ShadedGeometry for Lod {
+    fn geometry_pass(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.geometry_pass(render_states, viewport, camera)?;
+        }
+        Ok(())
+    }
+
+    fn render_with_lighting(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        ambient_light: Option<&AmbientLight>,
+        directional_lights: &[&DirectionalLight],
+        spot_lights: &[&SpotLight],
+        point_lights: &[&PointLight],
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.render_with_lighting(
+                render_states,
+                viewport,
+                camera,
+                ambient_light,
+                directional_lights,
+                spot_lights,
+                point_lights,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn render_with_lights(
+        &self,
+        render_states: RenderStates,
+        viewport: Viewport,
+        camera: &Camera,
+        ambient_light: Option<&AmbientLight>,
+        lights: &Lights,
+    ) -> Result<(), Error> {
+        if let Some(mesh) = self.select(camera) {
+            mesh.render_with_lights(render_states, viewport, camera, ambient_light, lights)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+
+uniform vec3 ambientColor;
+
+#ifdef USE_AMBIENT_OCCLUSION
+uniform sampler2D ambientOcclusionMap;
+uniform vec4 ambientOcclusionViewport;
+#endif
+
+vec3 calculate_ambient_light(vec3 surface_color, float metallic)
+{
+    vec3 color = ambientColor * mix(surface_color, vec3(0.0), metallic);
+#ifdef USE_AMBIENT_OCCLUSION
+    vec2 screen_uv = (gl_FragCoord.xy - ambientOcclusionViewport.xy) / ambientOcclusionViewport.zw;
+    color *= texture(ambientOcclusionMap, screen_uv).r;
+#endif
+    return color;
+}
@@ -42,6 +42,16 @@ struct SpotLight
     mat4 shadowMVP;
 };
 
+struct HemisphereLight
+{
+    vec3 skyColor;
+    float intensity;
+    vec3 groundColor;
+    float padding;
+    vec3 up;
+    float padding2;
+};
+
 #define PI 3.1415926
 
 // handy value clamping to 0 - 1 range
@@ -221,6 +231,33 @@ float calculate_shadow(sampler2D shadowMap, mat4 shadowMVP, vec3 position)
     return visibility * 0.25;
 }
 
+float calculate_shadow(sampler2DShadow shadowMap, mat4 shadowMVP, vec3 position)
+{
+    if(shadowMVP[3][3] < 0.1) // Shadow disabled
+    {
+        return 1.0;
+    }
+    vec4 shadow_coord = shadowMVP * vec4(position, 1.);
+    vec2 uv = shadow_coord.xy/shadow_coord.w;
+    if(uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0)
+    {
+        return 1.0;
+    }
+    shadow_coord.z -= 0.005;
+    return textureProj(shadowMap, shadow_coord);
+}
+
+vec3 calculate_directional_light(DirectionalLight directionalLight, vec3 surface_color, vec3 position, vec3 normal,
+    float metallic, float roughness, sampler2DShadow shadowMap)
+{
+    vec3 light_color = directionalLight.base.intensity * directionalLight.base.color;
+    vec3 light = calculate_light(light_color, -directionalLight.direction, surface_color, position, normal, metallic, roughness);
+    if(directionalLight.shadowEnabled > 0.5) {
+        light *= calculate_shadow(shadowMap, directionalLight.shadowMVP, position);
+    }
+    return light;
+}
+
 vec3 calculate_directional_light(DirectionalLight directionalLight, vec3 surface_color, vec3 position, vec3 normal,
     float metallic, float roughness, sampler2D shadowMap)
 {
@@ -257,4 +294,11 @@ vec3 calculate_spot_light(SpotLight spotLight, vec3 surface_color, vec3 position
         }
     }
     return light;
-}
\ No newline at end of file
+}
+
+vec3 calculate_hemisphere_light(HemisphereLight hemisphereLight, vec3 surface_color, vec3 normal, float metallic)
+{
+    float sky_weight = 0.5 + 0.5 * dot(normal, hemisphereLight.up);
+    vec3 light_color = hemisphereLight.intensity * mix(hemisphereLight.groundColor, hemisphereLight.skyColor, sky_weight);
+    return light_color * mix(surface_color, vec3(0.0), metallic);
+}
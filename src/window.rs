@@ -15,6 +15,23 @@ mod glutin_window;
 #[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
 pub use glutin_window::*;
 
+#[cfg(all(feature = "glutin-window", not(target_arch = "wasm32")))]
+mod headless;
+
+#[cfg(all(
+    feature = "glutin-window",
+    feature = "image-io",
+    not(target_arch = "wasm32")
+))]
+mod render_compare;
+#[doc(inline)]
+#[cfg(all(
+    feature = "glutin-window",
+    feature = "image-io",
+    not(target_arch = "wasm32")
+))]
+pub use render_compare::*;
+
 #[cfg(all(feature = "canvas", target_arch = "wasm32"))]
 mod canvas;
 #[doc(inline)]
@@ -1,6 +1,6 @@
 use crate::frame::*;
 use crate::math::*;
-use crate::window::WindowSettings;
+use crate::window::{SwapInterval, WindowSettings};
 use crate::Context;
 use glutin::event::{Event, WindowEvent};
 use glutin::event_loop::{ControlFlow, EventLoop};
@@ -93,7 +93,7 @@ impl Window {
 
         Ok(ContextBuilder::new()
             .with_multisampling(settings.multisamples as u16)
-            .with_vsync(settings.vsync)
+            .with_vsync(settings.swap_interval != SwapInterval::Immediate)
             .build_windowed(window_builder, event_loop)?)
     }
 
@@ -0,0 +1,119 @@
+use crate::core::Error;
+use crate::Context;
+use glutin::event_loop::EventLoop;
+use glutin::*;
+use std::rc::Rc;
+
+struct HeadlessState {
+    _context: glutin::Context<PossiblyCurrent>,
+    _event_loop: Option<EventLoop<()>>,
+}
+
+impl Context {
+    ///
+    /// Constructs an offscreen graphics context without a window, for example for rendering thumbnails on a server or for visual tests on a CI machine.
+    /// Render into a [ColorTargetTexture2D](crate::ColorTargetTexture2D) or [RenderTarget](crate::RenderTarget) of the given size
+    /// and get the pixels using for example [ColorTargetTexture2D::read](crate::ColorTargetTexture2D::read).
+    /// The context is current on the calling thread and can be created from any thread, for example from a test.
+    ///
+    /// The context is created by [glutin](https://crates.io/crates/glutin), which uses a pbuffer (EGL, WGL or CGL) if available
+    /// and otherwise a surfaceless context (EGL on Linux). On Linux, this requires a connection to a display server,
+    /// so if neither `DISPLAY` nor `WAYLAND_DISPLAY` is set, an OSMesa software context is created instead, which requires the OSMesa library.
+    /// When backed by a pbuffer, the default framebuffer used by the [Screen](crate::Screen) maps to the offscreen buffer of the given size,
+    /// but since that is not guaranteed on all platforms, rendering into a texture is recommended.
+    ///
+    /// # Errors
+    /// Returns [Error::ContextCreationError] if no headless context could be created on this machine.
+    ///
+    /// # Feature
+    /// Only available when the `glutin-window` feature is enabled. Not available on web.
+    ///
+    pub fn new_headless(width: u32, height: u32) -> Result<Context, Error> {
+        let size = dpi::PhysicalSize::new(width, height);
+        let (context, event_loop) = match new_event_loop() {
+            Some(event_loop) => (
+                ContextBuilder::new().build_headless(&event_loop, size),
+                Some(event_loop),
+            ),
+            None => (build_without_event_loop(size), None),
+        };
+        let context = context.map_err(|e| Error::ContextCreationError {
+            message: format!("Failed to create a headless context: {}", e),
+        })?;
+        let context = unsafe {
+            context
+                .make_current()
+                .map_err(|(_, e)| Error::ContextCreationError {
+                    message: format!("Failed to make the headless context current: {}", e),
+                })?
+        };
+        let gl = Context::load_with(|s| context.get_proc_address(s) as *const std::os::raw::c_void);
+        Ok(gl.with_owner(Rc::new(HeadlessState {
+            _context: context,
+            _event_loop: event_loop,
+        })))
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn new_event_loop() -> Option<EventLoop<()>> {
+    use glutin::platform::unix::EventLoopExtUnix;
+    if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        return None;
+    }
+    // Creating the event loop panics if the display server cannot be reached.
+    std::panic::catch_unwind(EventLoop::new_any_thread).ok()
+}
+
+#[cfg(target_os = "windows")]
+fn new_event_loop() -> Option<EventLoop<()>> {
+    use glutin::platform::windows::EventLoopExtWindows;
+    Some(EventLoop::new_any_thread())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "windows",
+)))]
+fn new_event_loop() -> Option<EventLoop<()>> {
+    Some(EventLoop::new())
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn build_without_event_loop(
+    size: dpi::PhysicalSize<u32>,
+) -> Result<glutin::Context<NotCurrent>, CreationError> {
+    use glutin::platform::unix::HeadlessContextExt;
+    ContextBuilder::new().build_osmesa(size)
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+)))]
+fn build_without_event_loop(
+    _size: dpi::PhysicalSize<u32>,
+) -> Result<glutin::Context<NotCurrent>, CreationError> {
+    Err(CreationError::NotSupported(
+        "a headless context requires an event loop on this platform".to_string(),
+    ))
+}
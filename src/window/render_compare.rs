@@ -0,0 +1,175 @@
+use crate::core::*;
+use crate::io::*;
+use crate::math::*;
+use std::path::{Path, PathBuf};
+
+///
+/// The maximum allowed difference for each color channel when comparing a rendered image with a reference image,
+/// see [render_and_compare].
+///
+#[derive(Debug, Copy, Clone)]
+pub struct Tolerance {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Tolerance {
+    ///
+    /// The same tolerance for all color channels.
+    ///
+    pub const fn uniform(value: u8) -> Self {
+        Self {
+            red: value,
+            green: value,
+            blue: value,
+            alpha: value,
+        }
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::uniform(2)
+    }
+}
+
+///
+/// Error message from [render_and_compare].
+///
+#[derive(Debug)]
+pub enum RenderCompareError {
+    /// Failed to render the image.
+    Render(Error),
+    /// Failed to load the reference image or save an image.
+    IO(IOError),
+    /// The reference image does not have the same size as the rendered image.
+    WrongSize {
+        /// The width and height of the reference image.
+        reference: (u32, u32),
+        /// The width and height of the rendered image.
+        rendered: (u32, u32),
+    },
+    /// The rendered image differs from the reference image by more than the tolerance.
+    Mismatch {
+        /// The number of pixels with a difference larger than the tolerance.
+        differing_pixels: usize,
+        /// The path of the saved image showing the absolute difference between the rendered and reference image.
+        diff_path: PathBuf,
+    },
+}
+
+impl From<Error> for RenderCompareError {
+    fn from(other: Error) -> Self {
+        RenderCompareError::Render(other)
+    }
+}
+
+impl From<IOError> for RenderCompareError {
+    fn from(other: IOError) -> Self {
+        RenderCompareError::IO(other)
+    }
+}
+
+///
+/// Renders whatever is rendered in the `draw` closure into an offscreen image of the given size using the given context
+/// and compares the result with the reference png image at the given path.
+/// The context is typically a [headless context](Context::new_headless) shared by all comparisons in a test,
+/// since creating a context is expensive.
+/// The image is cleared to black with a depth of 1 before the `draw` closure is called.
+///
+/// If the reference image does not exist, the rendered image is saved as the reference and the comparison succeeds,
+/// so the first run of a test creates the reference image.
+/// If any channel of any pixel differs by more than the given tolerance, an image with the absolute difference is saved next to
+/// the reference image with the `.diff.png` extension and a [Mismatch](RenderCompareError::Mismatch) error is returned.
+///
+/// # Feature
+/// Only available when the `glutin-window` and `image-io` features are enabled.
+///
+pub fn render_and_compare<P: AsRef<Path>, F: FnOnce(&Context, Viewport) -> Result<(), Error>>(
+    context: &Context,
+    width: u32,
+    height: u32,
+    reference_png: P,
+    tolerance: Tolerance,
+    draw: F,
+) -> Result<(), RenderCompareError> {
+    let viewport = Viewport::new_at_origo(width, height);
+    let color_texture = ColorTargetTexture2D::<u8>::new(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )?;
+    let depth_texture = DepthTargetTexture2D::new(
+        context,
+        width,
+        height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )?;
+    RenderTarget::new(context, &color_texture, &depth_texture)?
+        .write(ClearState::color_and_depth(0.0, 0.0, 0.0, 1.0, 1.0), || {
+            draw(context, viewport)
+        })?;
+    let pixels = color_texture.read(viewport)?;
+
+    let reference_png = reference_png.as_ref();
+    if !reference_png.exists() {
+        Saver::save_pixels(reference_png, &pixels, width, height)?;
+        return Ok(());
+    }
+    let reference = image::open(reference_png)
+        .map_err(|e| RenderCompareError::IO(IOError::Image(e)))?
+        .to_rgba8();
+    if reference.dimensions() != (width, height) {
+        Err(RenderCompareError::WrongSize {
+            reference: reference.dimensions(),
+            rendered: (width, height),
+        })?;
+    }
+
+    // The pixels are read from the bottom row and up, while the image is stored from the top row and down.
+    let tolerances = [
+        tolerance.red,
+        tolerance.green,
+        tolerance.blue,
+        tolerance.alpha,
+    ];
+    let mut diff = vec![0u8; pixels.len()];
+    let mut differing_pixels = 0;
+    for row in 0..height {
+        for col in 0..width {
+            let index = 4 * (row * width + col) as usize;
+            let reference_pixel = reference.get_pixel(col, height - row - 1);
+            let mut differs = false;
+            for channel in 0..4 {
+                let difference = pixels[index + channel].max(reference_pixel[channel])
+                    - pixels[index + channel].min(reference_pixel[channel]);
+                differs |= difference > tolerances[channel];
+                diff[index + channel] = difference;
+            }
+            diff[index + 3] = 255;
+            if differs {
+                differing_pixels += 1;
+            }
+        }
+    }
+
+    if differing_pixels > 0 {
+        let diff_path = reference_png.with_extension("diff.png");
+        Saver::save_pixels(&diff_path, &diff, width, height)?;
+        Err(RenderCompareError::Mismatch {
+            differing_pixels,
+            diff_path,
+        })?;
+    }
+    Ok(())
+}
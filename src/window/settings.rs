@@ -1,3 +1,32 @@
+///
+/// How the swapping of the front and back buffer is synchronized with the refresh rate of the display, see [WindowSettings::swap_interval].
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SwapInterval {
+    /// The buffers are swapped immediately without waiting for the display (swap interval 0), which is useful for benchmarking.
+    Immediate,
+    /// The buffers are swapped when the display refreshes (swap interval 1).
+    VSync,
+    /// The buffers are swapped when the display refreshes, unless the frame is late in which case they are swapped immediately (swap interval -1).
+    ///
+    /// Adaptive sync requires driver support for the `EXT_swap_control_tear` extension which the default desktop window does not expose,
+    /// so it currently falls back to [SwapInterval::VSync].
+    Adaptive,
+}
+
+impl SwapInterval {
+    ///
+    /// The swap interval as the integer used by the platform APIs, ie. 0 for immediate, 1 for vsync and -1 for adaptive sync.
+    ///
+    pub fn interval(&self) -> i32 {
+        match self {
+            SwapInterval::Immediate => 0,
+            SwapInterval::VSync => 1,
+            SwapInterval::Adaptive => -1,
+        }
+    }
+}
+
 ///
 /// Window settings.
 ///
@@ -11,10 +40,11 @@ pub struct WindowSettings {
     pub min_size: (u32, u32),
     /// The maximum size of the window (width, height). If None is specified, the window is maximized.
     pub max_size: Option<(u32, u32)>,
-    /// Whether VSync is enabled.
+    /// How the frames are synchronized with the display, for example [SwapInterval::Immediate] to turn off VSync
+    /// for consistent frame time measurements.
     ///
-    /// On web this has no effect since VSync is always on.
-    pub vsync: bool,
+    /// On web this has no effect since the browser always synchronizes with the display.
+    pub swap_interval: SwapInterval,
     /// Number of antialiasing samples.
     ///
     /// On web, this can only be off (0) or on (>0).
@@ -27,7 +57,7 @@ impl Default for WindowSettings {
             title: "".to_string(),
             min_size: (2, 2),
             max_size: None,
-            vsync: true,
+            swap_interval: SwapInterval::VSync,
             multisamples: 4,
         }
     }
@@ -0,0 +1,38 @@
+mod common;
+
+use three_d::*;
+
+#[test]
+fn taa_removes_the_jitter_when_rendering_fails() {
+    let context = match common::context(4, 4) {
+        Some(context) => context,
+        None => return,
+    };
+    let mut camera = Camera::new_perspective(
+        &context,
+        vec3(0.0, 0.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(45.0),
+        1.0,
+        0.1,
+        10.0,
+    )
+    .unwrap();
+    let mut renderer = AntiAliasingRenderer::new(&context, AntiAliasing::Taa);
+    let mut jitter = None;
+    let result = renderer.render(
+        &mut camera,
+        Viewport::new_at_origo(4, 4),
+        ClearState::default(),
+        |camera| {
+            jitter = Some(camera.jitter());
+            Err(Error::MeshError {
+                message: "failed".to_string(),
+            })
+        },
+    );
+    assert!(result.is_err());
+    assert_ne!(jitter, Some(vec2(0.0, 0.0)));
+    assert_eq!(camera.jitter(), vec2(0.0, 0.0));
+}
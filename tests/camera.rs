@@ -0,0 +1,124 @@
+mod common;
+
+use three_d::*;
+
+fn camera(context: &Context) -> Camera {
+    Camera::new_perspective(
+        context,
+        vec3(0.0, 0.0, 5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        degrees(90.0),
+        1.0,
+        1.0,
+        10.0,
+    )
+    .unwrap()
+}
+
+#[test]
+fn view_is_cached_until_the_camera_changes() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let mut camera = camera(&context);
+    let first = *camera.view();
+    let second = camera.view();
+    assert_eq!(&first, second);
+    assert!(std::ptr::eq(camera.view(), camera.view()));
+    assert!(std::ptr::eq(camera.projection(), camera.projection()));
+    assert!(std::ptr::eq(
+        camera.view_projection(),
+        camera.view_projection()
+    ));
+    assert_eq!(
+        *camera.view_projection(),
+        camera.projection() * camera.view()
+    );
+
+    camera
+        .set_view(
+            vec3(5.0, 0.0, 0.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        )
+        .unwrap();
+    assert_ne!(*camera.view(), first);
+    assert_eq!(
+        *camera.view_projection(),
+        camera.projection() * camera.view()
+    );
+}
+
+fn orthographic_camera(context: &Context) -> Camera {
+    // Sees everything from -1 to 1 in x and y and from -2 to 2 in z
+    Camera::new_orthographic(
+        context,
+        vec3(0.0, 0.0, 2.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        2.0,
+        2.0,
+        4.0,
+    )
+    .unwrap()
+}
+
+fn point_aabb(point: Vec3) -> AxisAlignedBoundingBox {
+    AxisAlignedBoundingBox::new_with_positions(&[point.x, point.y, point.z])
+}
+
+#[test]
+fn point_and_sphere_tests_agree_with_in_frustum_on_boundaries() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let camera = orthographic_camera(&context);
+    let inside = [
+        vec3(0.0, 0.0, 0.0),
+        vec3(1.0, 0.0, 0.0),
+        vec3(-1.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, -1.0, 0.0),
+        vec3(0.0, 0.0, 2.0),
+        vec3(0.0, 0.0, -2.0),
+        vec3(1.0, 1.0, 2.0),
+        vec3(-1.0, -1.0, -2.0),
+    ];
+    let outside = [
+        vec3(1.5, 0.0, 0.0),
+        vec3(0.0, -1.5, 0.0),
+        vec3(0.0, 0.0, 3.0),
+        vec3(0.0, 0.0, -3.0),
+    ];
+    for point in inside.iter() {
+        assert!(camera.contains_point(*point), "{:?}", point);
+        assert!(camera.in_frustum(&point_aabb(*point)), "{:?}", point);
+        assert!(camera.sphere_in_frustum(*point, 0.0), "{:?}", point);
+    }
+    for point in outside.iter() {
+        assert!(!camera.contains_point(*point), "{:?}", point);
+        assert!(!camera.in_frustum(&point_aabb(*point)), "{:?}", point);
+        assert!(!camera.sphere_in_frustum(*point, 0.0), "{:?}", point);
+    }
+}
+
+#[test]
+fn sphere_touching_the_frustum_is_inside() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let camera = orthographic_camera(&context);
+    assert!(camera.sphere_in_frustum(vec3(2.0, 0.0, 0.0), 1.0));
+    assert!(camera.sphere_in_frustum(vec3(0.0, 0.0, -4.0), 2.0));
+    assert!(!camera.sphere_in_frustum(vec3(2.5, 0.0, 0.0), 1.0));
+    assert!(!camera.sphere_in_frustum(vec3(0.0, 0.0, 5.0), 2.0));
+    assert!(
+        camera.in_frustum(&AxisAlignedBoundingBox::new_with_positions(&[
+            1.0, 0.0, 0.0, 3.0, 0.0, 0.0
+        ]))
+    );
+}
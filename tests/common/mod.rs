@@ -0,0 +1,137 @@
+#![allow(dead_code)]
+
+use three_d::*;
+
+///
+/// Creates a headless context of the given size or returns `None` if this machine cannot create one,
+/// in which case the calling test should return early.
+/// If the `THREE_D_REQUIRE_GL` environment variable is set, for example on CI, the test fails instead,
+/// so a test run on a machine without a graphics context cannot pass without testing anything.
+///
+pub fn context(width: u32, height: u32) -> Option<Context> {
+    match Context::new_headless(width, height) {
+        Ok(context) => Some(context),
+        Err(e) if std::env::var_os("THREE_D_REQUIRE_GL").is_some() => {
+            panic!("No headless context available: {:?}", e)
+        }
+        Err(e) => {
+            eprintln!(
+                "Skipping test, no headless context available (set THREE_D_REQUIRE_GL to fail instead): {:?}",
+                e
+            );
+            None
+        }
+    }
+}
+
+///
+/// Creates an RGBA color texture and a depth texture of the given size to render into.
+///
+pub fn targets(
+    context: &Context,
+    width: u32,
+    height: u32,
+) -> (ColorTargetTexture2D<u8>, DepthTargetTexture2D) {
+    let color_texture = ColorTargetTexture2D::<u8>::new(
+        context,
+        width,
+        height,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+    .unwrap();
+    let depth_texture = DepthTargetTexture2D::new(
+        context,
+        width,
+        height,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )
+    .unwrap();
+    (color_texture, depth_texture)
+}
+
+///
+/// Returns the RGBA value of the pixel at the given position, where (0, 0) is the bottom left corner.
+///
+pub fn pixel(pixels: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+    let index = 4 * (y * width + x) as usize;
+    [
+        pixels[index],
+        pixels[index + 1],
+        pixels[index + 2],
+        pixels[index + 3],
+    ]
+}
+
+///
+/// Renders whatever is rendered in the `draw` closure into an RGBA texture of the given size, which is cleared to transparent black,
+/// and returns the pixels from the bottom row and up.
+///
+pub fn render(
+    context: &Context,
+    width: u32,
+    height: u32,
+    draw: impl FnOnce(Viewport) -> Result<(), Error>,
+) -> Vec<u8> {
+    let (color_texture, depth_texture) = targets(context, width, height);
+    let viewport = Viewport::new_at_origo(width, height);
+    RenderTarget::new(context, &color_texture, &depth_texture)
+        .unwrap()
+        .write(ClearState::color_and_depth(0.0, 0.0, 0.0, 0.0, 1.0), || {
+            draw(viewport)
+        })
+        .unwrap();
+    color_texture.read(viewport).unwrap()
+}
+
+///
+/// Creates an RGBA texture with nearest interpolation and the given data and wrapping.
+///
+pub fn texture(
+    context: &Context,
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+    wrap: Wrapping,
+) -> Texture2D {
+    Texture2D::new(
+        context,
+        &CPUTexture {
+            data,
+            width,
+            height,
+            format: Format::RGBA,
+            min_filter: Interpolation::Nearest,
+            mag_filter: Interpolation::Nearest,
+            mip_map_filter: None,
+            wrap_s: wrap,
+            wrap_t: wrap,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+///
+/// Creates a full screen effect which outputs the texture `tex` sampled at the texture coordinates given by the GLSL expression,
+/// which can use the uv coordinates of the viewport `uv`.
+///
+pub fn sample_effect(context: &Context, uv_expression: &str) -> ImageEffect {
+    ImageEffect::new(
+        context,
+        &format!(
+            "uniform sampler2D tex;
+            in vec2 uv;
+            layout (location = 0) out vec4 color;
+            void main() {{ color = texture(tex, {}); }}",
+            uv_expression
+        ),
+    )
+    .unwrap()
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use three_d::*;
+
+fn covered_pixels(context: &Context, cull: CullType) -> usize {
+    // A clockwise triangle, which is back facing with the default counter clockwise front face
+    let mut mesh = Mesh::new(
+        context,
+        &CPUMesh {
+            positions: vec![-1.0, -1.0, 0.0, -1.0, 1.0, 0.0, 1.0, -1.0, 0.0],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    mesh.cull = cull;
+    let pixels = common::render(context, 4, 4, |viewport| {
+        mesh.render_ndc_with_color(&vec4(1.0, 1.0, 1.0, 1.0), RenderStates::default(), viewport)
+    });
+    pixels.chunks(4).filter(|p| p[0] > 0).count()
+}
+
+#[test]
+fn cull_none_renders_back_facing_triangle() {
+    let context = match common::context(4, 4) {
+        Some(context) => context,
+        None => return,
+    };
+    let covered = covered_pixels(&context, CullType::None);
+    assert!(covered >= 6);
+    assert_eq!(covered_pixels(&context, CullType::Front), covered);
+    assert_eq!(covered_pixels(&context, CullType::Back), 0);
+    assert_eq!(covered_pixels(&context, CullType::FrontAndBack), 0);
+}
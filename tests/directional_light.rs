@@ -0,0 +1,48 @@
+mod common;
+
+use three_d::*;
+
+#[test]
+fn shadow_maps_for_several_lights_are_distinct() {
+    let context = match common::context(8, 8) {
+        Some(context) => context,
+        None => return,
+    };
+    // A square in the xy plane, which covers the center of the shadow map of a light shining along the z axis
+    // and is seen edge on by a light shining along the y axis
+    let mesh = Mesh::new(&context, &CPUMesh::square(1.0)).unwrap();
+    let mut along_z =
+        DirectionalLight::new(&context, 1.0, &vec3(1.0, 1.0, 1.0), &vec3(0.0, 0.0, -1.0)).unwrap();
+    let mut along_y =
+        DirectionalLight::new(&context, 1.0, &vec3(1.0, 1.0, 1.0), &vec3(0.0, -1.0, 0.0)).unwrap();
+    DirectionalLight::generate_shadow_maps_for(
+        &mut [&mut along_z, &mut along_y],
+        &ShadowFrustum {
+            target: vec3(0.0, 0.0, 0.0),
+            width: 2.0,
+            height: 2.0,
+            depth: 4.0,
+            texture_width: 8,
+            texture_height: 8,
+        },
+        &[&mesh],
+    )
+    .unwrap();
+
+    assert_ne!(
+        along_z.buffer().get(4).unwrap(),
+        along_y.buffer().get(4).unwrap()
+    );
+
+    let shadow_map = |light: &mut DirectionalLight| {
+        common::render(&context, 8, 8, |viewport| {
+            light.debug_render_shadow_map(viewport)
+        })
+    };
+    let along_z_pixels = shadow_map(&mut along_z);
+    let along_y_pixels = shadow_map(&mut along_y);
+    // The center is covered by the square for one light and empty (far away) for the other
+    assert!(common::pixel(&along_z_pixels, 8, 4, 4)[0] < 255);
+    assert_eq!(common::pixel(&along_y_pixels, 8, 4, 4)[0], 255);
+    assert_eq!(common::pixel(&along_z_pixels, 8, 0, 0)[0], 255);
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use three_d::*;
+
+#[test]
+fn headless_context_renders_into_texture() {
+    let context = match common::context(4, 4) {
+        Some(context) => context,
+        None => return,
+    };
+    let (color_texture, depth_texture) = common::targets(&context, 4, 4);
+    RenderTarget::new(&context, &color_texture, &depth_texture)
+        .unwrap()
+        .write(ClearState::color_and_depth(1.0, 0.0, 0.0, 1.0, 1.0), || {
+            Ok(())
+        })
+        .unwrap();
+    let pixels = color_texture.read(Viewport::new_at_origo(4, 4)).unwrap();
+    assert_eq!(common::pixel(&pixels, 4, 2, 2), [255, 0, 0, 255]);
+}
+
+#[test]
+fn headless_context_from_another_thread() {
+    let result = std::thread::spawn(|| Context::new_headless(4, 4).map(|_| ()))
+        .join()
+        .expect("creating a headless context must not panic");
+    if let Err(e) = result {
+        assert!(
+            std::env::var_os("THREE_D_REQUIRE_GL").is_none(),
+            "No headless context available: {:?}",
+            e
+        );
+        eprintln!("Skipping test, no headless context available: {:?}", e);
+    }
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use three_d::*;
+
+#[test]
+fn memory_stats_count_created_and_dropped_resources() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let initial = context.memory_stats();
+    let texture_delta = |textures: u32, bytes: u64| {
+        let stats = context.memory_stats();
+        assert_eq!(stats.textures - initial.textures, textures);
+        assert_eq!(stats.texture_bytes - initial.texture_bytes, bytes);
+    };
+
+    let texture = common::texture(&context, 4, 4, vec![0; 64], Wrapping::ClampToEdge);
+    texture_delta(1, 64);
+    let color_target = ColorTargetTexture2D::<u8>::new(
+        &context,
+        4,
+        4,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+    .unwrap();
+    texture_delta(2, 128);
+    let depth_target = DepthTargetTexture2D::new(
+        &context,
+        4,
+        4,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth32F,
+    )
+    .unwrap();
+    texture_delta(3, 192);
+    let color_target_array = ColorTargetTexture2DArray::<u8>::new(
+        &context,
+        2,
+        2,
+        3,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+    .unwrap();
+    texture_delta(4, 240);
+    let depth_target_array = DepthTargetTexture2DArray::new(
+        &context,
+        2,
+        2,
+        3,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        DepthFormat::Depth16,
+    )
+    .unwrap();
+    texture_delta(5, 264);
+    let cube_map = TextureCubeMap::new_empty::<u8>(&context, 4, Format::RGBA, None).unwrap();
+    texture_delta(6, 648);
+
+    let element_buffer = ElementBuffer::new(&context, &[0u32, 1, 2]).unwrap();
+    let vertex_buffer = VertexBuffer::new_with_static(&context, &[0.0f32; 9]).unwrap();
+    let uniform_buffer = UniformBuffer::new(&context, &[4]).unwrap();
+    let stats = context.memory_stats();
+    assert_eq!(stats.element_buffers - initial.element_buffers, 1);
+    assert_eq!(
+        stats.element_buffer_bytes - initial.element_buffer_bytes,
+        12
+    );
+    assert_eq!(stats.vertex_buffers - initial.vertex_buffers, 1);
+    assert_eq!(stats.vertex_buffer_bytes - initial.vertex_buffer_bytes, 36);
+    assert_eq!(stats.uniform_buffers - initial.uniform_buffers, 1);
+    assert_eq!(
+        stats.uniform_buffer_bytes - initial.uniform_buffer_bytes,
+        16
+    );
+
+    drop(texture);
+    texture_delta(5, 584);
+    drop(color_target);
+    drop(depth_target);
+    drop(color_target_array);
+    drop(depth_target_array);
+    drop(cube_map);
+    drop(element_buffer);
+    drop(vertex_buffer);
+    drop(uniform_buffer);
+    assert_eq!(context.memory_stats(), initial);
+}
+
+#[test]
+fn memory_stats_count_the_bytes_of_the_internal_format() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let initial = context.memory_stats();
+    let half_float_target = ColorTargetTexture2D::<f32>::new(
+        &context,
+        4,
+        4,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::R16F,
+    )
+    .unwrap();
+    assert_eq!(
+        context.memory_stats().texture_bytes - initial.texture_bytes,
+        32
+    );
+    drop(half_float_target);
+    assert_eq!(context.memory_stats(), initial);
+}
+
+#[test]
+fn memory_stats_count_multisample_render_targets() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let initial = context.memory_stats();
+    let render_target =
+        RenderTargetMultisample::new::<u8>(&context, 4, 4, 4, Format::RGBA, DepthFormat::Depth32F)
+            .unwrap();
+    let stats = context.memory_stats();
+    assert_eq!(stats.textures - initial.textures, 2);
+    assert_eq!(stats.texture_bytes - initial.texture_bytes, 512);
+    drop(render_target);
+    assert_eq!(context.memory_stats(), initial);
+}
+
+#[test]
+fn refilling_an_element_buffer_updates_memory_stats() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let initial = context.memory_stats();
+    let mut buffer = ElementBuffer::new(&context, &[0u8, 1, 2]).unwrap();
+    assert_eq!(
+        context.memory_stats().element_buffer_bytes - initial.element_buffer_bytes,
+        3
+    );
+    buffer.fill_with(&[0u16, 1, 2, 2, 1, 3]);
+    assert_eq!(
+        context.memory_stats().element_buffer_bytes - initial.element_buffer_bytes,
+        12
+    );
+    drop(buffer);
+    assert_eq!(context.memory_stats(), initial);
+}
@@ -0,0 +1,304 @@
+mod common;
+
+use three_d::*;
+
+fn white_pixels(pixels: &[u8]) -> Vec<bool> {
+    pixels.chunks(4).map(|p| p[0] > 0).collect()
+}
+
+#[test]
+fn set_transformation_does_not_touch_the_vertex_buffers() {
+    let context = match common::context(4, 4) {
+        Some(context) => context,
+        None => return,
+    };
+    // Covers the left half of the viewport
+    let mut mesh = Mesh::new(
+        &context,
+        &CPUMesh {
+            positions: vec![
+                -1.0, -1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 1.0, 0.0, -1.0, 1.0, 0.0,
+            ],
+            indices: Some(Indices::U8(vec![0, 1, 2, 0, 2, 3])),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let render = |mesh: &Mesh| {
+        white_pixels(&common::render(&context, 4, 4, |viewport| {
+            mesh.render_ndc_with_color(&vec4(1.0, 1.0, 1.0, 1.0), RenderStates::default(), viewport)
+        }))
+    };
+    let left = (0..16).map(|i| i % 4 < 2).collect::<Vec<_>>();
+    let right = left.iter().map(|l| !l).collect::<Vec<_>>();
+    assert_eq!(render(&mesh), left);
+    let stats = context.memory_stats();
+
+    for frame in 0..10 {
+        let x = if frame % 2 == 0 { 1.0 } else { 0.0 };
+        mesh.set_transformation(Mat4::from_translation(vec3(x, 0.0, 0.0)));
+        assert_eq!(&render(&mesh), if x > 0.0 { &right } else { &left });
+    }
+    assert_eq!(context.memory_stats(), stats);
+}
+
+#[test]
+fn mirrored_mesh_is_not_culled() {
+    let context = match common::context(4, 4) {
+        Some(context) => context,
+        None => return,
+    };
+    // A counter clockwise, ie. front facing, square covering the left half of the viewport
+    let mut mesh = Mesh::new(
+        &context,
+        &CPUMesh {
+            positions: vec![
+                -1.0, -1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 1.0, 0.0, -1.0, 1.0, 0.0,
+            ],
+            indices: Some(Indices::U8(vec![0, 1, 2, 0, 2, 3])),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    mesh.cull = CullType::Back;
+    mesh.set_transformation(Mat4::from_nonuniform_scale(-1.0, 1.0, 1.0));
+    let camera = Camera::new_orthographic(
+        &context,
+        vec3(0.0, 0.0, 1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        2.0,
+        2.0,
+        2.0,
+    )
+    .unwrap();
+    let white = vec4(1.0, 1.0, 1.0, 1.0);
+    let right = (0..16).map(|i| i % 4 >= 2).collect::<Vec<_>>();
+
+    let pixels = common::render(&context, 4, 4, |viewport| {
+        mesh.render_ndc_with_color(&white, RenderStates::default(), viewport)
+    });
+    assert_eq!(white_pixels(&pixels), right);
+    let pixels = common::render(&context, 4, 4, |viewport| {
+        mesh.render_with_color(&white, RenderStates::default(), viewport, &camera)
+    });
+    assert_eq!(white_pixels(&pixels), right);
+
+    // Culling front faces of the mirrored mesh removes it
+    mesh.cull = CullType::Front;
+    let pixels = common::render(&context, 4, 4, |viewport| {
+        mesh.render_ndc_with_color(&white, RenderStates::default(), viewport)
+    });
+    assert!(white_pixels(&pixels).iter().all(|p| !p));
+}
+
+#[test]
+fn render_range_draws_the_first_half_of_the_indices() {
+    let context = match common::context(4, 4) {
+        Some(context) => context,
+        None => return,
+    };
+    // A square covering the viewport, where the first triangle is below the diagonal from the bottom left to the top right corner
+    let mesh = Mesh::new(
+        &context,
+        &CPUMesh {
+            positions: vec![
+                -1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0, -1.0, 1.0, 0.0,
+            ],
+            indices: Some(Indices::U8(vec![0, 1, 2, 0, 2, 3])),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let camera = Camera::new_orthographic(
+        &context,
+        vec3(0.0, 0.0, 1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        2.0,
+        2.0,
+        2.0,
+    )
+    .unwrap();
+    let program = MeshProgram::new(
+        &context,
+        "layout (location = 0) out vec4 outColor;
+        void main() { outColor = vec4(1.0); }",
+    )
+    .unwrap();
+
+    let pixels = common::render(&context, 4, 4, |viewport| {
+        mesh.render_range(&program, RenderStates::default(), viewport, &camera, 0, 3)
+    });
+    for y in 0..4 {
+        for x in 0..4 {
+            let white = common::pixel(&pixels, 4, x, y)[0] > 0;
+            // The pixels on the diagonal are on the shared edge of the two triangles
+            if x > y {
+                assert!(white, "pixel ({}, {}) should be drawn", x, y);
+            } else if x < y {
+                assert!(!white, "pixel ({}, {}) should not be drawn", x, y);
+            }
+        }
+    }
+
+    let pixels = common::render(&context, 4, 4, |viewport| {
+        mesh.render_range(&program, RenderStates::default(), viewport, &camera, 0, 6)
+    });
+    assert!(white_pixels(&pixels).iter().all(|p| *p));
+
+    let viewport = Viewport::new_at_origo(4, 4);
+    let states = RenderStates::default();
+    assert!(mesh
+        .render_range(&program, states, viewport, &camera, 3, 6)
+        .is_err());
+    let mesh = Mesh::new(
+        &context,
+        &CPUMesh {
+            positions: vec![-1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(mesh
+        .render_range(&program, states, viewport, &camera, 0, 3)
+        .is_err());
+}
+
+#[test]
+fn depth_program_key_depends_on_the_program_and_not_on_the_mesh() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let cpu_mesh = CPUMesh::square(1.0);
+    let mesh = Mesh::new(&context, &cpu_mesh).unwrap();
+    let key = mesh.depth_program_key();
+    assert_eq!(
+        Mesh::new(&context, &cpu_mesh).unwrap().depth_program_key(),
+        key
+    );
+
+    // The key is stable when the depth program is created
+    let camera = Camera::new_orthographic(
+        &context,
+        vec3(0.0, 0.0, 1.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        2.0,
+        2.0,
+        2.0,
+    )
+    .unwrap();
+    common::render(&context, 1, 1, |viewport| {
+        mesh.render_depth(RenderStates::default(), viewport, &camera)
+    });
+    assert_eq!(mesh.depth_program_key(), key);
+
+    let instanced_mesh = InstancedMesh::new(&context, &[Mat4::identity()], &cpu_mesh).unwrap();
+    assert_ne!(instanced_mesh.depth_program_key(), key);
+
+    let other_context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    assert_ne!(
+        Mesh::new(&other_context, &cpu_mesh)
+            .unwrap()
+            .depth_program_key(),
+        key
+    );
+}
+
+#[test]
+fn pick_instance_returns_the_original_index_with_gpu_culling() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let transformations = [
+        Mat4::from_translation(vec3(0.0, 0.0, 0.0)),
+        Mat4::from_translation(vec3(2.0, 0.0, 0.0)),
+        Mat4::from_translation(vec3(4.0, 0.0, 0.0)),
+    ];
+    let mut mesh = InstancedMesh::new(&context, &transformations, &CPUMesh::square(1.0)).unwrap();
+    mesh.set_gpu_culling(true).unwrap();
+    assert!(mesh.gpu_culling());
+    // Only sees the last instance, which is the first instance after culling
+    let camera = Camera::new_orthographic(
+        &context,
+        vec3(4.0, 0.0, 1.0),
+        vec3(4.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        2.0,
+        2.0,
+        2.0,
+    )
+    .unwrap();
+    assert_eq!(
+        mesh.pick_instance(&camera, (0.5, 0.5), 2.0).unwrap(),
+        Some(2)
+    );
+
+    mesh.set_instance_enabled(2, false).unwrap();
+    assert_eq!(mesh.pick_instance(&camera, (0.5, 0.5), 2.0).unwrap(), None);
+    mesh.set_gpu_culling(false).unwrap();
+    assert!(!mesh.gpu_culling());
+}
+
+#[test]
+fn instanced_mesh_aabb_covers_the_enabled_instances() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let transformations = [
+        Mat4::from_translation(vec3(-2.0, 0.0, 0.0)),
+        Mat4::from_translation(vec3(4.0, 0.0, 0.0)),
+    ];
+    let mut mesh = InstancedMesh::new(&context, &transformations, &CPUMesh::square(1.0)).unwrap();
+    let aabb = mesh.aabb().unwrap();
+    assert_eq!(*aabb.min(), vec3(-2.5, -0.5, 0.0));
+    assert_eq!(*aabb.max(), vec3(4.5, 0.5, 0.0));
+
+    mesh.transformation = Mat4::from_translation(vec3(0.0, 1.0, 0.0));
+    mesh.set_instance_enabled(1, false).unwrap();
+    let aabb = mesh.aabb().unwrap();
+    assert_eq!(*aabb.min(), vec3(-2.5, 0.5, 0.0));
+    assert_eq!(*aabb.max(), vec3(-1.5, 1.5, 0.0));
+
+    mesh.update_transformations(&[]);
+    assert!(mesh.aabb().is_none());
+}
+
+#[test]
+fn position_rotation_and_scale_are_read_back() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let close = |a: Vec3, b: Vec3| (a - b).magnitude() < 1e-5;
+    let mut mesh = Mesh::new(&context, &CPUMesh::square(1.0)).unwrap();
+    let position = vec3(1.0, -2.0, 3.0);
+    let rotation = Quat::from_axis_angle(vec3(0.0, 1.0, 0.0), degrees(30.0));
+    let scale = vec3(2.0, 0.5, 3.0);
+    mesh.set_position(position);
+    mesh.set_rotation(rotation);
+    mesh.set_scale(scale);
+    assert_eq!(mesh.position(), position);
+    assert_eq!(mesh.rotation(), rotation);
+    assert_eq!(mesh.scale(), scale);
+    let transformation = Mat4::from_translation(position)
+        * Mat4::from(rotation)
+        * Mat4::from_nonuniform_scale(scale.x, scale.y, scale.z);
+    assert_eq!(mesh.transformation(), transformation);
+
+    // Setting the transformation directly decomposes it again
+    mesh.set_transformation(Mat4::from_translation(vec3(0.0, 1.0, 0.0)) * transformation);
+    assert!(close(mesh.position(), position + vec3(0.0, 1.0, 0.0)));
+    assert!((mesh.rotation().dot(rotation).abs() - 1.0).abs() < 1e-5);
+    assert!(close(mesh.scale(), scale));
+    mesh.set_scale(vec3(1.0, 1.0, 1.0));
+    assert!(close(mesh.position(), position + vec3(0.0, 1.0, 0.0)));
+    assert!(close(mesh.scale(), vec3(1.0, 1.0, 1.0)));
+}
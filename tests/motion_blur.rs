@@ -0,0 +1,32 @@
+mod common;
+
+use three_d::*;
+
+#[test]
+fn zero_velocity_leaves_pixels_unchanged() {
+    let context = match common::context(4, 4) {
+        Some(context) => context,
+        None => return,
+    };
+    let mut colors = Vec::new();
+    for y in 0..4u8 {
+        for x in 0..4u8 {
+            colors.extend_from_slice(&[
+                60 * x,
+                60 * y,
+                if (x + y) % 2 == 0 { 255 } else { 0 },
+                255,
+            ]);
+        }
+    }
+    let color_texture = common::texture(&context, 4, 4, colors.clone(), Wrapping::ClampToEdge);
+    let motion_texture = common::texture(&context, 4, 4, vec![0; 64], Wrapping::ClampToEdge);
+    let mut effect = MotionBlurEffect::new(&context).unwrap();
+    effect.max_samples = 16;
+    effect.intensity = 10.0;
+
+    let pixels = common::render(&context, 4, 4, |viewport| {
+        effect.apply(viewport, &color_texture, &motion_texture)
+    });
+    assert_eq!(pixels, colors);
+}
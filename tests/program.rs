@@ -0,0 +1,99 @@
+mod common;
+
+use three_d::*;
+
+#[test]
+fn use_attribute_mat4_divisor_binds_one_matrix_per_instance() {
+    let context = match common::context(8, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    // Each instance fills half of the viewport and each pixel in that half outputs one column of the matrix of the instance
+    let program = Program::from_source(
+        &context,
+        "in vec3 position;
+        in mat4 matrix;
+        flat out vec4 columns[4];
+        void main() {
+            columns[0] = matrix[0];
+            columns[1] = matrix[1];
+            columns[2] = matrix[2];
+            columns[3] = matrix[3];
+            gl_Position = vec4(0.5 * position.x - 0.5 + float(gl_InstanceID), position.y, 0.0, 1.0);
+        }",
+        "flat in vec4 columns[4];
+        layout (location = 0) out vec4 color;
+        void main() {
+            color = columns[int(mod(gl_FragCoord.x, 4.0))];
+        }",
+    )
+    .unwrap();
+    let positions = VertexBuffer::new_with_static(
+        &context,
+        &[
+            -1.0f32, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 0.0, -1.0, 1.0, 0.0, -1.0,
+            -1.0, 0.0,
+        ],
+    )
+    .unwrap();
+    let values = (0..32).map(|i| i as f32 / 31.0).collect::<Vec<_>>();
+    let matrices = VertexBuffer::new_with_static(&context, &values).unwrap();
+
+    let pixels = common::render(&context, 8, 1, |viewport| {
+        program.use_attribute_vec3(&positions, "position")?;
+        program.use_attribute_mat4_divisor(&matrices, "matrix", 1)?;
+        program.draw_arrays_instanced(RenderStates::default(), CullType::None, viewport, 6, 2);
+        Ok(())
+    });
+    for (i, value) in values.iter().enumerate() {
+        let expected = (value * 255.0).round() as i32;
+        assert!(
+            (pixels[i] as i32 - expected).abs() <= 1,
+            "matrix value {} was read back as {}, expected {}",
+            i,
+            pixels[i],
+            expected
+        );
+    }
+}
+
+#[test]
+fn resources_from_another_context_return_context_mismatch() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let program = Program::from_source(
+        &context,
+        "in vec3 position;
+        void main() { gl_Position = vec4(position, 1.0); }",
+        "uniform sampler2D tex;
+        layout (location = 0) out vec4 color;
+        void main() { color = texture(tex, vec2(0.5)); }",
+    )
+    .unwrap();
+    let other = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let is_mismatch =
+        |result: Result<(), Error>| matches!(result, Err(Error::ContextMismatch { .. }));
+
+    let uniform_buffer = UniformBuffer::new(&other, &[4]).unwrap();
+    assert!(is_mismatch(
+        program.use_uniform_block(&uniform_buffer, "Data")
+    ));
+    let texture = common::texture(&other, 1, 1, vec![255; 4], Wrapping::ClampToEdge);
+    assert!(is_mismatch(program.use_texture(&texture, "tex")));
+    let vertex_buffer = VertexBuffer::new_with_static(&other, &[0.0; 9]).unwrap();
+    assert!(is_mismatch(
+        program.use_attribute_vec3(&vertex_buffer, "position")
+    ));
+    let element_buffer = ElementBuffer::new(&other, &[0u32, 1, 2]).unwrap();
+    assert!(is_mismatch(program.draw_elements(
+        RenderStates::default(),
+        CullType::None,
+        Viewport::new_at_origo(1, 1),
+        &element_buffer,
+    )));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use three_d::*;
+
+fn fill(color: Vec3) -> impl FnOnce(&Context, Viewport) -> Result<(), Error> {
+    move |context, viewport| {
+        let effect = ImageEffect::new(
+            context,
+            "uniform vec3 fill; layout (location = 0) out vec4 color; void main() { color = vec4(fill, 1.0); }",
+        )?;
+        effect.use_uniform_vec3("fill", &color)?;
+        effect.apply(RenderStates::default(), viewport)
+    }
+}
+
+#[test]
+fn render_and_compare_shares_the_context() {
+    let context = match common::context(8, 8) {
+        Some(context) => context,
+        None => return,
+    };
+    let directory =
+        std::env::temp_dir().join(format!("three-d-render-compare-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let reference = directory.join("red.png");
+    let _ = std::fs::remove_file(&reference);
+
+    render_and_compare(
+        &context,
+        8,
+        8,
+        &reference,
+        Tolerance::default(),
+        fill(vec3(1.0, 0.0, 0.0)),
+    )
+    .unwrap();
+    assert!(reference.exists());
+    render_and_compare(
+        &context,
+        8,
+        8,
+        &reference,
+        Tolerance::default(),
+        fill(vec3(1.0, 0.0, 0.0)),
+    )
+    .unwrap();
+    match render_and_compare(
+        &context,
+        8,
+        8,
+        &reference,
+        Tolerance::default(),
+        fill(vec3(0.0, 1.0, 0.0)),
+    ) {
+        Err(RenderCompareError::Mismatch {
+            differing_pixels, ..
+        }) => assert_eq!(differing_pixels, 64),
+        result => panic!("expected a mismatch, got {:?}", result),
+    }
+    std::fs::remove_dir_all(&directory).unwrap();
+}
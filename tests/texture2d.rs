@@ -0,0 +1,247 @@
+mod common;
+
+use three_d::*;
+
+const RED: [u8; 4] = [255, 0, 0, 255];
+const GREEN: [u8; 4] = [0, 255, 0, 255];
+
+fn row(pixels: &[u8], width: u32) -> Vec<[u8; 4]> {
+    (0..width)
+        .map(|x| common::pixel(pixels, width, x, 0))
+        .collect()
+}
+
+#[test]
+fn set_wrapping_takes_effect_on_next_draw() {
+    let context = match common::context(4, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let mut texture = common::texture(&context, 2, 1, [RED, GREEN].concat(), Wrapping::ClampToEdge);
+    // Samples the texture at u = 0.25, 0.75, 1.25 and 1.75
+    let effect = common::sample_effect(&context, "vec2(2.0 * uv.x, 0.5)");
+    let draw = |texture: &Texture2D| {
+        common::render(&context, 4, 1, |viewport| {
+            effect.use_texture(texture, "tex")?;
+            effect.apply(RenderStates::default(), viewport)
+        })
+    };
+
+    assert_eq!(row(&draw(&texture), 4), vec![RED, GREEN, GREEN, GREEN]);
+    texture.set_wrapping(Wrapping::Repeat, Wrapping::Repeat);
+    assert_eq!(row(&draw(&texture), 4), vec![RED, GREEN, RED, GREEN]);
+    texture.set_wrapping(Wrapping::ClampToEdge, Wrapping::ClampToEdge);
+    assert_eq!(row(&draw(&texture), 4), vec![RED, GREEN, GREEN, GREEN]);
+}
+
+#[test]
+fn set_interpolation_takes_effect_on_next_draw() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let mut texture = common::texture(&context, 2, 1, [RED, GREEN].concat(), Wrapping::ClampToEdge);
+    // Samples the texture exactly between the two texels
+    let effect = common::sample_effect(&context, "vec2(0.5, 0.5)");
+    let draw = |texture: &Texture2D| {
+        let pixels = common::render(&context, 1, 1, |viewport| {
+            effect.use_texture(texture, "tex")?;
+            effect.apply(RenderStates::default(), viewport)
+        });
+        common::pixel(&pixels, 1, 0, 0)
+    };
+
+    let nearest = draw(&texture);
+    assert!(nearest == RED || nearest == GREEN);
+    texture.set_interpolation(Interpolation::Linear, Interpolation::Linear);
+    let linear = draw(&texture);
+    assert!((linear[0] as i32 - 128).abs() <= 2 && (linear[1] as i32 - 128).abs() <= 2);
+}
+
+#[test]
+fn copy_to_copies_a_known_color() {
+    let context = match common::context(2, 2) {
+        Some(context) => context,
+        None => return,
+    };
+    let color = [10, 120, 200, 255];
+    let source = common::texture(&context, 2, 2, color.repeat(4), Wrapping::ClampToEdge);
+    let mut destination = common::texture(&context, 2, 2, RED.repeat(4), Wrapping::ClampToEdge);
+    source
+        .copy_to(&mut destination, Interpolation::Nearest)
+        .unwrap();
+
+    let effect = common::sample_effect(&context, "uv");
+    let pixels = common::render(&context, 2, 2, |viewport| {
+        effect.use_texture(&destination, "tex")?;
+        effect.apply(RenderStates::default(), viewport)
+    });
+    for y in 0..2 {
+        for x in 0..2 {
+            assert_eq!(common::pixel(&pixels, 2, x, y), color);
+        }
+    }
+}
+
+#[test]
+fn copy_to_scales_to_the_destination_size() {
+    let context = match common::context(4, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let source = common::texture(&context, 2, 1, [RED, GREEN].concat(), Wrapping::ClampToEdge);
+    let mut destination = common::texture(&context, 4, 1, vec![0; 16], Wrapping::ClampToEdge);
+    source
+        .copy_to(&mut destination, Interpolation::Nearest)
+        .unwrap();
+
+    let effect = common::sample_effect(&context, "uv");
+    let pixels = common::render(&context, 4, 1, |viewport| {
+        effect.use_texture(&destination, "tex")?;
+        effect.apply(RenderStates::default(), viewport)
+    });
+    assert_eq!(row(&pixels, 4), vec![RED, RED, GREEN, GREEN]);
+}
+
+#[test]
+fn copy_to_rejects_different_formats() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let source = common::texture(&context, 1, 1, RED.to_vec(), Wrapping::ClampToEdge);
+    let mut destination = Texture2D::new(
+        &context,
+        &CPUTexture {
+            data: vec![0u8; 3],
+            format: Format::RGB,
+            mip_map_filter: None,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(source
+        .copy_to(&mut destination, Interpolation::Nearest)
+        .is_err());
+}
+
+fn read_rgba_f32(
+    context: &Context,
+    width: u32,
+    draw: impl FnOnce(Viewport) -> Result<(), Error>,
+) -> Vec<f32> {
+    let texture = ColorTargetTexture2D::<f32>::new(
+        context,
+        width,
+        1,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+    .unwrap();
+    let viewport = Viewport::new_at_origo(width, 1);
+    texture
+        .write(ClearState::color(0.0, 0.0, 0.0, 0.0), || draw(viewport))
+        .unwrap();
+    texture.read(viewport).unwrap()
+}
+
+fn single_channel_texture<T: TextureDataType>(
+    context: &Context,
+    data: Vec<T>,
+    format: Format,
+) -> Texture2D {
+    Texture2D::new(
+        context,
+        &CPUTexture {
+            width: data.len() as u32,
+            height: 1,
+            data,
+            format,
+            min_filter: Interpolation::Nearest,
+            mag_filter: Interpolation::Nearest,
+            mip_map_filter: None,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn single_channel_data_is_uploaded_and_read_back() {
+    let context = match common::context(4, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let effect = common::sample_effect(&context, "uv");
+    let draw = |texture: &Texture2D| {
+        read_rgba_f32(&context, 4, |viewport| {
+            effect.use_texture(texture, "tex")?;
+            effect.apply(RenderStates::default(), viewport)
+        })
+    };
+    // The value is in the red channel, the green and blue channels are zero and alpha is one
+    let expected = |values: &[f32]| {
+        values
+            .iter()
+            .flat_map(|v| vec![*v, 0.0, 0.0, 1.0])
+            .collect::<Vec<_>>()
+    };
+
+    let r8 = single_channel_texture(&context, vec![0u8, 51, 204, 255], Format::R);
+    let pixels = draw(&r8);
+    for (pixel, expected) in pixels.iter().zip(expected(&[0.0, 0.2, 0.8, 1.0])) {
+        assert!((pixel - expected).abs() < 0.001, "{:?}", pixels);
+    }
+
+    let values = [0.125, 1.5, -2.0, 1000.0];
+    let r16f = single_channel_texture(&context, values.to_vec(), Format::R16F);
+    assert_eq!(draw(&r16f), expected(&values));
+    let r32f = single_channel_texture(&context, values.to_vec(), Format::R);
+    assert_eq!(draw(&r32f), expected(&values));
+}
+
+#[test]
+fn single_channel_render_target_keeps_the_red_channel() {
+    let context = match common::context(4, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let target = ColorTargetTexture2D::<f32>::new(
+        &context,
+        4,
+        1,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::R16F,
+    )
+    .unwrap();
+    let write_channels = ImageEffect::new(
+        &context,
+        "in vec2 uv;
+        layout (location = 0) out vec4 color;
+        void main() { color = vec4(floor(4.0 * uv.x) * 0.25, 1.0, 1.0, 0.0); }",
+    )
+    .unwrap();
+    target
+        .write(ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+            write_channels.apply(RenderStates::default(), Viewport::new_at_origo(4, 1))
+        })
+        .unwrap();
+
+    let effect = common::sample_effect(&context, "uv");
+    let pixels = read_rgba_f32(&context, 4, |viewport| {
+        effect.use_texture(&target, "tex")?;
+        effect.apply(RenderStates::default(), viewport)
+    });
+    assert_eq!(
+        pixels,
+        vec![0.0, 0.0, 0.0, 1.0, 0.25, 0.0, 0.0, 1.0, 0.5, 0.0, 0.0, 1.0, 0.75, 0.0, 0.0, 1.0]
+    );
+}
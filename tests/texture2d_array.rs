@@ -0,0 +1,79 @@
+mod common;
+
+use three_d::*;
+
+// Samples the second layer at u = 0.4, ie. in the first texel but close enough to the second texel that linear interpolation mixes them
+const SAMPLE_LAYER: &str = "uniform sampler2DArray tex;
+    in vec2 uv;
+    layout (location = 0) out vec4 color;
+    void main() { color = texture(tex, vec3(0.4, 0.5, 1.0)); }";
+
+fn sample<T: TextureArray>(context: &Context, effect: &ImageEffect, texture: &T) -> u8 {
+    let pixels = common::render(context, 1, 1, |viewport| {
+        effect.use_texture_array(texture, "tex")?;
+        effect.apply(RenderStates::default(), viewport)
+    });
+    common::pixel(&pixels, 1, 0, 0)[0]
+}
+
+#[test]
+fn set_interpolation_changes_the_sampling_of_a_captured_slice() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let mut texture = ColorTargetTexture2DArray::<u8>::new(
+        &context,
+        2,
+        1,
+        2,
+        Interpolation::Nearest,
+        Interpolation::Nearest,
+        None,
+        Wrapping::ClampToEdge,
+        Wrapping::ClampToEdge,
+        Format::RGBA,
+    )
+    .unwrap();
+    // Captures a black texel to the left of a white texel into the second layer, like an imposter view angle
+    let capture = ImageEffect::new(
+        &context,
+        "in vec2 uv;
+        layout (location = 0) out vec4 color;
+        void main() { color = vec4(step(0.5, uv.x)); }",
+    )
+    .unwrap();
+    texture
+        .write(&[1], ClearState::color(0.0, 0.0, 0.0, 0.0), || {
+            capture.apply(RenderStates::default(), Viewport::new_at_origo(2, 1))
+        })
+        .unwrap();
+    let effect = ImageEffect::new(&context, SAMPLE_LAYER).unwrap();
+
+    assert_eq!(sample(&context, &effect, &texture), 0);
+    texture.set_interpolation(Interpolation::Linear, Interpolation::Linear);
+    // 0.3 of the way from the center of the black texel to the center of the white texel
+    assert!((sample(&context, &effect, &texture) as i32 - 77).abs() <= 2);
+    texture.set_interpolation(Interpolation::Nearest, Interpolation::Nearest);
+    assert_eq!(sample(&context, &effect, &texture), 0);
+}
+
+#[test]
+fn set_interpolation_is_kept_for_uploaded_texture_arrays() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let layer = [0, 0, 0, 255, 255, 255, 255, 255];
+    let mut texture =
+        Texture2DArray::new_with_data(&context, 2, 1, 2, Format::RGBA, &[&layer, &layer]).unwrap();
+    let effect = ImageEffect::new(&context, SAMPLE_LAYER).unwrap();
+
+    assert!((sample(&context, &effect, &texture) as i32 - 77).abs() <= 2);
+    texture.set_interpolation(Interpolation::Nearest, Interpolation::Nearest);
+    assert_eq!(sample(&context, &effect, &texture), 0);
+    // The filter is kept for every later draw
+    assert_eq!(sample(&context, &effect, &texture), 0);
+    texture.set_interpolation(Interpolation::Linear, Interpolation::Linear);
+    assert!((sample(&context, &effect, &texture) as i32 - 77).abs() <= 2);
+}
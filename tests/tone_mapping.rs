@@ -0,0 +1,33 @@
+mod common;
+
+use three_d::*;
+
+#[test]
+fn intermediate_targets_are_kept_until_the_viewport_size_changes() {
+    let context = match common::context(4, 4) {
+        Some(context) => context,
+        None => return,
+    };
+    let mut tone_mapping = ToneMappingEffect::new(&context).unwrap();
+    tone_mapping.exposure = 2.0;
+    let mut frame = |viewport: Viewport| {
+        Screen::write_with_tone_mapping(
+            &context,
+            viewport,
+            ClearState::color_and_depth(0.1, 0.1, 0.1, 1.0, 1.0),
+            &mut tone_mapping,
+            || Ok(()),
+        )
+        .unwrap();
+        context.memory_stats()
+    };
+    let stats = frame(Viewport::new_at_origo(4, 4));
+    for _ in 0..3 {
+        assert_eq!(frame(Viewport::new_at_origo(4, 4)), stats);
+    }
+    // The targets are replaced by smaller targets when the viewport shrinks
+    let resized = frame(Viewport::new_at_origo(2, 2));
+    assert_eq!(resized.textures, stats.textures);
+    assert!(resized.texture_bytes < stats.texture_bytes);
+    assert_eq!(frame(Viewport::new_at_origo(2, 2)), resized);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use three_d::*;
+
+const SIZE: u32 = 16;
+
+// A grid of 3x3 quads in normalized device coordinates covering the center of the viewport, with 4x4 vertices
+fn grid_positions() -> Vec<f32> {
+    let mut positions = Vec::new();
+    for row in 0..4 {
+        for col in 0..4 {
+            positions.extend_from_slice(&[-0.75 + 0.5 * col as f32, -0.75 + 0.5 * row as f32, 0.0]);
+        }
+    }
+    positions
+}
+
+fn vertex(row: u16, col: u16) -> u16 {
+    row * 4 + col
+}
+
+fn render(context: &Context, primitive_type: PrimitiveType, indices: Vec<u16>) -> Vec<u8> {
+    let mesh = Mesh::new(
+        context,
+        &CPUMesh {
+            positions: grid_positions(),
+            indices: Some(Indices::U16(indices)),
+            primitive_type,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    common::render(context, SIZE, SIZE, |viewport| {
+        mesh.render_ndc_with_color(&vec4(1.0, 1.0, 1.0, 1.0), RenderStates::default(), viewport)
+    })
+}
+
+// One triangle strip for each of the given rows of quads, joined by the primitive restart index, and the same triangles as a triangle list
+fn strips_and_triangles(rows: &[u16]) -> (Vec<u16>, Vec<u16>) {
+    let mut strips = Vec::new();
+    let mut triangles = Vec::new();
+    for row in rows {
+        if !strips.is_empty() {
+            strips.push(u16::MAX);
+        }
+        for col in 0..4 {
+            strips.push(vertex(row + 1, col));
+            strips.push(vertex(*row, col));
+        }
+        for col in 0..3 {
+            triangles.extend_from_slice(&[
+                vertex(row + 1, col),
+                vertex(*row, col),
+                vertex(row + 1, col + 1),
+                vertex(row + 1, col + 1),
+                vertex(*row, col),
+                vertex(*row, col + 1),
+            ]);
+        }
+    }
+    (strips, triangles)
+}
+
+fn covered_pixels(pixels: &[u8]) -> usize {
+    pixels.chunks(4).filter(|p| p[0] > 0).count()
+}
+
+#[test]
+fn strip_grid_covers_the_same_pixels_as_triangle_list() {
+    let context = match common::context(SIZE, SIZE) {
+        Some(context) => context,
+        None => return,
+    };
+    let (strips, triangles) = strips_and_triangles(&[0, 1, 2]);
+    let strip_pixels = render(&context, PrimitiveType::TriangleStrip, strips);
+    let triangle_pixels = render(&context, PrimitiveType::Triangles, triangles);
+    assert_eq!(strip_pixels, triangle_pixels);
+    // The grid covers the center 12x12 pixels
+    assert_eq!(covered_pixels(&strip_pixels), 144);
+}
+
+#[test]
+fn primitive_restart_separates_strips() {
+    let context = match common::context(SIZE, SIZE) {
+        Some(context) => context,
+        None => return,
+    };
+    // Without primitive restart, the triangles joining the two strips would cover the middle row of quads
+    let (strips, triangles) = strips_and_triangles(&[0, 2]);
+    let strip_pixels = render(&context, PrimitiveType::TriangleStrip, strips);
+    let triangle_pixels = render(&context, PrimitiveType::Triangles, triangles);
+    assert_eq!(strip_pixels, triangle_pixels);
+    assert_eq!(covered_pixels(&strip_pixels), 96);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use three_d::*;
+
+fn render_block(context: &Context, buffer: &UniformBuffer) -> Vec<u8> {
+    let effect = ImageEffect::new(
+        context,
+        "uniform Data { float r; float g; float b; float a; };
+        in vec2 uv;
+        layout (location = 0) out vec4 color;
+        void main() { color = vec4(r, g, b, a); }",
+    )
+    .unwrap();
+    effect.use_uniform_block(buffer, "Data").unwrap();
+    common::render(context, 1, 1, |viewport| {
+        effect.apply(RenderStates::default(), viewport)
+    })
+}
+
+fn assert_identical(context: &Context, separate: &UniformBuffer, coalesced: &UniformBuffer) {
+    for index in 0..4 {
+        assert_eq!(separate.get(index).unwrap(), coalesced.get(index).unwrap());
+    }
+    assert_eq!(
+        render_block(context, separate),
+        render_block(context, coalesced)
+    );
+}
+
+#[test]
+fn coalesced_and_separate_updates_give_identical_contents() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let updates: [(u32, &[f32]); 4] = [(2, &[0.6]), (0, &[0.2]), (3, &[1.0]), (1, &[0.4])];
+
+    let mut separate = UniformBuffer::new(&context, &[1, 1, 1, 1]).unwrap();
+    for (index, data) in updates.iter() {
+        separate.update(*index, data).unwrap();
+    }
+    let mut coalesced = UniformBuffer::new(&context, &[1, 1, 1, 1]).unwrap();
+    coalesced.update_many(&updates).unwrap();
+    assert_identical(&context, &separate, &coalesced);
+    assert_eq!(render_block(&context, &coalesced), vec![51, 102, 153, 255]);
+
+    // Updates with a gap are sent as two ranges.
+    let updates: [(u32, &[f32]); 2] = [(3, &[0.8]), (0, &[0.0])];
+    for (index, data) in updates.iter() {
+        separate.update(*index, data).unwrap();
+    }
+    coalesced.update_many(&updates).unwrap();
+    assert_identical(&context, &separate, &coalesced);
+}
+
+#[test]
+fn invalid_coalesced_update_changes_nothing() {
+    let context = match common::context(1, 1) {
+        Some(context) => context,
+        None => return,
+    };
+    let mut buffer = UniformBuffer::new(&context, &[1, 2]).unwrap();
+    assert!(buffer.update_many(&[(0, &[1.0]), (1, &[1.0])]).is_err());
+    assert!(buffer.update_many(&[(0, &[1.0]), (2, &[1.0])]).is_err());
+    assert_eq!(buffer.get(0).unwrap(), &[0.0]);
+    assert_eq!(buffer.get(1).unwrap(), &[0.0, 0.0]);
+}